@@ -1,5 +1,7 @@
 use pge::*;
+use math::{Mat4, Quat, Vec3D};
 use render::IndexBuffer;
+use render::Sampler;
 use render::ShaderProgram;
 use render::VArray;
 use render::VbLayout;
@@ -29,17 +31,24 @@ fn main() {
         Ok(res) => res,
         Err(_) => app.client_logger.fatal(&"Res path is wrong!".to_string()),
     };
-    let texture = Texture::from_res(&app.gl, &res, "./Slimes/Slime_16x16.png").unwrap();
-    let vertex_array = VArray::new(&app.gl);
-    let prog = ShaderProgram::from_res(&app.gl, &res, "rect").unwrap();
-    let vertex_buffer = VertexBuffer::new(&app.gl);
+    let texture = Texture::from_res(&app.backend, &res, "./Slimes/Slime_16x16.png").unwrap();
+    let vertex_array = VArray::new(&app.backend);
+    let prog = ShaderProgram::from_res(&app.backend, &res, "rect").unwrap();
+    let transform = Mat4::from_trs(
+        Vec3D::new(0.0, 0.0, 0.0),
+        Quat::from_axis_angle(0.3, &Vec3D::new(0.0, 0.0, 1.0)),
+        Vec3D::new(1.5, 1.5, 1.0),
+    );
+    prog.set_uniform("u_Transform", &transform).unwrap();
+    prog.set_uniform("u_Texture", &Sampler(0)).unwrap();
+    let vertex_buffer = VertexBuffer::new(&app.backend);
     vertex_buffer.set_data::<f32>(&vertices);
     let mut vb_layout = VbLayout::new();
     vb_layout.push_f32(3 as GLint);
     vb_layout.push_f32(2 as GLint);
 
     vertex_array.add_buffer(&vertex_buffer, &vb_layout);
-    let index_buffer = IndexBuffer::new(&app.gl);
+    let index_buffer = IndexBuffer::new(&app.backend);
     index_buffer.set_data::<u32>(&indices);
 
     let draw = || {
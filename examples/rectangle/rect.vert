@@ -3,8 +3,10 @@
 layout (location = 0) in vec3 a_Pos;
 layout (location = 1) in vec2 a_TextCoord;
 
+uniform mat4 u_Transform;
+
 out vec2 v_TextCoord;
 void main() {
     v_TextCoord = a_TextCoord;
-    gl_Position = vec4(a_Pos.x, a_Pos.y, a_Pos.z, 1.0);
+    gl_Position = u_Transform * vec4(a_Pos.x, a_Pos.y, a_Pos.z, 1.0);
 }
\ No newline at end of file
@@ -0,0 +1,87 @@
+use pge::math::geometry::{Aabb3, Ray};
+use pge::math::spatial::{Bvh3, Octree, SpatialHash3};
+use pge::math::Vec3D;
+use std::time::Instant;
+
+const ITEMS: usize = 50_000;
+const QUERIES: usize = 1_000;
+const WORLD: f32 = 1000.0;
+
+// small LCG so the benchmark doesn't depend on anything else
+struct Lcg(u64);
+impl Lcg {
+    fn next(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+    fn point(&mut self) -> Vec3D {
+        Vec3D::new(self.next() * WORLD, self.next() * WORLD, self.next() * WORLD)
+    }
+}
+
+fn bench<F: FnMut() -> usize>(name: &str, mut f: F) {
+    let start = Instant::now();
+    let found = f();
+    println!("{:<28} {:>10.3?} ({} results)", name, start.elapsed(), found);
+}
+
+fn main() {
+    let mut rng = Lcg(42);
+    let boxes: Vec<Aabb3> = (0..ITEMS)
+        .map(|_| Aabb3::from_center(rng.point(), Vec3D::new(1.0, 1.0, 1.0)))
+        .collect();
+    let centers: Vec<Vec3D> = (0..QUERIES).map(|_| rng.point()).collect();
+    let rays: Vec<Ray> = (0..QUERIES)
+        .map(|_| Ray::new(rng.point(), (rng.point() - rng.point()).normalized()))
+        .collect();
+
+    let mut bvh = Bvh3::new();
+    let mut octree = Octree::new(Aabb3::new(Vec3D::null(), Vec3D::new(WORLD, WORLD, WORLD)), 8);
+    let mut hash = SpatialHash3::new(8.0);
+    bench("build bvh (sah)", || Bvh3::build(boxes.iter().map(|b| (*b, ())).collect()).len());
+    bench("insert bvh", || boxes.iter().map(|b| bvh.insert(*b, ())).count());
+    bench("insert octree", || boxes.iter().map(|b| octree.insert(*b, ())).count());
+    bench("insert spatial hash", || boxes.iter().map(|b| hash.insert(*b, ())).count());
+    println!();
+
+    let radius = 20.0;
+    bench("radius brute force", || {
+        centers
+            .iter()
+            .map(|c| boxes.iter().filter(|b| b.sq_distance(*c) <= radius * radius).count())
+            .sum()
+    });
+    bench("radius bvh", || centers.iter().map(|c| bvh.query_radius(*c, radius).len()).sum());
+    bench("radius octree", || centers.iter().map(|c| octree.query_radius(*c, radius).len()).sum());
+    bench("radius spatial hash", || centers.iter().map(|c| hash.query_radius(*c, radius).len()).sum());
+    println!();
+
+    bench("ray cast brute force", || {
+        rays.iter()
+            .filter(|r| boxes.iter().filter_map(|b| r.intersect_aabb(b)).any(|t| t <= WORLD))
+            .count()
+    });
+    bench("ray cast bvh", || rays.iter().filter_map(|r| bvh.ray_cast(r, WORLD)).count());
+    bench("ray cast octree", || rays.iter().filter_map(|r| octree.ray_cast(r, WORLD)).count());
+    bench("ray cast spatial hash", || rays.iter().filter_map(|r| hash.ray_cast(r, WORLD)).count());
+    println!();
+
+    let k = 8;
+    bench("k nearest brute force", || {
+        centers
+            .iter()
+            .map(|c| {
+                let mut d: Vec<f32> = boxes.iter().map(|b| b.sq_distance(*c)).collect();
+                d.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                d.truncate(k);
+                d.len()
+            })
+            .sum()
+    });
+    bench("k nearest bvh", || centers.iter().map(|c| bvh.k_nearest(*c, k).len()).sum());
+    bench("k nearest octree", || centers.iter().map(|c| octree.k_nearest(*c, k).len()).sum());
+    bench("k nearest spatial hash", || centers.iter().map(|c| hash.k_nearest(*c, k).len()).sum());
+}
@@ -19,13 +19,13 @@ fn main() {
         Err(_) => app.client_logger.fatal(&"Res path is wrong!".to_string()),
     };
 
-    let prog = ShaderProgram::from_res(&app.gl, &res, "tri").unwrap();
-    let vertex_buffer = VertexBuffer::new(&app.gl);
+    let prog = ShaderProgram::from_res(&app.backend, &res, "tri").unwrap();
+    let vertex_buffer = VertexBuffer::new(&app.backend);
     vertex_buffer.set_data::<f32>(&vertices);
     let mut vb_layout = VbLayout::new();
     vb_layout.push_f32(3 as GLint);
 
-    let vertex_array = VArray::new(&app.gl);
+    let vertex_array = VArray::new(&app.backend);
     vertex_array.add_buffer(&vertex_buffer, &vb_layout);
 
     let draw = || {
@@ -0,0 +1,53 @@
+// External imports
+use std::collections::VecDeque;
+// Crate imports
+use super::{DisjointSet, Graph, NodeId};
+
+/// Label of the connected component of each node, the edges being followed
+/// in both directions. Labels go from 0 in the order of their first node.
+pub fn connected_components<N>(graph: &Graph<N>) -> Vec<usize> {
+    let mut sets = DisjointSet::new(graph.node_count());
+    for edge in graph.edges() {
+        sets.union(edge.from, edge.to);
+    }
+    let mut labels = vec![usize::MAX; graph.node_count()];
+    let mut root_labels = vec![usize::MAX; graph.node_count()];
+    let mut count = 0;
+    for (node, label) in labels.iter_mut().enumerate() {
+        let root = sets.find(node);
+        if root_labels[root] == usize::MAX {
+            root_labels[root] = count;
+            count += 1;
+        }
+        *label = root_labels[root];
+    }
+    labels
+}
+
+/// Nodes ordered so that every edge goes forward, `None` if the graph has a cycle.
+///
+/// Among the nodes ready at the same time the lowest id comes first.
+pub fn topological_sort<N>(graph: &Graph<N>) -> Option<Vec<NodeId>> {
+    let mut incoming = vec![0usize; graph.node_count()];
+    for edge in graph.edges() {
+        incoming[edge.to] += 1;
+    }
+    let mut ready: VecDeque<NodeId> = (0..graph.node_count())
+        .filter(|&n| incoming[n] == 0)
+        .collect();
+    let mut order = Vec::with_capacity(graph.node_count());
+    while let Some(node) = ready.pop_front() {
+        order.push(node);
+        for edge in graph.out_edges(node) {
+            incoming[edge.to] -= 1;
+            if incoming[edge.to] == 0 {
+                ready.push_back(edge.to);
+            }
+        }
+    }
+    if order.len() == graph.node_count() {
+        Some(order)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,48 @@
+/// Union-find over the indices `0..len`, with path halving and union by size.
+#[derive(Clone, Debug)]
+pub struct DisjointSet {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+        }
+    }
+
+    /// Representative of the set containing `i`.
+    pub fn find(&mut self, mut i: usize) -> usize {
+        while self.parents[i] != i {
+            self.parents[i] = self.parents[self.parents[i]];
+            i = self.parents[i];
+        }
+        i
+    }
+
+    /// Merges the sets of `a` and `b`, false if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        true
+    }
+
+    pub fn same_set(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the set containing `i`.
+    pub fn set_size(&mut self, i: usize) -> usize {
+        let root = self.find(i);
+        self.sizes[root]
+    }
+}
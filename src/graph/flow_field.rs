@@ -0,0 +1,112 @@
+// External imports
+use std::collections::BinaryHeap;
+// Crate imports
+use super::grid::{GridGraph, NEIGHBOURS};
+use super::search::Open;
+use crate::math::Vec2D;
+
+/// Cost to the nearest goal from every cell of a grid and the step to take
+/// from each of them, for many agents heading to the same goals.
+#[derive(Clone, Debug)]
+pub struct FlowField {
+    width: usize,
+    height: usize,
+    costs: Vec<f32>,
+    steps: Vec<Option<(i32, i32)>>,
+}
+
+impl FlowField {
+    /// Integrates the costs of the grid from the goals, the cells that can't
+    /// reach any goal keep an infinite cost and no direction.
+    pub fn new(grid: &GridGraph, goals: &[(i32, i32)]) -> Self {
+        let (width, height) = (grid.width(), grid.height());
+        let mut costs = vec![f32::INFINITY; width * height];
+        let mut steps = vec![None; width * height];
+        let mut open = BinaryHeap::new();
+        let mut order = 0;
+        for &(x, y) in goals {
+            if grid.is_open(x, y) {
+                costs[x as usize + y as usize * width] = 0_f32;
+                order += 1;
+                open.push(Open {
+                    priority: 0_f32,
+                    order,
+                    node: (x, y),
+                });
+            }
+        }
+        let count = if grid.diagonal { 8 } else { 4 };
+        while let Some(entry) = open.pop() {
+            let (priority, (x, y)) = (entry.priority, entry.node);
+            if priority > costs[x as usize + y as usize * width] {
+                continue;
+            }
+            // cells stepping into this one pay for entering it
+            for &(dx, dy) in &NEIGHBOURS[..count] {
+                let (px, py) = (x - dx, y - dy);
+                if !grid.is_open(px, py) || !grid.can_step(px, py, dx, dy) {
+                    continue;
+                }
+                let length = if dx != 0 && dy != 0 {
+                    std::f32::consts::SQRT_2
+                } else {
+                    1_f32
+                };
+                let cost = priority + length * grid.cost(x, y);
+                let index = px as usize + py as usize * width;
+                if cost < costs[index] {
+                    costs[index] = cost;
+                    steps[index] = Some((dx, dy));
+                    order += 1;
+                    open.push(Open {
+                        priority: cost,
+                        order,
+                        node: (px, py),
+                    });
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            costs,
+            steps,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Cost to the nearest goal, infinite outside of the grid or if unreachable.
+    pub fn cost(&self, x: i32, y: i32) -> f32 {
+        match self.index(x, y) {
+            Some(i) => self.costs[i],
+            None => f32::INFINITY,
+        }
+    }
+
+    /// Offset of the next cell towards the nearest goal, `None` on the goals
+    /// and on the cells that can't reach one.
+    pub fn step(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        self.index(x, y).and_then(|i| self.steps[i])
+    }
+
+    /// Unit direction of the next step.
+    pub fn direction(&self, x: i32, y: i32) -> Option<Vec2D> {
+        self.step(x, y)
+            .map(|(dx, dy)| Vec2D::new(dx as f32, dy as f32).normalized())
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            Some(x as usize + y as usize * self.width)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,119 @@
+// Crate imports
+use super::SearchGraph;
+
+/// 2D grid where each cell has the cost of entering it, infinite for walls.
+///
+/// Diagonal steps cost `sqrt(2)` times the cell and are only allowed
+/// when both cells they pass between are open.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GridGraph {
+    width: usize,
+    height: usize,
+    costs: Vec<f32>,
+    pub diagonal: bool,
+}
+
+/// Offsets of the straight then diagonal neighbours.
+pub const NEIGHBOURS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (-1, 1),
+    (1, -1),
+    (-1, -1),
+];
+
+impl GridGraph {
+    /// Open grid of cost 1, with diagonal moves.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            costs: vec![1_f32; width * height],
+            diagonal: true,
+        }
+    }
+
+    /// Grid with the cost of each cell computed from its coordinates.
+    pub fn from_fn(width: usize, height: usize, mut cost: impl FnMut(usize, usize) -> f32) -> Self {
+        let mut grid = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                grid.costs[x + y * width] = cost(x, y);
+            }
+        }
+        grid
+    }
+
+    pub fn with_diagonal(mut self, diagonal: bool) -> Self {
+        self.diagonal = diagonal;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    /// Cost of entering the cell, infinite outside of the grid.
+    pub fn cost(&self, x: i32, y: i32) -> f32 {
+        if self.in_bounds(x, y) {
+            self.costs[x as usize + y as usize * self.width]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    pub fn set_cost(&mut self, x: i32, y: i32, cost: f32) {
+        if self.in_bounds(x, y) {
+            self.costs[x as usize + y as usize * self.width] = cost;
+        }
+    }
+
+    pub fn set_wall(&mut self, x: i32, y: i32) {
+        self.set_cost(x, y, f32::INFINITY);
+    }
+
+    pub fn is_open(&self, x: i32, y: i32) -> bool {
+        self.cost(x, y).is_finite()
+    }
+
+    /// Whether the step of `(dx, dy)` from the cell is allowed.
+    pub fn can_step(&self, x: i32, y: i32, dx: i32, dy: i32) -> bool {
+        if !self.is_open(x + dx, y + dy) {
+            return false;
+        }
+        if dx != 0 && dy != 0 {
+            self.diagonal && self.is_open(x + dx, y) && self.is_open(x, y + dy)
+        } else {
+            true
+        }
+    }
+}
+
+impl SearchGraph for GridGraph {
+    type Node = (i32, i32);
+
+    fn successors(&self, (x, y): (i32, i32), out: &mut Vec<((i32, i32), f32)>) {
+        let count = if self.diagonal { 8 } else { 4 };
+        for &(dx, dy) in &NEIGHBOURS[..count] {
+            if self.can_step(x, y, dx, dy) {
+                let length = if dx != 0 && dy != 0 {
+                    std::f32::consts::SQRT_2
+                } else {
+                    1_f32
+                };
+                out.push(((x + dx, y + dy), length * self.cost(x + dx, y + dy)));
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+// Distance estimates for A*, on positions or grid cells.
+
+// Crate imports
+use crate::math::{Vec2D, Vec3D};
+
+/// Anything with coordinates the heuristics can measure.
+pub trait Point {
+    /// Absolute differences of the coordinates, 0 for the missing axes.
+    fn deltas(&self, other: &Self) -> [f32; 3];
+}
+
+impl Point for Vec2D {
+    fn deltas(&self, other: &Self) -> [f32; 3] {
+        [(self.x - other.x).abs(), (self.y - other.y).abs(), 0_f32]
+    }
+}
+
+impl Point for Vec3D {
+    fn deltas(&self, other: &Self) -> [f32; 3] {
+        [
+            (self.x - other.x).abs(),
+            (self.y - other.y).abs(),
+            (self.z - other.z).abs(),
+        ]
+    }
+}
+
+impl Point for (i32, i32) {
+    fn deltas(&self, other: &Self) -> [f32; 3] {
+        [
+            (self.0 - other.0).abs() as f32,
+            (self.1 - other.1).abs() as f32,
+            0_f32,
+        ]
+    }
+}
+
+impl Point for (i32, i32, i32) {
+    fn deltas(&self, other: &Self) -> [f32; 3] {
+        [
+            (self.0 - other.0).abs() as f32,
+            (self.1 - other.1).abs() as f32,
+            (self.2 - other.2).abs() as f32,
+        ]
+    }
+}
+
+/// Straight line distance, for movement in any direction.
+pub fn euclidean<P: Point>(a: &P, b: &P) -> f32 {
+    let [x, y, z] = a.deltas(b);
+    (x * x + y * y + z * z).sqrt()
+}
+
+/// Sum of the axis distances, for movement along the axes only.
+pub fn manhattan<P: Point>(a: &P, b: &P) -> f32 {
+    let [x, y, z] = a.deltas(b);
+    x + y + z
+}
+
+/// Largest axis distance, for diagonal steps costing as much as straight ones.
+pub fn chebyshev<P: Point>(a: &P, b: &P) -> f32 {
+    let [x, y, z] = a.deltas(b);
+    x.max(y).max(z)
+}
+
+/// Distance with straight steps of 1 and diagonal steps of sqrt(2) (or sqrt(3)
+/// across the three axes), the exact cost on open 8 or 26 connected grids.
+pub fn octile<P: Point>(a: &P, b: &P) -> f32 {
+    let mut d = a.deltas(b);
+    d.sort_by(|a, b| b.total_cmp(a));
+    let (sqrt2, sqrt3) = (std::f32::consts::SQRT_2, 3_f32.sqrt());
+    sqrt3 * d[2] + sqrt2 * (d[1] - d[2]) + (d[0] - d[1])
+}
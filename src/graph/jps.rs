@@ -0,0 +1,200 @@
+// Jump point search, A* on uniform cost grids skipping the cells of the
+// straight runs that can't be on a better path. This is the variant
+// where diagonal moves can't cut corners, matching `GridGraph::can_step`.
+
+// External imports
+use std::collections::{BinaryHeap, HashMap, HashSet};
+// Crate imports
+use super::grid::GridGraph;
+use super::heuristic::{manhattan, octile};
+use super::search::{astar, Open, Path};
+
+type Cell = (i32, i32);
+
+/// Shortest path on the grid ignoring the cell costs, only walls matter.
+///
+/// The path lists every cell, not only the jump points. Grids without
+/// diagonal moves fall back to A*.
+pub fn jump_point_search(grid: &GridGraph, start: Cell, goal: Cell) -> Option<Path<Cell>> {
+    if !grid.is_open(start.0, start.1) || !grid.is_open(goal.0, goal.1) {
+        return None;
+    }
+    if !grid.diagonal {
+        let uniform = GridGraph::from_fn(grid.width(), grid.height(), |x, y| {
+            if grid.is_open(x as i32, y as i32) {
+                1_f32
+            } else {
+                f32::INFINITY
+            }
+        })
+        .with_diagonal(false);
+        return astar(&uniform, start, goal, |c| manhattan(&c, &goal));
+    }
+
+    let mut parents: HashMap<Cell, (f32, Option<Cell>)> = HashMap::new();
+    let mut closed = HashSet::new();
+    let mut open = BinaryHeap::new();
+    let mut order = 0usize;
+    parents.insert(start, (0_f32, None));
+    open.push(Open {
+        priority: octile(&start, &goal),
+        order,
+        node: start,
+    });
+    let mut neighbours = Vec::new();
+    while let Some(Open { node: cell, .. }) = open.pop() {
+        if cell == goal {
+            return Some(expand(&parents, goal));
+        }
+        if !closed.insert(cell) {
+            continue;
+        }
+        let (cost, parent) = parents[&cell];
+        neighbours.clear();
+        pruned_neighbours(grid, cell, parent, &mut neighbours);
+        for &(nx, ny) in &neighbours {
+            let jump_point = match jump(grid, (nx, ny), cell, goal) {
+                Some(jump_point) => jump_point,
+                None => continue,
+            };
+            let next_cost = cost + octile(&cell, &jump_point);
+            if parents
+                .get(&jump_point)
+                .map(|(c, _)| next_cost < *c)
+                .unwrap_or(true)
+            {
+                parents.insert(jump_point, (next_cost, Some(cell)));
+                order += 1;
+                open.push(Open {
+                    priority: next_cost + octile(&jump_point, &goal),
+                    order,
+                    node: jump_point,
+                });
+            }
+        }
+    }
+    None
+}
+
+// Neighbours worth exploring coming from the parent.
+fn pruned_neighbours(grid: &GridGraph, (x, y): Cell, parent: Option<Cell>, out: &mut Vec<Cell>) {
+    let open = |x: i32, y: i32| grid.is_open(x, y);
+    let (px, py) = match parent {
+        Some(parent) => parent,
+        None => {
+            for &(dx, dy) in super::grid::NEIGHBOURS.iter() {
+                if grid.can_step(x, y, dx, dy) {
+                    out.push((x + dx, y + dy));
+                }
+            }
+            return;
+        }
+    };
+    let (dx, dy) = ((x - px).signum(), (y - py).signum());
+    if dx != 0 && dy != 0 {
+        if open(x, y + dy) {
+            out.push((x, y + dy));
+        }
+        if open(x + dx, y) {
+            out.push((x + dx, y));
+        }
+        if open(x, y + dy) && open(x + dx, y) && open(x + dx, y + dy) {
+            out.push((x + dx, y + dy));
+        }
+    } else if dx != 0 {
+        let (next, top, bottom) = (open(x + dx, y), open(x, y + 1), open(x, y - 1));
+        if next {
+            out.push((x + dx, y));
+            if top && open(x + dx, y + 1) {
+                out.push((x + dx, y + 1));
+            }
+            if bottom && open(x + dx, y - 1) {
+                out.push((x + dx, y - 1));
+            }
+        }
+        if top {
+            out.push((x, y + 1));
+        }
+        if bottom {
+            out.push((x, y - 1));
+        }
+    } else {
+        let (next, right, left) = (open(x, y + dy), open(x + 1, y), open(x - 1, y));
+        if next {
+            out.push((x, y + dy));
+            if right && open(x + 1, y + dy) {
+                out.push((x + 1, y + dy));
+            }
+            if left && open(x - 1, y + dy) {
+                out.push((x - 1, y + dy));
+            }
+        }
+        if right {
+            out.push((x + 1, y));
+        }
+        if left {
+            out.push((x - 1, y));
+        }
+    }
+}
+
+// Follows the direction from `from` to `cell` until a jump point,
+// a cell with a neighbour only reachable optimally through it.
+fn jump(grid: &GridGraph, cell: Cell, from: Cell, goal: Cell) -> Option<Cell> {
+    let open = |x: i32, y: i32| grid.is_open(x, y);
+    let (dx, dy) = (cell.0 - from.0, cell.1 - from.1);
+    let (mut x, mut y) = cell;
+    loop {
+        if !open(x, y) {
+            return None;
+        }
+        if (x, y) == goal {
+            return Some((x, y));
+        }
+        if dx != 0 && dy != 0 {
+            if jump(grid, (x + dx, y), (x, y), goal).is_some()
+                || jump(grid, (x, y + dy), (x, y), goal).is_some()
+            {
+                return Some((x, y));
+            }
+        } else if dx != 0 {
+            if (open(x, y - 1) && !open(x - dx, y - 1)) || (open(x, y + 1) && !open(x - dx, y + 1))
+            {
+                return Some((x, y));
+            }
+        } else if (open(x - 1, y) && !open(x - 1, y - dy))
+            || (open(x + 1, y) && !open(x + 1, y - dy))
+        {
+            return Some((x, y));
+        }
+        // diagonal moves need both straight cells open
+        if !(open(x + dx, y) && open(x, y + dy)) {
+            return None;
+        }
+        x += dx;
+        y += dy;
+    }
+}
+
+// Path through every cell between the jump points.
+fn expand(parents: &HashMap<Cell, (f32, Option<Cell>)>, goal: Cell) -> Path<Cell> {
+    let cost = parents[&goal].0;
+    let mut jump_points = vec![goal];
+    let mut current = goal;
+    while let Some((_, Some(previous))) = parents.get(&current) {
+        jump_points.push(*previous);
+        current = *previous;
+    }
+    jump_points.reverse();
+    let mut nodes = vec![jump_points[0]];
+    for pair in jump_points.windows(2) {
+        let (mut x, mut y) = pair[0];
+        let (tx, ty) = pair[1];
+        while (x, y) != (tx, ty) {
+            x += (tx - x).signum();
+            y += (ty - y).signum();
+            nodes.push((x, y));
+        }
+    }
+    Path { nodes, cost }
+}
@@ -0,0 +1,117 @@
+pub mod components;
+pub mod disjoint_set;
+pub mod flow_field;
+pub mod grid;
+pub mod heuristic;
+pub mod jps;
+pub mod mst;
+pub mod search;
+
+pub use components::{connected_components, topological_sort};
+pub use disjoint_set::DisjointSet;
+pub use flow_field::FlowField;
+pub use grid::GridGraph;
+pub use jps::jump_point_search;
+pub use search::{astar, bfs, dfs, dijkstra, Path, ShortestPaths};
+
+pub type NodeId = usize;
+pub type EdgeId = usize;
+
+/// Graph that can be searched, `successors` fills `out` with the nodes
+/// reachable in one step and the cost of the step.
+pub trait SearchGraph {
+    type Node: Copy + Eq + std::hash::Hash;
+
+    fn successors(&self, node: Self::Node, out: &mut Vec<(Self::Node, f32)>);
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Edge {
+    pub from: NodeId,
+    pub to: NodeId,
+    pub weight: f32,
+}
+
+/// Directed graph with a value on each node and weighted edges,
+/// undirected graphs use a pair of edges for each link.
+#[derive(Clone, Debug, Default)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    edges: Vec<Edge>,
+    outgoing: Vec<Vec<EdgeId>>,
+}
+
+impl<N> Graph<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            outgoing: Vec::new(),
+        }
+    }
+
+    pub fn add_node(&mut self, value: N) -> NodeId {
+        self.nodes.push(value);
+        self.outgoing.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: f32) -> EdgeId {
+        self.edges.push(Edge { from, to, weight });
+        self.outgoing[from].push(self.edges.len() - 1);
+        self.edges.len() - 1
+    }
+
+    /// Adds the edges in both directions, returns the one from `a` to `b`.
+    pub fn add_undirected_edge(&mut self, a: NodeId, b: NodeId, weight: f32) -> EdgeId {
+        let edge = self.add_edge(a, b, weight);
+        self.add_edge(b, a, weight);
+        edge
+    }
+
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn node_mut(&mut self, id: NodeId) -> &mut N {
+        &mut self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn edge(&self, id: EdgeId) -> &Edge {
+        &self.edges[id]
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Edges leaving the node.
+    pub fn out_edges(&self, id: NodeId) -> impl Iterator<Item = &Edge> + '_ {
+        self.outgoing[id].iter().map(move |&e| &self.edges[e])
+    }
+
+    /// Nodes reachable from the node with the weight of the edge.
+    pub fn neighbours(&self, id: NodeId) -> impl Iterator<Item = (NodeId, f32)> + '_ {
+        self.out_edges(id).map(|e| (e.to, e.weight))
+    }
+}
+
+impl<N> SearchGraph for Graph<N> {
+    type Node = NodeId;
+
+    fn successors(&self, node: NodeId, out: &mut Vec<(NodeId, f32)>) {
+        out.extend(self.neighbours(node));
+    }
+}
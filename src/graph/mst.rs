@@ -0,0 +1,75 @@
+// Minimum spanning trees, the edges are taken as undirected so the pair
+// of edges of `Graph::add_undirected_edge` counts once. Disconnected
+// graphs give a spanning forest.
+
+// External imports
+use std::collections::BinaryHeap;
+// Crate imports
+use super::search::Open;
+use super::{DisjointSet, EdgeId, Graph};
+
+/// Kruskal, the edges of the tree in increasing weight.
+pub fn kruskal<N>(graph: &Graph<N>) -> Vec<EdgeId> {
+    let mut edges: Vec<EdgeId> = (0..graph.edge_count()).collect();
+    edges.sort_by(|&a, &b| graph.edge(a).weight.total_cmp(&graph.edge(b).weight));
+    let mut sets = DisjointSet::new(graph.node_count());
+    edges
+        .into_iter()
+        .filter(|&e| sets.union(graph.edge(e).from, graph.edge(e).to))
+        .collect()
+}
+
+/// Prim, the edges of the tree in the order they were added, growing
+/// from node 0 and then from the first node of each other component.
+pub fn prim<N>(graph: &Graph<N>) -> Vec<EdgeId> {
+    let mut incident: Vec<Vec<EdgeId>> = vec![Vec::new(); graph.node_count()];
+    for (id, edge) in graph.edges().iter().enumerate() {
+        incident[edge.from].push(id);
+        if edge.to != edge.from {
+            incident[edge.to].push(id);
+        }
+    }
+    let mut in_tree = vec![false; graph.node_count()];
+    let mut tree = Vec::new();
+    let mut open = BinaryHeap::new();
+    let mut order = 0;
+    for root in 0..graph.node_count() {
+        if in_tree[root] {
+            continue;
+        }
+        in_tree[root] = true;
+        let mut node = root;
+        loop {
+            for &e in &incident[node] {
+                order += 1;
+                open.push(Open {
+                    priority: graph.edge(e).weight,
+                    order,
+                    node: e,
+                });
+            }
+            let next = loop {
+                match open.pop() {
+                    Some(Open { node: e, .. }) => {
+                        let edge = graph.edge(e);
+                        match (in_tree[edge.from], in_tree[edge.to]) {
+                            (true, false) => break Some((e, edge.to)),
+                            (false, true) => break Some((e, edge.from)),
+                            _ => continue,
+                        }
+                    }
+                    None => break None,
+                }
+            };
+            match next {
+                Some((e, n)) => {
+                    in_tree[n] = true;
+                    tree.push(e);
+                    node = n;
+                }
+                None => break,
+            }
+        }
+    }
+    tree
+}
@@ -0,0 +1,200 @@
+// External imports
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+// Crate imports
+use super::SearchGraph;
+
+/// Nodes from the start to the goal, both included, and the total cost.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Path<N> {
+    pub nodes: Vec<N>,
+    pub cost: f32,
+}
+
+// Entry of the open lists, the lowest priority first and the oldest
+// entry among equal priorities so that searches are deterministic.
+pub(super) struct Open<N> {
+    pub(super) priority: f32,
+    pub(super) order: usize,
+    pub(super) node: N,
+}
+
+impl<N> PartialEq for Open<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N> Eq for Open<N> {}
+
+impl<N> PartialOrd for Open<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Open<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// Nodes in breadth first order from the start.
+pub fn bfs<G: SearchGraph>(graph: &G, start: G::Node) -> Vec<G::Node> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut queue = VecDeque::new();
+    let mut successors = Vec::new();
+    visited.insert(start);
+    queue.push_back(start);
+    while let Some(node) = queue.pop_front() {
+        order.push(node);
+        successors.clear();
+        graph.successors(node, &mut successors);
+        for &(next, _) in &successors {
+            if visited.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+    order
+}
+
+/// Nodes in depth first (preorder) order from the start, the successors
+/// being visited in the order the graph gives them.
+pub fn dfs<G: SearchGraph>(graph: &G, start: G::Node) -> Vec<G::Node> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    let mut stack = vec![start];
+    let mut successors = Vec::new();
+    while let Some(node) = stack.pop() {
+        if !visited.insert(node) {
+            continue;
+        }
+        order.push(node);
+        successors.clear();
+        graph.successors(node, &mut successors);
+        for &(next, _) in successors.iter().rev() {
+            if !visited.contains(&next) {
+                stack.push(next);
+            }
+        }
+    }
+    order
+}
+
+/// Shortest path tree from a start node.
+#[derive(Clone, Debug)]
+pub struct ShortestPaths<N: Copy + Eq + std::hash::Hash> {
+    pub start: N,
+    /// Cost to reach each reachable node and its predecessor on the way.
+    pub nodes: HashMap<N, (f32, Option<N>)>,
+}
+
+impl<N: Copy + Eq + std::hash::Hash> ShortestPaths<N> {
+    pub fn cost(&self, node: N) -> Option<f32> {
+        self.nodes.get(&node).map(|(cost, _)| *cost)
+    }
+
+    pub fn path_to(&self, node: N) -> Option<Path<N>> {
+        let cost = self.cost(node)?;
+        let mut nodes = vec![node];
+        let mut current = node;
+        while let Some((_, Some(previous))) = self.nodes.get(&current) {
+            nodes.push(*previous);
+            current = *previous;
+        }
+        nodes.reverse();
+        Some(Path { nodes, cost })
+    }
+}
+
+/// Dijkstra from the start over the whole reachable graph, the edge
+/// costs must not be negative.
+pub fn dijkstra<G: SearchGraph>(graph: &G, start: G::Node) -> ShortestPaths<G::Node> {
+    let mut nodes: HashMap<G::Node, (f32, Option<G::Node>)> = HashMap::new();
+    let mut closed = HashSet::new();
+    let mut open = BinaryHeap::new();
+    let mut successors = Vec::new();
+    let mut order = 0;
+    nodes.insert(start, (0_f32, None));
+    open.push(Open {
+        priority: 0_f32,
+        order,
+        node: start,
+    });
+    while let Some(Open { node, priority, .. }) = open.pop() {
+        if !closed.insert(node) {
+            continue;
+        }
+        successors.clear();
+        graph.successors(node, &mut successors);
+        for &(next, step) in &successors {
+            let cost = priority + step;
+            if nodes.get(&next).map(|(c, _)| cost < *c).unwrap_or(true) {
+                nodes.insert(next, (cost, Some(node)));
+                order += 1;
+                open.push(Open {
+                    priority: cost,
+                    order,
+                    node: next,
+                });
+            }
+        }
+    }
+    ShortestPaths { start, nodes }
+}
+
+/// A* from the start to the goal, `heuristic` estimates the cost left
+/// from a node to the goal and must not overestimate it for the path
+/// to be the shortest.
+pub fn astar<G: SearchGraph>(
+    graph: &G,
+    start: G::Node,
+    goal: G::Node,
+    mut heuristic: impl FnMut(G::Node) -> f32,
+) -> Option<Path<G::Node>> {
+    let mut nodes: HashMap<G::Node, (f32, Option<G::Node>)> = HashMap::new();
+    let mut closed = HashSet::new();
+    let mut open = BinaryHeap::new();
+    let mut successors = Vec::new();
+    let mut order = 0;
+    nodes.insert(start, (0_f32, None));
+    open.push(Open {
+        priority: heuristic(start),
+        order,
+        node: start,
+    });
+    while let Some(Open { node, .. }) = open.pop() {
+        if node == goal {
+            let paths = ShortestPaths { start, nodes };
+            return paths.path_to(goal);
+        }
+        if !closed.insert(node) {
+            continue;
+        }
+        let cost = nodes[&node].0;
+        successors.clear();
+        graph.successors(node, &mut successors);
+        for &(next, step) in &successors {
+            let next_cost = cost + step;
+            if nodes
+                .get(&next)
+                .map(|(c, _)| next_cost < *c)
+                .unwrap_or(true)
+            {
+                nodes.insert(next, (next_cost, Some(node)));
+                order += 1;
+                open.push(Open {
+                    priority: next_cost + heuristic(next),
+                    order,
+                    node: next,
+                });
+            }
+        }
+    }
+    None
+}
@@ -1,5 +1,7 @@
+pub mod graph;
 pub mod log;
 pub mod math;
+pub mod procgen;
 pub mod render;
 pub mod ressources;
 pub mod utils;
@@ -0,0 +1,111 @@
+use super::{Curve, CurvePoint};
+
+/// Table of the cumulated length along a curve, used to go from a distance to a parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArcLengthTable {
+    params: Vec<f32>,
+    lengths: Vec<f32>,
+}
+
+impl ArcLengthTable {
+    /// Approximate the curve with `samples` segments evenly spaced in parameter.
+    pub fn new<P: CurvePoint, C: Curve<P>>(curve: &C, samples: usize) -> Self {
+        let samples = samples.max(1);
+        let (start, end) = curve.domain();
+        let mut params = Vec::with_capacity(samples + 1);
+        let mut lengths = Vec::with_capacity(samples + 1);
+        let mut prev = curve.eval(start);
+        let mut total = 0_f32;
+        params.push(start);
+        lengths.push(0_f32);
+        for i in 1..=samples {
+            let t = start + (end - start) * i as f32 / samples as f32;
+            let point = curve.eval(t);
+            total += (point - prev).length();
+            params.push(t);
+            lengths.push(total);
+            prev = point;
+        }
+        Self { params, lengths }
+    }
+
+    /// Total length of the curve.
+    pub fn length(&self) -> f32 {
+        *self.lengths.last().unwrap()
+    }
+
+    /// Distance along the curve from its start to the parameter `t`.
+    pub fn length_at(&self, t: f32) -> f32 {
+        let i = match self.params.binary_search_by(|p| p.total_cmp(&t)) {
+            Ok(i) => return self.lengths[i],
+            Err(i) => i,
+        };
+        if i == 0 {
+            return 0_f32;
+        }
+        if i >= self.params.len() {
+            return self.length();
+        }
+        let k = (t - self.params[i - 1]) / (self.params[i] - self.params[i - 1]);
+        self.lengths[i - 1] + (self.lengths[i] - self.lengths[i - 1]) * k
+    }
+
+    /// Parameter of the point at the distance `s` from the start of the curve.
+    pub fn t_at_length(&self, s: f32) -> f32 {
+        let i = match self.lengths.binary_search_by(|l| l.total_cmp(&s)) {
+            Ok(i) => return self.params[i],
+            Err(i) => i,
+        };
+        if i == 0 {
+            return self.params[0];
+        }
+        if i >= self.lengths.len() {
+            return *self.params.last().unwrap();
+        }
+        let span = self.lengths[i] - self.lengths[i - 1];
+        let k = if span > 0_f32 {
+            (s - self.lengths[i - 1]) / span
+        } else {
+            0_f32
+        };
+        self.params[i - 1] + (self.params[i] - self.params[i - 1]) * k
+    }
+
+    /// Reparameterisation by arc length: `u` in `[0, 1]` is the fraction of the total length.
+    pub fn t_at_fraction(&self, u: f32) -> f32 {
+        self.t_at_length(u * self.length())
+    }
+
+    /// Evaluate the curve at the fraction `u` of its length.
+    pub fn eval<P: CurvePoint, C: Curve<P>>(&self, curve: &C, u: f32) -> P {
+        curve.eval(self.t_at_fraction(u))
+    }
+
+    /// `count` points evenly spaced along the curve, including both ends.
+    pub fn sample_evenly<P: CurvePoint, C: Curve<P>>(&self, curve: &C, count: usize) -> Vec<P> {
+        let last = (count.max(2) - 1) as f32;
+        (0..count)
+            .map(|i| self.eval(curve, i as f32 / last))
+            .collect()
+    }
+
+    /// Points every `spacing` units along the curve, starting at its beginning.
+    /// Empty if `spacing` isn't positive.
+    pub fn sample_spacing<P: CurvePoint, C: Curve<P>>(&self, curve: &C, spacing: f32) -> Vec<P> {
+        if spacing.is_nan() || spacing <= 0_f32 {
+            return Vec::new();
+        }
+        let count = (self.length() / spacing).floor() as usize + 1;
+        (0..count)
+            .map(|i| curve.eval(self.t_at_length(i as f32 * spacing)))
+            .collect()
+    }
+
+    /// Parameters of `count` points evenly spaced along the curve.
+    pub fn even_params(&self, count: usize) -> Vec<f32> {
+        let last = (count.max(2) - 1) as f32;
+        (0..count)
+            .map(|i| self.t_at_fraction(i as f32 / last))
+            .collect()
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Curve, CurvePoint};
+use crate::math::fct::lerp;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct QuadraticBezier<P> {
+    pub p0: P,
+    pub p1: P,
+    pub p2: P,
+}
+
+impl<P: CurvePoint> QuadraticBezier<P> {
+    pub fn new(p0: P, p1: P, p2: P) -> Self {
+        Self { p0, p1, p2 }
+    }
+
+    /// Split at `t` with de Casteljau's algorithm.
+    pub fn split(&self, t: f32) -> (Self, Self) {
+        let a = lerp(self.p0, self.p1, t);
+        let b = lerp(self.p1, self.p2, t);
+        let m = lerp(a, b, t);
+        (Self::new(self.p0, a, m), Self::new(m, b, self.p2))
+    }
+
+    /// Same curve as a cubic bezier.
+    pub fn elevate(&self) -> CubicBezier<P> {
+        CubicBezier::new(
+            self.p0,
+            self.p0 + (self.p1 - self.p0) * (2_f32 / 3_f32),
+            self.p2 + (self.p1 - self.p2) * (2_f32 / 3_f32),
+            self.p2,
+        )
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for QuadraticBezier<P> {
+    fn eval(&self, t: f32) -> P {
+        let u = 1_f32 - t;
+        self.p0 * (u * u) + self.p1 * (2_f32 * u * t) + self.p2 * (t * t)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        (self.p1 - self.p0) * (2_f32 * (1_f32 - t)) + (self.p2 - self.p1) * (2_f32 * t)
+    }
+
+    fn second_derivative(&self, _t: f32) -> P {
+        (self.p2 - self.p1 * 2_f32 + self.p0) * 2_f32
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CubicBezier<P> {
+    pub p0: P,
+    pub p1: P,
+    pub p2: P,
+    pub p3: P,
+}
+
+impl<P: CurvePoint> CubicBezier<P> {
+    pub fn new(p0: P, p1: P, p2: P, p3: P) -> Self {
+        Self { p0, p1, p2, p3 }
+    }
+
+    /// Split at `t` with de Casteljau's algorithm.
+    pub fn split(&self, t: f32) -> (Self, Self) {
+        let a = lerp(self.p0, self.p1, t);
+        let b = lerp(self.p1, self.p2, t);
+        let c = lerp(self.p2, self.p3, t);
+        let d = lerp(a, b, t);
+        let e = lerp(b, c, t);
+        let m = lerp(d, e, t);
+        (Self::new(self.p0, a, d, m), Self::new(m, e, c, self.p3))
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for CubicBezier<P> {
+    fn eval(&self, t: f32) -> P {
+        let u = 1_f32 - t;
+        self.p0 * (u * u * u)
+            + self.p1 * (3_f32 * u * u * t)
+            + self.p2 * (3_f32 * u * t * t)
+            + self.p3 * (t * t * t)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let u = 1_f32 - t;
+        (self.p1 - self.p0) * (3_f32 * u * u)
+            + (self.p2 - self.p1) * (6_f32 * u * t)
+            + (self.p3 - self.p2) * (3_f32 * t * t)
+    }
+
+    fn second_derivative(&self, t: f32) -> P {
+        let u = 1_f32 - t;
+        (self.p2 - self.p1 * 2_f32 + self.p0) * (6_f32 * u)
+            + (self.p3 - self.p2 * 2_f32 + self.p1) * (6_f32 * t)
+    }
+}
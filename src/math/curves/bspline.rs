@@ -0,0 +1,117 @@
+use super::{Curve, CurvePoint};
+
+/// B-spline of arbitrary degree evaluated with de Boor's algorithm.
+/// The parameter goes from `knots[degree]` to `knots[control.len()]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BSpline<P> {
+    pub control: Vec<P>,
+    pub knots: Vec<f32>,
+    pub degree: usize,
+}
+
+impl<P: CurvePoint> BSpline<P> {
+    /// B-spline with the given knot vector, which must hold `control.len() + degree + 1`
+    /// non decreasing values.
+    pub fn new(control: Vec<P>, knots: Vec<f32>, degree: usize) -> Self {
+        assert!(control.len() > degree, "A B-spline needs more control points than its degree!");
+        assert_eq!(knots.len(), control.len() + degree + 1);
+        Self {
+            control,
+            knots,
+            degree,
+        }
+    }
+
+    /// Uniform B-spline, the curve doesn't reach the first and last control points.
+    pub fn uniform(control: Vec<P>, degree: usize) -> Self {
+        let knots = (0..control.len() + degree + 1).map(|i| i as f32).collect();
+        Self::new(control, knots, degree)
+    }
+
+    /// Clamped uniform B-spline starting and ending on the first and last control points,
+    /// with the parameter in `[0, 1]`.
+    pub fn clamped(control: Vec<P>, degree: usize) -> Self {
+        let n = control.len();
+        let inner = n - degree;
+        let knots = (0..n + degree + 1)
+            .map(|i| {
+                if i <= degree {
+                    0_f32
+                } else if i >= n {
+                    1_f32
+                } else {
+                    (i - degree) as f32 / inner as f32
+                }
+            })
+            .collect();
+        Self::new(control, knots, degree)
+    }
+
+    /// Index of the knot span containing `t`.
+    fn span(&self, t: f32) -> usize {
+        let n = self.control.len();
+        if t >= self.knots[n] {
+            return n - 1;
+        }
+        let mut span = self.degree;
+        while span < n - 1 && self.knots[span + 1] <= t {
+            span += 1;
+        }
+        span
+    }
+
+    /// Control points of the derivative curve, which is a B-spline of degree - 1.
+    pub fn derivative_spline(&self) -> BSpline<P> {
+        if self.degree == 0 {
+            return BSpline::new(vec![P::zero()], vec![self.knots[0], self.knots[1]], 0);
+        }
+        let p = self.degree;
+        let control = (0..self.control.len() - 1)
+            .map(|i| {
+                let dk = self.knots[i + p + 1] - self.knots[i + 1];
+                if dk > 0_f32 {
+                    (self.control[i + 1] - self.control[i]) * (p as f32 / dk)
+                } else {
+                    P::zero()
+                }
+            })
+            .collect();
+        let knots = self.knots[1..self.knots.len() - 1].to_vec();
+        BSpline::new(control, knots, p - 1)
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for BSpline<P> {
+    fn eval(&self, t: f32) -> P {
+        let (start, end) = self.domain();
+        let t = t.clamp(start, end);
+        let p = self.degree;
+        let k = self.span(t);
+        let mut d: Vec<P> = (0..=p).map(|j| self.control[j + k - p]).collect();
+        for r in 1..=p {
+            for j in (r..=p).rev() {
+                let i = j + k - p;
+                let denom = self.knots[i + p + 1 - r] - self.knots[i];
+                let alpha = if denom > 0_f32 {
+                    (t - self.knots[i]) / denom
+                } else {
+                    0_f32
+                };
+                d[j] = d[j - 1] * (1_f32 - alpha) + d[j] * alpha;
+            }
+        }
+        d[p]
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        self.derivative_spline().eval(t)
+    }
+
+    fn second_derivative(&self, t: f32) -> P {
+        self.derivative_spline().derivative_spline().eval(t)
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        (self.knots[self.degree], self.knots[self.control.len()])
+    }
+}
@@ -0,0 +1,96 @@
+use super::hermite::{derivative_segment, eval_segment, second_derivative_segment};
+use super::{segment_param, Curve, CurvePoint};
+
+/// Catmull-Rom spline going through `points[1..n - 1]`, the first and last points only
+/// shape the ends. `alpha` sets the knot parameterisation: 0 uniform, 0.5 centripetal
+/// (no cusps nor self intersections), 1 chordal.
+/// The parameter goes from 0 to `points.len() - 3`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatmullRom<P> {
+    pub points: Vec<P>,
+    pub alpha: f32,
+}
+
+impl<P: CurvePoint> CatmullRom<P> {
+    pub fn new(points: Vec<P>, alpha: f32) -> Self {
+        assert!(points.len() >= 4, "A catmull rom spline needs at least 4 points!");
+        Self { points, alpha }
+    }
+
+    pub fn centripetal(points: Vec<P>) -> Self {
+        Self::new(points, 0.5)
+    }
+
+    /// Centripetal spline going through all the points, the end points are extrapolated.
+    pub fn through(points: &[P]) -> Self {
+        assert!(points.len() >= 2, "A catmull rom spline needs at least 2 points!");
+        let n = points.len();
+        let mut all = Vec::with_capacity(n + 2);
+        all.push(points[0] * 2_f32 - points[1]);
+        all.extend_from_slice(points);
+        all.push(points[n - 1] * 2_f32 - points[n - 2]);
+        Self::centripetal(all)
+    }
+
+    /// Closed centripetal spline looping through all the points.
+    pub fn closed(points: &[P]) -> Self {
+        assert!(points.len() >= 3, "A closed catmull rom spline needs at least 3 points!");
+        let n = points.len();
+        let mut all = Vec::with_capacity(n + 3);
+        all.push(points[n - 1]);
+        all.extend_from_slice(points);
+        all.push(points[0]);
+        all.push(points[1]);
+        Self::centripetal(all)
+    }
+
+    pub fn segments(&self) -> usize {
+        self.points.len() - 3
+    }
+
+    /// Hermite form `(p1, m1, p2, m2)` of a segment, with the non uniform tangents
+    /// rescaled to the unit parameter range of the segment.
+    fn hermite(&self, i: usize) -> (P, P, P, P) {
+        let (p0, p1, p2, p3) = (
+            self.points[i],
+            self.points[i + 1],
+            self.points[i + 2],
+            self.points[i + 3],
+        );
+        let knot = |a: P, b: P| (b - a).length().powf(self.alpha).max(1e-4);
+        let d01 = knot(p0, p1);
+        let d12 = knot(p1, p2);
+        let d23 = knot(p2, p3);
+        let m1 = ((p1 - p0) * (1_f32 / d01) - (p2 - p0) * (1_f32 / (d01 + d12))
+            + (p2 - p1) * (1_f32 / d12))
+            * d12;
+        let m2 = ((p2 - p1) * (1_f32 / d12) - (p3 - p1) * (1_f32 / (d12 + d23))
+            + (p3 - p2) * (1_f32 / d23))
+            * d12;
+        (p1, m1, p2, m2)
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for CatmullRom<P> {
+    fn eval(&self, t: f32) -> P {
+        let (i, u) = segment_param(t, self.segments());
+        let (p1, m1, p2, m2) = self.hermite(i);
+        eval_segment(p1, m1, p2, m2, u)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let (i, u) = segment_param(t, self.segments());
+        let (p1, m1, p2, m2) = self.hermite(i);
+        derivative_segment(p1, m1, p2, m2, u)
+    }
+
+    fn second_derivative(&self, t: f32) -> P {
+        let (i, u) = segment_param(t, self.segments());
+        let (p1, m1, p2, m2) = self.hermite(i);
+        second_derivative_segment(p1, m1, p2, m2, u)
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        (0_f32, self.segments() as f32)
+    }
+}
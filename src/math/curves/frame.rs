@@ -0,0 +1,107 @@
+use super::super::{Quat, Vec3D};
+use super::Curve;
+
+/// Orthonormal frame moving along a curve.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frame {
+    pub position: Vec3D,
+    pub tangent: Vec3D,
+    pub normal: Vec3D,
+    pub binormal: Vec3D,
+}
+
+impl Frame {
+    /// Rotation taking the x, y and z axes to the normal, binormal and tangent.
+    pub fn rotation(&self) -> Quat {
+        Quat::from_basis(self.normal, self.binormal, self.tangent)
+    }
+
+    /// Point of the plane orthogonal to the tangent, for sweeping a profile along the curve.
+    pub fn to_world(&self, x: f32, y: f32) -> Vec3D {
+        self.position + self.normal * x + self.binormal * y
+    }
+}
+
+/// Frenet frames at the parameters `ts`. The normal follows the curvature, so the frames
+/// flip at inflection points and are undefined on straight parts, where any normal is used.
+pub fn frenet_frames<C: Curve<Vec3D>>(curve: &C, ts: &[f32]) -> Vec<Frame> {
+    ts.iter()
+        .map(|&t| {
+            let tangent = curve.derivative(t).normalized();
+            let binormal = tangent.cross(&curve.second_derivative(t));
+            let binormal = if binormal.sq_norm() > 1e-10 {
+                binormal.normalized()
+            } else {
+                tangent.cross(&any_orthogonal(tangent)).normalized()
+            };
+            Frame {
+                position: curve.eval(t),
+                tangent,
+                normal: binormal.cross(&tangent),
+                binormal,
+            }
+        })
+        .collect()
+}
+
+/// Rotation minimising frames at the parameters `ts`, computed with the double reflection
+/// method (Wang et al. 2008). The first normal is `initial_normal` projected on the plane
+/// orthogonal to the tangent, or an arbitrary one.
+pub fn rotation_minimising_frames<C: Curve<Vec3D>>(
+    curve: &C,
+    ts: &[f32],
+    initial_normal: Option<Vec3D>,
+) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = Vec::with_capacity(ts.len());
+    for &t in ts.iter() {
+        let position = curve.eval(t);
+        let tangent = curve.derivative(t).normalized();
+        let normal = match frames.last() {
+            None => {
+                let n = initial_normal.unwrap_or_else(|| any_orthogonal(tangent));
+                let n = n - tangent * n.dot(&tangent);
+                if n.sq_norm() > 1e-10 {
+                    n.normalized()
+                } else {
+                    any_orthogonal(tangent)
+                }
+            }
+            Some(prev) => {
+                // reflect the previous frame on the bisector plane of the two positions
+                let v1 = position - prev.position;
+                let c1 = v1.dot(&v1);
+                if c1 < 1e-12 {
+                    prev.normal
+                } else {
+                    let r_l = prev.normal - v1 * (2_f32 / c1 * v1.dot(&prev.normal));
+                    let t_l = prev.tangent - v1 * (2_f32 / c1 * v1.dot(&prev.tangent));
+                    // then on the plane that brings the reflected tangent on the new one
+                    let v2 = tangent - t_l;
+                    let c2 = v2.dot(&v2);
+                    if c2 < 1e-12 {
+                        r_l
+                    } else {
+                        r_l - v2 * (2_f32 / c2 * v2.dot(&r_l))
+                    }
+                }
+            }
+        };
+        frames.push(Frame {
+            position,
+            tangent,
+            normal,
+            binormal: tangent.cross(&normal),
+        });
+    }
+    frames
+}
+
+/// Some unit vector orthogonal to `v`.
+fn any_orthogonal(v: Vec3D) -> Vec3D {
+    let other = if v.x.abs() < 0.9 {
+        Vec3D::new(1_f32, 0_f32, 0_f32)
+    } else {
+        Vec3D::new(0_f32, 1_f32, 0_f32)
+    };
+    v.cross(&other).normalized()
+}
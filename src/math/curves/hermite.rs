@@ -0,0 +1,80 @@
+use super::{segment_param, Curve, CurvePoint};
+
+/// Piecewise cubic Hermite curve through `points` with the given `tangents`.
+/// The parameter goes from 0 to `points.len() - 1`, each segment covering a unit range.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hermite<P> {
+    pub points: Vec<P>,
+    pub tangents: Vec<P>,
+}
+
+impl<P: CurvePoint> Hermite<P> {
+    pub fn new(points: Vec<P>, tangents: Vec<P>) -> Self {
+        assert!(points.len() >= 2, "An hermite curve needs at least 2 points!");
+        assert_eq!(points.len(), tangents.len());
+        Self { points, tangents }
+    }
+
+    /// Hermite curve with Catmull-Rom like tangents (cardinal spline of the given tension).
+    pub fn cardinal(points: Vec<P>, tension: f32) -> Self {
+        let n = points.len();
+        let tangents = (0..n)
+            .map(|i| {
+                let prev = points[i.saturating_sub(1)];
+                let next = points[(i + 1).min(n - 1)];
+                (next - prev) * ((1_f32 - tension) * 0.5)
+            })
+            .collect();
+        Self::new(points, tangents)
+    }
+
+    pub fn segments(&self) -> usize {
+        self.points.len() - 1
+    }
+}
+
+impl<P: CurvePoint> Curve<P> for Hermite<P> {
+    fn eval(&self, t: f32) -> P {
+        let (i, u) = segment_param(t, self.segments());
+        eval_segment(self.points[i], self.tangents[i], self.points[i + 1], self.tangents[i + 1], u)
+    }
+
+    fn derivative(&self, t: f32) -> P {
+        let (i, u) = segment_param(t, self.segments());
+        derivative_segment(self.points[i], self.tangents[i], self.points[i + 1], self.tangents[i + 1], u)
+    }
+
+    fn second_derivative(&self, t: f32) -> P {
+        let (i, u) = segment_param(t, self.segments());
+        second_derivative_segment(self.points[i], self.tangents[i], self.points[i + 1], self.tangents[i + 1], u)
+    }
+
+    fn domain(&self) -> (f32, f32) {
+        (0_f32, self.segments() as f32)
+    }
+}
+
+/// Cubic Hermite segment from `p0` to `p1` with tangents `m0` and `m1`, `t` in `[0, 1]`.
+pub(crate) fn eval_segment<P: CurvePoint>(p0: P, m0: P, p1: P, m1: P, t: f32) -> P {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    p0 * (2_f32 * t3 - 3_f32 * t2 + 1_f32)
+        + m0 * (t3 - 2_f32 * t2 + t)
+        + p1 * (-2_f32 * t3 + 3_f32 * t2)
+        + m1 * (t3 - t2)
+}
+
+pub(crate) fn derivative_segment<P: CurvePoint>(p0: P, m0: P, p1: P, m1: P, t: f32) -> P {
+    let t2 = t * t;
+    p0 * (6_f32 * t2 - 6_f32 * t)
+        + m0 * (3_f32 * t2 - 4_f32 * t + 1_f32)
+        + p1 * (-6_f32 * t2 + 6_f32 * t)
+        + m1 * (3_f32 * t2 - 2_f32 * t)
+}
+
+pub(crate) fn second_derivative_segment<P: CurvePoint>(p0: P, m0: P, p1: P, m1: P, t: f32) -> P {
+    p0 * (12_f32 * t - 6_f32)
+        + m0 * (6_f32 * t - 4_f32)
+        + p1 * (-12_f32 * t + 6_f32)
+        + m1 * (6_f32 * t - 2_f32)
+}
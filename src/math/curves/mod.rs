@@ -0,0 +1,84 @@
+pub mod arc_length;
+pub mod bezier;
+pub mod bspline;
+pub mod catmull_rom;
+pub mod frame;
+pub mod hermite;
+
+pub use arc_length::ArcLengthTable;
+pub use bezier::{CubicBezier, QuadraticBezier};
+pub use bspline::BSpline;
+pub use catmull_rom::CatmullRom;
+pub use frame::{frenet_frames, rotation_minimising_frames, Frame};
+pub use hermite::Hermite;
+
+use super::{Vec2D, Vec3D};
+use std::ops::{Add, Mul, Sub};
+
+/// Point types the curves can be built on.
+pub trait CurvePoint:
+    Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+    fn zero() -> Self;
+    fn dot_with(&self, other: &Self) -> f32;
+
+    fn length(&self) -> f32 {
+        self.dot_with(self).sqrt()
+    }
+}
+
+impl CurvePoint for Vec2D {
+    fn zero() -> Self {
+        Vec2D::null()
+    }
+    fn dot_with(&self, other: &Self) -> f32 {
+        self.dot(other)
+    }
+}
+
+impl CurvePoint for Vec3D {
+    fn zero() -> Self {
+        Vec3D::null()
+    }
+    fn dot_with(&self, other: &Self) -> f32 {
+        self.dot(other)
+    }
+}
+
+/// Parametric curve over `domain()`.
+pub trait Curve<P: CurvePoint> {
+    fn eval(&self, t: f32) -> P;
+
+    /// First derivative with respect to `t`.
+    fn derivative(&self, t: f32) -> P;
+
+    /// Second derivative with respect to `t`, by default a finite difference of the derivative.
+    fn second_derivative(&self, t: f32) -> P {
+        let (start, end) = self.domain();
+        let h = (end - start) * 1e-3;
+        let t0 = (t - h).max(start);
+        let t1 = (t + h).min(end);
+        (self.derivative(t1) - self.derivative(t0)) * (1_f32 / (t1 - t0))
+    }
+
+    /// Range of the parameter.
+    fn domain(&self) -> (f32, f32) {
+        (0_f32, 1_f32)
+    }
+
+    /// `count` points evenly spaced in parameter (not in distance, see `ArcLengthTable`).
+    fn sample(&self, count: usize) -> Vec<P> {
+        let (start, end) = self.domain();
+        let last = (count.max(2) - 1) as f32;
+        (0..count)
+            .map(|i| self.eval(start + (end - start) * i as f32 / last))
+            .collect()
+    }
+}
+
+/// Split a global parameter into a segment index and the local parameter in `[0, 1]`.
+pub(crate) fn segment_param(t: f32, segments: usize) -> (usize, f32) {
+    let t = t.clamp(0_f32, segments as f32);
+    let index = (t.floor() as usize).min(segments - 1);
+    (index, t - index as f32)
+}
@@ -0,0 +1,228 @@
+// Robert Penner's easing functions. They all take and return a value in [0, 1]
+// (the back and elastic ones overshoot in between).
+use std::f32::consts::PI;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    SineIn,
+    SineOut,
+    SineInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0f32, 1f32);
+        match self {
+            Easing::Linear => t,
+            Easing::QuadIn => quad_in(t),
+            Easing::QuadOut => quad_out(t),
+            Easing::QuadInOut => quad_in_out(t),
+            Easing::CubicIn => cubic_in(t),
+            Easing::CubicOut => cubic_out(t),
+            Easing::CubicInOut => cubic_in_out(t),
+            Easing::QuartIn => quart_in(t),
+            Easing::QuartOut => quart_out(t),
+            Easing::QuartInOut => quart_in_out(t),
+            Easing::QuintIn => quint_in(t),
+            Easing::QuintOut => quint_out(t),
+            Easing::QuintInOut => quint_in_out(t),
+            Easing::SineIn => sine_in(t),
+            Easing::SineOut => sine_out(t),
+            Easing::SineInOut => sine_in_out(t),
+            Easing::ExpoIn => expo_in(t),
+            Easing::ExpoOut => expo_out(t),
+            Easing::ExpoInOut => expo_in_out(t),
+            Easing::CircIn => circ_in(t),
+            Easing::CircOut => circ_out(t),
+            Easing::CircInOut => circ_in_out(t),
+            Easing::BackIn => back_in(t),
+            Easing::BackOut => back_out(t),
+            Easing::BackInOut => back_in_out(t),
+            Easing::ElasticIn => elastic_in(t),
+            Easing::ElasticOut => elastic_out(t),
+            Easing::ElasticInOut => elastic_in_out(t),
+            Easing::BounceIn => bounce_in(t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => bounce_in_out(t),
+        }
+    }
+}
+
+const BACK_C1: f32 = 1.70158;
+const BACK_C2: f32 = BACK_C1 * 1.525;
+const BACK_C3: f32 = BACK_C1 + 1f32;
+const ELASTIC_C4: f32 = 2f32 * PI / 3f32;
+const ELASTIC_C5: f32 = 2f32 * PI / 4.5;
+
+/// Build the in-out version from an "in" easing.
+fn in_out(t: f32, ease_in: fn(f32) -> f32) -> f32 {
+    if t < 0.5 {
+        ease_in(2f32 * t) * 0.5
+    } else {
+        1f32 - ease_in(2f32 - 2f32 * t) * 0.5
+    }
+}
+
+pub fn quad_in(t: f32) -> f32 {
+    t * t
+}
+pub fn quad_out(t: f32) -> f32 {
+    1f32 - quad_in(1f32 - t)
+}
+pub fn quad_in_out(t: f32) -> f32 {
+    in_out(t, quad_in)
+}
+
+pub fn cubic_in(t: f32) -> f32 {
+    t * t * t
+}
+pub fn cubic_out(t: f32) -> f32 {
+    1f32 - cubic_in(1f32 - t)
+}
+pub fn cubic_in_out(t: f32) -> f32 {
+    in_out(t, cubic_in)
+}
+
+pub fn quart_in(t: f32) -> f32 {
+    t * t * t * t
+}
+pub fn quart_out(t: f32) -> f32 {
+    1f32 - quart_in(1f32 - t)
+}
+pub fn quart_in_out(t: f32) -> f32 {
+    in_out(t, quart_in)
+}
+
+pub fn quint_in(t: f32) -> f32 {
+    t * t * t * t * t
+}
+pub fn quint_out(t: f32) -> f32 {
+    1f32 - quint_in(1f32 - t)
+}
+pub fn quint_in_out(t: f32) -> f32 {
+    in_out(t, quint_in)
+}
+
+pub fn sine_in(t: f32) -> f32 {
+    1f32 - (t * PI * 0.5).cos()
+}
+pub fn sine_out(t: f32) -> f32 {
+    (t * PI * 0.5).sin()
+}
+pub fn sine_in_out(t: f32) -> f32 {
+    -((PI * t).cos() - 1f32) * 0.5
+}
+
+pub fn expo_in(t: f32) -> f32 {
+    if t <= 0f32 {
+        0f32
+    } else {
+        2f32.powf(10f32 * t - 10f32)
+    }
+}
+pub fn expo_out(t: f32) -> f32 {
+    1f32 - expo_in(1f32 - t)
+}
+pub fn expo_in_out(t: f32) -> f32 {
+    in_out(t, expo_in)
+}
+
+pub fn circ_in(t: f32) -> f32 {
+    1f32 - (1f32 - t * t).sqrt()
+}
+pub fn circ_out(t: f32) -> f32 {
+    1f32 - circ_in(1f32 - t)
+}
+pub fn circ_in_out(t: f32) -> f32 {
+    in_out(t, circ_in)
+}
+
+pub fn back_in(t: f32) -> f32 {
+    BACK_C3 * t * t * t - BACK_C1 * t * t
+}
+pub fn back_out(t: f32) -> f32 {
+    1f32 - back_in(1f32 - t)
+}
+pub fn back_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        (2f32 * t).powi(2) * ((BACK_C2 + 1f32) * 2f32 * t - BACK_C2) * 0.5
+    } else {
+        ((2f32 * t - 2f32).powi(2) * ((BACK_C2 + 1f32) * (t * 2f32 - 2f32) + BACK_C2) + 2f32) * 0.5
+    }
+}
+
+pub fn elastic_in(t: f32) -> f32 {
+    if t <= 0f32 || t >= 1f32 {
+        return t;
+    }
+    -(2f32.powf(10f32 * t - 10f32)) * ((t * 10f32 - 10.75) * ELASTIC_C4).sin()
+}
+pub fn elastic_out(t: f32) -> f32 {
+    if t <= 0f32 || t >= 1f32 {
+        return t;
+    }
+    2f32.powf(-10f32 * t) * ((t * 10f32 - 0.75) * ELASTIC_C4).sin() + 1f32
+}
+pub fn elastic_in_out(t: f32) -> f32 {
+    if t <= 0f32 || t >= 1f32 {
+        return t;
+    }
+    let s = ((20f32 * t - 11.125) * ELASTIC_C5).sin();
+    if t < 0.5 {
+        -(2f32.powf(20f32 * t - 10f32) * s) * 0.5
+    } else {
+        2f32.powf(-20f32 * t + 10f32) * s * 0.5 + 1f32
+    }
+}
+
+pub fn bounce_out(t: f32) -> f32 {
+    const N1: f32 = 7.5625;
+    const D1: f32 = 2.75;
+    if t < 1f32 / D1 {
+        N1 * t * t
+    } else if t < 2f32 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+pub fn bounce_in(t: f32) -> f32 {
+    1f32 - bounce_out(1f32 - t)
+}
+pub fn bounce_in_out(t: f32) -> f32 {
+    in_out(t, bounce_in)
+}
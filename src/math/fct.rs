@@ -1,3 +1,5 @@
+use std::ops::{Add, Mul, Sub};
+
 pub fn fast_floor(x: f32) -> i32 {
     let xi = x as i32;
     if x < xi as f32 {
@@ -27,3 +29,29 @@ pub fn fast_isqrt(nb: f32) -> f32 {
     y = y * (1.5f32 - (x2 * y * y));
     return y;
 }
+
+/// Linear interpolation between `a` and `b`, floats, vectors or anything
+/// which can be scaled.
+pub fn lerp<T>(a: T, b: T, t: f32) -> T
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T>,
+{
+    a + (b - a) * t
+}
+
+/// Inverse of `lerp`: position of `val` between `a` and `b`.
+pub fn inverse_lerp(a: f32, b: f32, val: f32) -> f32 {
+    (val - a) / (b - a)
+}
+
+/// Hermite interpolation of `x` between the two edges, clamped to `[0, 1]`.
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = inverse_lerp(edge0, edge1, x).clamp(0f32, 1f32);
+    t * t * (3f32 - 2f32 * t)
+}
+
+/// Ken Perlin's smootherstep, with null first and second derivatives at the edges.
+pub fn smootherstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = inverse_lerp(edge0, edge1, x).clamp(0f32, 1f32);
+    t * t * t * (t * (t * 6f32 - 15f32) + 10f32)
+}
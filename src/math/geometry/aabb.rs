@@ -0,0 +1,238 @@
+use super::super::{Vec2D, Vec3D};
+
+/// 2D axis aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb2 {
+    pub min: Vec2D,
+    pub max: Vec2D,
+}
+
+impl Aabb2 {
+    pub fn new(min: Vec2D, max: Vec2D) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Vec2D, half_extents: Vec2D) -> Self {
+        Self {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+
+    /// Smallest box containing all the points, `None` if there is no point.
+    pub fn from_points(points: &[Vec2D]) -> Option<Self> {
+        let first = *points.first()?;
+        Some(points.iter().fold(Self::new(first, first), |acc, p| {
+            acc.grown(*p)
+        }))
+    }
+
+    pub fn center(&self) -> Vec2D {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec2D {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn size(&self) -> Vec2D {
+        self.max - self.min
+    }
+
+    pub fn area(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y
+    }
+
+    pub fn perimeter(&self) -> f32 {
+        let size = self.size();
+        2_f32 * (size.x + size.y)
+    }
+
+    pub fn contains(&self, point: Vec2D) -> bool {
+        point.x >= self.min.x && point.x <= self.max.x && point.y >= self.min.y && point.y <= self.max.y
+    }
+
+    pub fn contains_aabb(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    /// Smallest box containing both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(&other.min),
+            max: self.max.max(&other.max),
+        }
+    }
+
+    /// Smallest box containing this box and the point.
+    pub fn grown(&self, point: Vec2D) -> Self {
+        Self {
+            min: self.min.min(&point),
+            max: self.max.max(&point),
+        }
+    }
+
+    /// Box grown by `margin` on every side.
+    pub fn expanded(&self, margin: f32) -> Self {
+        Self {
+            min: self.min + -margin,
+            max: self.max + margin,
+        }
+    }
+
+    pub fn closest_point(&self, point: Vec2D) -> Vec2D {
+        point.max(&self.min).min(&self.max)
+    }
+
+    pub fn sq_distance(&self, point: Vec2D) -> f32 {
+        (self.closest_point(point) - point).sq_norm()
+    }
+
+    pub fn distance(&self, point: Vec2D) -> f32 {
+        self.sq_distance(point).sqrt()
+    }
+}
+
+/// 3D axis aligned bounding box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Aabb3 {
+    pub min: Vec3D,
+    pub max: Vec3D,
+}
+
+impl Aabb3 {
+    pub fn new(min: Vec3D, max: Vec3D) -> Self {
+        Self { min, max }
+    }
+
+    pub fn from_center(center: Vec3D, half_extents: Vec3D) -> Self {
+        Self {
+            min: center - half_extents,
+            max: center + half_extents,
+        }
+    }
+
+    /// Smallest box containing all the points, `None` if there is no point.
+    pub fn from_points(points: &[Vec3D]) -> Option<Self> {
+        let first = *points.first()?;
+        Some(points.iter().fold(Self::new(first, first), |acc, p| {
+            acc.grown(*p)
+        }))
+    }
+
+    pub fn center(&self) -> Vec3D {
+        (self.min + self.max) * 0.5
+    }
+
+    pub fn half_extents(&self) -> Vec3D {
+        (self.max - self.min) * 0.5
+    }
+
+    pub fn size(&self) -> Vec3D {
+        self.max - self.min
+    }
+
+    pub fn volume(&self) -> f32 {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    pub fn surface_area(&self) -> f32 {
+        let size = self.size();
+        2_f32 * (size.x * size.y + size.y * size.z + size.z * size.x)
+    }
+
+    /// Index of the longest axis (0 -> x, 1 -> y, 2 -> z).
+    pub fn longest_axis(&self) -> usize {
+        let size = self.size();
+        if size.x >= size.y && size.x >= size.z {
+            0
+        } else if size.y >= size.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Get the 8 corners of the box.
+    pub fn corners(&self) -> [Vec3D; 8] {
+        let (a, b) = (self.min, self.max);
+        [
+            Vec3D::new(a.x, a.y, a.z),
+            Vec3D::new(b.x, a.y, a.z),
+            Vec3D::new(a.x, b.y, a.z),
+            Vec3D::new(b.x, b.y, a.z),
+            Vec3D::new(a.x, a.y, b.z),
+            Vec3D::new(b.x, a.y, b.z),
+            Vec3D::new(a.x, b.y, b.z),
+            Vec3D::new(b.x, b.y, b.z),
+        ]
+    }
+
+    pub fn contains(&self, point: Vec3D) -> bool {
+        point.x >= self.min.x
+            && point.x <= self.max.x
+            && point.y >= self.min.y
+            && point.y <= self.max.y
+            && point.z >= self.min.z
+            && point.z <= self.max.z
+    }
+
+    pub fn contains_aabb(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+
+    /// Smallest box containing both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.min(&other.min),
+            max: self.max.max(&other.max),
+        }
+    }
+
+    /// Smallest box containing this box and the point.
+    pub fn grown(&self, point: Vec3D) -> Self {
+        Self {
+            min: self.min.min(&point),
+            max: self.max.max(&point),
+        }
+    }
+
+    /// Box grown by `margin` on every side.
+    pub fn expanded(&self, margin: f32) -> Self {
+        let margin = Vec3D::new(margin, margin, margin);
+        Self {
+            min: self.min - margin,
+            max: self.max + margin,
+        }
+    }
+
+    pub fn closest_point(&self, point: Vec3D) -> Vec3D {
+        point.max(&self.min).min(&self.max)
+    }
+
+    pub fn sq_distance(&self, point: Vec3D) -> f32 {
+        (self.closest_point(point) - point).sq_norm()
+    }
+
+    pub fn distance(&self, point: Vec3D) -> f32 {
+        self.sq_distance(point).sqrt()
+    }
+}
@@ -0,0 +1,415 @@
+use super::super::Vec2D;
+use super::{Polygon, Winding, EPSILON};
+use std::collections::{HashMap, HashSet};
+
+/// Boolean operation between two sets of shapes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// The first set minus the second one.
+    Difference,
+    /// Parts covered by exactly one of the sets.
+    Xor,
+}
+
+/// Polygon with holes, like the input of `triangulate`. The outline is counter
+/// clockwise and the holes clockwise in the results of the boolean operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shape {
+    pub outline: Polygon,
+    pub holes: Vec<Polygon>,
+}
+
+impl Shape {
+    pub fn new(outline: Polygon, holes: Vec<Polygon>) -> Self {
+        Self { outline, holes }
+    }
+
+    pub fn area(&self) -> f32 {
+        self.outline.area() - self.holes.iter().map(|h| h.area()).sum::<f32>()
+    }
+
+    /// Inside the outline and outside of the holes.
+    pub fn contains(&self, point: Vec2D) -> bool {
+        self.outline.contains(point) && !self.holes.iter().any(|h| h.contains(point))
+    }
+
+    pub fn boolean(&self, other: &Shape, op: BooleanOp) -> Vec<Shape> {
+        boolean(std::slice::from_ref(self), std::slice::from_ref(other), op)
+    }
+}
+
+impl From<Polygon> for Shape {
+    fn from(outline: Polygon) -> Self {
+        Self::new(outline, Vec::new())
+    }
+}
+
+type Key = (u32, u32);
+type Edge = (Vec2D, Vec2D);
+
+fn key(p: Vec2D) -> Key {
+    (p.x.to_bits(), p.y.to_bits())
+}
+
+/// Boolean operation between two sets of shapes, concave or with holes.
+/// The shapes of a set must not overlap each other, the outlines and holes
+/// must not intersect themselves.
+///
+/// The edges are split where they cross or overlap the other set, then kept
+/// depending on which side of the other set they are, and linked back into
+/// outlines and holes.
+pub fn boolean(a: &[Shape], b: &[Shape], op: BooleanOp) -> Vec<Shape> {
+    let mut rings_a = oriented_rings(a);
+    let mut rings_b = oriented_rings(b);
+    let eps = tolerance(&rings_a, &rings_b);
+    snap_vertices(&rings_a, &mut rings_b, eps);
+
+    let (edges_a, edges_b) = split_edges(&rings_a, &rings_b, eps);
+    rings_a.retain(|r| r.len() >= 3);
+    rings_b.retain(|r| r.len() >= 3);
+    let set_a: HashSet<(Key, Key)> = edges_a.iter().map(|(p, q)| (key(*p), key(*q))).collect();
+    let set_b: HashSet<(Key, Key)> = edges_b.iter().map(|(p, q)| (key(*p), key(*q))).collect();
+
+    let mut kept: Vec<Edge> = Vec::new();
+    for &(p, q) in edges_a.iter() {
+        let same = set_b.contains(&(key(p), key(q)));
+        let opposite = set_b.contains(&(key(q), key(p)));
+        let keep = if same {
+            // both interiors on the left of the edge
+            op == BooleanOp::Union || op == BooleanOp::Intersection
+        } else if opposite {
+            op == BooleanOp::Difference
+        } else {
+            let inside = contains(&rings_b, (p + q) * 0.5_f32);
+            match op {
+                BooleanOp::Union | BooleanOp::Difference => !inside,
+                BooleanOp::Intersection => inside,
+                BooleanOp::Xor => {
+                    kept.push(if inside { (q, p) } else { (p, q) });
+                    continue;
+                }
+            }
+        };
+        if keep {
+            kept.push((p, q));
+        }
+    }
+    for &(p, q) in edges_b.iter() {
+        // the shared edges were handled with the ones of `a`
+        if set_a.contains(&(key(p), key(q))) || set_a.contains(&(key(q), key(p))) {
+            continue;
+        }
+        let inside = contains(&rings_a, (p + q) * 0.5_f32);
+        match op {
+            BooleanOp::Union if !inside => kept.push((p, q)),
+            BooleanOp::Intersection if inside => kept.push((p, q)),
+            BooleanOp::Difference if inside => kept.push((q, p)),
+            BooleanOp::Xor => kept.push(if inside { (q, p) } else { (p, q) }),
+            _ => {}
+        }
+    }
+
+    let rings = link_rings(&kept, eps);
+    assemble_shapes(rings, eps)
+}
+
+impl Polygon {
+    pub fn union(&self, other: &Polygon) -> Vec<Shape> {
+        Shape::from(self.clone()).boolean(&other.clone().into(), BooleanOp::Union)
+    }
+
+    /// Intersection with any simple polygon, see `clip_convex` for the convex case.
+    pub fn intersection(&self, other: &Polygon) -> Vec<Shape> {
+        Shape::from(self.clone()).boolean(&other.clone().into(), BooleanOp::Intersection)
+    }
+
+    pub fn difference(&self, other: &Polygon) -> Vec<Shape> {
+        Shape::from(self.clone()).boolean(&other.clone().into(), BooleanOp::Difference)
+    }
+}
+
+/// Outlines counter clockwise and holes clockwise, so that the interior is
+/// always on the left of the edges. Repeated points are removed.
+fn oriented_rings(shapes: &[Shape]) -> Vec<Vec<Vec2D>> {
+    let mut rings = Vec::new();
+    for shape in shapes {
+        let contours = std::iter::once((&shape.outline, Winding::CounterClockwise))
+            .chain(shape.holes.iter().map(|h| (h, Winding::Clockwise)));
+        for (polygon, winding) in contours {
+            let mut polygon = polygon.clone();
+            polygon.points.dedup();
+            while polygon.len() > 1 && polygon.points.first() == polygon.points.last() {
+                polygon.points.pop();
+            }
+            if polygon.len() < 3 {
+                continue;
+            }
+            polygon.set_winding(winding);
+            rings.push(polygon.points);
+        }
+    }
+    rings
+}
+
+fn tolerance(a: &[Vec<Vec2D>], b: &[Vec<Vec2D>]) -> f32 {
+    let scale = a
+        .iter()
+        .chain(b.iter())
+        .flatten()
+        .fold(1_f32, |m, p| m.max(p.x.abs()).max(p.y.abs()));
+    EPSILON * 10_f32 * scale
+}
+
+/// Moves the vertices of `b` onto the ones of `a` closer than `eps`, so that
+/// the shared vertices are exactly equal.
+fn snap_vertices(a: &[Vec<Vec2D>], b: &mut [Vec<Vec2D>], eps: f32) {
+    let sq_eps = eps * eps;
+    for point in b.iter_mut().flatten() {
+        if let Some(v) = a
+            .iter()
+            .flatten()
+            .find(|v| (**v - *point).sq_norm() <= sq_eps)
+        {
+            *point = *v;
+        }
+    }
+    for ring in b.iter_mut() {
+        ring.dedup();
+        while ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+    }
+}
+
+fn ring_edges(rings: &[Vec<Vec2D>]) -> Vec<Edge> {
+    rings
+        .iter()
+        .filter(|r| r.len() >= 3)
+        .flat_map(|r| (0..r.len()).map(move |i| (r[i], r[(i + 1) % r.len()])))
+        .collect()
+}
+
+type Splits = Vec<(f32, Vec2D)>;
+
+/// Points where the segments `p` and `q` must be split because of each other,
+/// with their position along the segment. A crossing point is computed once
+/// and shared by both segments, so that the split edges meet exactly.
+fn intersect(p: Edge, q: Edge, eps: f32) -> (Splits, Splits) {
+    let (mut on_p, mut on_q) = (Vec::new(), Vec::new());
+    let (r, s) = (p.1 - p.0, q.1 - q.0);
+    let (len_r, len_s) = (r.norm(), s.norm());
+    if len_r <= eps || len_s <= eps {
+        return (on_p, on_q);
+    }
+    let along_p = |point: Vec2D| (point - p.0).dot(&r) / (len_r * len_r);
+    let along_q = |point: Vec2D| (point - q.0).dot(&s) / (len_s * len_s);
+    let inner_p = |t: f32| t * len_r > eps && (1_f32 - t) * len_r > eps;
+    let inner_q = |u: f32| u * len_s > eps && (1_f32 - u) * len_s > eps;
+    let denom = r.det(&s);
+    if denom.abs() <= EPSILON * len_r * len_s {
+        // parallel, only the overlapping collinear segments split each other
+        if ((q.0 - p.0).det(&r) / len_r).abs() <= eps {
+            for point in [q.0, q.1].iter() {
+                let t = along_p(*point);
+                if inner_p(t) {
+                    on_p.push((t, *point));
+                }
+            }
+            for point in [p.0, p.1].iter() {
+                let u = along_q(*point);
+                if inner_q(u) {
+                    on_q.push((u, *point));
+                }
+            }
+        }
+        return (on_p, on_q);
+    }
+    let t = (q.0 - p.0).det(&s) / denom;
+    let u = (q.0 - p.0).det(&r) / denom;
+    let (slack_t, slack_u) = (eps / len_r, eps / len_s);
+    if t < -slack_t || t > 1_f32 + slack_t || u < -slack_u || u > 1_f32 + slack_u {
+        return (on_p, on_q);
+    }
+    // a crossing at an end of a segment only splits the other one
+    let point = if !inner_p(t) {
+        if t < 0.5_f32 {
+            p.0
+        } else {
+            p.1
+        }
+    } else if !inner_q(u) {
+        if u < 0.5_f32 {
+            q.0
+        } else {
+            q.1
+        }
+    } else {
+        p.0 + r * t
+    };
+    if inner_p(t) && point != p.0 && point != p.1 {
+        on_p.push((t, point));
+    }
+    if inner_q(u) && point != q.0 && point != q.1 {
+        on_q.push((u, point));
+    }
+    (on_p, on_q)
+}
+
+/// Edges of both sets split at their intersections with the other set.
+fn split_edges(a: &[Vec<Vec2D>], b: &[Vec<Vec2D>], eps: f32) -> (Vec<Edge>, Vec<Edge>) {
+    let edges_a = ring_edges(a);
+    let edges_b = ring_edges(b);
+    let mut splits_a: Vec<Splits> = vec![Vec::new(); edges_a.len()];
+    let mut splits_b: Vec<Splits> = vec![Vec::new(); edges_b.len()];
+    for (i, ea) in edges_a.iter().enumerate() {
+        for (j, eb) in edges_b.iter().enumerate() {
+            let (on_a, on_b) = intersect(*ea, *eb, eps);
+            splits_a[i].extend(on_a);
+            splits_b[j].extend(on_b);
+        }
+    }
+    (
+        apply_splits(&edges_a, splits_a),
+        apply_splits(&edges_b, splits_b),
+    )
+}
+
+fn apply_splits(edges: &[Edge], splits: Vec<Splits>) -> Vec<Edge> {
+    let mut result = Vec::new();
+    for (&(p, q), mut points) in edges.iter().zip(splits) {
+        points.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut start = p;
+        for (_, point) in points.into_iter().chain(std::iter::once((1_f32, q))) {
+            if point != start {
+                result.push((start, point));
+                start = point;
+            }
+        }
+    }
+    result
+}
+
+/// Point in the region of the rings with the even-odd rule.
+fn contains(rings: &[Vec<Vec2D>], point: Vec2D) -> bool {
+    let mut inside = false;
+    for ring in rings {
+        let n = ring.len();
+        for i in 0..n {
+            let (a, b) = (ring[i], ring[(i + 1) % n]);
+            if (a.y > point.y) != (b.y > point.y) {
+                let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+    }
+    inside
+}
+
+/// Links the directed edges into closed rings, taking the leftmost turn at the
+/// vertices shared by several rings so that they are kept apart.
+fn link_rings(edges: &[Edge], eps: f32) -> Vec<Vec<Vec2D>> {
+    let mut outgoing: HashMap<Key, Vec<usize>> = HashMap::new();
+    for (i, (p, _)) in edges.iter().enumerate() {
+        outgoing.entry(key(*p)).or_default().push(i);
+    }
+    let mut used = vec![false; edges.len()];
+    let mut rings = Vec::new();
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let start = edges[first].0;
+        let mut ring = vec![start];
+        let mut current = first;
+        loop {
+            let (p, q) = edges[current];
+            if q == start {
+                break;
+            }
+            ring.push(q);
+            let back = p - q;
+            let back_angle = back.y.atan2(back.x);
+            let next = outgoing.get(&key(q)).and_then(|candidates| {
+                candidates
+                    .iter()
+                    .copied()
+                    .filter(|e| !used[*e])
+                    .min_by(|x, y| {
+                        let turn = |e: usize| {
+                            let d = edges[e].1 - edges[e].0;
+                            // clockwise angle from the way back, the smallest is the leftmost
+                            let angle =
+                                (back_angle - d.y.atan2(d.x)).rem_euclid(std::f32::consts::TAU);
+                            if angle <= 0_f32 {
+                                std::f32::consts::TAU
+                            } else {
+                                angle
+                            }
+                        };
+                        turn(*x).total_cmp(&turn(*y))
+                    })
+            });
+            match next {
+                Some(next) => {
+                    used[next] = true;
+                    current = next;
+                }
+                // open chain, only possible with invalid input
+                None => break,
+            }
+        }
+        let ring = remove_collinear(ring, eps);
+        if ring.len() >= 3 {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+fn remove_collinear(mut ring: Vec<Vec2D>, eps: f32) -> Vec<Vec2D> {
+    let mut i = 0;
+    while ring.len() >= 3 && i < ring.len() {
+        let n = ring.len();
+        let (prev, point, next) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let (d0, d1) = (point - prev, next - point);
+        let straight = d0.det(&d1).abs() <= eps * (d0.norm() + d1.norm()) && d0.dot(&d1) > 0_f32;
+        if straight {
+            ring.remove(i);
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+    ring
+}
+
+/// Counter clockwise rings become outlines and the clockwise ones the holes
+/// of the smallest outline around them.
+fn assemble_shapes(rings: Vec<Vec<Vec2D>>, eps: f32) -> Vec<Shape> {
+    let (outlines, holes): (Vec<Polygon>, Vec<Polygon>) = rings
+        .into_iter()
+        .map(Polygon::new)
+        .filter(|p| p.area() > eps * eps)
+        .partition(|p| p.signed_area() > 0_f32);
+    let mut shapes: Vec<Shape> = outlines.into_iter().map(Shape::from).collect();
+    for hole in holes {
+        // just beside the first edge, on the filled side
+        let (a, b) = (hole.points[0], hole.points[1]);
+        let d = b - a;
+        let normal = Vec2D::new(-d.y, d.x) * (1e-3_f32);
+        let probe = (a + b) * 0.5_f32 + normal;
+        let owner = shapes
+            .iter_mut()
+            .filter(|s| s.outline.contains(probe))
+            .min_by(|x, y| x.outline.area().total_cmp(&y.outline.area()));
+        if let Some(owner) = owner {
+            owner.holes.push(hole);
+        }
+    }
+    shapes
+}
@@ -0,0 +1,112 @@
+use super::super::Vec3D;
+use super::{Aabb3, Plane, Sphere};
+
+/// Result of a culling test.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Containment {
+    Outside,
+    Intersecting,
+    Inside,
+}
+
+/// View frustum made of 6 planes with normals pointing inside.
+/// Planes order is left, right, bottom, top, near, far.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Frustum {
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    pub fn from_planes(planes: [Plane; 6]) -> Self {
+        Self { planes }
+    }
+
+    /// Frustum of a perspective camera. `fov_y` is the vertical field of view in radians.
+    pub fn perspective(
+        pos: Vec3D,
+        forward: Vec3D,
+        up: Vec3D,
+        fov_y: f32,
+        aspect: f32,
+        near: f32,
+        far: f32,
+    ) -> Self {
+        let forward = forward.normalized();
+        let right = forward.cross(&up).normalized();
+        let up = right.cross(&forward);
+        let half_h = (fov_y * 0.5).tan();
+        let half_w = half_h * aspect;
+        let inside = pos + forward * ((near + far) * 0.5);
+
+        // plane through the camera position containing the given edge direction and axis
+        let side = |edge: Vec3D, axis: Vec3D| {
+            let plane = Plane::from_point_normal(pos, edge.cross(&axis));
+            if plane.signed_distance(inside) < 0_f32 {
+                plane.flipped()
+            } else {
+                plane
+            }
+        };
+
+        Self {
+            planes: [
+                side(forward - right * half_w, up),
+                side(forward + right * half_w, up),
+                side(forward - up * half_h, right),
+                side(forward + up * half_h, right),
+                Plane::from_point_normal(pos + forward * near, forward),
+                Plane::from_point_normal(pos + forward * far, -forward),
+            ],
+        }
+    }
+
+    pub fn contains(&self, point: Vec3D) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(point) >= 0_f32)
+    }
+
+    pub fn test_sphere(&self, sphere: &Sphere) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in self.planes.iter() {
+            let dist = plane.signed_distance(sphere.center);
+            if dist < -sphere.radius {
+                return Containment::Outside;
+            }
+            if dist < sphere.radius {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    /// AABB-frustum test using the positive and negative vertices of the box for each plane.
+    /// Boxes near the frustum corners can be reported as intersecting while being outside.
+    pub fn test_aabb(&self, aabb: &Aabb3) -> Containment {
+        let mut result = Containment::Inside;
+        for plane in self.planes.iter() {
+            let n = plane.normal;
+            let positive = Vec3D::new(
+                if n.x >= 0_f32 { aabb.max.x } else { aabb.min.x },
+                if n.y >= 0_f32 { aabb.max.y } else { aabb.min.y },
+                if n.z >= 0_f32 { aabb.max.z } else { aabb.min.z },
+            );
+            if plane.signed_distance(positive) < 0_f32 {
+                return Containment::Outside;
+            }
+            let negative = Vec3D::new(
+                if n.x >= 0_f32 { aabb.min.x } else { aabb.max.x },
+                if n.y >= 0_f32 { aabb.min.y } else { aabb.max.y },
+                if n.z >= 0_f32 { aabb.min.z } else { aabb.max.z },
+            );
+            if plane.signed_distance(negative) < 0_f32 {
+                result = Containment::Intersecting;
+            }
+        }
+        result
+    }
+
+    pub fn intersects_aabb(&self, aabb: &Aabb3) -> bool {
+        self.test_aabb(aabb) != Containment::Outside
+    }
+}
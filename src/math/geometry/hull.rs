@@ -0,0 +1,199 @@
+use super::super::{Vec2D, Vec3D};
+use std::collections::HashSet;
+
+/// Convex hull of a set of 2D points with Andrew's monotone chain algorithm.
+/// The hull is counter clockwise, without collinear points.
+pub fn convex_hull_2d(points: &[Vec2D]) -> Vec<Vec2D> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    let turn = |o: Vec2D, a: Vec2D, b: Vec2D| (a - o).det(&(b - o));
+    let mut hull: Vec<Vec2D> = Vec::with_capacity(sorted.len() * 2);
+    // lower hull
+    for p in sorted.iter() {
+        while hull.len() >= 2 && turn(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0f32 {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+    // upper hull
+    let lower_len = hull.len() + 1;
+    for p in sorted.iter().rev().skip(1) {
+        while hull.len() >= lower_len && turn(hull[hull.len() - 2], hull[hull.len() - 1], *p) <= 0f32 {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+    hull.pop();
+    hull
+}
+
+/// Triangulated convex hull. Faces index `points` and are counter clockwise seen from outside.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hull3 {
+    pub points: Vec<Vec3D>,
+    pub faces: Vec<[usize; 3]>,
+}
+
+struct Face {
+    vertices: [usize; 3],
+    normal: Vec3D,
+    d: f32,
+    outside: Vec<usize>,
+    alive: bool,
+}
+
+impl Face {
+    fn new(points: &[Vec3D], vertices: [usize; 3]) -> Self {
+        let [a, b, c] = vertices;
+        let normal = (points[b] - points[a]).cross(&(points[c] - points[a]));
+        let normal = normal * (1f32 / normal.norm());
+        Self {
+            vertices,
+            normal,
+            d: -normal.dot(&points[a]),
+            outside: Vec::new(),
+            alive: true,
+        }
+    }
+
+    fn distance(&self, point: Vec3D) -> f32 {
+        self.normal.dot(&point) + self.d
+    }
+}
+
+/// Convex hull of a set of 3D points with the quickhull algorithm.
+/// Returns `None` when all the points are coplanar.
+pub fn convex_hull_3d(points: &[Vec3D]) -> Option<Hull3> {
+    if points.len() < 4 {
+        return None;
+    }
+    let extent = points
+        .iter()
+        .fold(0f32, |acc, p| acc.max(p.x.abs()).max(p.y.abs()).max(p.z.abs()));
+    let eps = extent.max(1f32) * 1e-5;
+
+    // initial tetrahedron from extreme points
+    let (mut i0, mut i1) = (0, 0);
+    for (i, p) in points.iter().enumerate() {
+        if p.x < points[i0].x {
+            i0 = i;
+        }
+        if p.x > points[i1].x {
+            i1 = i;
+        }
+    }
+    if (points[i1] - points[i0]).norm() < eps {
+        return None;
+    }
+    let line = points[i1] - points[i0];
+    let i2 = (0..points.len()).max_by(|a, b| {
+        let da = line.cross(&(points[*a] - points[i0])).sq_norm();
+        let db = line.cross(&(points[*b] - points[i0])).sq_norm();
+        da.total_cmp(&db)
+    })?;
+    if line.cross(&(points[i2] - points[i0])).norm() < eps * line.norm() {
+        return None;
+    }
+    let base = Face::new(points, [i0, i1, i2]);
+    let i3 = (0..points.len()).max_by(|a, b| {
+        base.distance(points[*a]).abs().total_cmp(&base.distance(points[*b]).abs())
+    })?;
+    if base.distance(points[i3]).abs() < eps {
+        return None;
+    }
+
+    let mut faces: Vec<Face> = Vec::new();
+    let (a, b, c) = if base.distance(points[i3]) > 0f32 {
+        // the apex is in front of the base, flip it to face outward
+        (i0, i2, i1)
+    } else {
+        (i0, i1, i2)
+    };
+    for vertices in [[a, b, c], [a, i3, b], [b, i3, c], [c, i3, a]].iter() {
+        faces.push(Face::new(points, *vertices));
+    }
+
+    let assign = |faces: &mut Vec<Face>, candidates: &[usize], point: usize| {
+        for &f in candidates {
+            if faces[f].alive && faces[f].distance(points[point]) > eps {
+                faces[f].outside.push(point);
+                return;
+            }
+        }
+    };
+    let first: Vec<usize> = (0..4).collect();
+    for i in 0..points.len() {
+        if i != i0 && i != i1 && i != i2 && i != i3 {
+            assign(&mut faces, &first, i);
+        }
+    }
+
+    while let Some(face) = faces.iter().position(|f| f.alive && !f.outside.is_empty()) {
+        // farthest point of the face
+        let apex = *faces[face]
+            .outside
+            .iter()
+            .max_by(|a, b| {
+                faces[face].distance(points[**a]).total_cmp(&faces[face].distance(points[**b]))
+            })
+            .unwrap();
+
+        let visible: Vec<usize> = (0..faces.len())
+            .filter(|f| faces[*f].alive && faces[*f].distance(points[apex]) > eps)
+            .collect();
+
+        // horizon edges are the edges of the visible faces whose twin isn't visible
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for &f in visible.iter() {
+            let [a, b, c] = faces[f].vertices;
+            edges.extend_from_slice(&[(a, b), (b, c), (c, a)]);
+        }
+        let visited: HashSet<(usize, usize)> = edges.iter().copied().collect();
+        let horizon: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|(a, b)| !visited.contains(&(*b, *a)))
+            .copied()
+            .collect();
+
+        let mut orphans = Vec::new();
+        for &f in visible.iter() {
+            faces[f].alive = false;
+            orphans.append(&mut faces[f].outside);
+        }
+
+        let first_new = faces.len();
+        for (a, b) in horizon {
+            faces.push(Face::new(points, [a, b, apex]));
+        }
+        let new_faces: Vec<usize> = (first_new..faces.len()).collect();
+        for point in orphans {
+            if point != apex {
+                assign(&mut faces, &new_faces, point);
+            }
+        }
+    }
+
+    // compact the points used by the hull
+    let mut remap = vec![usize::MAX; points.len()];
+    let mut hull = Hull3 {
+        points: Vec::new(),
+        faces: Vec::new(),
+    };
+    for face in faces.iter().filter(|f| f.alive) {
+        let mut vertices = [0; 3];
+        for (k, &v) in face.vertices.iter().enumerate() {
+            if remap[v] == usize::MAX {
+                remap[v] = hull.points.len();
+                hull.points.push(points[v]);
+            }
+            vertices[k] = remap[v];
+        }
+        hull.faces.push(vertices);
+    }
+    Some(hull)
+}
@@ -0,0 +1,28 @@
+pub mod aabb;
+pub mod boolean;
+pub mod frustum;
+pub mod hull;
+pub mod obb;
+pub mod plane;
+pub mod polygon;
+pub mod ray;
+pub mod segment;
+pub mod sphere;
+pub mod triangle;
+pub mod triangulate;
+
+pub use aabb::{Aabb2, Aabb3};
+pub use boolean::{boolean, BooleanOp, Shape};
+pub use frustum::{Containment, Frustum};
+pub use hull::{convex_hull_2d, convex_hull_3d, Hull3};
+pub use obb::Obb;
+pub use plane::Plane;
+pub use polygon::{simplify_polyline, Polygon, Winding};
+pub use ray::{Ray, Ray2, RayHit};
+pub use segment::Segment;
+pub use sphere::Sphere;
+pub use triangle::Triangle;
+pub use triangulate::{triangulate, Triangulation};
+
+/// Tolerance used by the intersection queries to reject degenerate cases.
+pub const EPSILON: f32 = 1e-6;
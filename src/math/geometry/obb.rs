@@ -0,0 +1,167 @@
+use super::super::{Quat, Vec3D};
+use super::{Aabb3, Ray, Sphere, EPSILON};
+
+/// Oriented bounding box. `axes` must be orthonormal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Obb {
+    pub center: Vec3D,
+    pub axes: [Vec3D; 3],
+    pub half_extents: Vec3D,
+}
+
+impl Obb {
+    pub fn new(center: Vec3D, axes: [Vec3D; 3], half_extents: Vec3D) -> Self {
+        Self {
+            center,
+            axes,
+            half_extents,
+        }
+    }
+
+    /// Box with the world axes rotated by `rotation`.
+    pub fn from_rotation(center: Vec3D, half_extents: Vec3D, rotation: Quat) -> Self {
+        Self {
+            center,
+            axes: [
+                rotation.rotate(Vec3D::new(1_f32, 0_f32, 0_f32)),
+                rotation.rotate(Vec3D::new(0_f32, 1_f32, 0_f32)),
+                rotation.rotate(Vec3D::new(0_f32, 0_f32, 1_f32)),
+            ],
+            half_extents,
+        }
+    }
+
+    pub fn from_aabb(aabb: &Aabb3) -> Self {
+        Self {
+            center: aabb.center(),
+            axes: [
+                Vec3D::new(1_f32, 0_f32, 0_f32),
+                Vec3D::new(0_f32, 1_f32, 0_f32),
+                Vec3D::new(0_f32, 0_f32, 1_f32),
+            ],
+            half_extents: aabb.half_extents(),
+        }
+    }
+
+    /// Coordinates of a world point in the box frame.
+    pub fn to_local(&self, point: Vec3D) -> Vec3D {
+        let d = point - self.center;
+        Vec3D::new(
+            d.dot(&self.axes[0]),
+            d.dot(&self.axes[1]),
+            d.dot(&self.axes[2]),
+        )
+    }
+
+    /// World coordinates of a point given in the box frame.
+    pub fn to_world(&self, local: Vec3D) -> Vec3D {
+        self.center + self.axes[0] * local.x + self.axes[1] * local.y + self.axes[2] * local.z
+    }
+
+    pub fn corners(&self) -> [Vec3D; 8] {
+        let h = self.half_extents;
+        let mut corners = [Vec3D::null(); 8];
+        for (i, corner) in corners.iter_mut().enumerate() {
+            let sx = if i & 1 == 0 { -h.x } else { h.x };
+            let sy = if i & 2 == 0 { -h.y } else { h.y };
+            let sz = if i & 4 == 0 { -h.z } else { h.z };
+            *corner = self.to_world(Vec3D::new(sx, sy, sz));
+        }
+        corners
+    }
+
+    pub fn bounding_box(&self) -> Aabb3 {
+        let corners = self.corners();
+        Aabb3::from_points(&corners).unwrap()
+    }
+
+    pub fn contains(&self, point: Vec3D) -> bool {
+        let local = self.to_local(point);
+        local.x.abs() <= self.half_extents.x
+            && local.y.abs() <= self.half_extents.y
+            && local.z.abs() <= self.half_extents.z
+    }
+
+    pub fn closest_point(&self, point: Vec3D) -> Vec3D {
+        let local = self.to_local(point);
+        let h = self.half_extents;
+        self.to_world(Vec3D::new(
+            local.x.max(-h.x).min(h.x),
+            local.y.max(-h.y).min(h.y),
+            local.z.max(-h.z).min(h.z),
+        ))
+    }
+
+    pub fn sq_distance(&self, point: Vec3D) -> f32 {
+        (self.closest_point(point) - point).sq_norm()
+    }
+
+    pub fn distance(&self, point: Vec3D) -> f32 {
+        self.sq_distance(point).sqrt()
+    }
+
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        self.sq_distance(sphere.center) <= sphere.radius * sphere.radius
+    }
+
+    /// Ray cast done in the box frame, returns the entry distance.
+    pub fn intersect_ray(&self, ray: &Ray) -> Option<f32> {
+        let local_ray = Ray::new(
+            self.to_local(ray.origin),
+            Vec3D::new(
+                ray.dir.dot(&self.axes[0]),
+                ray.dir.dot(&self.axes[1]),
+                ray.dir.dot(&self.axes[2]),
+            ),
+        );
+        local_ray.intersect_aabb(&Aabb3::from_center(Vec3D::null(), self.half_extents))
+    }
+
+    /// Separating axis test between two oriented boxes.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let ea = [self.half_extents.x, self.half_extents.y, self.half_extents.z];
+        let eb = [other.half_extents.x, other.half_extents.y, other.half_extents.z];
+
+        // rotation of other expressed in the frame of self
+        let mut r = [[0_f32; 3]; 3];
+        let mut abs_r = [[0_f32; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                r[i][j] = self.axes[i].dot(&other.axes[j]);
+                abs_r[i][j] = r[i][j].abs() + EPSILON;
+            }
+        }
+        let d = other.center - self.center;
+        let t = [d.dot(&self.axes[0]), d.dot(&self.axes[1]), d.dot(&self.axes[2])];
+
+        // axes of self
+        for i in 0..3 {
+            let rb = eb[0] * abs_r[i][0] + eb[1] * abs_r[i][1] + eb[2] * abs_r[i][2];
+            if t[i].abs() > ea[i] + rb {
+                return false;
+            }
+        }
+        // axes of other
+        for j in 0..3 {
+            let ra = ea[0] * abs_r[0][j] + ea[1] * abs_r[1][j] + ea[2] * abs_r[2][j];
+            let proj = t[0] * r[0][j] + t[1] * r[1][j] + t[2] * r[2][j];
+            if proj.abs() > ra + eb[j] {
+                return false;
+            }
+        }
+        // cross products of the axes
+        for i in 0..3 {
+            let (i1, i2) = ((i + 1) % 3, (i + 2) % 3);
+            for j in 0..3 {
+                let (j1, j2) = ((j + 1) % 3, (j + 2) % 3);
+                let ra = ea[i1] * abs_r[i2][j] + ea[i2] * abs_r[i1][j];
+                let rb = eb[j1] * abs_r[i][j2] + eb[j2] * abs_r[i][j1];
+                let proj = t[i2] * r[i1][j] - t[i1] * r[i2][j];
+                if proj.abs() > ra + rb {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,53 @@
+use super::super::Vec3D;
+
+/// Plane of equation `normal . p + d = 0`, with `normal` of unit length.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane {
+    pub normal: Vec3D,
+    pub d: f32,
+}
+
+impl Plane {
+    /// Build a plane from a (not necessarily unit) normal and the `d` coefficient.
+    pub fn new(normal: Vec3D, d: f32) -> Self {
+        let k = 1_f32 / normal.norm();
+        Self {
+            normal: normal * k,
+            d: d * k,
+        }
+    }
+
+    pub fn from_point_normal(point: Vec3D, normal: Vec3D) -> Self {
+        let normal = normal.normalized();
+        Self {
+            normal,
+            d: -normal.dot(&point),
+        }
+    }
+
+    /// Plane through three points, facing the side where `a, b, c` is counter clockwise.
+    pub fn from_points(a: Vec3D, b: Vec3D, c: Vec3D) -> Self {
+        Self::from_point_normal(a, (b - a).cross(&(c - a)))
+    }
+
+    /// Signed distance, positive on the side the normal points to.
+    pub fn signed_distance(&self, point: Vec3D) -> f32 {
+        self.normal.dot(&point) + self.d
+    }
+
+    pub fn distance(&self, point: Vec3D) -> f32 {
+        self.signed_distance(point).abs()
+    }
+
+    /// Orthogonal projection of the point on the plane.
+    pub fn project(&self, point: Vec3D) -> Vec3D {
+        point - self.normal * self.signed_distance(point)
+    }
+
+    pub fn flipped(&self) -> Self {
+        Self {
+            normal: -self.normal,
+            d: -self.d,
+        }
+    }
+}
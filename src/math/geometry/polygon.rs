@@ -0,0 +1,276 @@
+use super::super::Vec2D;
+use super::{Aabb2, EPSILON};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Winding {
+    Clockwise,
+    CounterClockwise,
+}
+
+/// Simple 2D polygon given by its vertices, the last vertex is connected to the first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polygon {
+    pub points: Vec<Vec2D>,
+}
+
+impl Polygon {
+    pub fn new(points: Vec<Vec2D>) -> Self {
+        Self { points }
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Iterate over the edges as `(start, end)` pairs.
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2D, Vec2D)> + '_ {
+        let n = self.points.len();
+        (0..n).map(move |i| (self.points[i], self.points[(i + 1) % n]))
+    }
+
+    pub fn bounding_box(&self) -> Option<Aabb2> {
+        Aabb2::from_points(&self.points)
+    }
+
+    /// Point in polygon test with the crossing number (even-odd) rule.
+    pub fn contains(&self, point: Vec2D) -> bool {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (a.y > point.y) != (b.y > point.y) {
+                let x = a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x);
+                if point.x < x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Distance from the point to the polygon outline.
+    pub fn distance_to_edge(&self, point: Vec2D) -> f32 {
+        self.edges()
+            .map(|(a, b)| {
+                let ab = b - a;
+                let len = ab.sq_norm();
+                let t = if len > 0_f32 {
+                    ((point - a).dot(&ab) / len).clamp(0_f32, 1_f32)
+                } else {
+                    0_f32
+                };
+                (a + ab * t - point).sq_norm()
+            })
+            .fold(f32::INFINITY, f32::min)
+            .sqrt()
+    }
+
+    /// Signed area, positive for counter clockwise polygons (shoelace formula).
+    pub fn signed_area(&self) -> f32 {
+        self.edges().map(|(a, b)| a.det(&b)).sum::<f32>() * 0.5
+    }
+
+    pub fn area(&self) -> f32 {
+        self.signed_area().abs()
+    }
+
+    pub fn winding(&self) -> Winding {
+        if self.signed_area() < 0f32 {
+            Winding::Clockwise
+        } else {
+            Winding::CounterClockwise
+        }
+    }
+
+    /// Reverse the order of the vertices if needed to get the given winding.
+    pub fn set_winding(&mut self, winding: Winding) {
+        if self.winding() != winding {
+            self.points.reverse();
+        }
+    }
+
+    /// Center of mass of the polygon area.
+    pub fn centroid(&self) -> Vec2D {
+        let area = self.signed_area();
+        if area.abs() < EPSILON {
+            // degenerated polygon, average of the vertices
+            let sum = self.points.iter().fold(Vec2D::null(), |acc, p| acc + *p);
+            return sum * (1f32 / self.points.len().max(1) as f32);
+        }
+        let sum = self.edges().fold(Vec2D::null(), |acc, (a, b)| {
+            acc + (a + b) * a.det(&b)
+        });
+        sum * (1f32 / (6f32 * area))
+    }
+
+    pub fn perimeter(&self) -> f32 {
+        self.edges().map(|(a, b)| (b - a).norm()).sum()
+    }
+
+    pub fn is_convex(&self) -> bool {
+        let n = self.points.len();
+        if n < 3 {
+            return false;
+        }
+        let mut sign = 0f32;
+        for i in 0..n {
+            let a = self.points[i];
+            let b = self.points[(i + 1) % n];
+            let c = self.points[(i + 2) % n];
+            let cross = (b - a).det(&(c - b));
+            if cross.abs() > EPSILON {
+                if sign != 0f32 && cross.signum() != sign {
+                    return false;
+                }
+                sign = cross.signum();
+            }
+        }
+        true
+    }
+
+    /// Grow the polygon by `distance` (shrink it when negative) with mitered corners.
+    /// The miters are limited to `miter_limit` times the distance, beveling sharper corners.
+    /// The result can self intersect when insetting by more than the local thickness.
+    pub fn offset(&self, distance: f32, miter_limit: f32) -> Polygon {
+        let n = self.points.len();
+        if n < 3 {
+            return self.clone();
+        }
+        // outward normal of the edge going from a to b
+        let side = if self.winding() == Winding::CounterClockwise {
+            1f32
+        } else {
+            -1f32
+        };
+        let normal = |a: Vec2D, b: Vec2D| {
+            let d = (b - a).normalized();
+            Vec2D::new(d.y, -d.x) * side
+        };
+
+        let mut points = Vec::with_capacity(n);
+        for i in 0..n {
+            let prev = self.points[(i + n - 1) % n];
+            let cur = self.points[i];
+            let next = self.points[(i + 1) % n];
+            let n1 = normal(prev, cur);
+            let n2 = normal(cur, next);
+            let bisector = n1 + n2;
+            let cos = if bisector.sq_norm() > EPSILON {
+                bisector.normalized().dot(&n1)
+            } else {
+                0f32
+            };
+            if cos > EPSILON && 1f32 / cos <= miter_limit {
+                points.push(cur + bisector.normalized() * (distance / cos));
+            } else {
+                // bevel
+                points.push(cur + n1 * distance);
+                points.push(cur + n2 * distance);
+            }
+        }
+        Polygon::new(points)
+    }
+
+    /// Simplify the outline with the Ramer–Douglas–Peucker algorithm.
+    pub fn simplify(&self, epsilon: f32) -> Polygon {
+        let n = self.points.len();
+        if n <= 3 {
+            return self.clone();
+        }
+        // split the closed outline at the vertex the farthest from the first one
+        let far = (1..n)
+            .max_by(|a, b| {
+                let da = (self.points[*a] - self.points[0]).sq_norm();
+                let db = (self.points[*b] - self.points[0]).sq_norm();
+                da.total_cmp(&db)
+            })
+            .unwrap();
+        let mut first = simplify_polyline(&self.points[..=far], epsilon);
+        let mut second: Vec<Vec2D> = self.points[far..].to_vec();
+        second.push(self.points[0]);
+        let second = simplify_polyline(&second, epsilon);
+        first.pop();
+        first.extend_from_slice(&second[..second.len() - 1]);
+        Polygon::new(first)
+    }
+
+    /// Intersection with a convex polygon (Sutherland–Hodgman clipping).
+    /// `clip` must be convex, `self` can be concave but the result may then contain
+    /// degenerated edges where it should be split in several parts, use
+    /// `intersection` in that case.
+    pub fn clip_convex(&self, clip: &Polygon) -> Polygon {
+        let side = if clip.winding() == Winding::CounterClockwise {
+            1f32
+        } else {
+            -1f32
+        };
+        let mut output = self.points.clone();
+        for (a, b) in clip.edges() {
+            let input = std::mem::take(&mut output);
+            if input.is_empty() {
+                break;
+            }
+            let inside = |p: Vec2D| (b - a).det(&(p - a)) * side >= 0f32;
+            let intersection = |p: Vec2D, q: Vec2D| {
+                let d = q - p;
+                let t = (a - p).det(&(b - a)) / d.det(&(b - a));
+                p + d * t
+            };
+            for i in 0..input.len() {
+                let cur = input[i];
+                let prev = input[(i + input.len() - 1) % input.len()];
+                if inside(cur) {
+                    if !inside(prev) {
+                        output.push(intersection(prev, cur));
+                    }
+                    output.push(cur);
+                } else if inside(prev) {
+                    output.push(intersection(prev, cur));
+                }
+            }
+        }
+        Polygon::new(output)
+    }
+}
+
+/// Simplify an open polyline with the Ramer–Douglas–Peucker algorithm,
+/// keeping the points farther than `epsilon` from the simplified line.
+pub fn simplify_polyline(points: &[Vec2D], epsilon: f32) -> Vec<Vec2D> {
+    if points.len() < 3 {
+        return points.to_vec();
+    }
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((start, end)) = stack.pop() {
+        let (a, b) = (points[start], points[end]);
+        let ab = b - a;
+        let len = ab.sq_norm();
+        let mut farthest = (0f32, start);
+        for (i, p) in points.iter().enumerate().take(end).skip(start + 1) {
+            let dist = if len < EPSILON {
+                (*p - a).norm()
+            } else {
+                let t = ((*p - a).dot(&ab) / len).clamp(0f32, 1f32);
+                (a + ab * t - *p).norm()
+            };
+            if dist > farthest.0 {
+                farthest = (dist, i);
+            }
+        }
+        if farthest.0 > epsilon {
+            keep[farthest.1] = true;
+            stack.push((start, farthest.1));
+            stack.push((farthest.1, end));
+        }
+    }
+    points
+        .iter()
+        .zip(keep.iter())
+        .filter(|(_, k)| **k)
+        .map(|(p, _)| *p)
+        .collect()
+}
@@ -0,0 +1,193 @@
+use super::super::{Vec2D, Vec3D};
+use super::{Aabb2, Aabb3, Plane, Sphere, Triangle, EPSILON};
+
+/// Result of a ray-triangle intersection.
+/// `u` and `v` are the barycentric coordinates of the hit point relative to `b` and `c`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RayHit {
+    pub t: f32,
+    pub u: f32,
+    pub v: f32,
+}
+
+/// Half line starting at `origin` going in the `dir` direction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray {
+    pub origin: Vec3D,
+    pub dir: Vec3D,
+}
+
+impl Ray {
+    pub fn new(origin: Vec3D, dir: Vec3D) -> Self {
+        Self { origin, dir }
+    }
+
+    /// Ray going from `from` toward `to` with a unit direction.
+    pub fn between(from: Vec3D, to: Vec3D) -> Self {
+        Self {
+            origin: from,
+            dir: (to - from).normalized(),
+        }
+    }
+
+    pub fn at(&self, t: f32) -> Vec3D {
+        self.origin + self.dir * t
+    }
+
+    /// Slab test. Returns the distance along the ray where it enters the box
+    /// (0 if the origin is inside).
+    pub fn intersect_aabb(&self, aabb: &Aabb3) -> Option<f32> {
+        let mut t_min = 0_f32;
+        let mut t_max = f32::INFINITY;
+        for axis in 0..3 {
+            let origin = self.origin.axis(axis);
+            let dir = self.dir.axis(axis);
+            let (min, max) = (aabb.min.axis(axis), aabb.max.axis(axis));
+            if dir.abs() < EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1_f32 / dir;
+            let (mut t0, mut t1) = ((min - origin) * inv, (max - origin) * inv);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+
+    /// Möller–Trumbore ray-triangle intersection. Both faces are hit.
+    pub fn intersect_triangle(&self, triangle: &Triangle) -> Option<RayHit> {
+        let edge1 = triangle.b - triangle.a;
+        let edge2 = triangle.c - triangle.a;
+        let p = self.dir.cross(&edge2);
+        let det = edge1.dot(&p);
+        if det.abs() < EPSILON {
+            // the ray is parallel to the triangle
+            return None;
+        }
+        let inv_det = 1_f32 / det;
+        let s = self.origin - triangle.a;
+        let u = s.dot(&p) * inv_det;
+        if !(0_f32..=1_f32).contains(&u) {
+            return None;
+        }
+        let q = s.cross(&edge1);
+        let v = self.dir.dot(&q) * inv_det;
+        if v < 0_f32 || u + v > 1_f32 {
+            return None;
+        }
+        let t = edge2.dot(&q) * inv_det;
+        if t < EPSILON {
+            return None;
+        }
+        Some(RayHit { t, u, v })
+    }
+
+    pub fn intersect_plane(&self, plane: &Plane) -> Option<f32> {
+        let denom = plane.normal.dot(&self.dir);
+        if denom.abs() < EPSILON {
+            return None;
+        }
+        let t = -plane.signed_distance(self.origin) / denom;
+        if t < 0_f32 {
+            None
+        } else {
+            Some(t)
+        }
+    }
+
+    /// Distance to the first hit of the sphere (0 if the origin is inside).
+    pub fn intersect_sphere(&self, sphere: &Sphere) -> Option<f32> {
+        let m = self.origin - sphere.center;
+        let a = self.dir.sq_norm();
+        let b = m.dot(&self.dir);
+        let c = m.sq_norm() - sphere.radius * sphere.radius;
+        if c > 0_f32 && b > 0_f32 {
+            // origin outside and pointing away
+            return None;
+        }
+        let discr = b * b - a * c;
+        if discr < 0_f32 {
+            return None;
+        }
+        Some(((-b - discr.sqrt()) / a).max(0_f32))
+    }
+
+    /// Squared distance between the ray and a point.
+    pub fn sq_distance(&self, point: Vec3D) -> f32 {
+        let t = ((point - self.origin).dot(&self.dir) / self.dir.sq_norm()).max(0_f32);
+        (self.at(t) - point).sq_norm()
+    }
+}
+
+/// 2D half line starting at `origin` going in the `dir` direction.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ray2 {
+    pub origin: Vec2D,
+    pub dir: Vec2D,
+}
+
+impl Ray2 {
+    pub fn new(origin: Vec2D, dir: Vec2D) -> Self {
+        Self { origin, dir }
+    }
+
+    pub fn at(&self, t: f32) -> Vec2D {
+        self.origin + self.dir * t
+    }
+
+    /// Slab test. Returns the distance along the ray where it enters the box
+    /// (0 if the origin is inside).
+    pub fn intersect_aabb(&self, aabb: &Aabb2) -> Option<f32> {
+        let mut t_min = 0_f32;
+        let mut t_max = f32::INFINITY;
+        let axes = [
+            (self.origin.x, self.dir.x, aabb.min.x, aabb.max.x),
+            (self.origin.y, self.dir.y, aabb.min.y, aabb.max.y),
+        ];
+        for (origin, dir, min, max) in axes.iter().copied() {
+            if dir.abs() < EPSILON {
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let inv = 1_f32 / dir;
+            let (mut t0, mut t1) = ((min - origin) * inv, (max - origin) * inv);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+            t_min = t_min.max(t0);
+            t_max = t_max.min(t1);
+            if t_min > t_max {
+                return None;
+            }
+        }
+        Some(t_min)
+    }
+
+    /// Intersection with the segment `[a, b]`, returns the distance along the ray.
+    pub fn intersect_segment(&self, a: Vec2D, b: Vec2D) -> Option<f32> {
+        let edge = b - a;
+        let denom = self.dir.det(&edge);
+        if denom.abs() < EPSILON {
+            return None;
+        }
+        let diff = a - self.origin;
+        let t = diff.det(&edge) / denom;
+        let s = diff.det(&self.dir) / denom;
+        if t >= 0_f32 && (0_f32..=1_f32).contains(&s) {
+            Some(t)
+        } else {
+            None
+        }
+    }
+}
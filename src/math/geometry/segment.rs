@@ -0,0 +1,90 @@
+use super::super::Vec3D;
+use super::EPSILON;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub a: Vec3D,
+    pub b: Vec3D,
+}
+
+impl Segment {
+    pub fn new(a: Vec3D, b: Vec3D) -> Self {
+        Self { a, b }
+    }
+
+    pub fn length(&self) -> f32 {
+        (self.b - self.a).norm()
+    }
+
+    pub fn at(&self, t: f32) -> Vec3D {
+        self.a + (self.b - self.a) * t
+    }
+
+    /// Parameter in `[0, 1]` of the closest point of the segment to `point`.
+    pub fn closest_t(&self, point: Vec3D) -> f32 {
+        let ab = self.b - self.a;
+        let len = ab.sq_norm();
+        if len < EPSILON {
+            return 0_f32;
+        }
+        ((point - self.a).dot(&ab) / len).clamp(0_f32, 1_f32)
+    }
+
+    pub fn closest_point(&self, point: Vec3D) -> Vec3D {
+        self.at(self.closest_t(point))
+    }
+
+    pub fn sq_distance(&self, point: Vec3D) -> f32 {
+        (self.closest_point(point) - point).sq_norm()
+    }
+
+    pub fn distance(&self, point: Vec3D) -> f32 {
+        self.sq_distance(point).sqrt()
+    }
+
+    /// Closest points between two segments, returned as `(on self, on other)`.
+    pub fn closest_points(&self, other: &Self) -> (Vec3D, Vec3D) {
+        let d1 = self.b - self.a;
+        let d2 = other.b - other.a;
+        let r = self.a - other.a;
+        let a = d1.sq_norm();
+        let e = d2.sq_norm();
+        let f = d2.dot(&r);
+        let clamp = |x: f32| x.clamp(0_f32, 1_f32);
+
+        let (s, t) = if a < EPSILON && e < EPSILON {
+            (0_f32, 0_f32)
+        } else if a < EPSILON {
+            (0_f32, clamp(f / e))
+        } else {
+            let c = d1.dot(&r);
+            if e < EPSILON {
+                (clamp(-c / a), 0_f32)
+            } else {
+                let b = d1.dot(&d2);
+                let denom = a * e - b * b;
+                let mut s = if denom > EPSILON {
+                    clamp((b * f - c * e) / denom)
+                } else {
+                    0_f32
+                };
+                let mut t = (b * s + f) / e;
+                if t < 0_f32 {
+                    t = 0_f32;
+                    s = clamp(-c / a);
+                } else if t > 1_f32 {
+                    t = 1_f32;
+                    s = clamp((b - c) / a);
+                }
+                (s, t)
+            }
+        };
+        (self.at(s), other.at(t))
+    }
+
+    /// Squared distance between two segments.
+    pub fn sq_distance_segment(&self, other: &Self) -> f32 {
+        let (p, q) = self.closest_points(other);
+        (q - p).sq_norm()
+    }
+}
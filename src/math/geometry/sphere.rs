@@ -0,0 +1,40 @@
+use super::super::Vec3D;
+use super::Aabb3;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sphere {
+    pub center: Vec3D,
+    pub radius: f32,
+}
+
+impl Sphere {
+    pub fn new(center: Vec3D, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn contains(&self, point: Vec3D) -> bool {
+        (point - self.center).sq_norm() <= self.radius * self.radius
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        let radii = self.radius + other.radius;
+        (other.center - self.center).sq_norm() <= radii * radii
+    }
+
+    /// Sphere-AABB overlap test using the closest point of the box to the center.
+    pub fn intersects_aabb(&self, aabb: &Aabb3) -> bool {
+        aabb.sq_distance(self.center) <= self.radius * self.radius
+    }
+
+    /// Signed distance to the surface, negative inside the sphere.
+    pub fn distance(&self, point: Vec3D) -> f32 {
+        (point - self.center).norm() - self.radius
+    }
+
+    pub fn bounding_box(&self) -> Aabb3 {
+        Aabb3::from_center(
+            self.center,
+            Vec3D::new(self.radius, self.radius, self.radius),
+        )
+    }
+}
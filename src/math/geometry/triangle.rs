@@ -0,0 +1,117 @@
+use super::super::Vec3D;
+use super::{Aabb3, Plane};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle {
+    pub a: Vec3D,
+    pub b: Vec3D,
+    pub c: Vec3D,
+}
+
+impl Triangle {
+    pub fn new(a: Vec3D, b: Vec3D, c: Vec3D) -> Self {
+        Self { a, b, c }
+    }
+
+    /// Unit normal, following the counter clockwise winding of `a, b, c`.
+    pub fn normal(&self) -> Vec3D {
+        (self.b - self.a).cross(&(self.c - self.a)).normalized()
+    }
+
+    pub fn area(&self) -> f32 {
+        (self.b - self.a).cross(&(self.c - self.a)).norm() * 0.5
+    }
+
+    pub fn centroid(&self) -> Vec3D {
+        (self.a + self.b + self.c) * (1_f32 / 3_f32)
+    }
+
+    pub fn plane(&self) -> Plane {
+        Plane::from_points(self.a, self.b, self.c)
+    }
+
+    pub fn bounding_box(&self) -> Aabb3 {
+        Aabb3::new(
+            self.a.min(&self.b).min(&self.c),
+            self.a.max(&self.b).max(&self.c),
+        )
+    }
+
+    /// Barycentric coordinates `(u, v, w)` of the point projected on the triangle plane,
+    /// such that `p = u * a + v * b + w * c`.
+    pub fn barycentric(&self, point: Vec3D) -> (f32, f32, f32) {
+        let v0 = self.b - self.a;
+        let v1 = self.c - self.a;
+        let v2 = point - self.a;
+        let d00 = v0.dot(&v0);
+        let d01 = v0.dot(&v1);
+        let d11 = v1.dot(&v1);
+        let d20 = v2.dot(&v0);
+        let d21 = v2.dot(&v1);
+        let denom = d00 * d11 - d01 * d01;
+        let v = (d11 * d20 - d01 * d21) / denom;
+        let w = (d00 * d21 - d01 * d20) / denom;
+        (1_f32 - v - w, v, w)
+    }
+
+    /// Closest point of the triangle to `point` by Voronoi region classification.
+    pub fn closest_point(&self, point: Vec3D) -> Vec3D {
+        let (a, b, c) = (self.a, self.b, self.c);
+        let ab = b - a;
+        let ac = c - a;
+
+        // vertex region of a
+        let ap = point - a;
+        let d1 = ab.dot(&ap);
+        let d2 = ac.dot(&ap);
+        if d1 <= 0_f32 && d2 <= 0_f32 {
+            return a;
+        }
+
+        // vertex region of b
+        let bp = point - b;
+        let d3 = ab.dot(&bp);
+        let d4 = ac.dot(&bp);
+        if d3 >= 0_f32 && d4 <= d3 {
+            return b;
+        }
+
+        // edge region of ab
+        let vc = d1 * d4 - d3 * d2;
+        if vc <= 0_f32 && d1 >= 0_f32 && d3 <= 0_f32 {
+            return a + ab * (d1 / (d1 - d3));
+        }
+
+        // vertex region of c
+        let cp = point - c;
+        let d5 = ab.dot(&cp);
+        let d6 = ac.dot(&cp);
+        if d6 >= 0_f32 && d5 <= d6 {
+            return c;
+        }
+
+        // edge region of ac
+        let vb = d5 * d2 - d1 * d6;
+        if vb <= 0_f32 && d2 >= 0_f32 && d6 <= 0_f32 {
+            return a + ac * (d2 / (d2 - d6));
+        }
+
+        // edge region of bc
+        let va = d3 * d6 - d5 * d4;
+        if va <= 0_f32 && (d4 - d3) >= 0_f32 && (d5 - d6) >= 0_f32 {
+            return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+        }
+
+        // inside the face
+        let denom = 1_f32 / (va + vb + vc);
+        a + ab * (vb * denom) + ac * (vc * denom)
+    }
+
+    pub fn sq_distance(&self, point: Vec3D) -> f32 {
+        (self.closest_point(point) - point).sq_norm()
+    }
+
+    pub fn distance(&self, point: Vec3D) -> f32 {
+        self.sq_distance(point).sqrt()
+    }
+}
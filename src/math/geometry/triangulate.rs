@@ -0,0 +1,159 @@
+use super::super::Vec2D;
+use super::polygon::{Polygon, Winding};
+
+/// Triangles indexing `points`, which are the outline points followed by the holes points.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Triangulation {
+    pub points: Vec<Vec2D>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+/// Ear clipping triangulation of a simple polygon with holes. The holes are first
+/// connected to the outline with bridge edges (D. Eberly, "Triangulation by Ear Clipping").
+/// The triangles are counter clockwise, whatever the winding of the inputs.
+pub fn triangulate(outline: &Polygon, holes: &[Polygon]) -> Triangulation {
+    let mut points = outline.points.clone();
+    let mut ring: Vec<usize> = (0..outline.len()).collect();
+    if outline.winding() == Winding::Clockwise {
+        ring.reverse();
+    }
+
+    // holes must be clockwise, merged from the rightmost one
+    let mut hole_rings: Vec<Vec<usize>> = Vec::new();
+    for hole in holes.iter().filter(|h| h.len() >= 3) {
+        let start = points.len();
+        points.extend_from_slice(&hole.points);
+        let mut hole_ring: Vec<usize> = (start..points.len()).collect();
+        if hole.winding() == Winding::CounterClockwise {
+            hole_ring.reverse();
+        }
+        hole_rings.push(hole_ring);
+    }
+    let max_x = |r: &Vec<usize>| r.iter().map(|i| points[*i].x).fold(f32::MIN, f32::max);
+    hole_rings.sort_by(|a, b| max_x(b).total_cmp(&max_x(a)));
+    for hole_ring in hole_rings {
+        ring = bridge_hole(&points, ring, &hole_ring);
+    }
+
+    let triangles = clip_ears(&points, ring);
+    Triangulation { points, triangles }
+}
+
+/// Insert the hole in the counter clockwise ring through a bridge from its rightmost vertex.
+fn bridge_hole(points: &[Vec2D], ring: Vec<usize>, hole: &[usize]) -> Vec<usize> {
+    let m_pos = (0..hole.len())
+        .max_by(|a, b| points[hole[*a]].x.total_cmp(&points[hole[*b]].x))
+        .unwrap();
+    let m = points[hole[m_pos]];
+
+    // closest edge hit by a ray going toward +x from m
+    let n = ring.len();
+    let mut best: Option<(f32, usize)> = None;
+    let mut best_x = f32::MAX;
+    for i in 0..n {
+        let a = points[ring[i]];
+        let b = points[ring[(i + 1) % n]];
+        if (a.y > m.y) == (b.y > m.y) {
+            continue;
+        }
+        let x = a.x + (m.y - a.y) / (b.y - a.y) * (b.x - a.x);
+        if x >= m.x && x < best_x {
+            best_x = x;
+            // visible candidate is the endpoint of the edge with the largest x
+            let candidate = if a.x > b.x { i } else { (i + 1) % n };
+            best = Some((x, candidate));
+        }
+    }
+    let (hit_x, mut bridge) = match best {
+        Some(best) => best,
+        // the hole isn't inside the outline, ignore it
+        None => return ring,
+    };
+
+    // a reflex vertex inside the triangle (m, hit, candidate) could hide the candidate,
+    // then take the one with the smallest angle to the ray
+    let hit = Vec2D::new(hit_x, m.y);
+    let p = points[ring[bridge]];
+    let mut best_angle = f32::MAX;
+    for i in 0..n {
+        let v = points[ring[i]];
+        let prev = points[ring[(i + n - 1) % n]];
+        let next = points[ring[(i + 1) % n]];
+        let reflex = (v - prev).det(&(next - v)) <= 0f32;
+        if !reflex || i == bridge || v.x < m.x {
+            continue;
+        }
+        if point_in_triangle(v, m, hit, p) || point_in_triangle(v, m, p, hit) {
+            let d = v - m;
+            let angle = d.y.abs().atan2(d.x);
+            if angle < best_angle {
+                best_angle = angle;
+                bridge = i;
+            }
+        }
+    }
+
+    // ring[..=bridge], hole from m back to m, then ring[bridge..]
+    let mut merged = Vec::with_capacity(n + hole.len() + 2);
+    merged.extend_from_slice(&ring[..=bridge]);
+    for k in 0..=hole.len() {
+        merged.push(hole[(m_pos + k) % hole.len()]);
+    }
+    merged.extend_from_slice(&ring[bridge..]);
+    merged
+}
+
+fn clip_ears(points: &[Vec2D], mut ring: Vec<usize>) -> Vec<[usize; 3]> {
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    while ring.len() > 3 {
+        let n = ring.len();
+        let ear = (0..n).find(|i| is_ear(points, &ring, *i)).unwrap_or_else(|| {
+            // degenerated input, clip the first convex vertex found
+            (0..n)
+                .find(|i| {
+                    let (a, b, c) = corner(points, &ring, *i);
+                    (b - a).det(&(c - b)) > 0f32
+                })
+                .unwrap_or(0)
+        });
+        let prev = ring[(ear + n - 1) % n];
+        let next = ring[(ear + 1) % n];
+        let (a, b, c) = corner(points, &ring, ear);
+        if (b - a).det(&(c - b)).abs() > 0f32 {
+            triangles.push([prev, ring[ear], next]);
+        }
+        ring.remove(ear);
+    }
+    if ring.len() == 3 {
+        triangles.push([ring[0], ring[1], ring[2]]);
+    }
+    triangles
+}
+
+fn corner(points: &[Vec2D], ring: &[usize], i: usize) -> (Vec2D, Vec2D, Vec2D) {
+    let n = ring.len();
+    (
+        points[ring[(i + n - 1) % n]],
+        points[ring[i]],
+        points[ring[(i + 1) % n]],
+    )
+}
+
+fn is_ear(points: &[Vec2D], ring: &[usize], i: usize) -> bool {
+    let (a, b, c) = corner(points, ring, i);
+    if (b - a).det(&(c - b)) <= 0f32 {
+        return false;
+    }
+    let n = ring.len();
+    (0..n)
+        .filter(|k| *k != i && *k != (i + 1) % n && *k != (i + n - 1) % n)
+        .map(|k| points[ring[k]])
+        // bridge vertices are duplicated, they can touch the ear corners
+        .filter(|p| *p != a && *p != b && *p != c)
+        .all(|p| !point_in_triangle(p, a, b, c))
+}
+
+/// Inclusive point in counter clockwise triangle test.
+fn point_in_triangle(p: Vec2D, a: Vec2D, b: Vec2D, c: Vec2D) -> bool {
+    (b - a).det(&(p - a)) >= 0f32 && (c - b).det(&(p - b)) >= 0f32 && (a - c).det(&(p - c)) >= 0f32
+}
@@ -0,0 +1,127 @@
+use super::{Quat, Vec3D, Vec4D};
+
+/// 4x4 matrix stored row by row, `get(i, j)` is row `i` column `j`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Mat4 {
+    pub(crate) m_data: [f32; 16],
+}
+
+impl Mat4 {
+    pub fn new() -> Self {
+        Self { m_data: [0f32; 16] }
+    }
+
+    pub fn from_data(data: [f32; 16]) -> Self {
+        Self { m_data: data }
+    }
+
+    pub fn id() -> Self {
+        let mut m = Self::new();
+        for i in 0..4 {
+            m.m_data[5 * i] = 1f32;
+        }
+        m
+    }
+
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        self.m_data[4 * i + j]
+    }
+
+    pub fn set(&mut self, i: usize, j: usize, value: &f32) {
+        self.m_data[4 * i + j] = *value;
+    }
+
+    pub fn translation(t: Vec3D) -> Self {
+        let mut m = Self::id();
+        m.set(0, 3, &t.x);
+        m.set(1, 3, &t.y);
+        m.set(2, 3, &t.z);
+        m
+    }
+
+    pub fn scaling(s: Vec3D) -> Self {
+        let mut m = Self::id();
+        m.set(0, 0, &s.x);
+        m.set(1, 1, &s.y);
+        m.set(2, 2, &s.z);
+        m
+    }
+
+    /// Rotation of the unit quaternion.
+    pub fn rotation(q: Quat) -> Self {
+        let mut m = Self::id();
+        let axes = [
+            Vec3D::new(1_f32, 0_f32, 0_f32),
+            Vec3D::new(0_f32, 1_f32, 0_f32),
+            Vec3D::new(0_f32, 0_f32, 1_f32),
+        ];
+        for (j, axis) in axes.iter().enumerate() {
+            let column = q.rotate(*axis);
+            m.set(0, j, &column.x);
+            m.set(1, j, &column.y);
+            m.set(2, j, &column.z);
+        }
+        m
+    }
+
+    /// Scales, then rotates, then translates.
+    pub fn from_trs(translation: Vec3D, rotation: Quat, scale: Vec3D) -> Self {
+        Self::translation(translation) * Self::rotation(rotation) * Self::scaling(scale)
+    }
+
+    pub fn transposed(&self) -> Self {
+        let mut m = Self::new();
+        for i in 0..4 {
+            for j in 0..4 {
+                m.set(j, i, &self.get(i, j));
+            }
+        }
+        m
+    }
+
+    /// Values column by column, the layout OpenGL expects.
+    pub fn to_cols_array(&self) -> [f32; 16] {
+        self.transposed().m_data
+    }
+
+    pub fn dot(&self, vec: Vec4D) -> Vec4D {
+        let row = |i: usize| {
+            self.get(i, 0) * vec.x
+                + self.get(i, 1) * vec.y
+                + self.get(i, 2) * vec.z
+                + self.get(i, 3) * vec.w
+        };
+        Vec4D::new(row(0), row(1), row(2), row(3))
+    }
+
+    /// Transforms a point, the translation applies.
+    pub fn transform_point(&self, p: Vec3D) -> Vec3D {
+        let v = self.dot(Vec4D::new(p.x, p.y, p.z, 1_f32));
+        Vec3D::new(v.x, v.y, v.z)
+    }
+}
+
+impl Default for Mat4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::ops::Mul for Mat4 {
+    type Output = Mat4;
+    fn mul(self, other: Self) -> Self {
+        let mut m = Self::new();
+        for i in 0..4 {
+            for j in 0..4 {
+                let v = (0..4).map(|k| self.get(i, k) * other.get(k, j)).sum();
+                m.set(i, j, &v);
+            }
+        }
+        m
+    }
+}
+impl std::ops::MulAssign for Mat4 {
+    fn mul_assign(&mut self, other: Self) {
+        *self = *self * other;
+    }
+}
@@ -1,12 +1,22 @@
+pub mod curves;
+pub mod easing;
+pub mod geometry;
 pub mod mat3;
+pub mod mat4;
 pub mod quat;
+pub mod rng;
+pub mod spatial;
+pub mod tween;
 pub mod vectors;
-//pub mod noise; // TODO make it work with f32
+pub mod noise;
 pub mod fct;
 
 // exposing structs and fct
 pub use mat3::Mat3;
+pub use mat4::Mat4;
 pub use quat::Quat;
+pub use rng::Rng;
+pub use vectors::DVec3D;
 pub use vectors::Vec2D;
 pub use vectors::Vec3D;
 pub use vectors::Vec4D;
@@ -0,0 +1,56 @@
+// Crate imports
+use super::OpenSimplex;
+use crate::math::{Vec2D, Vec3D};
+
+/// Fractal sum of noise, each octave at `lacunarity` times the frequency
+/// and `persistence` times the amplitude of the previous one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fractal {
+    pub octaves: u32,
+    /// Frequency of the first octave.
+    pub frequency: f32,
+    pub lacunarity: f32,
+    pub persistence: f32,
+}
+
+impl Default for Fractal {
+    fn default() -> Self {
+        Self {
+            octaves: 4,
+            frequency: 1_f32,
+            lacunarity: 2_f32,
+            persistence: 0.5,
+        }
+    }
+}
+
+impl Fractal {
+    pub fn new(octaves: u32, frequency: f32) -> Self {
+        Self {
+            octaves,
+            frequency,
+            ..Self::default()
+        }
+    }
+
+    /// Sum of the octaves divided by the sum of the amplitudes, about in `[-1, 1]`.
+    pub fn eval2(&self, noise: &OpenSimplex, point: Vec2D) -> f32 {
+        self.sum(|frequency| noise.eval2(&(point * frequency)))
+    }
+
+    pub fn eval3(&self, noise: &OpenSimplex, point: Vec3D) -> f32 {
+        self.sum(|frequency| noise.eval3(&(point * frequency)))
+    }
+
+    fn sum(&self, mut octave: impl FnMut(f32) -> f32) -> f32 {
+        let (mut frequency, mut amplitude) = (self.frequency, 1_f32);
+        let (mut value, mut total) = (0_f32, 0_f32);
+        for _ in 0..self.octaves.max(1) {
+            value += octave(frequency) * amplitude;
+            total += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.persistence;
+        }
+        value / total
+    }
+}
@@ -1,2 +1,5 @@
+pub mod fractal;
 pub mod open_simplex;
-pub use open_simplex::OpenSimplex;
\ No newline at end of file
+
+pub use fractal::Fractal;
+pub use open_simplex::OpenSimplex;
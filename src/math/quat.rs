@@ -15,6 +15,57 @@ impl Quat {
         }
     }
 
+    pub fn id() -> Self {
+        Self {
+            s: 1_f32,
+            v: Vec3D::null(),
+        }
+    }
+
+    /// Rotation of `angle` radians around `axis`.
+    pub fn from_axis_angle(angle: f32, axis: &Vec3D) -> Self {
+        Self {
+            s: (angle * 0.5).cos(),
+            v: (angle * 0.5).sin() * axis.normalized(),
+        }
+    }
+
+    /// Rotation taking the x, y and z axes to the given orthonormal basis.
+    pub fn from_basis(x: Vec3D, y: Vec3D, z: Vec3D) -> Self {
+        // Shepperd's method on the rotation matrix of columns x, y, z
+        let trace = x.x + y.y + z.z;
+        if trace > 0_f32 {
+            let s = (trace + 1_f32).sqrt() * 2_f32;
+            Self {
+                s: 0.25 * s,
+                v: Vec3D::new((y.z - z.y) / s, (z.x - x.z) / s, (x.y - y.x) / s),
+            }
+        } else if x.x > y.y && x.x > z.z {
+            let s = (1_f32 + x.x - y.y - z.z).sqrt() * 2_f32;
+            Self {
+                s: (y.z - z.y) / s,
+                v: Vec3D::new(0.25 * s, (y.x + x.y) / s, (z.x + x.z) / s),
+            }
+        } else if y.y > z.z {
+            let s = (1_f32 + y.y - x.x - z.z).sqrt() * 2_f32;
+            Self {
+                s: (z.x - x.z) / s,
+                v: Vec3D::new((y.x + x.y) / s, 0.25 * s, (z.y + y.z) / s),
+            }
+        } else {
+            let s = (1_f32 + z.z - x.x - y.y).sqrt() * 2_f32;
+            Self {
+                s: (x.y - y.x) / s,
+                v: Vec3D::new((z.x + x.z) / s, (z.y + y.z) / s, 0.25 * s),
+            }
+        }
+    }
+
+    /// Rotate a vector by this quaternion, which must be of unit norm.
+    pub fn rotate(&self, vec: Vec3D) -> Vec3D {
+        (*self * Quat { s: 0_f32, v: vec } * self.conj()).v
+    }
+
     pub fn sq_norm(&self) -> f32 {
         self.s * self.s + self.v.sq_norm()
     }
@@ -0,0 +1,123 @@
+// PCG32 random number generator (M. E. O'Neill, pcg-random.org).
+// Small and fast with a good statistical quality, every procedural generator of the
+// engine takes one so that a seed always gives the same result.
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const DEFAULT_STREAM: u64 = 1442695040888963407;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+    inc: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// Generators with the same seed but different streams give independent sequences.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        let mut rng = Self {
+            state: 0,
+            inc: (stream << 1) | 1,
+        };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    /// New generator seeded from this one, to give a sub task its own deterministic sequence.
+    pub fn fork(&mut self) -> Self {
+        let seed = self.next_u64();
+        let stream = self.next_u64();
+        Self::with_stream(seed, stream)
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(MULTIPLIER).wrapping_add(self.inc);
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// Uniform float in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 * (1f32 / (1u32 << 24) as f32)
+    }
+
+    /// Uniform integer in `[0, bound)` without modulo bias.
+    pub fn below(&mut self, bound: u32) -> u32 {
+        assert!(bound > 0, "The bound must be positive!");
+        let threshold = bound.wrapping_neg() % bound;
+        loop {
+            let r = self.next_u32();
+            if r >= threshold {
+                return r % bound;
+            }
+        }
+    }
+
+    /// Uniform integer in `[min, max)`.
+    pub fn range_i32(&mut self, min: i32, max: i32) -> i32 {
+        assert!(min < max, "The range is empty!");
+        min.wrapping_add(self.below(max.wrapping_sub(min) as u32) as i32)
+    }
+
+    /// Uniform index in `[min, max)`.
+    pub fn range_usize(&mut self, min: usize, max: usize) -> usize {
+        assert!(min < max, "The range is empty!");
+        min + self.below((max - min) as u32) as usize
+    }
+
+    /// Uniform float in `[min, max)`.
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    /// `true` with the probability `p`.
+    pub fn chance(&mut self, p: f32) -> bool {
+        self.next_f32() < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.range_usize(0, items.len())])
+        }
+    }
+
+    /// Index picked with a probability proportional to its weight, `None` if all weights are null.
+    pub fn weighted_index(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().filter(|w| **w > 0f32).sum();
+        if total <= 0f32 {
+            return None;
+        }
+        let mut r = self.next_f32() * total;
+        for (i, w) in weights.iter().enumerate() {
+            if *w <= 0f32 {
+                continue;
+            }
+            if r < *w {
+                return Some(i);
+            }
+            r -= w;
+        }
+        weights.iter().rposition(|w| *w > 0f32)
+    }
+
+    /// Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range_usize(0, i + 1);
+            items.swap(i, j);
+        }
+    }
+}
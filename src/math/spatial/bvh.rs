@@ -0,0 +1,531 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::{sorted_nearest, Bounds, Handle, OrdF32, Slab};
+
+/// Maximum number of items in a leaf before it gets split.
+const MAX_LEAF_SIZE: usize = 4;
+/// Number of bins used to evaluate the surface area heuristic.
+const SAH_BINS: usize = 12;
+const NONE: usize = usize::MAX;
+
+enum NodeKind {
+    Leaf(Vec<usize>),
+    Internal(usize, usize),
+}
+
+/// Split plane found by the SAH, the items going left being the ones in the
+/// bins before `bin` along `axis`.
+#[derive(Clone, Copy)]
+struct Split {
+    axis: usize,
+    min: f32,
+    scale: f32,
+    bin: usize,
+}
+
+impl Split {
+    fn bin_of(&self, center: &[f32; 3]) -> usize {
+        (((center[self.axis] - self.min) * self.scale) as usize).min(SAH_BINS - 1)
+    }
+}
+
+struct Node<B> {
+    bounds: B,
+    parent: usize,
+    kind: NodeKind,
+}
+
+/// Bounding volume hierarchy built with the surface area heuristic.
+/// Insertions and removals update the tree in place, `rebuild` restores an optimal tree.
+pub struct Bvh<B: Bounds, T> {
+    items: Slab<B, T>,
+    // leaf node holding each item, indexed like the slab
+    leaf_of: Vec<usize>,
+    nodes: Vec<Node<B>>,
+    free_nodes: Vec<usize>,
+    root: usize,
+}
+
+impl<B: Bounds, T> Bvh<B, T> {
+    pub fn new() -> Self {
+        Self {
+            items: Slab::new(),
+            leaf_of: Vec::new(),
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            root: NONE,
+        }
+    }
+
+    /// Build the tree from all the items at once.
+    pub fn build(items: Vec<(B, T)>) -> Self {
+        let mut bvh = Self::new();
+        for (bounds, value) in items {
+            let index = bvh.items.insert(bounds, value);
+            bvh.set_leaf_of(index, NONE);
+        }
+        bvh.rebuild();
+        bvh
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.len() == 0
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.items.get(handle.0).map(|e| &e.1)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.items.get_mut(handle.0).map(|e| &mut e.1)
+    }
+
+    pub fn bounds(&self, handle: Handle) -> Option<B> {
+        self.items.get(handle.0).map(|e| e.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.items.iter().map(|(i, e)| (Handle(i), &e.1))
+    }
+
+    /// Insert an item in the leaf whose bounds grow the least.
+    pub fn insert(&mut self, bounds: B, value: T) -> Handle {
+        let index = self.items.insert(bounds, value);
+        if self.root == NONE {
+            self.root = self.alloc_node(bounds, NONE, NodeKind::Leaf(vec![index]));
+            self.set_leaf_of(index, self.root);
+            return Handle(index);
+        }
+
+        let mut node = self.root;
+        while let NodeKind::Internal(left, right) = self.nodes[node].kind {
+            let cost = |child: usize| {
+                let b = &self.nodes[child].bounds;
+                b.union(&bounds).measure() - b.measure()
+            };
+            node = if cost(left) <= cost(right) { left } else { right };
+        }
+        if let NodeKind::Leaf(leaf) = &mut self.nodes[node].kind {
+            leaf.push(index);
+        }
+        self.set_leaf_of(index, node);
+        self.refit(node);
+        self.split_leaf(node);
+        Handle(index)
+    }
+
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let (_, value) = self.items.remove(handle.0)?;
+        let leaf = self.leaf_of[handle.0];
+        self.leaf_of[handle.0] = NONE;
+        if let NodeKind::Leaf(items) = &mut self.nodes[leaf].kind {
+            items.retain(|i| *i != handle.0);
+            if items.is_empty() {
+                self.remove_leaf(leaf);
+                return Some(value);
+            }
+        }
+        self.refit(leaf);
+        Some(value)
+    }
+
+    /// Move an item, keeping its handle.
+    pub fn update(&mut self, handle: Handle, bounds: B) {
+        if let Some(value) = self.remove(handle) {
+            let new = self.insert(bounds, value);
+            debug_assert_eq!(new, handle);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.leaf_of.clear();
+        self.nodes.clear();
+        self.free_nodes.clear();
+        self.root = NONE;
+    }
+
+    /// Rebuild the whole tree with the binned surface area heuristic.
+    pub fn rebuild(&mut self) {
+        self.nodes.clear();
+        self.free_nodes.clear();
+        self.root = NONE;
+        let mut indices: Vec<usize> = self.items.iter().map(|(i, _)| i).collect();
+        if !indices.is_empty() {
+            self.root = self.build_node(&mut indices, NONE);
+        }
+    }
+
+    /// Handles of the items whose bounds intersect `area`.
+    pub fn query_box(&self, area: &B) -> Vec<Handle> {
+        let mut found = Vec::new();
+        self.traverse(
+            |bounds| bounds.intersects(area),
+            |index, bounds| {
+                if bounds.intersects(area) {
+                    found.push(Handle(index));
+                }
+            },
+        );
+        found
+    }
+
+    /// Handles of the items whose bounds are closer than `radius` to `center`.
+    pub fn query_radius(&self, center: B::Point, radius: f32) -> Vec<Handle> {
+        let sq_radius = radius * radius;
+        let mut found = Vec::new();
+        self.traverse(
+            |bounds| bounds.sq_distance(center) <= sq_radius,
+            |index, bounds| {
+                if bounds.sq_distance(center) <= sq_radius {
+                    found.push(Handle(index));
+                }
+            },
+        );
+        found
+    }
+
+    /// Closest item whose bounds are hit by the ray, with the hit distance.
+    pub fn ray_cast(&self, ray: &B::Ray, max_dist: f32) -> Option<(Handle, f32)> {
+        self.ray_cast_by(ray, max_dist, |_, _| Some(0_f32))
+    }
+
+    /// Closest hit where `hit` gives the exact distance for an item whose bounds are hit
+    /// (for example a ray-triangle test), `None` if the item is missed.
+    pub fn ray_cast_by<F>(&self, ray: &B::Ray, max_dist: f32, mut hit: F) -> Option<(Handle, f32)>
+    where
+        F: FnMut(Handle, &T) -> Option<f32>,
+    {
+        if self.root == NONE {
+            return None;
+        }
+        let mut best: Option<(Handle, f32)> = None;
+        let mut best_t = max_dist;
+        let mut stack = vec![self.root];
+        while let Some(node) = stack.pop() {
+            match self.nodes[node].bounds.ray_cast(ray) {
+                Some(t) if t <= best_t => {}
+                _ => continue,
+            }
+            match &self.nodes[node].kind {
+                NodeKind::Leaf(items) => {
+                    for &index in items {
+                        let (bounds, value) = self.items.get(index).unwrap();
+                        let box_t = match bounds.ray_cast(ray) {
+                            Some(t) if t <= best_t => t,
+                            _ => continue,
+                        };
+                        if let Some(t) = hit(Handle(index), value) {
+                            let t = t.max(box_t);
+                            if t <= best_t {
+                                best_t = t;
+                                best = Some((Handle(index), t));
+                            }
+                        }
+                    }
+                }
+                NodeKind::Internal(left, right) => {
+                    // visit the nearest child first
+                    let tl = self.nodes[*left].bounds.ray_cast(ray).unwrap_or(f32::INFINITY);
+                    let tr = self.nodes[*right].bounds.ray_cast(ray).unwrap_or(f32::INFINITY);
+                    if tl < tr {
+                        stack.push(*right);
+                        stack.push(*left);
+                    } else {
+                        stack.push(*left);
+                        stack.push(*right);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// The `k` items whose bounds are the closest to `point`, sorted by distance.
+    pub fn k_nearest(&self, point: B::Point, k: usize) -> Vec<(Handle, f32)> {
+        let mut found: Vec<(Handle, f32)> = Vec::with_capacity(k + 1);
+        if self.root == NONE || k == 0 {
+            return found;
+        }
+        // best first search over the nodes, found is kept sorted
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((OrdF32(self.nodes[self.root].bounds.sq_distance(point)), self.root)));
+        while let Some(Reverse((OrdF32(dist), node))) = heap.pop() {
+            if found.len() == k && dist > found[k - 1].1 {
+                break;
+            }
+            match &self.nodes[node].kind {
+                NodeKind::Leaf(items) => {
+                    for &index in items {
+                        let d = self.items.bounds(index).sq_distance(point);
+                        found.push((Handle(index), d));
+                    }
+                    found = sorted_nearest(found, k);
+                }
+                NodeKind::Internal(left, right) => {
+                    for &child in [*left, *right].iter() {
+                        let d = self.nodes[child].bounds.sq_distance(point);
+                        heap.push(Reverse((OrdF32(d), child)));
+                    }
+                }
+            }
+        }
+        found.into_iter().map(|(h, d)| (h, d.sqrt())).collect()
+    }
+
+    fn traverse<N, L>(&self, mut visit_node: N, mut visit_item: L)
+    where
+        N: FnMut(&B) -> bool,
+        L: FnMut(usize, &B),
+    {
+        if self.root == NONE {
+            return;
+        }
+        let mut stack = vec![self.root];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if !visit_node(&node.bounds) {
+                continue;
+            }
+            match &node.kind {
+                NodeKind::Leaf(items) => {
+                    for &index in items {
+                        visit_item(index, &self.items.bounds(index));
+                    }
+                }
+                NodeKind::Internal(left, right) => {
+                    stack.push(*left);
+                    stack.push(*right);
+                }
+            }
+        }
+    }
+
+    fn set_leaf_of(&mut self, index: usize, leaf: usize) {
+        if self.leaf_of.len() <= index {
+            self.leaf_of.resize(index + 1, NONE);
+        }
+        self.leaf_of[index] = leaf;
+    }
+
+    fn alloc_node(&mut self, bounds: B, parent: usize, kind: NodeKind) -> usize {
+        let node = Node {
+            bounds,
+            parent,
+            kind,
+        };
+        match self.free_nodes.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn items_bounds(&self, items: &[usize]) -> B {
+        items[1..]
+            .iter()
+            .fold(self.items.bounds(items[0]), |acc, i| acc.union(&self.items.bounds(*i)))
+    }
+
+    fn build_node(&mut self, items: &mut [usize], parent: usize) -> usize {
+        let bounds = self.items_bounds(items);
+        let split = if items.len() > MAX_LEAF_SIZE {
+            self.find_split(items)
+        } else {
+            None
+        };
+
+        match split {
+            None => {
+                let node = self.alloc_node(bounds, parent, NodeKind::Leaf(items.to_vec()));
+                for &index in items.iter() {
+                    self.set_leaf_of(index, node);
+                }
+                node
+            }
+            Some(split) => {
+                let mid = self.split_items(items, split);
+                let node = self.alloc_node(bounds, parent, NodeKind::Internal(NONE, NONE));
+                let (left_items, right_items) = items.split_at_mut(mid);
+                let left = self.build_node(left_items, node);
+                let right = self.build_node(right_items, node);
+                self.nodes[node].kind = NodeKind::Internal(left, right);
+                node
+            }
+        }
+    }
+
+    /// Binned SAH, returns the best split plane if any.
+    fn find_split(&self, items: &[usize]) -> Option<Split> {
+        let centers: Vec<[f32; 3]> = items
+            .iter()
+            .map(|i| B::point_coords(&self.items.bounds(*i).center()))
+            .collect();
+        let mut best: Option<Split> = None;
+        let mut best_cost = f32::INFINITY;
+        for axis in 0..B::DIM {
+            let min = centers.iter().map(|c| c[axis]).fold(f32::INFINITY, f32::min);
+            let max = centers.iter().map(|c| c[axis]).fold(f32::NEG_INFINITY, f32::max);
+            if max - min <= f32::EPSILON {
+                continue;
+            }
+            let scale = SAH_BINS as f32 / (max - min);
+            let binning = Split {
+                axis,
+                min,
+                scale,
+                bin: 0,
+            };
+            let mut bins: Vec<(usize, Option<B>)> = vec![(0, None); SAH_BINS];
+            for (k, &index) in items.iter().enumerate() {
+                let bin = binning.bin_of(&centers[k]);
+                let b = self.items.bounds(index);
+                bins[bin].0 += 1;
+                bins[bin].1 = Some(bins[bin].1.map_or(b, |acc| acc.union(&b)));
+            }
+            for split in 1..SAH_BINS {
+                let side = |range: &[(usize, Option<B>)]| {
+                    range.iter().fold((0, None::<B>), |(n, acc), (count, b)| match b {
+                        Some(b) => (n + count, Some(acc.map_or(*b, |acc| acc.union(b)))),
+                        None => (n, acc),
+                    })
+                };
+                let (nl, bl) = side(&bins[..split]);
+                let (nr, br) = side(&bins[split..]);
+                if nl == 0 || nr == 0 {
+                    continue;
+                }
+                let cost = nl as f32 * bl.unwrap().measure() + nr as f32 * br.unwrap().measure();
+                if cost < best_cost {
+                    best_cost = cost;
+                    best = Some(Split {
+                        bin: split,
+                        ..binning
+                    });
+                }
+            }
+        }
+        best
+    }
+
+    fn split_leaf(&mut self, node: usize) {
+        let items = match &self.nodes[node].kind {
+            NodeKind::Leaf(items) if items.len() > MAX_LEAF_SIZE => items.clone(),
+            _ => return,
+        };
+        let split = match self.find_split(&items) {
+            Some(split) => split,
+            None => return,
+        };
+        let mut items = items;
+        let mid = self.split_items(&mut items, split);
+        let right = items.split_off(mid);
+        let left = items;
+        let left_bounds = self.items_bounds(&left);
+        let right_bounds = self.items_bounds(&right);
+        let left_node = self.alloc_node(left_bounds, node, NodeKind::Leaf(Vec::new()));
+        let right_node = self.alloc_node(right_bounds, node, NodeKind::Leaf(Vec::new()));
+        for &i in left.iter() {
+            self.set_leaf_of(i, left_node);
+        }
+        for &i in right.iter() {
+            self.set_leaf_of(i, right_node);
+        }
+        self.nodes[left_node].kind = NodeKind::Leaf(left);
+        self.nodes[right_node].kind = NodeKind::Leaf(right);
+        self.nodes[node].kind = NodeKind::Internal(left_node, right_node);
+    }
+
+    /// Moves the items left of the split first and returns their count, using
+    /// the same bins as `find_split`. Falls back to the median along the axis
+    /// if a side ends up empty, so both children always get items.
+    fn split_items(&self, items: &mut [usize], split: Split) -> usize {
+        let center = |i: &usize| B::point_coords(&self.items.bounds(*i).center());
+        let mid = partition(items, |i| split.bin_of(&center(i)) < split.bin);
+        if mid > 0 && mid < items.len() {
+            return mid;
+        }
+        let mid = items.len() / 2;
+        items.select_nth_unstable_by(mid, |a, b| {
+            center(a)[split.axis].total_cmp(&center(b)[split.axis])
+        });
+        mid
+    }
+
+    /// Recompute the bounds from `node` up to the root.
+    fn refit(&mut self, mut node: usize) {
+        while node != NONE {
+            let bounds = match &self.nodes[node].kind {
+                NodeKind::Leaf(items) => self.items_bounds(items),
+                NodeKind::Internal(left, right) => {
+                    self.nodes[*left].bounds.union(&self.nodes[*right].bounds)
+                }
+            };
+            self.nodes[node].bounds = bounds;
+            node = self.nodes[node].parent;
+        }
+    }
+
+    /// Remove an empty leaf, its sibling takes the place of their parent.
+    fn remove_leaf(&mut self, leaf: usize) {
+        let parent = self.nodes[leaf].parent;
+        self.free_nodes.push(leaf);
+        if parent == NONE {
+            self.root = NONE;
+            return;
+        }
+        let sibling = match self.nodes[parent].kind {
+            NodeKind::Internal(left, right) => {
+                if left == leaf {
+                    right
+                } else {
+                    left
+                }
+            }
+            NodeKind::Leaf(_) => unreachable!(),
+        };
+        let grand_parent = self.nodes[parent].parent;
+        self.free_nodes.push(parent);
+        self.nodes[sibling].parent = grand_parent;
+        if grand_parent == NONE {
+            self.root = sibling;
+            return;
+        }
+        if let NodeKind::Internal(left, right) = &mut self.nodes[grand_parent].kind {
+            if *left == parent {
+                *left = sibling;
+            } else {
+                *right = sibling;
+            }
+        }
+        self.refit(grand_parent);
+    }
+}
+
+impl<B: Bounds, T> Default for Bvh<B, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// In place partition, returns the number of elements satisfying the predicate.
+fn partition<F: Fn(&usize) -> bool>(items: &mut [usize], pred: F) -> usize {
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if pred(&items[i]) {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
@@ -0,0 +1,262 @@
+use super::{sorted_nearest, Bounds, Handle, Slab};
+
+const NONE: usize = usize::MAX;
+
+struct Node<B> {
+    // cell of the node, the loose bounds are this cell scaled by the looseness
+    cell: B,
+    loose: B,
+    depth: u32,
+    // index of the first of the children, which are allocated together
+    children: usize,
+    items: Vec<usize>,
+}
+
+/// Loose quadtree (with `Aabb2`) or octree (with `Aabb3`).
+/// Each item is stored in a single node whose loose bounds contain it,
+/// the items outside of the world bounds are kept in the root.
+pub struct LooseTree<B: Bounds, T> {
+    items: Slab<B, T>,
+    node_of: Vec<usize>,
+    nodes: Vec<Node<B>>,
+    looseness: f32,
+    max_depth: u32,
+}
+
+impl<B: Bounds, T> LooseTree<B, T> {
+    /// Create a tree covering `world` with a looseness of 2.
+    pub fn new(world: B, max_depth: u32) -> Self {
+        Self::with_looseness(world, max_depth, 2_f32)
+    }
+
+    /// `looseness` is the factor applied to the cells size to get the nodes bounds, it must be >= 1.
+    pub fn with_looseness(world: B, max_depth: u32, looseness: f32) -> Self {
+        Self {
+            items: Slab::new(),
+            node_of: Vec::new(),
+            nodes: vec![Node {
+                cell: world,
+                loose: world.scaled(looseness),
+                depth: 0,
+                children: NONE,
+                items: Vec::new(),
+            }],
+            looseness,
+            max_depth,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.len() == 0
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.items.get(handle.0).map(|e| &e.1)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.items.get_mut(handle.0).map(|e| &mut e.1)
+    }
+
+    pub fn bounds(&self, handle: Handle) -> Option<B> {
+        self.items.get(handle.0).map(|e| e.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.items.iter().map(|(i, e)| (Handle(i), &e.1))
+    }
+
+    pub fn insert(&mut self, bounds: B, value: T) -> Handle {
+        let index = self.items.insert(bounds, value);
+        let node = self.find_node(&bounds);
+        self.nodes[node].items.push(index);
+        if self.node_of.len() <= index {
+            self.node_of.resize(index + 1, NONE);
+        }
+        self.node_of[index] = node;
+        Handle(index)
+    }
+
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let (_, value) = self.items.remove(handle.0)?;
+        let node = self.node_of[handle.0];
+        self.nodes[node].items.retain(|i| *i != handle.0);
+        self.node_of[handle.0] = NONE;
+        Some(value)
+    }
+
+    /// Move an item, keeping its handle.
+    pub fn update(&mut self, handle: Handle, bounds: B) {
+        if let Some(value) = self.remove(handle) {
+            let new = self.insert(bounds, value);
+            debug_assert_eq!(new, handle);
+        }
+    }
+
+    /// Handles of the items whose bounds intersect `area`.
+    pub fn query_box(&self, area: &B) -> Vec<Handle> {
+        let mut found = Vec::new();
+        self.traverse(
+            |loose| loose.intersects(area),
+            |index, bounds| {
+                if bounds.intersects(area) {
+                    found.push(Handle(index));
+                }
+            },
+        );
+        found
+    }
+
+    /// Handles of the items whose bounds are closer than `radius` to `center`.
+    pub fn query_radius(&self, center: B::Point, radius: f32) -> Vec<Handle> {
+        let sq_radius = radius * radius;
+        let mut found = Vec::new();
+        self.traverse(
+            |loose| loose.sq_distance(center) <= sq_radius,
+            |index, bounds| {
+                if bounds.sq_distance(center) <= sq_radius {
+                    found.push(Handle(index));
+                }
+            },
+        );
+        found
+    }
+
+    /// Closest item whose bounds are hit by the ray, with the hit distance.
+    pub fn ray_cast(&self, ray: &B::Ray, max_dist: f32) -> Option<(Handle, f32)> {
+        self.ray_cast_by(ray, max_dist, |_, _| Some(0_f32))
+    }
+
+    /// Closest hit where `hit` gives the exact distance for an item whose bounds are hit,
+    /// `None` if the item is missed.
+    pub fn ray_cast_by<F>(&self, ray: &B::Ray, max_dist: f32, mut hit: F) -> Option<(Handle, f32)>
+    where
+        F: FnMut(Handle, &T) -> Option<f32>,
+    {
+        let mut best: Option<(Handle, f32)> = None;
+        let mut best_t = max_dist;
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            // the items outside of the world are stored in the root
+            if node.depth > 0 {
+                match node.loose.ray_cast(ray) {
+                    Some(t) if t <= best_t => {}
+                    _ => continue,
+                }
+            }
+            for &index in node.items.iter() {
+                let (bounds, value) = self.items.get(index).unwrap();
+                let box_t = match bounds.ray_cast(ray) {
+                    Some(t) if t <= best_t => t,
+                    _ => continue,
+                };
+                if let Some(t) = hit(Handle(index), value) {
+                    let t = t.max(box_t);
+                    if t <= best_t {
+                        best_t = t;
+                        best = Some((Handle(index), t));
+                    }
+                }
+            }
+            if node.children != NONE {
+                stack.extend(node.children..node.children + (1 << B::DIM));
+            }
+        }
+        best
+    }
+
+    /// The `k` items whose bounds are the closest to `point`, sorted by distance.
+    pub fn k_nearest(&self, point: B::Point, k: usize) -> Vec<(Handle, f32)> {
+        let mut found: Vec<(Handle, f32)> = Vec::new();
+        if k == 0 {
+            return found;
+        }
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.depth > 0
+                && found.len() == k
+                && node.loose.sq_distance(point) > found[k - 1].1
+            {
+                continue;
+            }
+            for &index in node.items.iter() {
+                found.push((Handle(index), self.items.bounds(index).sq_distance(point)));
+            }
+            found = sorted_nearest(found, k);
+            if node.children != NONE {
+                // push the farthest children first to visit the nearest ones first
+                let mut children: Vec<usize> = (node.children..node.children + (1 << B::DIM)).collect();
+                children.sort_by(|a, b| {
+                    let da = self.nodes[*a].loose.sq_distance(point);
+                    let db = self.nodes[*b].loose.sq_distance(point);
+                    db.total_cmp(&da)
+                });
+                stack.extend(children);
+            }
+        }
+        found.into_iter().map(|(h, d)| (h, d.sqrt())).collect()
+    }
+
+    fn traverse<N, L>(&self, mut visit_node: N, mut visit_item: L)
+    where
+        N: FnMut(&B) -> bool,
+        L: FnMut(usize, &B),
+    {
+        let mut stack = vec![0];
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.depth > 0 && !visit_node(&node.loose) {
+                continue;
+            }
+            for &index in node.items.iter() {
+                visit_item(index, &self.items.bounds(index));
+            }
+            if node.children != NONE {
+                stack.extend(node.children..node.children + (1 << B::DIM));
+            }
+        }
+    }
+
+    /// Deepest node whose loose bounds contain the item, creating the nodes on the way.
+    fn find_node(&mut self, bounds: &B) -> usize {
+        let center = bounds.center();
+        let mut node = 0;
+        if !self.nodes[0].cell.contains_bounds(&B::from_point(center)) {
+            return 0;
+        }
+        while self.nodes[node].depth < self.max_depth {
+            let child_index = self.nodes[node].cell.child_index(center);
+            let child_cell = self.nodes[node].cell.child(child_index);
+            if !child_cell.scaled(self.looseness).contains_bounds(bounds) {
+                break;
+            }
+            if self.nodes[node].children == NONE {
+                self.split(node);
+            }
+            node = self.nodes[node].children + child_index;
+        }
+        node
+    }
+
+    fn split(&mut self, node: usize) {
+        let first = self.nodes.len();
+        let depth = self.nodes[node].depth + 1;
+        for i in 0..(1 << B::DIM) {
+            let cell = self.nodes[node].cell.child(i);
+            self.nodes.push(Node {
+                cell,
+                loose: cell.scaled(self.looseness),
+                depth,
+                children: NONE,
+                items: Vec::new(),
+            });
+        }
+        self.nodes[node].children = first;
+    }
+}
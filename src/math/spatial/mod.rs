@@ -0,0 +1,290 @@
+pub mod bvh;
+pub mod loose_tree;
+pub mod spatial_hash;
+
+pub use bvh::Bvh;
+pub use loose_tree::LooseTree;
+pub use spatial_hash::SpatialHash;
+
+use super::geometry::{Aabb2, Aabb3, Ray, Ray2};
+use super::{Vec2D, Vec3D};
+
+pub type Bvh2<T> = Bvh<Aabb2, T>;
+pub type Bvh3<T> = Bvh<Aabb3, T>;
+pub type QuadTree<T> = LooseTree<Aabb2, T>;
+pub type Octree<T> = LooseTree<Aabb3, T>;
+pub type SpatialHash2<T> = SpatialHash<Aabb2, T>;
+pub type SpatialHash3<T> = SpatialHash<Aabb3, T>;
+
+/// Handle to an item stored in one of the spatial structures, used to get or remove it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle(pub(crate) usize);
+
+impl Handle {
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Bounding volume the spatial structures are built on.
+/// Implemented for `Aabb2` (points are `Vec2D`) and `Aabb3` (points are `Vec3D`).
+pub trait Bounds: Copy {
+    type Point: Copy;
+    type Ray: Copy;
+    /// Number of dimensions.
+    const DIM: usize;
+
+    fn from_point(point: Self::Point) -> Self;
+    /// Box containing the ball of center `point`.
+    fn around(point: Self::Point, radius: f32) -> Self;
+    fn union(&self, other: &Self) -> Self;
+    fn intersects(&self, other: &Self) -> bool;
+    fn contains_bounds(&self, other: &Self) -> bool;
+    /// Measure used by the surface area heuristic (surface area in 3D, perimeter in 2D).
+    fn measure(&self) -> f32;
+    fn center(&self) -> Self::Point;
+    fn sq_distance(&self, point: Self::Point) -> f32;
+    fn ray_cast(&self, ray: &Self::Ray) -> Option<f32>;
+    /// Box with the same center and its size multiplied by `factor`.
+    fn scaled(&self, factor: f32) -> Self;
+    /// Sub-box of index `i` when splitting in half along every axis.
+    fn child(&self, i: usize) -> Self;
+    /// Index of the child containing the point.
+    fn child_index(&self, point: Self::Point) -> usize;
+
+    fn point_coords(point: &Self::Point) -> [f32; 3];
+    fn ray_coords(ray: &Self::Ray) -> ([f32; 3], [f32; 3]);
+    fn min_coords(&self) -> [f32; 3];
+    fn max_coords(&self) -> [f32; 3];
+
+    fn sq_distance_points(a: Self::Point, b: Self::Point) -> f32 {
+        let (a, b) = (Self::point_coords(&a), Self::point_coords(&b));
+        (0..3).map(|i| (a[i] - b[i]) * (a[i] - b[i])).sum()
+    }
+}
+
+impl Bounds for Aabb2 {
+    type Point = Vec2D;
+    type Ray = Ray2;
+    const DIM: usize = 2;
+
+    fn from_point(point: Vec2D) -> Self {
+        Aabb2::new(point, point)
+    }
+    fn around(point: Vec2D, radius: f32) -> Self {
+        Aabb2::from_center(point, Vec2D::new(radius, radius))
+    }
+    fn union(&self, other: &Self) -> Self {
+        Aabb2::union(self, other)
+    }
+    fn intersects(&self, other: &Self) -> bool {
+        Aabb2::intersects(self, other)
+    }
+    fn contains_bounds(&self, other: &Self) -> bool {
+        self.contains_aabb(other)
+    }
+    fn measure(&self) -> f32 {
+        self.perimeter()
+    }
+    fn center(&self) -> Vec2D {
+        Aabb2::center(self)
+    }
+    fn sq_distance(&self, point: Vec2D) -> f32 {
+        Aabb2::sq_distance(self, point)
+    }
+    fn ray_cast(&self, ray: &Ray2) -> Option<f32> {
+        ray.intersect_aabb(self)
+    }
+    fn scaled(&self, factor: f32) -> Self {
+        Aabb2::from_center(self.center(), self.half_extents() * factor)
+    }
+    fn child(&self, i: usize) -> Self {
+        let c = self.center();
+        Aabb2::new(
+            Vec2D::new(
+                if i & 1 == 0 { self.min.x } else { c.x },
+                if i & 2 == 0 { self.min.y } else { c.y },
+            ),
+            Vec2D::new(
+                if i & 1 == 0 { c.x } else { self.max.x },
+                if i & 2 == 0 { c.y } else { self.max.y },
+            ),
+        )
+    }
+    fn child_index(&self, point: Vec2D) -> usize {
+        let c = self.center();
+        (point.x >= c.x) as usize | ((point.y >= c.y) as usize) << 1
+    }
+    fn point_coords(point: &Vec2D) -> [f32; 3] {
+        [point.x, point.y, 0_f32]
+    }
+    fn ray_coords(ray: &Ray2) -> ([f32; 3], [f32; 3]) {
+        (Self::point_coords(&ray.origin), Self::point_coords(&ray.dir))
+    }
+    fn min_coords(&self) -> [f32; 3] {
+        Self::point_coords(&self.min)
+    }
+    fn max_coords(&self) -> [f32; 3] {
+        Self::point_coords(&self.max)
+    }
+}
+
+impl Bounds for Aabb3 {
+    type Point = Vec3D;
+    type Ray = Ray;
+    const DIM: usize = 3;
+
+    fn from_point(point: Vec3D) -> Self {
+        Aabb3::new(point, point)
+    }
+    fn around(point: Vec3D, radius: f32) -> Self {
+        Aabb3::from_center(point, Vec3D::new(radius, radius, radius))
+    }
+    fn union(&self, other: &Self) -> Self {
+        Aabb3::union(self, other)
+    }
+    fn intersects(&self, other: &Self) -> bool {
+        Aabb3::intersects(self, other)
+    }
+    fn contains_bounds(&self, other: &Self) -> bool {
+        self.contains_aabb(other)
+    }
+    fn measure(&self) -> f32 {
+        self.surface_area()
+    }
+    fn center(&self) -> Vec3D {
+        Aabb3::center(self)
+    }
+    fn sq_distance(&self, point: Vec3D) -> f32 {
+        Aabb3::sq_distance(self, point)
+    }
+    fn ray_cast(&self, ray: &Ray) -> Option<f32> {
+        ray.intersect_aabb(self)
+    }
+    fn scaled(&self, factor: f32) -> Self {
+        Aabb3::from_center(self.center(), self.half_extents() * factor)
+    }
+    fn child(&self, i: usize) -> Self {
+        let c = self.center();
+        Aabb3::new(
+            Vec3D::new(
+                if i & 1 == 0 { self.min.x } else { c.x },
+                if i & 2 == 0 { self.min.y } else { c.y },
+                if i & 4 == 0 { self.min.z } else { c.z },
+            ),
+            Vec3D::new(
+                if i & 1 == 0 { c.x } else { self.max.x },
+                if i & 2 == 0 { c.y } else { self.max.y },
+                if i & 4 == 0 { c.z } else { self.max.z },
+            ),
+        )
+    }
+    fn child_index(&self, point: Vec3D) -> usize {
+        let c = self.center();
+        (point.x >= c.x) as usize | ((point.y >= c.y) as usize) << 1 | ((point.z >= c.z) as usize) << 2
+    }
+    fn point_coords(point: &Vec3D) -> [f32; 3] {
+        [point.x, point.y, point.z]
+    }
+    fn ray_coords(ray: &Ray) -> ([f32; 3], [f32; 3]) {
+        (Self::point_coords(&ray.origin), Self::point_coords(&ray.dir))
+    }
+    fn min_coords(&self) -> [f32; 3] {
+        Self::point_coords(&self.min)
+    }
+    fn max_coords(&self) -> [f32; 3] {
+        Self::point_coords(&self.max)
+    }
+}
+
+/// Storage of the items with stable indices, shared by the spatial structures.
+pub(crate) struct Slab<B, T> {
+    entries: Vec<Option<(B, T)>>,
+    free: Vec<usize>,
+    len: usize,
+}
+
+impl<B: Copy, T> Slab<B, T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub(crate) fn insert(&mut self, bounds: B, value: T) -> usize {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                self.entries[index] = Some((bounds, value));
+                index
+            }
+            None => {
+                self.entries.push(Some((bounds, value)));
+                self.entries.len() - 1
+            }
+        }
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) -> Option<(B, T)> {
+        let entry = self.entries.get_mut(index)?.take()?;
+        self.free.push(index);
+        self.len -= 1;
+        Some(entry)
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&(B, T)> {
+        self.entries.get(index)?.as_ref()
+    }
+
+    pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut (B, T)> {
+        self.entries.get_mut(index)?.as_mut()
+    }
+
+    pub(crate) fn bounds(&self, index: usize) -> B {
+        self.entries[index].as_ref().unwrap().0
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (usize, &(B, T))> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| e.as_ref().map(|e| (i, e)))
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.free.clear();
+        self.len = 0;
+    }
+}
+
+/// Float with a total order, for the priority queues of the nearest neighbour searches.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct OrdF32(pub(crate) f32);
+
+impl Eq for OrdF32 {}
+
+impl PartialOrd for OrdF32 {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdF32 {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// Keep the `k` nearest `(handle, sq distance)` pairs sorted by distance.
+pub(crate) fn sorted_nearest(mut found: Vec<(Handle, f32)>, k: usize) -> Vec<(Handle, f32)> {
+    found.sort_by_key(|a| OrdF32(a.1));
+    found.truncate(k);
+    found
+}
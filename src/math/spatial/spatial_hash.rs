@@ -0,0 +1,333 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{sorted_nearest, Bounds, Handle, Slab};
+
+type Cell = [i32; 3];
+
+/// Uniform grid hashing the items into every cell their bounds overlap.
+/// Best suited to items of a size close to the cell size.
+pub struct SpatialHash<B: Bounds, T> {
+    items: Slab<B, T>,
+    cells: HashMap<Cell, Vec<usize>>,
+    cell_size: f32,
+    // smallest and largest cells ever holding an item, the queries don't look outside
+    lo: Cell,
+    hi: Cell,
+}
+
+impl<B: Bounds, T> SpatialHash<B, T> {
+    pub fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0_f32, "The cell size must be positive!");
+        Self {
+            items: Slab::new(),
+            cells: HashMap::new(),
+            cell_size,
+            lo: [i32::MAX; 3],
+            hi: [i32::MIN; 3],
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.len() == 0
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.items.get(handle.0).map(|e| &e.1)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.items.get_mut(handle.0).map(|e| &mut e.1)
+    }
+
+    pub fn bounds(&self, handle: Handle) -> Option<B> {
+        self.items.get(handle.0).map(|e| e.0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.items.iter().map(|(i, e)| (Handle(i), &e.1))
+    }
+
+    pub fn insert(&mut self, bounds: B, value: T) -> Handle {
+        let index = self.items.insert(bounds, value);
+        let (min, max) = self.cell_range(&bounds);
+        for axis in 0..3 {
+            self.lo[axis] = self.lo[axis].min(min[axis]);
+            self.hi[axis] = self.hi[axis].max(max[axis]);
+        }
+        for cell in self.cells_of(&bounds) {
+            self.cells.entry(cell).or_default().push(index);
+        }
+        Handle(index)
+    }
+
+    pub fn remove(&mut self, handle: Handle) -> Option<T> {
+        let (bounds, value) = self.items.remove(handle.0)?;
+        for cell in self.cells_of(&bounds) {
+            if let Some(items) = self.cells.get_mut(&cell) {
+                items.retain(|i| *i != handle.0);
+                if items.is_empty() {
+                    self.cells.remove(&cell);
+                }
+            }
+        }
+        Some(value)
+    }
+
+    /// Move an item, keeping its handle.
+    pub fn update(&mut self, handle: Handle, bounds: B) {
+        if let Some(value) = self.remove(handle) {
+            let new = self.insert(bounds, value);
+            debug_assert_eq!(new, handle);
+        }
+    }
+
+    /// Handles of the items whose bounds intersect `area`.
+    pub fn query_box(&self, area: &B) -> Vec<Handle> {
+        self.candidates(area)
+            .into_iter()
+            .filter(|i| self.items.bounds(*i).intersects(area))
+            .map(Handle)
+            .collect()
+    }
+
+    /// Handles of the items whose bounds are closer than `radius` to `center`.
+    pub fn query_radius(&self, center: B::Point, radius: f32) -> Vec<Handle> {
+        let sq_radius = radius * radius;
+        self.candidates(&B::around(center, radius))
+            .into_iter()
+            .filter(|i| self.items.bounds(*i).sq_distance(center) <= sq_radius)
+            .map(Handle)
+            .collect()
+    }
+
+    /// Closest item whose bounds are hit by the ray, with the hit distance.
+    pub fn ray_cast(&self, ray: &B::Ray, max_dist: f32) -> Option<(Handle, f32)> {
+        self.ray_cast_by(ray, max_dist, |_, _| Some(0_f32))
+    }
+
+    /// Closest hit where `hit` gives the exact distance for an item whose bounds are hit,
+    /// `None` if the item is missed. The cells are walked in order along the ray (3D DDA),
+    /// until the ray leaves the box of the occupied cells, so `max_dist` may be infinite.
+    pub fn ray_cast_by<F>(&self, ray: &B::Ray, max_dist: f32, mut hit: F) -> Option<(Handle, f32)>
+    where
+        F: FnMut(Handle, &T) -> Option<f32>,
+    {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let (lo, hi) = (self.lo, self.hi);
+        let (origin, dir) = B::ray_coords(ray);
+        let mut cell = self.cell_of(origin);
+        let mut step = [0_i32; 3];
+        let mut t_max = [f32::INFINITY; 3];
+        let mut t_delta = [f32::INFINITY; 3];
+        for axis in 0..3 {
+            if dir[axis] > 0_f32 {
+                step[axis] = 1;
+                let boundary = (cell[axis] + 1) as f32 * self.cell_size;
+                t_max[axis] = (boundary - origin[axis]) / dir[axis];
+                t_delta[axis] = self.cell_size / dir[axis];
+            } else if dir[axis] < 0_f32 {
+                step[axis] = -1;
+                let boundary = cell[axis] as f32 * self.cell_size;
+                t_max[axis] = (boundary - origin[axis]) / dir[axis];
+                t_delta[axis] = -self.cell_size / dir[axis];
+            }
+        }
+
+        // a ray parallel to an axis outside of the occupied cells can't hit anything
+        if (0..3).any(|axis| step[axis] == 0 && (cell[axis] < lo[axis] || cell[axis] > hi[axis])) {
+            return None;
+        }
+
+        let mut best: Option<(Handle, f32)> = None;
+        let mut best_t = max_dist;
+        let mut visited = HashSet::new();
+        let mut t_cell = 0_f32;
+        while t_cell <= best_t {
+            if let Some(items) = self.cells.get(&cell) {
+                for &index in items {
+                    if !visited.insert(index) {
+                        continue;
+                    }
+                    let (bounds, value) = self.items.get(index).unwrap();
+                    let box_t = match bounds.ray_cast(ray) {
+                        Some(t) if t <= best_t => t,
+                        _ => continue,
+                    };
+                    if let Some(t) = hit(Handle(index), value) {
+                        let t = t.max(box_t);
+                        if t <= best_t {
+                            best_t = t;
+                            best = Some((Handle(index), t));
+                        }
+                    }
+                }
+            }
+            // step to the next cell along the axis with the closest boundary
+            let axis = (0..3)
+                .min_by(|a, b| t_max[*a].total_cmp(&t_max[*b]))
+                .unwrap();
+            if t_max[axis] == f32::INFINITY {
+                break;
+            }
+            t_cell = t_max[axis];
+            cell[axis] += step[axis];
+            t_max[axis] += t_delta[axis];
+            // moving away from the occupied cells, nothing left to hit
+            let past_hi = step[axis] > 0 && cell[axis] > hi[axis];
+            if past_hi || (step[axis] < 0 && cell[axis] < lo[axis]) {
+                break;
+            }
+        }
+        best
+    }
+
+    /// The `k` items whose bounds are the closest to `point`, sorted by distance.
+    /// The occupied cells are searched ring by ring around the cell of `point`,
+    /// until the items left can't be closer than the `k` found.
+    pub fn k_nearest(&self, point: B::Point, k: usize) -> Vec<(Handle, f32)> {
+        if k == 0 || self.cells.is_empty() {
+            return Vec::new();
+        }
+        let coords = B::point_coords(&point);
+        let cell = self.cell_of(coords);
+        let center = [cell[0] as i64, cell[1] as i64, cell[2] as i64];
+        let (lo, hi) = (self.lo, self.hi);
+        // the rings closer than the occupied cells are empty, the last one holds them all
+        let gap = |axis: usize| (lo[axis] as i64 - center[axis]).max(center[axis] - hi[axis] as i64);
+        let reach = |axis: usize| (center[axis] - lo[axis] as i64).max(hi[axis] as i64 - center[axis]);
+        let mut ring = (0..3).map(gap).max().unwrap().max(0);
+        let last = (0..3).map(reach).max().unwrap();
+
+        let mut seen = HashSet::new();
+        let mut found = Vec::new();
+        loop {
+            self.visit_ring(center, ring, |items| {
+                for &index in items {
+                    if seen.insert(index) {
+                        let sq_distance = self.items.bounds(index).sq_distance(point);
+                        found.push((Handle(index), sq_distance));
+                    }
+                }
+            });
+            if ring >= last {
+                break;
+            }
+            // the items not found yet are in the next rings, outside of the searched cells
+            if found.len() >= k {
+                found = sorted_nearest(found, k);
+                let reached = (0..B::DIM)
+                    .map(|axis| {
+                        let min = (center[axis] - ring) as f32 * self.cell_size;
+                        let max = (center[axis] + ring + 1) as f32 * self.cell_size;
+                        (coords[axis] - min).min(max - coords[axis])
+                    })
+                    .fold(f32::INFINITY, f32::min);
+                if found[k - 1].1 <= reached * reached {
+                    break;
+                }
+            }
+            ring += 1;
+        }
+        sorted_nearest(found, k)
+            .into_iter()
+            .map(|(h, d)| (h, d.sqrt()))
+            .collect()
+    }
+
+    /// Calls `visit` with the items of the occupied cells `ring` cells away from `center`.
+    fn visit_ring<F: FnMut(&[usize])>(&self, center: [i64; 3], ring: i64, mut visit: F) {
+        let clamp = |axis: usize| {
+            let min = (center[axis] - ring).max(self.lo[axis] as i64);
+            let max = (center[axis] + ring).min(self.hi[axis] as i64);
+            min..=max
+        };
+        let on_ring = |v: i64, axis: usize| (v - center[axis]).abs() == ring;
+        let mut cell = |x: i64, y: i64, z: i64| {
+            if let Some(items) = self.cells.get(&[x as i32, y as i32, z as i32]) {
+                visit(items);
+            }
+        };
+        for x in clamp(0) {
+            for y in clamp(1) {
+                let zs = clamp(2);
+                if on_ring(x, 0) || on_ring(y, 1) {
+                    for z in zs {
+                        cell(x, y, z);
+                    }
+                } else {
+                    // inside the ring on x and y, only its two faces along z
+                    for &z in [center[2] - ring, center[2] + ring].iter() {
+                        if zs.contains(&z) {
+                            cell(x, y, z);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn cell_of(&self, coords: [f32; 3]) -> Cell {
+        let mut cell = [0; 3];
+        for axis in 0..B::DIM {
+            cell[axis] = (coords[axis] / self.cell_size).floor() as i32;
+        }
+        cell
+    }
+
+    /// Smallest and largest cells overlapped by `bounds`.
+    fn cell_range(&self, bounds: &B) -> (Cell, Cell) {
+        (
+            self.cell_of(bounds.min_coords()),
+            self.cell_of(bounds.max_coords()),
+        )
+    }
+
+    fn cells_of(&self, bounds: &B) -> Vec<Cell> {
+        let (min, max) = self.cell_range(bounds);
+        let mut cells = Vec::new();
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    cells.push([x, y, z]);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Items in the cells overlapped by `area`, without duplicates.
+    /// Only the occupied cells are looked at, however large `area` is.
+    fn candidates(&self, area: &B) -> Vec<usize> {
+        let mut seen = HashSet::new();
+        let mut found = Vec::new();
+        let (mut min, mut max) = self.cell_range(area);
+        for axis in 0..3 {
+            min[axis] = min[axis].max(self.lo[axis]);
+            max[axis] = max[axis].min(self.hi[axis]);
+        }
+        for x in min[0]..=max[0] {
+            for y in min[1]..=max[1] {
+                for z in min[2]..=max[2] {
+                    if let Some(items) = self.cells.get(&[x, y, z]) {
+                        for &index in items {
+                            if seen.insert(index) {
+                                found.push(index);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,269 @@
+use super::easing::Easing;
+pub use super::fct::lerp;
+use std::ops::{Add, Mul, Sub};
+
+/// Types that can be interpolated, implemented for `f32`, the vectors and `Quat`.
+pub trait Tweenable: Copy + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self> {}
+
+impl<T> Tweenable for T where T: Copy + Add<Output = T> + Sub<Output = T> + Mul<f32, Output = T> {}
+
+/// How many times a tween or a sequence is played.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Repeat {
+    Once,
+    /// `Times(0)` never plays, the value staying at the start.
+    Times(u32),
+    Forever,
+}
+
+impl Repeat {
+    fn count(&self) -> Option<u32> {
+        match self {
+            Repeat::Once => Some(1),
+            Repeat::Times(n) => Some(*n),
+            Repeat::Forever => None,
+        }
+    }
+}
+
+/// Interpolation from `from` to `to` over `duration` seconds, driven by `update`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tween<T> {
+    pub from: T,
+    pub to: T,
+    pub duration: f32,
+    pub delay: f32,
+    pub easing: Easing,
+    pub repeat: Repeat,
+    /// Play every other cycle backward.
+    pub ping_pong: bool,
+    elapsed: f32,
+}
+
+impl<T: Tweenable> Tween<T> {
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            delay: 0f32,
+            easing: Easing::Linear,
+            repeat: Repeat::Once,
+            ping_pong: false,
+            elapsed: 0f32,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Wait `delay` seconds before starting, only once.
+    pub fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    pub fn with_ping_pong(mut self, ping_pong: bool) -> Self {
+        self.ping_pong = ping_pong;
+        self
+    }
+
+    /// Advance the tween by `dt` seconds and get the new value.
+    pub fn update(&mut self, dt: f32) -> T {
+        self.elapsed += dt;
+        self.value()
+    }
+
+    pub fn value(&self) -> T {
+        self.value_at(self.elapsed)
+    }
+
+    /// Value `time` seconds after the start, delay included.
+    pub fn value_at(&self, time: f32) -> T {
+        let local = time - self.delay;
+        if local <= 0f32 {
+            return self.from;
+        }
+        if self.duration <= 0f32 {
+            return self.end_value();
+        }
+        let cycle = (local / self.duration).floor() as u32;
+        if let Some(count) = self.repeat.count() {
+            if cycle >= count {
+                return self.end_value();
+            }
+        }
+        let t = (local - cycle as f32 * self.duration) / self.duration;
+        let t = if self.ping_pong && cycle % 2 == 1 {
+            1f32 - t
+        } else {
+            t
+        };
+        lerp(self.from, self.to, self.easing.apply(t))
+    }
+
+    /// Total time including the delay, `None` when repeating forever.
+    pub fn total_duration(&self) -> Option<f32> {
+        self.repeat
+            .count()
+            .map(|count| self.delay + self.duration * count as f32)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.total_duration().is_some_and(|total| self.elapsed >= total)
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0f32;
+    }
+
+    fn end_value(&self) -> T {
+        match self.repeat.count() {
+            Some(0) => self.from,
+            Some(count) if self.ping_pong && count % 2 == 0 => self.from,
+            _ => self.to,
+        }
+    }
+}
+
+/// Tweens played one after the other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequence<T> {
+    tweens: Vec<Tween<T>>,
+    pub repeat: Repeat,
+    elapsed: f32,
+}
+
+impl<T: Tweenable> Sequence<T> {
+    /// The tweens must not repeat forever, or the ones after them would never play.
+    pub fn new(tweens: Vec<Tween<T>>) -> Self {
+        assert!(!tweens.is_empty(), "A sequence needs at least one tween!");
+        Self {
+            tweens,
+            repeat: Repeat::Once,
+            elapsed: 0f32,
+        }
+    }
+
+    pub fn with_repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Append a tween, usually starting from the end value of the previous one.
+    pub fn then(mut self, tween: Tween<T>) -> Self {
+        self.tweens.push(tween);
+        self
+    }
+
+    /// Duration of one play of the sequence.
+    pub fn cycle_duration(&self) -> f32 {
+        self.tweens
+            .iter()
+            .map(|t| t.total_duration().unwrap_or(f32::INFINITY))
+            .sum()
+    }
+
+    pub fn total_duration(&self) -> Option<f32> {
+        self.repeat
+            .count()
+            .map(|count| self.cycle_duration() * count as f32)
+    }
+
+    pub fn update(&mut self, dt: f32) -> T {
+        self.elapsed += dt;
+        self.value()
+    }
+
+    pub fn value(&self) -> T {
+        self.value_at(self.elapsed)
+    }
+
+    pub fn value_at(&self, time: f32) -> T {
+        let cycle_duration = self.cycle_duration();
+        let last = self.tweens.last().unwrap();
+        if self.repeat == Repeat::Times(0) {
+            return self.tweens[0].value_at(0f32);
+        }
+        if cycle_duration <= 0f32 {
+            return last.value_at(f32::INFINITY);
+        }
+        if let Some(total) = self.total_duration() {
+            if time >= total {
+                return last.value_at(f32::INFINITY);
+            }
+        }
+        let mut local = if cycle_duration.is_finite() {
+            time % cycle_duration
+        } else {
+            time
+        };
+        for tween in self.tweens.iter() {
+            let duration = tween.total_duration().unwrap_or(f32::INFINITY);
+            if local < duration {
+                return tween.value_at(local);
+            }
+            local -= duration;
+        }
+        last.value_at(f32::INFINITY)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.total_duration().is_some_and(|total| self.elapsed >= total)
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = 0f32;
+    }
+}
+
+/// Critically damped spring following a moving target without overshooting.
+/// `smooth_time` is roughly the time needed to reach the target.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Spring<T> {
+    pub value: T,
+    pub velocity: T,
+    pub smooth_time: f32,
+}
+
+impl<T: Tweenable> Spring<T> {
+    pub fn new(value: T, smooth_time: f32) -> Self {
+        Self {
+            value,
+            velocity: value * 0f32,
+            smooth_time,
+        }
+    }
+
+    /// Move toward `target` by `dt` seconds and get the new value.
+    pub fn update(&mut self, target: T, dt: f32) -> T {
+        let (value, velocity) = smooth_damp(self.value, target, self.velocity, self.smooth_time, dt);
+        self.value = value;
+        self.velocity = velocity;
+        value
+    }
+}
+
+/// One step of a critically damped spring, returns the new value and velocity.
+/// Uses the approximation of the exponential from Game Programming Gems 4, chapter 1.10.
+pub fn smooth_damp<T: Tweenable>(current: T, target: T, velocity: T, smooth_time: f32, dt: f32) -> (T, T) {
+    let omega = 2f32 / smooth_time.max(1e-4);
+    let x = omega * dt;
+    let exp = 1f32 / (1f32 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (velocity + change * omega) * dt;
+    let new_velocity = (velocity - temp * omega) * exp;
+    let value = target + (change + temp) * exp;
+    (value, new_velocity)
+}
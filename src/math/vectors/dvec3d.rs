@@ -0,0 +1,117 @@
+use super::vec3d::Vec3D;
+
+/// Double precision 3D vector, for positions too far from the origin for `f32`
+/// like on planets. Convert the offsets from a nearby origin to `Vec3D` to render.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DVec3D {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl DVec3D {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn null() -> Self {
+        Self::new(0_f64, 0_f64, 0_f64)
+    }
+
+    pub fn sq_norm(&self) -> f64 {
+        self.dot(self)
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.sq_norm().sqrt()
+    }
+
+    /// Unit vector with the same direction, the null vector stays null.
+    pub fn normalized(&self) -> Self {
+        let norm = self.norm();
+        if norm > 0_f64 {
+            *self / norm
+        } else {
+            *self
+        }
+    }
+
+    pub fn dot(&self, v2: &Self) -> f64 {
+        self.x * v2.x + self.y * v2.y + self.z * v2.z
+    }
+
+    pub fn cross(&self, v2: &Self) -> Self {
+        Self {
+            x: self.y * v2.z - self.z * v2.y,
+            y: self.z * v2.x - self.x * v2.z,
+            z: self.x * v2.y - self.y * v2.x,
+        }
+    }
+
+    pub fn distance(&self, v2: &Self) -> f64 {
+        (*self - *v2).norm()
+    }
+
+    /// Single precision copy, only exact enough for small coordinates.
+    pub fn to_vec3d(&self) -> Vec3D {
+        Vec3D::new(self.x as f32, self.y as f32, self.z as f32)
+    }
+}
+
+impl From<Vec3D> for DVec3D {
+    fn from(v: Vec3D) -> Self {
+        Self::new(v.x as f64, v.y as f64, v.z as f64)
+    }
+}
+
+impl std::ops::Add for DVec3D {
+    type Output = DVec3D;
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+impl std::ops::AddAssign for DVec3D {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+impl std::ops::Sub for DVec3D {
+    type Output = DVec3D;
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+impl std::ops::SubAssign for DVec3D {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+impl std::ops::Neg for DVec3D {
+    type Output = DVec3D;
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+impl std::ops::Mul<f64> for DVec3D {
+    type Output = DVec3D;
+    fn mul(self, other: f64) -> Self {
+        Self::new(self.x * other, self.y * other, self.z * other)
+    }
+}
+impl std::ops::Mul<DVec3D> for f64 {
+    type Output = DVec3D;
+    fn mul(self, other: DVec3D) -> DVec3D {
+        other * self
+    }
+}
+impl std::ops::Div<f64> for DVec3D {
+    type Output = DVec3D;
+    fn div(self, other: f64) -> Self {
+        Self::new(self.x / other, self.y / other, self.z / other)
+    }
+}
+impl std::fmt::Display for DVec3D {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.x, self.y, self.z)
+    }
+}
@@ -1,8 +1,10 @@
 // internal imports
+pub mod dvec3d;
 pub mod vec2d;
 pub mod vec3d;
 pub mod vec4d;
 
+pub use dvec3d::DVec3D;
 pub use vec2d::Vec2D;
 pub use vec3d::Vec3D;
 pub use vec4d::Vec4D;
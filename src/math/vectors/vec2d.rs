@@ -28,6 +28,14 @@ impl Vec2D {
         self.y *= k;
     }
 
+    pub fn normalized(&self) -> Self {
+        let k = self.inv_norm();
+        Self {
+            x: self.x * k,
+            y: self.y * k,
+        }
+    }
+
     pub fn dot(&self, v2: &Self) -> f32 {
         self.x * v2.x + self.y * v2.y
     }
@@ -45,6 +53,22 @@ impl Vec2D {
         self.dot(v2) * self.inv_norm() * v2.inv_norm()
     }
 
+    /// Component wise minimum.
+    pub fn min(&self, v2: &Self) -> Self {
+        Self {
+            x: self.x.min(v2.x),
+            y: self.y.min(v2.y),
+        }
+    }
+
+    /// Component wise maximum.
+    pub fn max(&self, v2: &Self) -> Self {
+        Self {
+            x: self.x.max(v2.x),
+            y: self.y.max(v2.y),
+        }
+    }
+
     pub fn null() -> Self {
         Vec2D { x: 0_f32, y: 0_f32 }
     }
@@ -104,6 +128,14 @@ impl std::ops::Sub for Vec2D {
         }
     }
 }
+impl std::ops::SubAssign for Vec2D {
+    fn sub_assign(&mut self, other: Self) {
+        *self = Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        };
+    }
+}
 impl std::ops::Neg for Vec2D {
     type Output = Vec2D;
     fn neg(self) -> Self {
@@ -141,6 +173,25 @@ impl std::ops::MulAssign<f32> for Vec2D {
     }
 }
 
+impl std::ops::Mul<Vec2D> for f32 {
+    type Output = Vec2D;
+    fn mul(self, other: Vec2D) -> Vec2D {
+        Vec2D {
+            x: self * other.x,
+            y: self * other.y,
+        }
+    }
+}
+impl std::ops::Div<f32> for Vec2D {
+    type Output = Vec2D;
+    fn div(self, other: f32) -> Self {
+        Self {
+            x: self.x / other,
+            y: self.y / other,
+        }
+    }
+}
+
 impl std::fmt::Display for Vec2D {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({},{})", self.x, self.y)
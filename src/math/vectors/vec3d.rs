@@ -40,11 +40,38 @@ impl Vec3D {
     pub fn cross(&self, v2: &Self) -> Self {
         Self {
             x: self.y * v2.z - self.z * v2.y,
-            y: self.x * v2.z - self.z * v2.x,
+            y: self.z * v2.x - self.x * v2.z,
             z: self.x * v2.y - self.y * v2.x,
         }
     }
 
+    /// Component wise minimum.
+    pub fn min(&self, v2: &Self) -> Self {
+        Self {
+            x: self.x.min(v2.x),
+            y: self.y.min(v2.y),
+            z: self.z.min(v2.z),
+        }
+    }
+
+    /// Component wise maximum.
+    pub fn max(&self, v2: &Self) -> Self {
+        Self {
+            x: self.x.max(v2.x),
+            y: self.y.max(v2.y),
+            z: self.z.max(v2.z),
+        }
+    }
+
+    /// Get the component along the given axis (0 -> x, 1 -> y, 2 -> z).
+    pub fn axis(&self, axis: usize) -> f32 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => self.z,
+        }
+    }
+
     pub fn angle(&self, v2: &Self) -> f32 {
         let cnorm = self.cross(v2).norm();
         cnorm * self.inv_norm() * v2.inv_norm()
@@ -115,6 +142,12 @@ impl Vec3D {
     }
 }
 
+impl From<(f32, f32, f32)> for Vec3D {
+    fn from(other: (f32, f32, f32)) -> Self {
+        Vec3D::new(other.0, other.1, other.2)
+    }
+}
+
 impl std::ops::Add for Vec3D {
     type Output = Vec3D;
     fn add(self, other: Self) -> Self {
@@ -144,6 +177,15 @@ impl std::ops::Sub for Vec3D {
         }
     }
 }
+impl std::ops::SubAssign for Vec3D {
+    fn sub_assign(&mut self, other: Self) {
+        *self = Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+            z: self.z - other.z,
+        };
+    }
+}
 impl std::ops::Neg for Vec3D {
     type Output = Vec3D;
     fn neg(self) -> Self {
@@ -183,6 +225,16 @@ impl std::ops::Mul<Vec3D> for f32 {
         }
     }
 }
+impl std::ops::Div<f32> for Vec3D {
+    type Output = Vec3D;
+    fn div(self, other: f32) -> Self {
+        Self {
+            x: self.x / other,
+            y: self.y / other,
+            z: self.z / other,
+        }
+    }
+}
 impl std::fmt::Display for Vec3D {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({},{},{})", self.x, self.y, self.z)
@@ -99,7 +99,7 @@ impl std::ops::Sub for Vec4D {
             x: self.x - other.x,
             y: self.y - other.y,
             z: self.z - other.z,
-            w: self.w - other.z,
+            w: self.w - other.w,
         }
     }
 }
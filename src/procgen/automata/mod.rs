@@ -0,0 +1,198 @@
+pub mod regions;
+pub mod rule;
+
+pub use regions::{connect_regions, keep_largest_region, remove_small_regions, Regions};
+pub use rule::Rule;
+
+// Crate imports
+use crate::math::Rng;
+use crate::procgen::grid::Grid;
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidRule(String),
+}
+
+/// Cells counted as neighbours, in 3D the Moore and von Neumann
+/// neighbourhoods extend along z and the hex one adds the cells above and below.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Neighborhood {
+    /// Cells within the given Chebyshev distance.
+    Moore(u32),
+    /// Cells within the given Manhattan distance.
+    VonNeumann(u32),
+    /// Six neighbours of "odd-r" hexagonal rows, odd rows being shifted right.
+    Hex,
+    Custom(Vec<(i32, i32, i32)>),
+}
+
+impl Neighborhood {
+    /// Offsets of the neighbours of a cell on the row `y`.
+    pub fn offsets(&self, y: i32, is_3d: bool) -> Vec<(i32, i32, i32)> {
+        let z_range = |r: i32| if is_3d { -r..=r } else { 0..=0 };
+        let mut offsets = Vec::new();
+        match self {
+            Neighborhood::Moore(r) | Neighborhood::VonNeumann(r) => {
+                let r = *r as i32;
+                for dz in z_range(r) {
+                    for dy in -r..=r {
+                        for dx in -r..=r {
+                            let inside = match self {
+                                Neighborhood::Moore(_) => true,
+                                _ => dx.abs() + dy.abs() + dz.abs() <= r,
+                            };
+                            if inside && (dx, dy, dz) != (0, 0, 0) {
+                                offsets.push((dx, dy, dz));
+                            }
+                        }
+                    }
+                }
+            }
+            Neighborhood::Hex => {
+                let shift = if y.rem_euclid(2) == 1 { 1 } else { 0 };
+                offsets.extend_from_slice(&[
+                    (1, 0, 0),
+                    (-1, 0, 0),
+                    (shift - 1, -1, 0),
+                    (shift, -1, 0),
+                    (shift - 1, 1, 0),
+                    (shift, 1, 0),
+                ]);
+                if is_3d {
+                    offsets.extend_from_slice(&[(0, 0, 1), (0, 0, -1)]);
+                }
+            }
+            Neighborhood::Custom(custom) => offsets.extend_from_slice(custom),
+        }
+        offsets
+    }
+
+    // the offsets of the hex neighbourhood depend on the parity of the row
+    fn offsets_by_parity(&self, is_3d: bool) -> [Vec<(i32, i32, i32)>; 2] {
+        [self.offsets(0, is_3d), self.offsets(1, is_3d)]
+    }
+}
+
+/// What the neighbours outside of the grid are.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Boundary {
+    /// The grid wraps around.
+    Wrap,
+    /// The nearest cell of the grid.
+    Clamp,
+    /// A cell of the given state.
+    Constant(u8),
+}
+
+impl Boundary {
+    /// State of the cell at the possibly out of bounds coordinates.
+    pub fn sample(&self, grid: &Grid<u8>, x: i32, y: i32, z: i32) -> u8 {
+        if let Some(state) = grid.get(x, y, z) {
+            return *state;
+        }
+        let (w, h, d) = (grid.width() as i32, grid.height() as i32, grid.depth() as i32);
+        match self {
+            Boundary::Wrap => grid[(
+                x.rem_euclid(w) as usize,
+                y.rem_euclid(h) as usize,
+                z.rem_euclid(d) as usize,
+            )],
+            Boundary::Clamp => grid[(
+                x.clamp(0, w - 1) as usize,
+                y.clamp(0, h - 1) as usize,
+                z.clamp(0, d - 1) as usize,
+            )],
+            Boundary::Constant(state) => *state,
+        }
+    }
+}
+
+/// Runs a rule over grids of states, 0 being dead and 1 alive.
+#[derive(Clone, Debug)]
+pub struct CellularAutomaton {
+    pub rule: Rule,
+    pub neighborhood: Neighborhood,
+    pub boundary: Boundary,
+}
+
+impl CellularAutomaton {
+    /// Automaton with the Moore neighbourhood and clamped boundaries.
+    pub fn new(rule: Rule) -> Self {
+        Self {
+            rule,
+            neighborhood: Neighborhood::Moore(1),
+            boundary: Boundary::Clamp,
+        }
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_boundary(mut self, boundary: Boundary) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Cave automaton, walls being alive cells and the outside counting as
+    /// walls so that caves stay closed.
+    pub fn cave() -> Self {
+        Self::new(Rule::cave()).with_boundary(Boundary::Constant(1))
+    }
+
+    /// Number of alive neighbours of the cell.
+    pub fn alive_neighbours(&self, grid: &Grid<u8>, x: usize, y: usize, z: usize) -> usize {
+        self.neighborhood
+            .offsets(y as i32, grid.is_3d())
+            .iter()
+            .filter(|(dx, dy, dz)| {
+                self.boundary
+                    .sample(grid, x as i32 + dx, y as i32 + dy, z as i32 + dz)
+                    == 1
+            })
+            .count()
+    }
+
+    pub fn step(&self, grid: &Grid<u8>) -> Grid<u8> {
+        let offsets = self.neighborhood.offsets_by_parity(grid.is_3d());
+        Grid::from_fn(grid.width(), grid.height(), grid.depth(), |x, y, z| {
+            let (x, y, z) = (x as i32, y as i32, z as i32);
+            let alive = offsets[(y % 2) as usize]
+                .iter()
+                .filter(|(dx, dy, dz)| self.boundary.sample(grid, x + dx, y + dy, z + dz) == 1)
+                .count();
+            self.rule.next(grid[(x as usize, y as usize, z as usize)], alive)
+        })
+    }
+
+    pub fn run(&self, grid: &Grid<u8>, iterations: usize) -> Grid<u8> {
+        let mut grid = grid.clone();
+        for _ in 0..iterations {
+            grid = self.step(&grid);
+        }
+        grid
+    }
+
+    /// Steps with any multi-state rule, `f` gets the state of the cell and
+    /// the states of its neighbours in the order of the neighbourhood offsets.
+    pub fn step_with(&self, grid: &Grid<u8>, mut f: impl FnMut(u8, &[u8]) -> u8) -> Grid<u8> {
+        let offsets = self.neighborhood.offsets_by_parity(grid.is_3d());
+        let mut neighbours = Vec::new();
+        Grid::from_fn(grid.width(), grid.height(), grid.depth(), |x, y, z| {
+            let (x, y, z) = (x as i32, y as i32, z as i32);
+            neighbours.clear();
+            neighbours.extend(
+                offsets[(y % 2) as usize]
+                    .iter()
+                    .map(|(dx, dy, dz)| self.boundary.sample(grid, x + dx, y + dy, z + dz)),
+            );
+            f(grid[(x as usize, y as usize, z as usize)], &neighbours)
+        })
+    }
+}
+
+/// Grid where each cell is alive with the probability `fill`.
+pub fn random_grid(width: usize, height: usize, depth: usize, fill: f32, rng: &mut Rng) -> Grid<u8> {
+    Grid::from_fn(width, height, depth, |_, _, _| rng.chance(fill) as u8)
+}
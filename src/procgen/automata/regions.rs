@@ -0,0 +1,147 @@
+// Crate imports
+use super::Neighborhood;
+use crate::procgen::grid::Grid;
+
+/// Connected regions of the cells selected by a predicate.
+#[derive(Clone, Debug)]
+pub struct Regions {
+    /// Region of each cell, `None` for the cells not selected.
+    pub labels: Grid<Option<usize>>,
+    /// Cell indices of each region, in the order the regions were found.
+    pub regions: Vec<Vec<usize>>,
+}
+
+impl Regions {
+    /// Flood fills the selected cells, two cells being connected when
+    /// one is in the neighbourhood of the other. The grid doesn't wrap.
+    pub fn label<T>(grid: &Grid<T>, neighborhood: &Neighborhood, mut selected: impl FnMut(&T) -> bool) -> Self {
+        let mut labels = grid.map(|_| None);
+        let mut regions = Vec::new();
+        let mut stack = Vec::new();
+        for start in 0..grid.len() {
+            if labels.cells()[start].is_some() || !selected(&grid.cells()[start]) {
+                continue;
+            }
+            let label = regions.len();
+            let mut cells = vec![start];
+            labels.cells_mut()[start] = Some(label);
+            stack.push(start);
+            while let Some(cell) = stack.pop() {
+                let (x, y, z) = grid.coords(cell);
+                let (x, y, z) = (x as i32, y as i32, z as i32);
+                for (dx, dy, dz) in neighborhood.offsets(y, grid.is_3d()) {
+                    let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+                    if !grid.in_bounds(nx, ny, nz) {
+                        continue;
+                    }
+                    let next = grid.index(nx as usize, ny as usize, nz as usize);
+                    if labels.cells()[next].is_none() && selected(&grid.cells()[next]) {
+                        labels.cells_mut()[next] = Some(label);
+                        cells.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            regions.push(cells);
+        }
+        Self { labels, regions }
+    }
+
+    pub fn count(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn largest(&self) -> Option<usize> {
+        (0..self.regions.len()).max_by_key(|&r| (self.regions[r].len(), std::cmp::Reverse(r)))
+    }
+}
+
+/// Replaces the regions of `state` smaller than `min_size` cells by `replacement`,
+/// returns the number of regions removed.
+pub fn remove_small_regions(
+    grid: &mut Grid<u8>,
+    neighborhood: &Neighborhood,
+    state: u8,
+    min_size: usize,
+    replacement: u8,
+) -> usize {
+    let regions = Regions::label(grid, neighborhood, |s| *s == state);
+    let mut removed = 0;
+    for region in regions.regions.iter().filter(|r| r.len() < min_size) {
+        for &cell in region {
+            grid.cells_mut()[cell] = replacement;
+        }
+        removed += 1;
+    }
+    removed
+}
+
+/// Replaces every region of `state` but the largest one by `replacement`.
+pub fn keep_largest_region(grid: &mut Grid<u8>, neighborhood: &Neighborhood, state: u8, replacement: u8) {
+    let regions = Regions::label(grid, neighborhood, |s| *s == state);
+    if let Some(largest) = regions.largest() {
+        for (r, region) in regions.regions.iter().enumerate() {
+            if r != largest {
+                for &cell in region {
+                    grid.cells_mut()[cell] = replacement;
+                }
+            }
+        }
+    }
+}
+
+/// Connects every region of `state` to the largest one by carving straight
+/// tunnels of `state` between their closest cells, returns the number of tunnels.
+///
+/// Tunnels move along one axis at a time so they connect with any neighbourhood.
+pub fn connect_regions(grid: &mut Grid<u8>, neighborhood: &Neighborhood, state: u8) -> usize {
+    let regions = Regions::label(grid, neighborhood, |s| *s == state);
+    let largest = match regions.largest() {
+        Some(largest) => largest,
+        None => return 0,
+    };
+    let mut connected: Vec<usize> = regions.regions[largest].clone();
+    let mut others: Vec<&Vec<usize>> = (0..regions.count())
+        .filter(|&r| r != largest)
+        .map(|r| &regions.regions[r])
+        .collect();
+    others.sort_by_key(|r| std::cmp::Reverse(r.len()));
+
+    let position = |grid: &Grid<u8>, cell: usize| {
+        let (x, y, z) = grid.coords(cell);
+        (x as i32, y as i32, z as i32)
+    };
+    for region in &others {
+        let mut best = (i32::MAX, region[0], connected[0]);
+        for &a in region.iter() {
+            let pa = position(grid, a);
+            for &b in &connected {
+                let pb = position(grid, b);
+                let (dx, dy, dz) = (pa.0 - pb.0, pa.1 - pb.1, pa.2 - pb.2);
+                let distance = dx * dx + dy * dy + dz * dz;
+                if distance < best.0 {
+                    best = (distance, a, b);
+                }
+            }
+        }
+
+        let (mut p, target) = (position(grid, best.1), position(grid, best.2));
+        while p != target {
+            let delta = (target.0 - p.0, target.1 - p.1, target.2 - p.2);
+            if delta.0.abs() >= delta.1.abs() && delta.0.abs() >= delta.2.abs() {
+                p.0 += delta.0.signum();
+            } else if delta.1.abs() >= delta.2.abs() {
+                p.1 += delta.1.signum();
+            } else {
+                p.2 += delta.2.signum();
+            }
+            let cell = grid.index(p.0 as usize, p.1 as usize, p.2 as usize);
+            if grid.cells()[cell] != state {
+                grid.cells_mut()[cell] = state;
+                connected.push(cell);
+            }
+        }
+        connected.extend_from_slice(region);
+    }
+    others.len()
+}
@@ -0,0 +1,128 @@
+// Crate imports
+use super::Error;
+
+/// Birth/survival rule, with `states > 2` the cells that die go through
+/// `states - 2` dying states before being dead (the "Generations" rules).
+///
+/// State 0 is dead and state 1 alive, only alive cells count as neighbours.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    /// `birth[n]` is whether a dead cell with `n` alive neighbours is born.
+    pub birth: Vec<bool>,
+    /// `survival[n]` is whether an alive cell with `n` alive neighbours survives.
+    pub survival: Vec<bool>,
+    pub states: u8,
+}
+
+impl Rule {
+    pub fn new(birth: &[usize], survival: &[usize], states: u8) -> Self {
+        let flags = |counts: &[usize]| {
+            let mut flags = vec![false; counts.iter().max().map_or(0, |m| m + 1)];
+            for &n in counts {
+                flags[n] = true;
+            }
+            flags
+        };
+        Self {
+            birth: flags(birth),
+            survival: flags(survival),
+            states: states.max(2),
+        }
+    }
+
+    /// Conway's game of life, `B3/S23`.
+    pub fn life() -> Self {
+        Self::new(&[3], &[2, 3], 2)
+    }
+
+    /// The usual cave rule, walls are born with 5 or more walls around
+    /// and stay with 4 or more, `B5678/S45678`.
+    pub fn cave() -> Self {
+        Self::new(&[5, 6, 7, 8], &[4, 5, 6, 7, 8], 2)
+    }
+
+    /// Parses rule strings like `B3/S23`, `S23/B3`, `B2/S/C3` (or `B2/S/3`)
+    /// for the number of states, and `B5-7,9/S4,12-26` for neighbourhoods
+    /// with more than 9 cells.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let invalid = || Error::InvalidRule(source.to_string());
+        let mut birth = None;
+        let mut survival = None;
+        let mut states = 2;
+        for part in source.trim().split('/') {
+            let part = part.trim();
+            let mut chars = part.chars();
+            match chars.next().map(|c| c.to_ascii_uppercase()) {
+                Some('B') => birth = Some(parse_counts(chars.as_str()).ok_or_else(invalid)?),
+                Some('S') => survival = Some(parse_counts(chars.as_str()).ok_or_else(invalid)?),
+                Some('C') | Some('G') => states = chars.as_str().parse().map_err(|_| invalid())?,
+                Some(c) if c.is_ascii_digit() && birth.is_some() && survival.is_some() => {
+                    states = part.parse().map_err(|_| invalid())?
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        match (birth, survival) {
+            (Some(birth), Some(survival)) if states >= 2 => Ok(Self::new(&birth, &survival, states)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Next state of a cell from its state and its number of alive neighbours.
+    pub fn next(&self, state: u8, alive_neighbours: usize) -> u8 {
+        let flag = |flags: &[bool]| flags.get(alive_neighbours).copied().unwrap_or(false);
+        match state {
+            0 if flag(&self.birth) => 1,
+            0 => 0,
+            1 if flag(&self.survival) => 1,
+            s if s + 1 < self.states => s + 1,
+            _ => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let counts = |flags: &[bool]| -> Vec<usize> { (0..flags.len()).filter(|&n| flags[n]).collect() };
+        let (birth, survival) = (counts(&self.birth), counts(&self.survival));
+        let format = |counts: &[usize]| -> String {
+            if counts.iter().all(|&n| n < 10) {
+                counts.iter().map(|n| n.to_string()).collect()
+            } else {
+                // a single count still needs a comma to not be read as digits
+                let list = counts.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(",");
+                if counts.len() == 1 {
+                    list + ","
+                } else {
+                    list
+                }
+            }
+        };
+        write!(f, "B{}/S{}", format(&birth), format(&survival))?;
+        if self.states > 2 {
+            write!(f, "/C{}", self.states)?;
+        }
+        Ok(())
+    }
+}
+
+// Digits each being a count, or comma separated counts and ranges.
+fn parse_counts(source: &str) -> Option<Vec<usize>> {
+    if !source.contains(',') && !source.contains('-') {
+        return source
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as usize))
+            .collect();
+    }
+    let mut counts = Vec::new();
+    for item in source.split(',').filter(|s| !s.trim().is_empty()) {
+        match item.split_once('-') {
+            Some((a, b)) => {
+                let (a, b) = (a.trim().parse::<usize>().ok()?, b.trim().parse::<usize>().ok()?);
+                counts.extend(a..=b);
+            }
+            None => counts.push(item.trim().parse().ok()?),
+        }
+    }
+    Some(counts)
+}
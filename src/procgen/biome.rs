@@ -0,0 +1,415 @@
+// Biomes from a climate: temperature and moisture come from latitude,
+// elevation and noise, then a Whittaker style table maps them to biomes.
+
+// External imports
+use image::RgbaImage;
+use std::path::Path;
+// Crate imports
+use crate::math::fct::{lerp, smoothstep};
+use crate::math::noise::{Fractal, OpenSimplex};
+use crate::math::Vec2D;
+use crate::procgen::grid::Grid;
+use crate::procgen::terrain::Heightmap;
+use crate::ressources::{self, RessourceLoader};
+use crate::utils::color::RgbColor;
+
+#[derive(Debug)]
+pub enum Error {
+    ResourceLoad {
+        name: String,
+        inner: ressources::Error,
+    },
+    Image(image::ImageError),
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+pub type BiomeId = usize;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Biome {
+    pub name: String,
+    pub color: RgbColor,
+}
+
+#[derive(Clone, Debug)]
+pub struct ClimateParams {
+    pub seed: i64,
+    /// Noise of both fields, the frequency being per cell.
+    pub noise: Fractal,
+    /// Latitudes in degrees of the first and last rows of the map.
+    pub latitudes: (f32, f32),
+    /// Temperatures at sea level at the equator and at the poles, 0 being
+    /// the coldest and 1 the hottest.
+    pub equator_temperature: f32,
+    pub pole_temperature: f32,
+    /// Temperature lost for each unit of height above the sea.
+    pub lapse_rate: f32,
+    pub base_moisture: f32,
+    /// Moisture lost for each unit of height above the sea.
+    pub elevation_drying: f32,
+    /// Amplitudes of the noise added to the fields.
+    pub temperature_noise: f32,
+    pub moisture_noise: f32,
+    pub sea_level: f32,
+}
+
+impl Default for ClimateParams {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            noise: Fractal::new(4, 0.02),
+            latitudes: (60_f32, -60_f32),
+            equator_temperature: 1_f32,
+            pole_temperature: 0_f32,
+            lapse_rate: 0.01,
+            base_moisture: 0.5,
+            elevation_drying: 0.005,
+            temperature_noise: 0.15,
+            moisture_noise: 0.5,
+            sea_level: 0_f32,
+        }
+    }
+}
+
+/// Temperature and moisture of each cell, both in `[0, 1]`.
+#[derive(Clone, Debug)]
+pub struct Climate {
+    pub temperature: Grid<f32>,
+    pub moisture: Grid<f32>,
+}
+
+impl Climate {
+    pub fn new(heightmap: &Heightmap, params: &ClimateParams) -> Self {
+        let (width, height) = (heightmap.width(), heightmap.height());
+        let noise = OpenSimplex::new(params.seed);
+        // far from the temperature samples so that the fields don't match
+        let moisture_offset = Vec2D::new(10_000_f32, -10_000_f32);
+        let last_row = height.saturating_sub(1).max(1) as f32;
+        let mut temperature = Grid::new_2d(width, height, 0_f32);
+        let mut moisture = Grid::new_2d(width, height, 0_f32);
+        for y in 0..height {
+            let latitude = lerp(params.latitudes.0, params.latitudes.1, y as f32 / last_row);
+            let sea_temperature = lerp(
+                params.pole_temperature,
+                params.equator_temperature,
+                latitude.to_radians().cos(),
+            );
+            for x in 0..width {
+                let point = Vec2D::new(x as f32, y as f32);
+                let elevation = (heightmap.get(x as i32, y as i32) - params.sea_level).max(0_f32);
+                let t = sea_temperature - params.lapse_rate * elevation
+                    + params.temperature_noise * params.noise.eval2(&noise, point);
+                let m = params.base_moisture - params.elevation_drying * elevation
+                    + params.moisture_noise * params.noise.eval2(&noise, point + moisture_offset);
+                temperature[(x, y, 0)] = t.clamp(0_f32, 1_f32);
+                moisture[(x, y, 0)] = m.clamp(0_f32, 1_f32);
+            }
+        }
+        Self {
+            temperature,
+            moisture,
+        }
+    }
+}
+
+/// Whittaker style table, the rows going from cold to hot and the
+/// columns from dry to wet, each cell being a biome.
+#[derive(Clone, Debug)]
+pub struct BiomeTable {
+    biomes: Vec<Biome>,
+    rows: Vec<Vec<BiomeId>>,
+    /// Biome of the cells under the sea level.
+    pub water: Option<BiomeId>,
+    /// Fraction of the table cells over which neighbouring biomes blend, 0 for hard borders.
+    pub blend: f32,
+}
+
+impl BiomeTable {
+    pub fn new() -> Self {
+        Self {
+            biomes: Vec::new(),
+            rows: Vec::new(),
+            water: None,
+            blend: 0.3,
+        }
+    }
+
+    /// Simplified Whittaker diagram with an ocean.
+    pub fn whittaker() -> Self {
+        Self::parse(
+            "biome ocean 40 80 160
+             biome tundra 190 200 200
+             biome boreal_forest 90 120 90
+             biome grassland 160 180 90
+             biome shrubland 150 150 100
+             biome temperate_forest 60 130 60
+             biome temperate_rainforest 30 110 80
+             biome desert 230 210 140
+             biome savanna 190 170 80
+             biome tropical_seasonal_forest 120 160 40
+             biome tropical_rainforest 20 100 30
+             water ocean
+             row tundra tundra boreal_forest boreal_forest
+             row grassland shrubland temperate_forest temperate_rainforest
+             row desert grassland temperate_forest temperate_rainforest
+             row desert savanna tropical_seasonal_forest tropical_rainforest",
+        )
+        .unwrap()
+    }
+
+    /// Reads a table with the statements `biome NAME R G B`, `water NAME` and
+    /// `row NAME...` for each row from cold to hot, `#` starting a comment.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut table = Self::new();
+        for (i, line) in source.lines().enumerate() {
+            let parse_error = |message: String| Error::Parse {
+                line: i + 1,
+                message,
+            };
+            let line = line.split('#').next().unwrap_or("");
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["biome", name, r, g, b] => {
+                    if table.find(name).is_some() {
+                        return Err(parse_error(format!("biome `{}` declared twice", name)));
+                    }
+                    let channel = |c: &str| {
+                        c.parse::<u8>()
+                            .map_err(|_| parse_error(format!("invalid color channel `{}`", c)))
+                    };
+                    let color = RgbColor::new(channel(r)?, channel(g)?, channel(b)?);
+                    table.add_biome(name, color);
+                }
+                ["water", name] => {
+                    let id = table
+                        .find(name)
+                        .ok_or_else(|| parse_error(format!("unknown biome `{}`", name)))?;
+                    table.water = Some(id);
+                }
+                ["row", names @ ..] => {
+                    let row = names
+                        .iter()
+                        .map(|name| {
+                            table
+                                .find(name)
+                                .ok_or_else(|| parse_error(format!("unknown biome `{}`", name)))
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if row.is_empty() {
+                        return Err(parse_error("empty row".to_string()));
+                    }
+                    if table
+                        .rows
+                        .first()
+                        .map(|r| r.len() != row.len())
+                        .unwrap_or(false)
+                    {
+                        return Err(parse_error(format!(
+                            "row of {} biomes, expected {}",
+                            row.len(),
+                            table.rows[0].len()
+                        )));
+                    }
+                    table.push_row(row);
+                }
+                [statement, ..] => {
+                    return Err(parse_error(format!("unknown statement `{}`", statement)));
+                }
+            }
+        }
+        if table.rows.is_empty() {
+            return Err(Error::Parse {
+                line: source.lines().count(),
+                message: "the table has no row".to_string(),
+            });
+        }
+        Ok(table)
+    }
+
+    pub fn from_res(res: &RessourceLoader, name: &str) -> Result<Self, Error> {
+        let bytes = res.load_bytes(name, &[]).map_err(|e| Error::ResourceLoad {
+            name: name.to_string(),
+            inner: e,
+        })?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    pub fn add_biome(&mut self, name: &str, color: RgbColor) -> BiomeId {
+        self.biomes.push(Biome {
+            name: name.to_string(),
+            color,
+        });
+        self.biomes.len() - 1
+    }
+
+    /// Adds a row hotter than the previous ones, as long as them.
+    /// Panics if the row is empty, of another length or has unknown biomes.
+    pub fn push_row(&mut self, row: Vec<BiomeId>) {
+        assert!(!row.is_empty(), "A biome row needs at least one biome!");
+        if let Some(first) = self.rows.first() {
+            assert_eq!(row.len(), first.len(), "The biome rows must have the same length!");
+        }
+        assert!(
+            row.iter().all(|id| *id < self.biomes.len()),
+            "Unknown biome in the row!"
+        );
+        self.rows.push(row);
+    }
+
+    pub fn find(&self, name: &str) -> Option<BiomeId> {
+        self.biomes.iter().position(|b| b.name == name)
+    }
+
+    pub fn biome(&self, id: BiomeId) -> &Biome {
+        &self.biomes[id]
+    }
+
+    pub fn biomes(&self) -> &[Biome] {
+        &self.biomes
+    }
+
+    /// Biome of the table cell containing the climate.
+    /// Panics if the table has no row.
+    pub fn classify(&self, temperature: f32, moisture: f32) -> BiomeId {
+        self.assert_rows();
+        let row = band(temperature, self.rows.len());
+        let column = band(moisture, self.rows[row].len());
+        self.rows[row][column]
+    }
+
+    /// Weights of the biomes at the climate, summing to 1. They only differ
+    /// from the biome of the table cell within `blend` of its borders.
+    /// Panics if the table has no row.
+    pub fn weights(&self, temperature: f32, moisture: f32) -> Vec<(BiomeId, f32)> {
+        self.assert_rows();
+        let (row0, row1, row_t) = self.blend_axis(temperature, self.rows.len());
+        let (column0, column1, column_t) = self.blend_axis(moisture, self.rows[0].len());
+        let mut weights: Vec<(BiomeId, f32)> = Vec::with_capacity(4);
+        let corners = [
+            (row0, column0, (1_f32 - row_t) * (1_f32 - column_t)),
+            (row0, column1, (1_f32 - row_t) * column_t),
+            (row1, column0, row_t * (1_f32 - column_t)),
+            (row1, column1, row_t * column_t),
+        ];
+        for &(row, column, weight) in &corners {
+            if weight <= 0_f32 {
+                continue;
+            }
+            let id = self.rows[row][column];
+            match weights.iter_mut().find(|(b, _)| *b == id) {
+                Some((_, w)) => *w += weight,
+                None => weights.push((id, weight)),
+            }
+        }
+        weights
+    }
+
+    fn assert_rows(&self) {
+        assert!(!self.rows.is_empty(), "The biome table has no row!");
+    }
+
+    // The two cells around the value along an axis and how far towards the
+    // second one the blend goes.
+    fn blend_axis(&self, value: f32, count: usize) -> (usize, usize, f32) {
+        // position relative to the cell centers
+        let position = value.clamp(0_f32, 1_f32) * count as f32 - 0.5;
+        let first = position.floor().clamp(0_f32, (count - 1) as f32) as usize;
+        let second = (first + 1).min(count - 1);
+        let fraction = (position - first as f32).clamp(0_f32, 1_f32);
+        let half = self.blend.clamp(0_f32, 1_f32) * 0.5;
+        let t = if half > 0_f32 {
+            smoothstep(0.5 - half, 0.5 + half, fraction)
+        } else if fraction < 0.5 {
+            0_f32
+        } else {
+            1_f32
+        };
+        (first, second, t)
+    }
+
+    /// Color of the climate, the colors of the biomes mixed by their weights.
+    pub fn color(&self, temperature: f32, moisture: f32) -> RgbColor {
+        mix_colors(self, &self.weights(temperature, moisture))
+    }
+}
+
+impl Default for BiomeTable {
+    fn default() -> Self {
+        Self::whittaker()
+    }
+}
+
+// Index of the band of `count` equal bands of [0, 1] containing the value.
+fn band(value: f32, count: usize) -> usize {
+    ((value.clamp(0_f32, 1_f32) * count as f32) as usize).min(count - 1)
+}
+
+fn mix_colors(table: &BiomeTable, weights: &[(BiomeId, f32)]) -> RgbColor {
+    let mut rgb = [0_f32; 3];
+    for &(id, weight) in weights {
+        let color = table.biome(id).color;
+        rgb[0] += color.red as f32 * weight;
+        rgb[1] += color.green as f32 * weight;
+        rgb[2] += color.blue as f32 * weight;
+    }
+    RgbColor::new(
+        rgb[0].round() as u8,
+        rgb[1].round() as u8,
+        rgb[2].round() as u8,
+    )
+}
+
+/// Biome of each cell of a heightmap with the blend weights at the borders.
+#[derive(Clone, Debug)]
+pub struct BiomeMap {
+    pub biomes: Grid<BiomeId>,
+    pub weights: Grid<Vec<(BiomeId, f32)>>,
+}
+
+impl BiomeMap {
+    /// Classifies the climate, the cells under `sea_level` getting the
+    /// water biome of the table if it has one.
+    pub fn new(
+        table: &BiomeTable,
+        climate: &Climate,
+        heightmap: &Heightmap,
+        sea_level: f32,
+    ) -> Self {
+        let (width, height) = (heightmap.width(), heightmap.height());
+        let weights = Grid::from_fn(width, height, 1, |x, y, _| match table.water {
+            Some(water) if heightmap.get(x as i32, y as i32) < sea_level => vec![(water, 1_f32)],
+            _ => table.weights(climate.temperature[(x, y, 0)], climate.moisture[(x, y, 0)]),
+        });
+        let biomes = weights.map(|w| {
+            w.iter()
+                .fold((0, f32::NEG_INFINITY), |best, &(id, weight)| {
+                    if weight > best.1 {
+                        (id, weight)
+                    } else {
+                        best
+                    }
+                })
+                .0
+        });
+        Self { biomes, weights }
+    }
+
+    /// Image with the blended colors of the biomes.
+    pub fn to_image(&self, table: &BiomeTable) -> RgbaImage {
+        self.weights.to_image(0, |w| {
+            let color = mix_colors(table, w);
+            [color.red, color.green, color.blue, 255]
+        })
+    }
+
+    /// Saves the blended colors as a PNG image for debugging.
+    pub fn save_png(&self, table: &BiomeTable, path: impl AsRef<Path>) -> Result<(), Error> {
+        self.to_image(table)
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(Error::Image)
+    }
+}
@@ -0,0 +1,328 @@
+// External imports
+use image::RgbaImage;
+// Crate imports
+use crate::math::Rng;
+use crate::procgen::grid::Grid;
+use crate::procgen::tile::{tile_indices, tiles_to_image, Tile};
+
+/// Rectangular room, in tiles.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Room {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Room {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn center(&self) -> (usize, usize) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Whether the rooms overlap or are less than `margin` tiles apart.
+    pub fn intersects(&self, other: &Room, margin: usize) -> bool {
+        self.x < other.x + other.width + margin
+            && other.x < self.x + self.width + margin
+            && self.y < other.y + other.height + margin
+            && other.y < self.y + self.height + margin
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x >= self.x && y >= self.y && x < self.x + self.width && y < self.y + self.height
+    }
+}
+
+/// Generated level, the outer border of the grid is always wall.
+#[derive(Clone, Debug)]
+pub struct Dungeon {
+    pub grid: Grid<Tile>,
+    pub rooms: Vec<Room>,
+    /// Pairs of rooms joined by a corridor.
+    pub corridors: Vec<(usize, usize)>,
+}
+
+impl Dungeon {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            grid: Grid::new_2d(width, height, Tile::Wall),
+            rooms: Vec::new(),
+            corridors: Vec::new(),
+        }
+    }
+
+    fn carve_room(&mut self, room: Room) {
+        for y in room.y..room.y + room.height {
+            for x in room.x..room.x + room.width {
+                self.grid[(x, y, 0)] = Tile::Floor;
+            }
+        }
+        self.rooms.push(room);
+    }
+
+    // L shaped corridor between the centers of two rooms, randomly
+    // starting horizontally or vertically.
+    fn carve_corridor(&mut self, a: usize, b: usize, rng: &mut Rng) {
+        let (ax, ay) = self.rooms[a].center();
+        let (bx, by) = self.rooms[b].center();
+        let mut carve = |x: usize, y: usize| {
+            if self.grid[(x, y, 0)] == Tile::Wall {
+                self.grid[(x, y, 0)] = Tile::Corridor;
+            }
+        };
+        let corner = if rng.chance(0.5) { (bx, ay) } else { (ax, by) };
+        for &((x0, y0), (x1, y1)) in &[((ax, ay), corner), (corner, (bx, by))] {
+            for x in x0.min(x1)..=x0.max(x1) {
+                for y in y0.min(y1)..=y0.max(y1) {
+                    carve(x, y);
+                }
+            }
+        }
+        self.corridors.push((a, b));
+    }
+
+    pub fn to_image(&self) -> RgbaImage {
+        tiles_to_image(&self.grid)
+    }
+
+    pub fn tile_indices(&self) -> Vec<u32> {
+        tile_indices(&self.grid)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct BspParams {
+    /// Smallest size of a partition.
+    pub min_leaf: usize,
+    pub min_room: usize,
+    /// Minimal number of tiles between a room and the border of its partition.
+    pub padding: usize,
+}
+
+impl Default for BspParams {
+    fn default() -> Self {
+        Self {
+            min_leaf: 10,
+            min_room: 4,
+            padding: 1,
+        }
+    }
+}
+
+/// Splits the level recursively in two and places a room in each leaf,
+/// sibling partitions being joined by a corridor.
+pub fn bsp(width: usize, height: usize, params: &BspParams, rng: &mut Rng) -> Dungeon {
+    let mut params = *params;
+    params.padding = params.padding.max(1);
+    params.min_room = params.min_room.max(1);
+    params.min_leaf = params.min_leaf.max(params.min_room + 2 * params.padding);
+    let mut dungeon = Dungeon::new(width, height);
+    split(&mut dungeon, Room::new(0, 0, width, height), &params, rng);
+    dungeon
+}
+
+// Returns the rooms placed in the partition.
+fn split(dungeon: &mut Dungeon, area: Room, params: &BspParams, rng: &mut Rng) -> Vec<usize> {
+    let can_split_x = area.width >= params.min_leaf * 2;
+    let can_split_y = area.height >= params.min_leaf * 2;
+    let split_x = match (can_split_x, can_split_y) {
+        (false, false) => {
+            let (max_w, max_h) = (
+                area.width.saturating_sub(2 * params.padding),
+                area.height.saturating_sub(2 * params.padding),
+            );
+            if max_w < params.min_room || max_h < params.min_room {
+                return Vec::new();
+            }
+            let w = rng.range_usize(params.min_room, max_w + 1);
+            let h = rng.range_usize(params.min_room, max_h + 1);
+            let x = area.x + params.padding + rng.range_usize(0, max_w - w + 1);
+            let y = area.y + params.padding + rng.range_usize(0, max_h - h + 1);
+            dungeon.carve_room(Room::new(x, y, w, h));
+            return vec![dungeon.rooms.len() - 1];
+        }
+        (true, false) => true,
+        (false, true) => false,
+        // prefer cutting the long side to avoid thin partitions
+        (true, true) if area.width * 4 > area.height * 5 => true,
+        (true, true) if area.height * 4 > area.width * 5 => false,
+        (true, true) => rng.chance(0.5),
+    };
+
+    let (first, second) = if split_x {
+        let at = rng.range_usize(params.min_leaf, area.width - params.min_leaf + 1);
+        (
+            Room::new(area.x, area.y, at, area.height),
+            Room::new(area.x + at, area.y, area.width - at, area.height),
+        )
+    } else {
+        let at = rng.range_usize(params.min_leaf, area.height - params.min_leaf + 1);
+        (
+            Room::new(area.x, area.y, area.width, at),
+            Room::new(area.x, area.y + at, area.width, area.height - at),
+        )
+    };
+    let mut rooms = split(dungeon, first, params, rng);
+    let others = split(dungeon, second, params, rng);
+    if let (Some(&a), Some(&b)) = (rng.choose(&rooms), rng.choose(&others)) {
+        dungeon.carve_corridor(a, b, rng);
+    }
+    rooms.extend(others);
+    rooms
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct RoomsParams {
+    /// Number of placements tried, the ones overlapping a room are dropped.
+    pub attempts: usize,
+    pub min_size: usize,
+    pub max_size: usize,
+    /// Chance of adding a corridor to each of the nearest rooms not already
+    /// joined by the spanning tree, creating loops.
+    pub extra_corridors: f32,
+}
+
+impl Default for RoomsParams {
+    fn default() -> Self {
+        Self {
+            attempts: 60,
+            min_size: 4,
+            max_size: 10,
+            extra_corridors: 0.15,
+        }
+    }
+}
+
+/// Scatters non overlapping rooms and joins them along their minimum
+/// spanning tree plus a few extra corridors.
+pub fn random_rooms(width: usize, height: usize, params: &RoomsParams, rng: &mut Rng) -> Dungeon {
+    let mut dungeon = Dungeon::new(width, height);
+    let min_size = params.min_size.max(1);
+    let max_size = params.max_size.max(min_size);
+    for _ in 0..params.attempts {
+        let w = rng.range_usize(min_size, max_size + 1);
+        let h = rng.range_usize(min_size, max_size + 1);
+        if w + 2 > width || h + 2 > height {
+            continue;
+        }
+        let room = Room::new(
+            rng.range_usize(1, width - w),
+            rng.range_usize(1, height - h),
+            w,
+            h,
+        );
+        if dungeon.rooms.iter().all(|r| !r.intersects(&room, 1)) {
+            dungeon.carve_room(room);
+        }
+    }
+
+    let count = dungeon.rooms.len();
+    let centers: Vec<(f32, f32)> = dungeon
+        .rooms
+        .iter()
+        .map(|r| {
+            let (x, y) = r.center();
+            (x as f32, y as f32)
+        })
+        .collect();
+    let distance = |a: usize, b: usize| {
+        let (dx, dy) = (centers[a].0 - centers[b].0, centers[a].1 - centers[b].1);
+        dx * dx + dy * dy
+    };
+
+    // Prim on the complete graph of the rooms
+    let mut in_tree = vec![false; count];
+    let mut best: Vec<(f32, usize)> = vec![(f32::MAX, 0); count];
+    let mut edges = Vec::new();
+    if count > 0 {
+        best[0] = (0_f32, 0);
+    }
+    for _ in 0..count {
+        let next = (0..count)
+            .filter(|&r| !in_tree[r])
+            .min_by(|&a, &b| best[a].0.partial_cmp(&best[b].0).unwrap())
+            .unwrap();
+        in_tree[next] = true;
+        if next != best[next].1 {
+            edges.push((best[next].1, next));
+        }
+        for r in 0..count {
+            let d = distance(next, r);
+            if !in_tree[r] && d < best[r].0 {
+                best[r] = (d, next);
+            }
+        }
+    }
+
+    // extra corridors towards the three nearest rooms
+    for a in 0..count {
+        let mut nearest: Vec<usize> = (0..count).filter(|&b| b != a).collect();
+        nearest.sort_by(|&x, &y| distance(a, x).partial_cmp(&distance(a, y)).unwrap());
+        for &b in nearest.iter().take(3) {
+            let known = edges.contains(&(a, b)) || edges.contains(&(b, a));
+            if !known && rng.chance(params.extra_corridors) {
+                edges.push((a, b));
+            }
+        }
+    }
+
+    for (a, b) in edges {
+        dungeon.carve_corridor(a, b, rng);
+    }
+    dungeon
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct WalkParams {
+    /// Fraction of the inner tiles to turn into floor.
+    pub coverage: f32,
+    /// Upper bound of steps, in case the coverage can't be reached.
+    pub max_steps: usize,
+}
+
+impl Default for WalkParams {
+    fn default() -> Self {
+        Self {
+            coverage: 0.4,
+            max_steps: 1_000_000,
+        }
+    }
+}
+
+/// Random walk from the center carving floor until enough of the level is open,
+/// giving organic caves.
+pub fn drunkards_walk(width: usize, height: usize, params: &WalkParams, rng: &mut Rng) -> Dungeon {
+    let mut dungeon = Dungeon::new(width, height);
+    if width < 3 || height < 3 {
+        return dungeon;
+    }
+    let inner = (width - 2) * (height - 2);
+    let target = ((inner as f32 * params.coverage.clamp(0_f32, 1_f32)) as usize).max(1);
+    let (mut x, mut y) = (width / 2, height / 2);
+    let mut carved = 0;
+    for _ in 0..params.max_steps {
+        if dungeon.grid[(x, y, 0)] == Tile::Wall {
+            dungeon.grid[(x, y, 0)] = Tile::Floor;
+            carved += 1;
+            if carved >= target {
+                break;
+            }
+        }
+        match rng.below(4) {
+            0 if x > 1 => x -= 1,
+            1 if x < width - 2 => x += 1,
+            2 if y > 1 => y -= 1,
+            3 if y < height - 2 => y += 1,
+            _ => {}
+        }
+    }
+    dungeon
+}
@@ -0,0 +1,366 @@
+// Tracery style grammars. A rule text can contain:
+//
+//   #symbol#                 expansion of a random option of the symbol
+//   #symbol.s.capitalize#    expansion with modifiers applied in order
+//   [name:text]              pushes a rule `name` whose options are the comma
+//                            separated expansions of `text`
+//   [name:POP]               pops the last rule pushed for `name`
+//   #[hero:#name#]story#     actions local to the tag, popped afterwards
+//   \#                       a literal `#` (also `\[` and `\]`)
+//
+// Grammar files have one symbol per line, options separated by `|`, and
+// `//` starts a comment line:
+//
+//   origin: #greeting.capitalize#, #place#!
+//   greeting: hello | good morning
+//   place: world | #animal.s# land
+
+// External imports
+use std::collections::HashMap;
+// Crate imports
+use crate::math::Rng;
+use crate::ressources::{self, RessourceLoader};
+
+#[derive(Debug)]
+pub enum Error {
+    ResourceLoad {
+        name: String,
+        inner: ressources::Error,
+    },
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+pub type Modifier = fn(&str) -> String;
+
+/// Recursive grammar, expansions only depend on the rng so a seed always
+/// gives the same text.
+#[derive(Clone)]
+pub struct Grammar {
+    rules: HashMap<String, Vec<String>>,
+    modifiers: HashMap<String, Modifier>,
+    /// Deepest nesting of expansions, deeper tags are left as is.
+    pub max_depth: usize,
+}
+
+impl Default for Grammar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Grammar {
+    /// Empty grammar with the default modifiers: `capitalize`, `capitalizeAll`,
+    /// `uppercase`, `lowercase`, `s`, `a` and `ed`.
+    pub fn new() -> Self {
+        let mut modifiers: HashMap<String, Modifier> = HashMap::new();
+        modifiers.insert("capitalize".to_string(), capitalize);
+        modifiers.insert("capitalizeAll".to_string(), capitalize_all);
+        modifiers.insert("uppercase".to_string(), |s| s.to_uppercase());
+        modifiers.insert("lowercase".to_string(), |s| s.to_lowercase());
+        modifiers.insert("s".to_string(), plural);
+        modifiers.insert("a".to_string(), article);
+        modifiers.insert("ed".to_string(), past_tense);
+        Self {
+            rules: HashMap::new(),
+            modifiers,
+            max_depth: 64,
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut grammar = Self::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") {
+                continue;
+            }
+            let (symbol, options) = line.split_once(':').ok_or_else(|| Error::Parse {
+                line: i + 1,
+                message: format!("expected `symbol: options` in `{}`", line),
+            })?;
+            let symbol = symbol.trim();
+            if symbol.is_empty() || symbol.contains(|c: char| c.is_whitespace() || "#[].".contains(c)) {
+                return Err(Error::Parse {
+                    line: i + 1,
+                    message: format!("invalid symbol `{}`", symbol),
+                });
+            }
+            let options: Vec<&str> = options.split('|').map(str::trim).collect();
+            grammar.add_rule(symbol, &options);
+        }
+        Ok(grammar)
+    }
+
+    pub fn from_res(res: &RessourceLoader, name: &str) -> Result<Self, Error> {
+        let bytes = res.load_bytes(name, &[]).map_err(|e| Error::ResourceLoad {
+            name: name.to_string(),
+            inner: e,
+        })?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Adds options to a symbol.
+    pub fn add_rule(&mut self, symbol: &str, options: &[&str]) {
+        self.rules
+            .entry(symbol.to_string())
+            .or_default()
+            .extend(options.iter().map(|o| o.to_string()));
+    }
+
+    pub fn add_modifier(&mut self, name: &str, modifier: Modifier) {
+        self.modifiers.insert(name.to_string(), modifier);
+    }
+
+    /// Expands the text, e.g. `#origin#`.
+    pub fn flatten(&self, text: &str, rng: &mut Rng) -> String {
+        let mut expansion = Expansion {
+            grammar: self,
+            pushed: HashMap::new(),
+            rng,
+        };
+        expansion.text(text, 0)
+    }
+
+    /// Expands the text until the result satisfies the length (in characters)
+    /// and the filter, `None` after `max_attempts` failures.
+    pub fn flatten_filtered(
+        &self,
+        text: &str,
+        rng: &mut Rng,
+        min_length: usize,
+        max_length: usize,
+        max_attempts: usize,
+        mut filter: impl FnMut(&str) -> bool,
+    ) -> Option<String> {
+        for _ in 0..max_attempts {
+            let result = self.flatten(text, rng);
+            let length = result.chars().count();
+            if length >= min_length && length <= max_length && filter(&result) {
+                return Some(result);
+            }
+        }
+        None
+    }
+}
+
+// State of one expansion, the rules pushed by the actions hide the
+// grammar ones until they are popped.
+struct Expansion<'a, 'r> {
+    grammar: &'a Grammar,
+    pushed: HashMap<String, Vec<Vec<String>>>,
+    rng: &'r mut Rng,
+}
+
+impl<'a, 'r> Expansion<'a, 'r> {
+    fn text(&mut self, text: &str, depth: usize) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut out = String::new();
+        let mut i = 0;
+        while i < chars.len() {
+            match chars[i] {
+                '\\' if i + 1 < chars.len() => {
+                    out.push(chars[i + 1]);
+                    i += 2;
+                }
+                '[' => {
+                    let end = closing_bracket(&chars, i);
+                    let action: String = chars[i + 1..end].iter().collect();
+                    self.action(&action, depth);
+                    i = end + 1;
+                }
+                '#' => {
+                    let end = (i + 1..chars.len())
+                        .scan(0, |brackets, j| {
+                            match chars[j] {
+                                '[' => *brackets += 1,
+                                ']' => *brackets -= 1,
+                                _ => {}
+                            }
+                            Some((j, *brackets))
+                        })
+                        .find(|&(j, brackets)| chars[j] == '#' && brackets == 0 && chars[j - 1] != '\\')
+                        .map(|(j, _)| j);
+                    match end {
+                        Some(end) => {
+                            let tag: String = chars[i + 1..end].iter().collect();
+                            out.push_str(&self.tag(&tag, depth));
+                            i = end + 1;
+                        }
+                        None => {
+                            out.push('#');
+                            i += 1;
+                        }
+                    }
+                }
+                c => {
+                    out.push(c);
+                    i += 1;
+                }
+            }
+        }
+        out
+    }
+
+    fn tag(&mut self, tag: &str, depth: usize) -> String {
+        if depth >= self.grammar.max_depth {
+            return format!("#{}#", tag);
+        }
+        let chars: Vec<char> = tag.chars().collect();
+        let mut i = 0;
+        let mut local = Vec::new();
+        while i < chars.len() && chars[i] == '[' {
+            let end = closing_bracket(&chars, i);
+            let action: String = chars[i + 1..end].iter().collect();
+            if let Some(name) = self.action(&action, depth) {
+                local.push(name);
+            }
+            i = end + 1;
+        }
+        let rest: String = chars[i..].iter().collect();
+        let mut parts = rest.split('.');
+        let symbol = parts.next().unwrap_or("");
+
+        let mut result = if symbol.is_empty() {
+            String::new()
+        } else {
+            let options = match self.pushed.get(symbol).and_then(|stack| stack.last()) {
+                Some(options) => Some(options.clone()),
+                None => self.grammar.rules.get(symbol).cloned(),
+            };
+            match options {
+                Some(options) if !options.is_empty() => {
+                    let option = options[self.rng.range_usize(0, options.len())].clone();
+                    self.text(&option, depth + 1)
+                }
+                _ => format!("(({}))", symbol),
+            }
+        };
+        for modifier in parts {
+            if let Some(f) = self.grammar.modifiers.get(modifier) {
+                result = f(&result);
+            }
+        }
+
+        for name in local {
+            self.pop(&name);
+        }
+        result
+    }
+
+    // Runs `name:value` or `name:POP`, returns the name of a pushed rule.
+    fn action(&mut self, action: &str, depth: usize) -> Option<String> {
+        let (name, value) = action.split_once(':')?;
+        let name = name.trim().to_string();
+        if value == "POP" {
+            self.pop(&name);
+            return None;
+        }
+        let options = split_options(value)
+            .iter()
+            .map(|option| self.text(option, depth + 1))
+            .collect();
+        self.pushed.entry(name.clone()).or_default().push(options);
+        Some(name)
+    }
+
+    fn pop(&mut self, name: &str) {
+        if let Some(stack) = self.pushed.get_mut(name) {
+            stack.pop();
+        }
+    }
+}
+
+// Index of the `]` closing the `[` at `open`, or the end of the text.
+fn closing_bracket(chars: &[char], open: usize) -> usize {
+    let mut depth = 0;
+    for (j, c) in chars.iter().enumerate().skip(open) {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return j;
+                }
+            }
+            _ => {}
+        }
+    }
+    chars.len()
+}
+
+// Splits at the commas outside of tags and actions.
+fn split_options(value: &str) -> Vec<String> {
+    let mut options = vec![String::new()];
+    let (mut in_tag, mut brackets) = (false, 0);
+    for c in value.chars() {
+        match c {
+            '#' if brackets == 0 => in_tag = !in_tag,
+            '[' => brackets += 1,
+            ']' => brackets -= 1,
+            ',' if !in_tag && brackets == 0 => {
+                options.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        options.last_mut().unwrap().push(c);
+    }
+    options
+}
+
+pub fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub fn capitalize_all(s: &str) -> String {
+    s.split(' ').map(capitalize).collect::<Vec<_>>().join(" ")
+}
+
+fn is_vowel(c: char) -> bool {
+    "aeiouAEIOU".contains(c)
+}
+
+/// English plural of the last word.
+pub fn plural(s: &str) -> String {
+    let lower = s.to_lowercase();
+    let before_last = s.chars().rev().nth(1).unwrap_or('a');
+    if ["s", "sh", "ch", "x", "z"].iter().any(|e| lower.ends_with(e)) {
+        format!("{}es", s)
+    } else if lower.ends_with('y') && !is_vowel(before_last) {
+        format!("{}ies", &s[..s.len() - 1])
+    } else {
+        format!("{}s", s)
+    }
+}
+
+/// Prefixes `a` or `an`.
+pub fn article(s: &str) -> String {
+    match s.chars().next() {
+        Some(c) if is_vowel(c) => format!("an {}", s),
+        _ => format!("a {}", s),
+    }
+}
+
+/// English past tense of the first word.
+pub fn past_tense(s: &str) -> String {
+    let (word, rest) = match s.find(' ') {
+        Some(i) => s.split_at(i),
+        None => (s, ""),
+    };
+    let before_last = word.chars().rev().nth(1).unwrap_or('a');
+    let past = if word.ends_with('e') {
+        format!("{}d", word)
+    } else if word.ends_with('y') && !is_vowel(before_last) {
+        format!("{}ied", &word[..word.len() - 1])
+    } else {
+        format!("{}ed", word)
+    };
+    past + rest
+}
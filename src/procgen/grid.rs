@@ -0,0 +1,193 @@
+// External imports
+use image::RgbaImage;
+
+/// Dense 2D (`depth == 1`) or 3D grid, cells are stored x first then y then z.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    depth: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+            cells: vec![fill; width * height * depth],
+        }
+    }
+
+    pub fn new_2d(width: usize, height: usize, fill: T) -> Self {
+        Self::new(width, height, 1, fill)
+    }
+
+    pub fn fill(&mut self, value: T) {
+        for cell in &mut self.cells {
+            *cell = value.clone();
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Wraps cells already in the grid order, `None` if their count doesn't match.
+    pub fn from_vec(width: usize, height: usize, depth: usize, cells: Vec<T>) -> Option<Self> {
+        if cells.len() != width * height * depth {
+            return None;
+        }
+        Some(Self {
+            width,
+            height,
+            depth,
+            cells,
+        })
+    }
+
+    /// Grid whose cells are computed from their coordinates.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        depth: usize,
+        mut f: impl FnMut(usize, usize, usize) -> T,
+    ) -> Self {
+        let mut cells = Vec::with_capacity(width * height * depth);
+        for z in 0..depth {
+            for y in 0..height {
+                for x in 0..width {
+                    cells.push(f(x, y, z));
+                }
+            }
+        }
+        Self {
+            width,
+            height,
+            depth,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn is_3d(&self) -> bool {
+        self.depth > 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.cells
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32, z: i32) -> bool {
+        x >= 0
+            && y >= 0
+            && z >= 0
+            && (x as usize) < self.width
+            && (y as usize) < self.height
+            && (z as usize) < self.depth
+    }
+
+    pub fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + self.width * (y + self.height * z)
+    }
+
+    pub fn coords(&self, index: usize) -> (usize, usize, usize) {
+        (
+            index % self.width,
+            (index / self.width) % self.height,
+            index / (self.width * self.height),
+        )
+    }
+
+    pub fn get(&self, x: i32, y: i32, z: i32) -> Option<&T> {
+        if self.in_bounds(x, y, z) {
+            Some(&self.cells[self.index(x as usize, y as usize, z as usize)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: i32, y: i32, z: i32) -> Option<&mut T> {
+        if self.in_bounds(x, y, z) {
+            let index = self.index(x as usize, y as usize, z as usize);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the cell, returns false when out of bounds.
+    pub fn set(&mut self, x: i32, y: i32, z: i32, value: T) -> bool {
+        match self.get_mut(x, y, z) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Iterates over the cells with their coordinates.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize, usize), &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (self.coords(i), cell))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            depth: self.depth,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Image of the layer `z` with a color for each cell.
+    pub fn to_image(&self, z: usize, mut color: impl FnMut(&T) -> [u8; 4]) -> RgbaImage {
+        RgbaImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            image::Rgba(color(&self.cells[self.index(x as usize, y as usize, z)]))
+        })
+    }
+}
+
+impl<T> std::ops::Index<(usize, usize, usize)> for Grid<T> {
+    type Output = T;
+    fn index(&self, (x, y, z): (usize, usize, usize)) -> &T {
+        &self.cells[Grid::index(self, x, y, z)]
+    }
+}
+
+impl<T> std::ops::IndexMut<(usize, usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y, z): (usize, usize, usize)) -> &mut T {
+        let index = Grid::index(self, x, y, z);
+        &mut self.cells[index]
+    }
+}
@@ -0,0 +1,329 @@
+// Arithmetic expressions used in the parameters and conditions of the rules.
+
+/// Expression over the parameters bound by a rule, variables are indices
+/// into those parameters, booleans are 0 or 1.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Num(f32),
+    Var(usize),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(Func, Vec<Expr>),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Pow,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Func {
+    Sin,
+    Cos,
+    Tan,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Exp,
+    Ln,
+    Min,
+    Max,
+    Pow,
+}
+
+impl Func {
+    fn from_name(name: &str) -> Option<(Self, usize)> {
+        Some(match name {
+            "sin" => (Func::Sin, 1),
+            "cos" => (Func::Cos, 1),
+            "tan" => (Func::Tan, 1),
+            "sqrt" => (Func::Sqrt, 1),
+            "abs" => (Func::Abs, 1),
+            "floor" => (Func::Floor, 1),
+            "ceil" => (Func::Ceil, 1),
+            "exp" => (Func::Exp, 1),
+            "ln" => (Func::Ln, 1),
+            "min" => (Func::Min, 2),
+            "max" => (Func::Max, 2),
+            "pow" => (Func::Pow, 2),
+            _ => return None,
+        })
+    }
+}
+
+impl Expr {
+    pub fn eval(&self, vars: &[f32]) -> f32 {
+        let truth = |b: bool| if b { 1_f32 } else { 0_f32 };
+        match self {
+            Expr::Num(v) => *v,
+            Expr::Var(i) => vars[*i],
+            Expr::Neg(e) => -e.eval(vars),
+            Expr::Not(e) => truth(e.eval(vars) == 0_f32),
+            Expr::Binary(op, a, b) => {
+                let a = a.eval(vars);
+                // short circuit so that conditions like `x > 0 && 1 / x < 2` behave
+                match op {
+                    BinOp::And if a == 0_f32 => return 0_f32,
+                    BinOp::Or if a != 0_f32 => return 1_f32,
+                    _ => {}
+                }
+                let b = b.eval(vars);
+                match op {
+                    BinOp::Add => a + b,
+                    BinOp::Sub => a - b,
+                    BinOp::Mul => a * b,
+                    BinOp::Div => a / b,
+                    BinOp::Pow => a.powf(b),
+                    BinOp::Lt => truth(a < b),
+                    BinOp::Gt => truth(a > b),
+                    BinOp::Le => truth(a <= b),
+                    BinOp::Ge => truth(a >= b),
+                    BinOp::Eq => truth(a == b),
+                    BinOp::Ne => truth(a != b),
+                    BinOp::And | BinOp::Or => truth(b != 0_f32),
+                }
+            }
+            Expr::Call(func, args) => {
+                let a = args[0].eval(vars);
+                let b = || args[1].eval(vars);
+                match func {
+                    Func::Sin => a.sin(),
+                    Func::Cos => a.cos(),
+                    Func::Tan => a.tan(),
+                    Func::Sqrt => a.sqrt(),
+                    Func::Abs => a.abs(),
+                    Func::Floor => a.floor(),
+                    Func::Ceil => a.ceil(),
+                    Func::Exp => a.exp(),
+                    Func::Ln => a.ln(),
+                    Func::Min => a.min(b()),
+                    Func::Max => a.max(b()),
+                    Func::Pow => a.powf(b()),
+                }
+            }
+        }
+    }
+
+    /// Parses an expression, names are looked up in `vars` (giving their
+    /// index) then in `constants` (replaced by their value).
+    pub fn parse(source: &str, vars: &[String], constants: &[(String, f32)]) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            vars,
+            constants,
+        };
+        let expr = parser.or()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(format!("unexpected `{}` in `{}`", token, source)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Num(f32),
+    Ident(String),
+    Op(&'static str),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Num(v) => write!(f, "{}", v),
+            Token::Ident(name) => write!(f, "{}", name),
+            Token::Op(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+const OPERATORS: [&str; 17] = [
+    "<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "^", "<", ">", "!", "(", ")", ",",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse::<f32>()
+                .map_err(|_| format!("invalid number `{}`", text))?;
+            tokens.push(Token::Num(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let op = OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .ok_or_else(|| format!("unexpected character `{}`", c))?;
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    vars: &'a [String],
+    constants: &'a [(String, f32)],
+}
+
+impl<'a> Parser<'a> {
+    fn eat(&mut self, op: &str) -> bool {
+        if matches!(self.tokens.get(self.pos), Some(Token::Op(o)) if *o == op) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), String> {
+        if self.eat(op) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}`", op))
+        }
+    }
+
+    // one level of left associative binary operators
+    fn binary(
+        &mut self,
+        ops: &[(&str, BinOp)],
+        next: fn(&mut Self) -> Result<Expr, String>,
+    ) -> Result<Expr, String> {
+        let mut lhs = next(self)?;
+        'outer: loop {
+            for (text, op) in ops {
+                if self.eat(text) {
+                    let rhs = next(self)?;
+                    lhs = Expr::Binary(*op, Box::new(lhs), Box::new(rhs));
+                    continue 'outer;
+                }
+            }
+            return Ok(lhs);
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        self.binary(&[("||", BinOp::Or)], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        self.binary(&[("&&", BinOp::And)], Self::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        self.binary(
+            &[
+                ("<=", BinOp::Le),
+                (">=", BinOp::Ge),
+                ("==", BinOp::Eq),
+                ("!=", BinOp::Ne),
+                ("<", BinOp::Lt),
+                (">", BinOp::Gt),
+            ],
+            Self::sum,
+        )
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        self.binary(&[("+", BinOp::Add), ("-", BinOp::Sub)], Self::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        self.binary(&[("*", BinOp::Mul), ("/", BinOp::Div)], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat("-") {
+            Ok(Expr::Neg(Box::new(self.unary()?)))
+        } else if self.eat("!") {
+            Ok(Expr::Not(Box::new(self.unary()?)))
+        } else {
+            self.power()
+        }
+    }
+
+    // right associative, binds tighter than the unary minus on its left
+    fn power(&mut self) -> Result<Expr, String> {
+        let base = self.primary()?;
+        if self.eat("^") {
+            let exponent = self.unary()?;
+            return Ok(Expr::Binary(BinOp::Pow, Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "unexpected end of expression".to_string())?;
+        self.pos += 1;
+        match token {
+            Token::Num(v) => Ok(Expr::Num(v)),
+            Token::Op("(") => {
+                let expr = self.or()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            Token::Ident(name) => {
+                if self.eat("(") {
+                    let (func, arity) =
+                        Func::from_name(&name).ok_or_else(|| format!("unknown function `{}`", name))?;
+                    let mut args = vec![self.or()?];
+                    while self.eat(",") {
+                        args.push(self.or()?);
+                    }
+                    self.expect(")")?;
+                    if args.len() != arity {
+                        return Err(format!("`{}` takes {} arguments", name, arity));
+                    }
+                    Ok(Expr::Call(func, args))
+                } else if let Some(i) = self.vars.iter().position(|v| *v == name) {
+                    Ok(Expr::Var(i))
+                } else if let Some((_, value)) = self.constants.iter().find(|(c, _)| *c == name) {
+                    Ok(Expr::Num(*value))
+                } else {
+                    Err(format!("unknown variable `{}`", name))
+                }
+            }
+            Token::Op(op) => Err(format!("unexpected `{}`", op)),
+        }
+    }
+}
@@ -0,0 +1,262 @@
+pub mod expr;
+pub mod parser;
+pub mod turtle;
+
+pub use expr::Expr;
+pub use turtle::{tube_mesh, Segment, Turtle};
+
+// Crate imports
+use crate::math::Rng;
+use crate::ressources::{self, RessourceLoader};
+
+#[derive(Debug)]
+pub enum Error {
+    ResourceLoad {
+        name: String,
+        inner: ressources::Error,
+    },
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+/// A symbol of the string being rewritten with its actual parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Module {
+    pub symbol: char,
+    pub params: Vec<f32>,
+}
+
+impl Module {
+    pub fn new(symbol: char, params: Vec<f32>) -> Self {
+        Self { symbol, params }
+    }
+
+    /// First parameter or `default` when the module has none.
+    pub fn param_or(&self, default: f32) -> f32 {
+        self.params.first().copied().unwrap_or(default)
+    }
+}
+
+impl std::fmt::Display for Module {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol)?;
+        if !self.params.is_empty() {
+            let params: Vec<String> = self.params.iter().map(|p| p.to_string()).collect();
+            write!(f, "({})", params.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a string of modules in the textual syntax, e.g. `F(1)[+A]`.
+pub fn word_to_string(word: &[Module]) -> String {
+    word.iter().map(|m| m.to_string()).collect()
+}
+
+/// Symbol with the number of formal parameters it binds in a rule.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    pub symbol: char,
+    pub arity: usize,
+}
+
+/// Module of a successor, its parameters are computed from the bound ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModuleTemplate {
+    pub symbol: char,
+    pub args: Vec<Expr>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Successor {
+    pub probability: f32,
+    pub modules: Vec<ModuleTemplate>,
+}
+
+/// Production `left < predecessor > right : condition -> successor`.
+///
+/// The parameters of the left context, the predecessor then the right context
+/// are bound in that order and indexed by the `Expr::Var` of the condition
+/// and successors. With several successors one is picked at random according
+/// to the probabilities.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub left: Vec<Pattern>,
+    pub predecessor: Pattern,
+    pub right: Vec<Pattern>,
+    pub condition: Option<Expr>,
+    pub successors: Vec<Successor>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct LSystem {
+    pub axiom: Vec<Module>,
+    pub rules: Vec<Rule>,
+    /// Symbols skipped when matching contexts, usually the turtle commands.
+    pub ignore: Vec<char>,
+}
+
+impl LSystem {
+    pub fn new(axiom: Vec<Module>) -> Self {
+        Self {
+            axiom,
+            ..Self::default()
+        }
+    }
+
+    /// Parses the textual syntax, see the `parser` module.
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        parser::parse(source)
+    }
+
+    pub fn from_res(res: &RessourceLoader, name: &str) -> Result<Self, Error> {
+        let bytes = res.load_bytes(name, &[]).map_err(|e| Error::ResourceLoad {
+            name: name.to_string(),
+            inner: e,
+        })?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Rewrites the axiom `iterations` times.
+    pub fn derive(&self, iterations: usize, rng: &mut Rng) -> Vec<Module> {
+        let mut word = self.axiom.clone();
+        for _ in 0..iterations {
+            word = self.step(&word, rng);
+        }
+        word
+    }
+
+    /// Applies the rules once to every module of the word in parallel,
+    /// modules without a matching rule are copied unchanged.
+    pub fn step(&self, word: &[Module], rng: &mut Rng) -> Vec<Module> {
+        let mut next = Vec::with_capacity(word.len() * 2);
+        let mut vars = Vec::new();
+        for (i, module) in word.iter().enumerate() {
+            match self.find_rule(word, i, &mut vars) {
+                Some(rule) => {
+                    let successor = if rule.successors.len() == 1 {
+                        &rule.successors[0]
+                    } else {
+                        let weights: Vec<f32> = rule.successors.iter().map(|s| s.probability).collect();
+                        &rule.successors[rng.weighted_index(&weights).unwrap_or(0)]
+                    };
+                    next.extend(successor.modules.iter().map(|m| Module {
+                        symbol: m.symbol,
+                        params: m.args.iter().map(|a| a.eval(&vars)).collect(),
+                    }));
+                }
+                None => next.push(module.clone()),
+            }
+        }
+        next
+    }
+
+    // First rule applying to the module at `index`, its parameters are left in `vars`.
+    fn find_rule(&self, word: &[Module], index: usize, vars: &mut Vec<f32>) -> Option<&Rule> {
+        let module = &word[index];
+        self.rules.iter().find(|rule| {
+            if !matches(&rule.predecessor, module) {
+                return false;
+            }
+            vars.clear();
+            let left = match self.match_left(word, index, &rule.left) {
+                Some(left) => left,
+                None => return false,
+            };
+            for i in left {
+                vars.extend_from_slice(&word[i].params);
+            }
+            vars.extend_from_slice(&module.params);
+            let right = match self.match_right(word, index, &rule.right) {
+                Some(right) => right,
+                None => return false,
+            };
+            for i in right {
+                vars.extend_from_slice(&word[i].params);
+            }
+            rule.condition
+                .as_ref()
+                .map(|c| c.eval(vars) != 0_f32)
+                .unwrap_or(true)
+        })
+    }
+
+    // Indices of the modules matching the left context, walking back
+    // over the ignored symbols and the branches ending before the module.
+    fn match_left(&self, word: &[Module], index: usize, patterns: &[Pattern]) -> Option<Vec<usize>> {
+        let mut found = Vec::with_capacity(patterns.len());
+        let mut i = index;
+        for pattern in patterns.iter().rev() {
+            loop {
+                i = i.checked_sub(1)?;
+                match word[i].symbol {
+                    ']' => i = matching_open(word, i)?,
+                    '[' => {}
+                    s if self.ignore.contains(&s) => {}
+                    _ => break,
+                }
+            }
+            if !matches(pattern, &word[i]) {
+                return None;
+            }
+            found.push(i);
+        }
+        found.reverse();
+        Some(found)
+    }
+
+    // Indices of the modules matching the right context, skipping the
+    // ignored symbols and the branches starting after the module.
+    fn match_right(&self, word: &[Module], index: usize, patterns: &[Pattern]) -> Option<Vec<usize>> {
+        let mut found = Vec::with_capacity(patterns.len());
+        let mut i = index;
+        for pattern in patterns {
+            loop {
+                i += 1;
+                match word.get(i)?.symbol {
+                    '[' => i = matching_close(word, i)?,
+                    ']' => return None,
+                    s if self.ignore.contains(&s) => {}
+                    _ => break,
+                }
+            }
+            if !matches(pattern, &word[i]) {
+                return None;
+            }
+            found.push(i);
+        }
+        Some(found)
+    }
+}
+
+fn matches(pattern: &Pattern, module: &Module) -> bool {
+    pattern.symbol == module.symbol && pattern.arity == module.params.len()
+}
+
+fn matching_open(word: &[Module], close: usize) -> Option<usize> {
+    let mut depth = 0;
+    for i in (0..close).rev() {
+        match word[i].symbol {
+            ']' => depth += 1,
+            '[' if depth == 0 => return Some(i),
+            '[' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+fn matching_close(word: &[Module], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, module) in word.iter().enumerate().skip(open + 1) {
+        match module.symbol {
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some(i),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
@@ -0,0 +1,210 @@
+// Textual syntax of the L-systems, one statement per line, `//` starts a comment:
+//
+//   #define angle 25
+//   #ignore +-/
+//   axiom: A(1)
+//   p1: A(x) : x < 5 -> F(x)[+(angle)A(x*0.5)]A(x+1)
+//   B < A > C -> D
+//   F ->(0.5) F[+F]F
+//   F ->(0.5) F[-F]F
+//
+// Rules with the same left hand side are merged into one stochastic rule,
+// the number after the arrow being the probability of each successor.
+
+// Crate imports
+use super::{Error, Expr, LSystem, Module, ModuleTemplate, Pattern, Rule, Successor};
+
+pub fn parse(source: &str) -> Result<LSystem, Error> {
+    let mut system = LSystem::default();
+    let mut constants: Vec<(String, f32)> = Vec::new();
+    let mut rule_keys: Vec<String> = Vec::new();
+
+    for (i, line) in source.lines().enumerate() {
+        let parse_error = |message: String| Error::Parse {
+            line: i + 1,
+            message,
+        };
+        let line = line.split("//").next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("#define") {
+            let mut words = rest.split_whitespace();
+            let name = words
+                .next()
+                .ok_or_else(|| parse_error("missing constant name".to_string()))?;
+            let value_src: Vec<&str> = words.collect();
+            let value = Expr::parse(&value_src.join(" "), &[], &constants).map_err(parse_error)?;
+            constants.push((name.to_string(), value.eval(&[])));
+        } else if let Some(rest) = line.strip_prefix("#ignore") {
+            let rest = rest.trim_start().trim_start_matches(':');
+            system.ignore.extend(rest.chars().filter(|c| !c.is_whitespace()));
+        } else if let Some(rest) = line.strip_prefix("axiom:") {
+            system.axiom = parse_modules(rest, |arg| {
+                Expr::parse(arg, &[], &constants).map(|e| e.eval(&[]))
+            })
+            .map_err(parse_error)?
+            .into_iter()
+            .map(|(symbol, params)| Module::new(symbol, params))
+            .collect();
+        } else {
+            let (key, rule) = parse_rule(strip_label(line), &constants).map_err(parse_error)?;
+            match rule_keys.iter().position(|k| *k == key) {
+                Some(index) => system.rules[index].successors.extend(rule.successors),
+                None => {
+                    rule_keys.push(key);
+                    system.rules.push(rule);
+                }
+            }
+        }
+    }
+    Ok(system)
+}
+
+// Removes a leading `p1:` style label.
+fn strip_label(line: &str) -> &str {
+    if let Some(colon) = line.find(':') {
+        let label = line[..colon].trim();
+        if label.len() > 1 && label.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return &line[colon + 1..];
+        }
+    }
+    line
+}
+
+// Parses a rule and returns it with the key identifying its left hand side.
+fn parse_rule(line: &str, constants: &[(String, f32)]) -> Result<(String, Rule), String> {
+    let arrow = line.find("->").ok_or_else(|| format!("expected `->` in `{}`", line))?;
+    let lhs = &line[..arrow];
+    let mut rhs = line[arrow + 2..].trim_start();
+
+    let mut probability = 1_f32;
+    if rhs.starts_with('(') {
+        let close = rhs.find(')').ok_or_else(|| "unclosed probability".to_string())?;
+        probability = Expr::parse(&rhs[1..close], &[], constants)?.eval(&[]);
+        rhs = &rhs[close + 1..];
+    }
+
+    let (context, condition) = match split_top_level(lhs, ':') {
+        Some((context, condition)) => (context, Some(condition)),
+        None => (lhs, None),
+    };
+    let (left, rest) = match context.find('<') {
+        Some(i) => (&context[..i], &context[i + 1..]),
+        None => ("", context),
+    };
+    let (predecessor, right) = match rest.find('>') {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, ""),
+    };
+
+    let mut vars: Vec<String> = Vec::new();
+    let mut patterns = |text: &str| -> Result<Vec<Pattern>, String> {
+        parse_modules(text, |arg| {
+            let name = arg.trim();
+            if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                return Err(format!("invalid parameter name `{}`", name));
+            }
+            Ok(name.to_string())
+        })?
+        .into_iter()
+        .map(|(symbol, names)| {
+            let arity = names.len();
+            vars.extend(names);
+            Ok(Pattern { symbol, arity })
+        })
+        .collect()
+    };
+    let left = patterns(left)?;
+    let mut predecessor = patterns(predecessor)?;
+    let right = patterns(right)?;
+    if predecessor.len() != 1 {
+        return Err(format!("expected a single predecessor in `{}`", lhs.trim()));
+    }
+
+    let condition = match condition {
+        Some(c) => Some(Expr::parse(c, &vars, constants)?),
+        None => None,
+    };
+    let modules = parse_modules(rhs, |arg| Expr::parse(arg, &vars, constants))?
+        .into_iter()
+        .map(|(symbol, args)| ModuleTemplate { symbol, args })
+        .collect();
+
+    let key = lhs.chars().filter(|c| !c.is_whitespace()).collect();
+    Ok((
+        key,
+        Rule {
+            left,
+            predecessor: predecessor.remove(0),
+            right,
+            condition,
+            successors: vec![Successor {
+                probability,
+                modules,
+            }],
+        },
+    ))
+}
+
+// Splits at the first `separator` outside of parentheses.
+fn split_top_level(text: &str, separator: char) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c == separator && depth == 0 => return Some((&text[..i], &text[i + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+// Parses a string of modules like `F(x,2)[+A]`, each argument being
+// converted by `parse_arg`.
+fn parse_modules<T>(
+    text: &str,
+    mut parse_arg: impl FnMut(&str) -> Result<T, String>,
+) -> Result<Vec<(char, Vec<T>)>, String> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut modules = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (_, symbol) = chars[i];
+        i += 1;
+        if symbol.is_whitespace() {
+            continue;
+        }
+        if symbol == '(' || symbol == ')' || symbol == ',' {
+            return Err(format!("unexpected `{}` in `{}`", symbol, text.trim()));
+        }
+        let mut args = Vec::new();
+        if i < chars.len() && chars[i].1 == '(' {
+            let mut depth = 0;
+            let mut start = chars[i].0 + 1;
+            i += 1;
+            loop {
+                let (pos, c) = *chars
+                    .get(i)
+                    .ok_or_else(|| format!("unclosed parameters of `{}`", symbol))?;
+                i += 1;
+                match c {
+                    '(' => depth += 1,
+                    ')' if depth > 0 => depth -= 1,
+                    ')' | ',' if depth == 0 => {
+                        args.push(parse_arg(&text[start..pos])?);
+                        start = pos + 1;
+                        if c == ')' {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        modules.push((symbol, args));
+    }
+    Ok(modules)
+}
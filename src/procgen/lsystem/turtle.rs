@@ -0,0 +1,176 @@
+// Turtle interpretation of the modules:
+//
+//   F(l)  move forward drawing a segment      f(l)  move forward without drawing
+//   +(a)  turn left around up                 -(a)  turn right around up
+//   &(a)  pitch down around left              ^(a)  pitch up around left
+//   \(a)  roll left around heading            /(a)  roll right around heading
+//   |     turn around                         $     roll so that left is horizontal
+//   [     push the state                      ]     pop the state
+//   !(w)  set the width, or scale it          '(c)  set the color, or increment it
+//
+// Angles are in degrees, modules without parameters use the turtle defaults.
+
+// Crate imports
+use super::Module;
+use crate::math::{Quat, Vec2D, Vec3D};
+use crate::procgen::mesh::Mesh;
+
+/// Segment drawn by the turtle, `rotation` takes the x, y and z axes to
+/// its heading, left and up directions.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Segment {
+    pub start: Vec3D,
+    pub end: Vec3D,
+    pub rotation: Quat,
+    pub width: f32,
+    /// Number of branches opened when the segment was drawn.
+    pub depth: usize,
+    pub color: usize,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct State {
+    position: Vec3D,
+    rotation: Quat,
+    width: f32,
+    color: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Turtle {
+    pub step: f32,
+    /// Default rotation angle in degrees.
+    pub angle: f32,
+    pub width: f32,
+    /// Factor applied to the width by `!` without parameter.
+    pub width_factor: f32,
+    pub position: Vec3D,
+    pub rotation: Quat,
+    /// Ignores the rotations leaving the xy plane.
+    pub planar: bool,
+}
+
+impl Turtle {
+    /// 3D turtle growing along the z (up) axis.
+    pub fn new(step: f32, angle: f32) -> Self {
+        Self {
+            step,
+            angle,
+            width: 1_f32,
+            width_factor: 0.7,
+            position: Vec3D::null(),
+            rotation: Quat::from_basis(Vec3D::up(), Vec3D::left(), Vec3D::back()),
+            planar: false,
+        }
+    }
+
+    /// 2D turtle in the xy plane growing along the y axis.
+    pub fn new_2d(step: f32, angle: f32) -> Self {
+        Self {
+            rotation: Quat::from_basis(
+                Vec3D::new(0_f32, 1_f32, 0_f32),
+                Vec3D::new(-1_f32, 0_f32, 0_f32),
+                Vec3D::new(0_f32, 0_f32, 1_f32),
+            ),
+            planar: true,
+            ..Self::new(step, angle)
+        }
+    }
+
+    /// Walks the modules and returns the drawn segments.
+    pub fn interpret(&self, word: &[Module]) -> Vec<Segment> {
+        let mut segments = Vec::new();
+        let mut stack: Vec<State> = Vec::new();
+        let mut state = State {
+            position: self.position,
+            rotation: self.rotation,
+            width: self.width,
+            color: 0,
+        };
+        for module in word {
+            let angle = module.param_or(self.angle).to_radians();
+            match module.symbol {
+                'F' | 'f' => {
+                    let end = state.position + module.param_or(self.step) * state.rotation.rotate(Vec3D::forward());
+                    if module.symbol == 'F' {
+                        segments.push(Segment {
+                            start: state.position,
+                            end,
+                            rotation: state.rotation,
+                            width: state.width,
+                            depth: stack.len(),
+                            color: state.color,
+                        });
+                    }
+                    state.position = end;
+                }
+                '+' => rotate(&mut state, angle, Vec3D::up()),
+                '-' => rotate(&mut state, -angle, Vec3D::up()),
+                '|' => rotate(&mut state, std::f32::consts::PI, Vec3D::up()),
+                '&' if !self.planar => rotate(&mut state, angle, Vec3D::left()),
+                '^' if !self.planar => rotate(&mut state, -angle, Vec3D::left()),
+                '\\' if !self.planar => rotate(&mut state, angle, Vec3D::forward()),
+                '/' if !self.planar => rotate(&mut state, -angle, Vec3D::forward()),
+                '$' if !self.planar => {
+                    let heading = state.rotation.rotate(Vec3D::forward());
+                    let left = Vec3D::up().cross(&heading);
+                    if left.sq_norm() > 1e-6 {
+                        let left = left * (1_f32 / left.norm());
+                        state.rotation = Quat::from_basis(heading, left, heading.cross(&left));
+                    }
+                }
+                '!' => {
+                    state.width = match module.params.first() {
+                        Some(w) => *w,
+                        None => state.width * self.width_factor,
+                    }
+                }
+                '\'' => {
+                    state.color = match module.params.first() {
+                        Some(c) => c.max(0_f32) as usize,
+                        None => state.color + 1,
+                    }
+                }
+                '[' => stack.push(state),
+                ']' => {
+                    if let Some(previous) = stack.pop() {
+                        state = previous;
+                    }
+                }
+                _ => {}
+            }
+        }
+        segments
+    }
+}
+
+// Rotation around an axis of the turtle frame, renormalised to avoid drift.
+fn rotate(state: &mut State, angle: f32, local_axis: Vec3D) {
+    let rotation = state.rotation * Quat::from_axis_angle(angle, &local_axis);
+    state.rotation = rotation * (1_f32 / rotation.norm());
+}
+
+/// Mesh of the segments as open tubes of `sides` faces, their diameter being the width.
+pub fn tube_mesh(segments: &[Segment], sides: usize) -> Mesh {
+    let sides = sides.max(3);
+    let mut mesh = Mesh::new();
+    for segment in segments {
+        let left = segment.rotation.rotate(Vec3D::left());
+        let up = segment.rotation.rotate(Vec3D::up());
+        let radius = segment.width * 0.5;
+        let length = (segment.end - segment.start).norm();
+        let base = mesh.vertex_count() as u32;
+        for side in 0..=sides {
+            let t = side as f32 / sides as f32;
+            let (sin, cos) = (t * std::f32::consts::PI * 2_f32).sin_cos();
+            let normal = cos * left + sin * up;
+            mesh.push_vertex(segment.start + radius * normal, normal, Vec2D::new(t, 0_f32));
+            mesh.push_vertex(segment.end + radius * normal, normal, Vec2D::new(t, length));
+        }
+        for side in 0..sides as u32 {
+            let (a, b) = (base + side * 2, base + side * 2 + 2);
+            mesh.push_quad(a, b, b + 1, a + 1);
+        }
+    }
+    mesh
+}
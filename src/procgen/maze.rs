@@ -0,0 +1,309 @@
+// Perfect maze generators, every cell is reachable from every other
+// through exactly one path.
+
+// Crate imports
+use crate::graph::DisjointSet;
+use crate::math::Rng;
+use crate::procgen::grid::Grid;
+use crate::procgen::tile::Tile;
+
+/// Directions of the passages, y going down like in images.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::East => (1, 0),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+        }
+    }
+
+    pub fn opposite(&self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    fn bit(&self) -> u8 {
+        1 << *self as u8
+    }
+}
+
+/// Grid of cells with the passages opened between them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Maze {
+    width: usize,
+    height: usize,
+    // bit set of the open directions of each cell
+    links: Vec<u8>,
+}
+
+impl Maze {
+    /// Maze with every wall closed.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            links: vec![0; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        x + y * self.width
+    }
+
+    pub fn coords(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    /// Neighbouring cell in the direction, if inside the maze.
+    pub fn neighbour(&self, cell: usize, direction: Direction) -> Option<usize> {
+        let (x, y) = self.coords(cell);
+        let (dx, dy) = direction.offset();
+        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        if nx >= 0 && ny >= 0 && (nx as usize) < self.width && (ny as usize) < self.height {
+            Some(self.index(nx as usize, ny as usize))
+        } else {
+            None
+        }
+    }
+
+    /// Opens the passage between the cell and its neighbour.
+    pub fn link(&mut self, cell: usize, direction: Direction) {
+        if let Some(other) = self.neighbour(cell, direction) {
+            self.links[cell] |= direction.bit();
+            self.links[other] |= direction.opposite().bit();
+        }
+    }
+
+    pub fn is_linked(&self, cell: usize, direction: Direction) -> bool {
+        self.links[cell] & direction.bit() != 0
+    }
+
+    /// Cells reachable from the cell through an open passage.
+    pub fn passages(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        Direction::ALL
+            .iter()
+            .filter(move |d| self.is_linked(cell, **d))
+            .filter_map(move |d| self.neighbour(cell, *d))
+    }
+
+    /// Tiles of the maze, each cell and passage being one floor tile
+    /// separated by walls, so `2 * width + 1` by `2 * height + 1` tiles.
+    pub fn to_grid(&self) -> Grid<Tile> {
+        let mut grid = Grid::new_2d(self.width * 2 + 1, self.height * 2 + 1, Tile::Wall);
+        for cell in 0..self.len() {
+            let (x, y) = self.coords(cell);
+            let (tx, ty) = (x * 2 + 1, y * 2 + 1);
+            grid[(tx, ty, 0)] = Tile::Floor;
+            if self.is_linked(cell, Direction::East) {
+                grid[(tx + 1, ty, 0)] = Tile::Floor;
+            }
+            if self.is_linked(cell, Direction::South) {
+                grid[(tx, ty + 1, 0)] = Tile::Floor;
+            }
+        }
+        grid
+    }
+}
+
+/// Depth first search carving, long winding corridors with few dead ends.
+pub fn recursive_backtracker(width: usize, height: usize, rng: &mut Rng) -> Maze {
+    let mut maze = Maze::new(width, height);
+    if maze.is_empty() {
+        return maze;
+    }
+    let mut visited = vec![false; maze.len()];
+    let start = rng.range_usize(0, maze.len());
+    let mut stack = vec![start];
+    visited[start] = true;
+    while let Some(&cell) = stack.last() {
+        let unvisited: Vec<Direction> = Direction::ALL
+            .iter()
+            .copied()
+            .filter(|d| maze.neighbour(cell, *d).map(|n| !visited[n]).unwrap_or(false))
+            .collect();
+        match rng.choose(&unvisited) {
+            Some(&direction) => {
+                let next = maze.neighbour(cell, direction).unwrap();
+                maze.link(cell, direction);
+                visited[next] = true;
+                stack.push(next);
+            }
+            None => {
+                stack.pop();
+            }
+        }
+    }
+    maze
+}
+
+/// Randomised Prim, grows from a cell by opening a random frontier cell, many short dead ends.
+pub fn prim(width: usize, height: usize, rng: &mut Rng) -> Maze {
+    let mut maze = Maze::new(width, height);
+    if maze.is_empty() {
+        return maze;
+    }
+    let mut in_maze = vec![false; maze.len()];
+    let mut in_frontier = vec![false; maze.len()];
+    let mut frontier = Vec::new();
+    let start = rng.range_usize(0, maze.len());
+    let mut add = |maze: &Maze, cell: usize, in_maze: &mut Vec<bool>, frontier: &mut Vec<usize>| {
+        in_maze[cell] = true;
+        for d in Direction::ALL.iter() {
+            if let Some(n) = maze.neighbour(cell, *d) {
+                if !in_maze[n] && !in_frontier[n] {
+                    in_frontier[n] = true;
+                    frontier.push(n);
+                }
+            }
+        }
+    };
+    add(&maze, start, &mut in_maze, &mut frontier);
+    while !frontier.is_empty() {
+        let cell = frontier.swap_remove(rng.range_usize(0, frontier.len()));
+        let inside: Vec<Direction> = Direction::ALL
+            .iter()
+            .copied()
+            .filter(|d| maze.neighbour(cell, *d).map(|n| in_maze[n]).unwrap_or(false))
+            .collect();
+        if let Some(&direction) = rng.choose(&inside) {
+            maze.link(cell, direction);
+        }
+        add(&maze, cell, &mut in_maze, &mut frontier);
+    }
+    maze
+}
+
+/// Randomised Kruskal, opens the walls in random order when they join two separate areas.
+pub fn kruskal(width: usize, height: usize, rng: &mut Rng) -> Maze {
+    let mut maze = Maze::new(width, height);
+    let mut walls = Vec::with_capacity(maze.len() * 2);
+    for cell in 0..maze.len() {
+        for &direction in &[Direction::East, Direction::South] {
+            if maze.neighbour(cell, direction).is_some() {
+                walls.push((cell, direction));
+            }
+        }
+    }
+    rng.shuffle(&mut walls);
+    let mut sets = DisjointSet::new(maze.len());
+    for (cell, direction) in walls {
+        let other = maze.neighbour(cell, direction).unwrap();
+        if sets.union(cell, other) {
+            maze.link(cell, direction);
+        }
+    }
+    maze
+}
+
+/// Wilson's algorithm, loop erased random walks giving an unbiased maze.
+pub fn wilson(width: usize, height: usize, rng: &mut Rng) -> Maze {
+    let mut maze = Maze::new(width, height);
+    if maze.is_empty() {
+        return maze;
+    }
+    let mut in_maze = vec![false; maze.len()];
+    in_maze[rng.range_usize(0, maze.len())] = true;
+    let mut order: Vec<usize> = (0..maze.len()).collect();
+    rng.shuffle(&mut order);
+    // direction last taken from each cell, overwriting it erases the loops
+    let mut walk: Vec<Option<Direction>> = vec![None; maze.len()];
+    for start in order {
+        if in_maze[start] {
+            continue;
+        }
+        let mut cell = start;
+        while !in_maze[cell] {
+            let directions: Vec<Direction> = Direction::ALL
+                .iter()
+                .copied()
+                .filter(|d| maze.neighbour(cell, *d).is_some())
+                .collect();
+            let direction = *rng.choose(&directions).unwrap();
+            walk[cell] = Some(direction);
+            cell = maze.neighbour(cell, direction).unwrap();
+        }
+        let mut cell = start;
+        while !in_maze[cell] {
+            let direction = walk[cell].unwrap();
+            in_maze[cell] = true;
+            maze.link(cell, direction);
+            cell = maze.neighbour(cell, direction).unwrap();
+        }
+    }
+    maze
+}
+
+/// Eller's algorithm, builds the maze one row at a time.
+///
+/// `horizontal_bias` is the chance of joining two neighbouring cells of a row.
+pub fn eller(width: usize, height: usize, horizontal_bias: f32, rng: &mut Rng) -> Maze {
+    let mut maze = Maze::new(width, height);
+    if maze.is_empty() {
+        return maze;
+    }
+    // the sets are only tracked for the current row
+    let mut sets = DisjointSet::new(maze.len());
+    for y in 0..height {
+        let last_row = y + 1 == height;
+        for x in 0..width - 1 {
+            let cell = maze.index(x, y);
+            let joined = sets.find(cell) == sets.find(cell + 1);
+            if !joined && (last_row || rng.chance(horizontal_bias)) {
+                sets.union(cell, cell + 1);
+                maze.link(cell, Direction::East);
+            }
+        }
+        if last_row {
+            break;
+        }
+        // at least one passage down for each set of the row
+        let mut cells: Vec<usize> = (0..width).map(|x| maze.index(x, y)).collect();
+        rng.shuffle(&mut cells);
+        let mut has_down: Vec<usize> = Vec::new();
+        for &cell in &cells {
+            let root = sets.find(cell);
+            if !has_down.contains(&root) || rng.chance(0.3) {
+                has_down.push(root);
+                sets.union(cell, cell + width);
+                maze.link(cell, Direction::South);
+            }
+        }
+    }
+    maze
+}
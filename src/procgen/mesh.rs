@@ -0,0 +1,66 @@
+// Crate imports
+use crate::math::{Vec2D, Vec3D};
+
+/// Indexed triangle mesh produced by the generators.
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub positions: Vec<Vec3D>,
+    pub normals: Vec<Vec3D>,
+    pub uvs: Vec<Vec2D>,
+    pub indices: Vec<u32>,
+}
+
+impl Mesh {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn vertex_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.indices.len() / 3
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Adds a vertex and returns its index.
+    pub fn push_vertex(&mut self, position: Vec3D, normal: Vec3D, uv: Vec2D) -> u32 {
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push(uv);
+        (self.positions.len() - 1) as u32
+    }
+
+    pub fn push_triangle(&mut self, a: u32, b: u32, c: u32) {
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    /// Adds the quad `a b c d` given counter-clockwise as two triangles.
+    pub fn push_quad(&mut self, a: u32, b: u32, c: u32, d: u32) {
+        self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+    }
+
+    /// Appends the vertices and triangles of another mesh.
+    pub fn append(&mut self, other: &Mesh) {
+        let offset = self.positions.len() as u32;
+        self.positions.extend_from_slice(&other.positions);
+        self.normals.extend_from_slice(&other.normals);
+        self.uvs.extend_from_slice(&other.uvs);
+        self.indices.extend(other.indices.iter().map(|i| i + offset));
+    }
+
+    /// Vertices interleaved as position (3), normal (3) and uv (2) floats,
+    /// matching a `VbLayout` with `push_f32(3)`, `push_f32(3)` and `push_f32(2)`.
+    pub fn interleaved(&self) -> Vec<f32> {
+        let mut data = Vec::with_capacity(self.positions.len() * 8);
+        for i in 0..self.positions.len() {
+            let (p, n, uv) = (self.positions[i], self.normals[i], self.uvs[i]);
+            data.extend_from_slice(&[p.x, p.y, p.z, n.x, n.y, n.z, uv.x, uv.y]);
+        }
+        data
+    }
+}
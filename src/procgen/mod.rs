@@ -0,0 +1,18 @@
+pub mod automata;
+pub mod biome;
+pub mod dungeon;
+pub mod grammar;
+pub mod grid;
+pub mod lsystem;
+pub mod maze;
+pub mod mesh;
+pub mod names;
+pub mod planet;
+pub mod terrain;
+pub mod tile;
+pub mod voxel;
+pub mod wfc;
+
+pub use grid::Grid;
+pub use mesh::Mesh;
+pub use tile::Tile;
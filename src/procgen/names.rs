@@ -0,0 +1,167 @@
+// External imports
+use std::collections::HashMap;
+// Crate imports
+use crate::math::Rng;
+use crate::ressources::{self, RessourceLoader};
+
+#[derive(Debug)]
+pub enum Error {
+    ResourceLoad {
+        name: String,
+        inner: ressources::Error,
+    },
+}
+
+// Markers of the start and end of a word in the contexts.
+const START: char = '\u{2}';
+const END: char = '\u{3}';
+
+/// Constraints on the generated names.
+#[derive(Clone, Debug)]
+pub struct Constraints {
+    pub min_length: usize,
+    pub max_length: usize,
+    /// Whether names of the training list can be generated.
+    pub allow_training_words: bool,
+    /// Names generated before giving up.
+    pub max_attempts: usize,
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            min_length: 3,
+            max_length: 12,
+            allow_training_words: false,
+            max_attempts: 1000,
+        }
+    }
+}
+
+/// Character level Markov chain, the next letter depends on the last `order`
+/// letters, backing off to shorter contexts when a longer one was never seen.
+#[derive(Clone, Debug)]
+pub struct NameGenerator {
+    order: usize,
+    // models[k] maps contexts of k + 1 letters to the letters seen after them
+    models: Vec<HashMap<String, Vec<(char, f32)>>>,
+    words: Vec<String>,
+}
+
+impl NameGenerator {
+    pub fn new(order: usize) -> Self {
+        let order = order.max(1);
+        Self {
+            order,
+            models: vec![HashMap::new(); order],
+            words: Vec::new(),
+        }
+    }
+
+    pub fn from_words<'a>(order: usize, words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut generator = Self::new(order);
+        generator.train(words);
+        generator
+    }
+
+    /// Trains on a word list with one word per line, `#` starting a comment line.
+    pub fn from_res(res: &RessourceLoader, name: &str, order: usize) -> Result<Self, Error> {
+        let bytes = res.load_bytes(name, &[]).map_err(|e| Error::ResourceLoad {
+            name: name.to_string(),
+            inner: e,
+        })?;
+        let text = String::from_utf8_lossy(&bytes);
+        let words = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'));
+        Ok(Self::from_words(order, words))
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn train<'a>(&mut self, words: impl IntoIterator<Item = &'a str>) {
+        for word in words {
+            let word = word.trim();
+            if word.is_empty() {
+                continue;
+            }
+            self.words.push(word.to_string());
+            let padded: Vec<char> = std::iter::repeat_n(START, self.order)
+                .chain(word.chars())
+                .chain(std::iter::once(END))
+                .collect();
+            for i in self.order..padded.len() {
+                for k in 0..self.order {
+                    let context: String = padded[i - k - 1..i].iter().collect();
+                    let next = self.models[k].entry(context).or_default();
+                    match next.iter_mut().find(|(c, _)| *c == padded[i]) {
+                        Some((_, count)) => *count += 1_f32,
+                        None => next.push((padded[i], 1_f32)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Generates a name, `None` if no name satisfied the constraints in time.
+    pub fn generate(&self, rng: &mut Rng, constraints: &Constraints) -> Option<String> {
+        self.generate_filtered(rng, constraints, |_| true)
+    }
+
+    /// Generates a name also accepted by `filter`.
+    pub fn generate_filtered(
+        &self,
+        rng: &mut Rng,
+        constraints: &Constraints,
+        mut filter: impl FnMut(&str) -> bool,
+    ) -> Option<String> {
+        if self.words.is_empty() {
+            return None;
+        }
+        for _ in 0..constraints.max_attempts {
+            let name = match self.sample(rng, constraints.max_length) {
+                Some(name) => name,
+                None => continue,
+            };
+            let length = name.chars().count();
+            if length < constraints.min_length || length > constraints.max_length {
+                continue;
+            }
+            if !constraints.allow_training_words && self.words.contains(&name) {
+                continue;
+            }
+            if filter(&name) {
+                return Some(name);
+            }
+        }
+        None
+    }
+
+    // One walk of the chain, `None` if it got longer than `max_length`.
+    fn sample(&self, rng: &mut Rng, max_length: usize) -> Option<String> {
+        let mut letters: Vec<char> = vec![START; self.order];
+        loop {
+            let next = (0..self.order)
+                .rev()
+                .find_map(|k| {
+                    let context: String = letters[letters.len() - k - 1..].iter().collect();
+                    self.models[k].get(&context)
+                })
+                .map(|next| {
+                    let weights: Vec<f32> = next.iter().map(|(_, w)| *w).collect();
+                    next[rng.weighted_index(&weights).unwrap_or(0)].0
+                })
+                .unwrap_or(END);
+            if next == END {
+                return Some(letters[self.order..].iter().collect());
+            }
+            letters.push(next);
+            if letters.len() - self.order > max_length {
+                return None;
+            }
+        }
+    }
+}
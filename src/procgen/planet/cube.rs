@@ -0,0 +1,69 @@
+// Crate imports
+use crate::math::DVec3D;
+
+/// Faces of the cube, named after the axis they look along.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CubeFace {
+    PosX,
+    NegX,
+    PosY,
+    NegY,
+    PosZ,
+    NegZ,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PosX,
+        CubeFace::NegX,
+        CubeFace::PosY,
+        CubeFace::NegY,
+        CubeFace::PosZ,
+        CubeFace::NegZ,
+    ];
+
+    /// Normal of the face and the axes of its `u` and `v` coordinates,
+    /// `u × v` being the normal so that the faces are counter-clockwise
+    /// seen from outside.
+    pub fn axes(&self) -> (DVec3D, DVec3D, DVec3D) {
+        let x = DVec3D::new(1_f64, 0_f64, 0_f64);
+        let y = DVec3D::new(0_f64, 1_f64, 0_f64);
+        let z = DVec3D::new(0_f64, 0_f64, 1_f64);
+        match self {
+            CubeFace::PosX => (x, y, z),
+            CubeFace::NegX => (-x, z, y),
+            CubeFace::PosY => (y, z, x),
+            CubeFace::NegY => (-y, x, z),
+            CubeFace::PosZ => (z, x, y),
+            CubeFace::NegZ => (-z, y, x),
+        }
+    }
+
+    /// Point of the cube of half size 1 at the face coordinates, `u` and `v` in `[-1, 1]`.
+    pub fn cube_point(&self, u: f64, v: f64) -> DVec3D {
+        let (normal, u_axis, v_axis) = self.axes();
+        normal + u_axis * u + v_axis * v
+    }
+
+    /// Direction of the sphere matching the face coordinates.
+    pub fn sphere_point(&self, u: f64, v: f64) -> DVec3D {
+        cube_to_sphere(self.cube_point(u, v)).normalized()
+    }
+}
+
+/// Maps a point of the cube of half size 1 to the unit sphere, spreading
+/// the area more evenly than a normalisation.
+pub fn cube_to_sphere(p: DVec3D) -> DVec3D {
+    let (x2, y2, z2) = (p.x * p.x, p.y * p.y, p.z * p.z);
+    DVec3D::new(
+        p.x * (1_f64 - y2 / 2_f64 - z2 / 2_f64 + y2 * z2 / 3_f64)
+            .max(0_f64)
+            .sqrt(),
+        p.y * (1_f64 - z2 / 2_f64 - x2 / 2_f64 + z2 * x2 / 3_f64)
+            .max(0_f64)
+            .sqrt(),
+        p.z * (1_f64 - x2 / 2_f64 - y2 / 2_f64 + x2 * y2 / 3_f64)
+            .max(0_f64)
+            .sqrt(),
+    )
+}
@@ -0,0 +1,187 @@
+// Crate imports
+use super::quadtree::{ChunkKey, LodChanges, LodParams, LodTree};
+use crate::math::noise::{Fractal, OpenSimplex};
+use crate::math::{DVec3D, Vec2D, Vec3D};
+use crate::procgen::mesh::Mesh;
+use crate::procgen::terrain::Heightmap;
+use crate::utils::parallel::parallel_map;
+
+#[derive(Clone, Debug)]
+pub struct PlanetParams {
+    pub radius: f64,
+    pub seed: i64,
+    /// Noise sampled on the unit sphere.
+    pub noise: Fractal,
+    /// Height of the relief above and below the radius.
+    pub amplitude: f64,
+    /// Quads along each side of a chunk.
+    pub resolution: usize,
+    /// Depth of the skirts hiding the cracks between levels of detail.
+    pub skirt_depth: f64,
+    pub lod: LodParams,
+}
+
+impl Default for PlanetParams {
+    fn default() -> Self {
+        Self {
+            radius: 6_371_000_f64,
+            seed: 0,
+            noise: Fractal::new(8, 2_f32),
+            amplitude: 8_000_f64,
+            resolution: 32,
+            skirt_depth: 1_000_f64,
+            lod: LodParams::default(),
+        }
+    }
+}
+
+/// Chunk of the planet surface, the mesh being relative to `origin` so
+/// that its `f32` positions stay precise.
+#[derive(Clone, Debug)]
+pub struct PlanetChunk {
+    pub key: ChunkKey,
+    pub origin: DVec3D,
+    /// Height of each vertex above the radius, `resolution + 1` on each side.
+    pub heights: Heightmap,
+    /// Surface vertices on a `resolution + 1` square grid like
+    /// `Heightmap::to_mesh`, then the skirt vertices.
+    pub mesh: Mesh,
+}
+
+/// Planet surface with its levels of detail around the camera.
+pub struct Planet {
+    params: PlanetParams,
+    noise: OpenSimplex,
+    lod: LodTree,
+}
+
+impl Planet {
+    pub fn new(params: PlanetParams) -> Self {
+        Self {
+            noise: OpenSimplex::new(params.seed),
+            params,
+            lod: LodTree::new(),
+        }
+    }
+
+    pub fn params(&self) -> &PlanetParams {
+        &self.params
+    }
+
+    /// Height above the radius in a direction of the unit sphere.
+    pub fn height(&self, direction: DVec3D) -> f64 {
+        let value = self.params.noise.eval3(&self.noise, direction.to_vec3d());
+        value as f64 * self.params.amplitude
+    }
+
+    /// Point of the surface in a direction.
+    pub fn surface_point(&self, direction: DVec3D) -> DVec3D {
+        let direction = direction.normalized();
+        direction * (self.params.radius + self.height(direction))
+    }
+
+    /// Chunks currently covering the planet.
+    pub fn chunks(&self) -> impl Iterator<Item = &ChunkKey> + '_ {
+        self.lod.leaves()
+    }
+
+    /// Refines the chunks around the camera, in planet coordinates.
+    pub fn update(&mut self, camera: DVec3D) -> LodChanges {
+        self.lod
+            .update(camera, self.params.radius, &self.params.lod)
+    }
+
+    pub fn build_chunk(&self, key: ChunkKey) -> PlanetChunk {
+        let n = self.params.resolution.max(1);
+        let (u0, v0, u1, _) = key.bounds();
+        let step = (u1 - u0) / n as f64;
+        let origin = self.surface_point(key.center());
+        // positions with a ring around the chunk so the normals of the
+        // border match the neighbours
+        let side = n + 3;
+        let mut heights = vec![0_f64; side * side];
+        let mut positions = vec![DVec3D::null(); side * side];
+        for j in 0..side {
+            for i in 0..side {
+                let u = u0 + (i as f64 - 1_f64) * step;
+                let v = v0 + (j as f64 - 1_f64) * step;
+                let direction = key.face.sphere_point(u, v);
+                let height = self.height(direction);
+                heights[i + j * side] = height;
+                positions[i + j * side] = direction * (self.params.radius + height);
+            }
+        }
+        let at = |i: usize, j: usize| positions[(i + 1) + (j + 1) * side];
+
+        let mut mesh = Mesh::new();
+        let mut normals = Vec::with_capacity((n + 1) * (n + 1));
+        for j in 0..=n {
+            for i in 0..=n {
+                let du = positions[(i + 2) + (j + 1) * side] - positions[i + (j + 1) * side];
+                let dv = positions[(i + 1) + (j + 2) * side] - positions[(i + 1) + j * side];
+                let normal = du.cross(&dv).normalized().to_vec3d();
+                let uv = Vec2D::new(i as f32 / n as f32, j as f32 / n as f32);
+                mesh.push_vertex((at(i, j) - origin).to_vec3d(), normal, uv);
+                normals.push(normal);
+            }
+        }
+        for j in 0..n {
+            for i in 0..n {
+                let a = (i + j * (n + 1)) as u32;
+                let b = a + 1;
+                let c = b + (n + 1) as u32;
+                let d = a + (n + 1) as u32;
+                mesh.push_quad(a, b, c, d);
+            }
+        }
+
+        // skirts around the border, walked counter-clockwise
+        let mut border: Vec<(usize, usize)> = Vec::with_capacity(4 * n + 1);
+        border.extend((0..n).map(|i| (i, 0)));
+        border.extend((0..n).map(|j| (n, j)));
+        border.extend((0..n).map(|i| (n - i, n)));
+        border.extend((0..n).map(|j| (0, n - j)));
+        border.push((0, 0));
+        let mut previous: Option<(u32, u32)> = None;
+        for &(i, j) in &border {
+            let top = (i + j * (n + 1)) as u32;
+            let position = at(i, j);
+            let lowered = position - position.normalized() * self.params.skirt_depth;
+            let uv = mesh.uvs[top as usize];
+            let bottom = mesh.push_vertex((lowered - origin).to_vec3d(), normals[top as usize], uv);
+            if let Some((previous_top, previous_bottom)) = previous {
+                mesh.push_quad(previous_top, previous_bottom, bottom, top);
+            }
+            previous = Some((top, bottom));
+        }
+
+        let heightmap = Heightmap::from_fn(n + 1, n + 1, |i, j| {
+            heights[(i + 1) + (j + 1) * side] as f32
+        });
+        PlanetChunk {
+            key,
+            origin,
+            heights: heightmap,
+            mesh,
+        }
+    }
+
+    /// Builds the chunks on `threads` worker threads, in the order of the keys.
+    pub fn build_chunks(&self, keys: &[ChunkKey], threads: usize) -> Vec<PlanetChunk> {
+        parallel_map(keys, threads, |&key| self.build_chunk(key))
+    }
+}
+
+impl PlanetChunk {
+    /// Position of a mesh vertex on the planet.
+    pub fn world_position(&self, vertex: usize) -> DVec3D {
+        self.origin + DVec3D::from(self.mesh.positions[vertex])
+    }
+
+    /// Mesh positions relative to a camera, for rendering with `f32`
+    /// without the jitter of large coordinates.
+    pub fn camera_relative(&self, camera: DVec3D) -> Vec<Vec3D> {
+        let offset = (self.origin - camera).to_vec3d();
+        self.mesh.positions.iter().map(|&p| p + offset).collect()
+    }
+}
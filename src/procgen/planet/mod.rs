@@ -0,0 +1,11 @@
+// Planets as cubes inflated into spheres: each face of the cube is a
+// quadtree of chunks refined around the camera, the relief coming from
+// 3D noise so that the faces join without seams nor poles.
+
+pub mod cube;
+pub mod generator;
+pub mod quadtree;
+
+pub use cube::{cube_to_sphere, CubeFace};
+pub use generator::{Planet, PlanetChunk, PlanetParams};
+pub use quadtree::{ChunkKey, LodChanges, LodParams, LodTree};
@@ -0,0 +1,144 @@
+// External imports
+use std::collections::BTreeSet;
+// Crate imports
+use super::cube::CubeFace;
+use crate::math::DVec3D;
+
+/// Tile of a face quadtree, `x` and `y` counting tiles along `u` and `v`
+/// among the `2^level` of its level.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChunkKey {
+    pub face: CubeFace,
+    pub level: u8,
+    pub x: u32,
+    pub y: u32,
+}
+
+impl ChunkKey {
+    /// Tile covering the whole face.
+    pub fn root(face: CubeFace) -> Self {
+        Self {
+            face,
+            level: 0,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    /// Tiles along each side of the face at this level.
+    pub fn tiles(&self) -> u32 {
+        1 << self.level
+    }
+
+    /// Face coordinates `(u0, v0, u1, v1)` covered by the tile.
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        let size = 2_f64 / self.tiles() as f64;
+        let (u0, v0) = (-1_f64 + self.x as f64 * size, -1_f64 + self.y as f64 * size);
+        (u0, v0, u0 + size, v0 + size)
+    }
+
+    /// Direction of the center of the tile.
+    pub fn center(&self) -> DVec3D {
+        let (u0, v0, u1, v1) = self.bounds();
+        self.face.sphere_point((u0 + u1) * 0.5, (v0 + v1) * 0.5)
+    }
+
+    /// The four tiles of the next level, in `u` then `v` order.
+    pub fn children(&self) -> [ChunkKey; 4] {
+        let child = |dx: u32, dy: u32| ChunkKey {
+            face: self.face,
+            level: self.level + 1,
+            x: self.x * 2 + dx,
+            y: self.y * 2 + dy,
+        };
+        [child(0, 0), child(1, 0), child(0, 1), child(1, 1)]
+    }
+
+    pub fn parent(&self) -> Option<ChunkKey> {
+        if self.level == 0 {
+            return None;
+        }
+        Some(ChunkKey {
+            face: self.face,
+            level: self.level - 1,
+            x: self.x / 2,
+            y: self.y / 2,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct LodParams {
+    pub max_level: u8,
+    /// A tile splits when the camera is closer to its center than this
+    /// many times its width.
+    pub split_distance: f64,
+}
+
+impl Default for LodParams {
+    fn default() -> Self {
+        Self {
+            max_level: 12,
+            split_distance: 2_f64,
+        }
+    }
+}
+
+/// Chunks to build and to drop after a camera move.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LodChanges {
+    pub added: Vec<ChunkKey>,
+    pub removed: Vec<ChunkKey>,
+}
+
+impl LodChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Leaves of the six face quadtrees, covering the sphere once.
+#[derive(Clone, Debug, Default)]
+pub struct LodTree {
+    leaves: BTreeSet<ChunkKey>,
+}
+
+impl LodTree {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn leaves(&self) -> impl Iterator<Item = &ChunkKey> + '_ {
+        self.leaves.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Refines the tiles around the camera on a sphere of the radius.
+    pub fn update(&mut self, camera: DVec3D, radius: f64, params: &LodParams) -> LodChanges {
+        let mut leaves = BTreeSet::new();
+        let mut stack: Vec<ChunkKey> = CubeFace::ALL.iter().map(|&f| ChunkKey::root(f)).collect();
+        while let Some(key) = stack.pop() {
+            // a face spans a quarter of the circumference
+            let width = radius * std::f64::consts::FRAC_PI_2 / key.tiles() as f64;
+            let distance = camera.distance(&(key.center() * radius));
+            if key.level < params.max_level && distance < params.split_distance * width {
+                stack.extend_from_slice(&key.children());
+            } else {
+                leaves.insert(key);
+            }
+        }
+        let changes = LodChanges {
+            added: leaves.difference(&self.leaves).copied().collect(),
+            removed: self.leaves.difference(&leaves).copied().collect(),
+        };
+        self.leaves = leaves;
+        changes
+    }
+}
@@ -0,0 +1,206 @@
+// External imports
+use image::RgbaImage;
+// Crate imports
+use crate::math::fct::{lerp, smoothstep};
+use crate::math::{Vec2D, Vec3D};
+use crate::procgen::grid::Grid;
+use crate::procgen::mesh::Mesh;
+
+/// How `Heightmap::carve` changes the terrain under the path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CarveMode {
+    /// Only digs down to the path, like a river bed.
+    Lower,
+    /// Brings the terrain to the path from above and below, like a road bed.
+    Flatten,
+}
+
+/// Grid of heights, one unit between neighbouring cells.
+///
+/// Coordinates outside of the map are clamped to its border.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Heightmap {
+    heights: Grid<f32>,
+}
+
+impl Heightmap {
+    /// Flat map at height 0.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            heights: Grid::new_2d(width, height, 0_f32),
+        }
+    }
+
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> f32) -> Self {
+        Self {
+            heights: Grid::from_fn(width, height, 1, |x, y, _| f(x, y)),
+        }
+    }
+
+    /// Uses the first layer of the grid.
+    pub fn from_grid(grid: &Grid<f32>) -> Self {
+        Self::from_fn(grid.width(), grid.height(), |x, y| grid[(x, y, 0)])
+    }
+
+    pub fn width(&self) -> usize {
+        self.heights.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.heights.height()
+    }
+
+    pub fn grid(&self) -> &Grid<f32> {
+        &self.heights
+    }
+
+    pub fn grid_mut(&mut self) -> &mut Grid<f32> {
+        &mut self.heights
+    }
+
+    pub fn in_bounds(&self, x: i32, y: i32) -> bool {
+        self.heights.in_bounds(x, y, 0)
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> f32 {
+        let x = x.clamp(0, self.width() as i32 - 1) as usize;
+        let y = y.clamp(0, self.height() as i32 - 1) as usize;
+        self.heights[(x, y, 0)]
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, height: f32) {
+        self.heights.set(x, y, 0, height);
+    }
+
+    /// Bilinear interpolation of the heights.
+    pub fn sample(&self, point: Vec2D) -> f32 {
+        let (x0, y0) = (point.x.floor(), point.y.floor());
+        let (tx, ty) = (point.x - x0, point.y - y0);
+        let (x0, y0) = (x0 as i32, y0 as i32);
+        let top = lerp(self.get(x0, y0), self.get(x0 + 1, y0), tx);
+        let bottom = lerp(self.get(x0, y0 + 1), self.get(x0 + 1, y0 + 1), tx);
+        lerp(top, bottom, ty)
+    }
+
+    /// Central differences of the heights.
+    pub fn gradient(&self, x: i32, y: i32) -> Vec2D {
+        Vec2D::new(
+            (self.get(x + 1, y) - self.get(x - 1, y)) * 0.5,
+            (self.get(x, y + 1) - self.get(x, y - 1)) * 0.5,
+        )
+    }
+
+    /// Height difference per unit of distance along the steepest direction.
+    pub fn slope(&self, x: i32, y: i32) -> f32 {
+        self.gradient(x, y).norm()
+    }
+
+    /// Normal of the surface, z being up.
+    pub fn normal(&self, x: i32, y: i32) -> Vec3D {
+        let gradient = self.gradient(x, y);
+        Vec3D::new(-gradient.x, -gradient.y, 1_f32).normalized()
+    }
+
+    /// Lowest and highest heights.
+    pub fn range(&self) -> (f32, f32) {
+        self.heights
+            .cells()
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &h| {
+                (min.min(h), max.max(h))
+            })
+    }
+
+    /// Grayscale image from the lowest (black) to the highest (white) height.
+    pub fn to_image(&self) -> RgbaImage {
+        let (min, max) = self.range();
+        let scale = if max > min {
+            255_f32 / (max - min)
+        } else {
+            0_f32
+        };
+        self.heights.to_image(0, |h| {
+            let v = ((h - min) * scale) as u8;
+            [v, v, v, 255]
+        })
+    }
+
+    /// Grid mesh of the terrain with a vertex per cell, the heights along z
+    /// multiplied by `height_scale` and the uvs covering the whole map.
+    pub fn to_mesh(&self, height_scale: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        let (width, height) = (self.width(), self.height());
+        if width < 2 || height < 2 {
+            return mesh;
+        }
+        for y in 0..height as i32 {
+            for x in 0..width as i32 {
+                let gradient = self.gradient(x, y) * height_scale;
+                let normal = Vec3D::new(-gradient.x, -gradient.y, 1_f32).normalized();
+                let position = Vec3D::new(x as f32, y as f32, self.get(x, y) * height_scale);
+                let uv = Vec2D::new(
+                    x as f32 / (width - 1) as f32,
+                    y as f32 / (height - 1) as f32,
+                );
+                mesh.push_vertex(position, normal, uv);
+            }
+        }
+        for y in 0..height - 1 {
+            for x in 0..width - 1 {
+                let a = (x + y * width) as u32;
+                let b = a + 1;
+                let c = b + width as u32;
+                let d = a + width as u32;
+                mesh.push_quad(a, b, c, d);
+            }
+        }
+        mesh
+    }
+
+    /// Brings the terrain to the height of a path given as points with
+    /// their target height in `z` and half width in `radii`.
+    ///
+    /// The change fades out over `falloff` past the radius, each cell
+    /// following the point of the path that affects it the most.
+    pub fn carve(&mut self, points: &[Vec3D], radii: &[f32], falloff: f32, mode: CarveMode) {
+        let falloff = falloff.max(0_f32);
+        // strongest (weight, target) for each cell
+        let mut strongest: Vec<Option<(f32, f32)>> = vec![None; self.heights.len()];
+        for (point, &radius) in points.iter().zip(radii) {
+            let reach = radius + falloff;
+            let x0 = (point.x - reach).floor().max(0_f32) as usize;
+            let y0 = (point.y - reach).floor().max(0_f32) as usize;
+            let x1 = ((point.x + reach).ceil() as usize).min(self.width().saturating_sub(1));
+            let y1 = ((point.y + reach).ceil() as usize).min(self.height().saturating_sub(1));
+            for y in y0..=y1 {
+                for x in x0..=x1 {
+                    let distance = Vec2D::new(x as f32 - point.x, y as f32 - point.y).norm();
+                    if distance > reach {
+                        continue;
+                    }
+                    let weight = 1_f32 - smoothstep(radius, reach, distance);
+                    let index = self.heights.index(x, y, 0);
+                    let replace = match strongest[index] {
+                        None => true,
+                        Some((w, target)) => {
+                            weight > w
+                                || (weight == w && mode == CarveMode::Lower && point.z < target)
+                        }
+                    };
+                    if replace && weight > 0_f32 {
+                        strongest[index] = Some((weight, point.z));
+                    }
+                }
+            }
+        }
+        for (height, strongest) in self.heights.cells_mut().iter_mut().zip(strongest) {
+            if let Some((weight, target)) = strongest {
+                let carved = lerp(*height, target, weight);
+                *height = match mode {
+                    CarveMode::Lower => height.min(carved),
+                    CarveMode::Flatten => carved,
+                };
+            }
+        }
+    }
+}
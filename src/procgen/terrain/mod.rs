@@ -0,0 +1,25 @@
+// Terrain generation on heightmaps: flow accumulation and rivers, roads
+// following the relief, both carved back into the heightmap.
+
+pub mod heightmap;
+pub mod rivers;
+pub mod roads;
+
+pub use heightmap::{CarveMode, Heightmap};
+pub use rivers::{carve_rivers, rivers, FlowMap, River, RiverParams};
+pub use roads::{carve_roads, find_road, road_network, Road, RoadParams};
+
+// Crate imports
+use crate::math::curves::{ArcLengthTable, CatmullRom, Curve};
+use crate::math::Vec3D;
+
+// Parameters and points about every `spacing` units along the spline.
+fn sample_spline(spline: &CatmullRom<Vec3D>, spacing: f32) -> Vec<(f32, Vec3D)> {
+    let table = ArcLengthTable::new(spline, spline.segments() * 8);
+    let count = (table.length() / spacing.max(1e-3)).ceil() as usize + 1;
+    table
+        .even_params(count)
+        .into_iter()
+        .map(|t| (t, spline.eval(t)))
+        .collect()
+}
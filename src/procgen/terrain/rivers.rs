@@ -0,0 +1,306 @@
+// External imports
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+// Crate imports
+use super::heightmap::{CarveMode, Heightmap};
+use super::sample_spline;
+use crate::graph::grid::NEIGHBOURS;
+use crate::math::curves::CatmullRom;
+use crate::math::fct::lerp;
+use crate::math::Vec3D;
+
+/// Where the water of each cell of a heightmap goes and how much of it
+/// goes through, every cell draining towards the border of the map.
+#[derive(Clone, Debug)]
+pub struct FlowMap {
+    width: usize,
+    height: usize,
+    filled: Vec<f32>,
+    receivers: Vec<Option<usize>>,
+    accumulation: Vec<f32>,
+}
+
+// Cell of the flood queue, the lowest first then the oldest.
+struct Flood {
+    height: f32,
+    order: usize,
+    index: usize,
+}
+
+impl PartialEq for Flood {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Flood {}
+
+impl PartialOrd for Flood {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Flood {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .height
+            .total_cmp(&self.height)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl FlowMap {
+    /// Fills the depressions of the heightmap (priority flood) so that water
+    /// can't get stuck, then sends each cell to its steepest lower neighbour
+    /// and accumulates one unit of rain per cell downstream.
+    pub fn new(heightmap: &Heightmap) -> Self {
+        let (width, height) = (heightmap.width(), heightmap.height());
+        let len = width * height;
+        let coords = |i: usize| ((i % width) as i32, (i / width) as i32);
+        let mut filled: Vec<f32> = heightmap.grid().cells().to_vec();
+        let mut visited = vec![false; len];
+        let mut queue = BinaryHeap::new();
+        let mut order = 0;
+        for (index, visited) in visited.iter_mut().enumerate() {
+            let (x, y) = coords(index);
+            if x == 0 || y == 0 || x as usize == width - 1 || y as usize == height - 1 {
+                *visited = true;
+                order += 1;
+                queue.push(Flood {
+                    height: filled[index],
+                    order,
+                    index,
+                });
+            }
+        }
+        while let Some(Flood { index, .. }) = queue.pop() {
+            let (x, y) = coords(index);
+            for &(dx, dy) in &NEIGHBOURS {
+                if !heightmap.in_bounds(x + dx, y + dy) {
+                    continue;
+                }
+                let next = (x + dx) as usize + (y + dy) as usize * width;
+                if visited[next] {
+                    continue;
+                }
+                visited[next] = true;
+                // slightly above the spill point so the flat stays sloped
+                let raised = filled[index] + 1e-5 * (1_f32 + filled[index].abs());
+                filled[next] = filled[next].max(raised);
+                order += 1;
+                queue.push(Flood {
+                    height: filled[next],
+                    order,
+                    index: next,
+                });
+            }
+        }
+
+        let mut receivers = vec![None; len];
+        for (index, receiver) in receivers.iter_mut().enumerate() {
+            let (x, y) = coords(index);
+            let mut steepest = 0_f32;
+            for &(dx, dy) in &NEIGHBOURS {
+                if !heightmap.in_bounds(x + dx, y + dy) {
+                    continue;
+                }
+                let next = (x + dx) as usize + (y + dy) as usize * width;
+                let length = if dx != 0 && dy != 0 {
+                    std::f32::consts::SQRT_2
+                } else {
+                    1_f32
+                };
+                let drop = (filled[index] - filled[next]) / length;
+                if drop > steepest {
+                    steepest = drop;
+                    *receiver = Some(next);
+                }
+            }
+        }
+
+        let mut accumulation = vec![1_f32; len];
+        let mut by_height: Vec<usize> = (0..len).collect();
+        by_height.sort_by(|&a, &b| filled[b].total_cmp(&filled[a]));
+        for index in by_height {
+            if let Some(receiver) = receivers[index] {
+                accumulation[receiver] += accumulation[index];
+            }
+        }
+        Self {
+            width,
+            height,
+            filled,
+            receivers,
+            accumulation,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        x + y * self.width
+    }
+
+    /// Height with the depressions filled.
+    pub fn filled_height(&self, x: i32, y: i32) -> f32 {
+        self.filled[self.index(x, y)]
+    }
+
+    /// Cell the water of the cell flows to, `None` where it leaves the map.
+    pub fn receiver(&self, x: i32, y: i32) -> Option<(i32, i32)> {
+        self.receivers[self.index(x, y)].map(|i| ((i % self.width) as i32, (i / self.width) as i32))
+    }
+
+    /// Number of cells draining through the cell, itself included.
+    pub fn accumulation(&self, x: i32, y: i32) -> f32 {
+        self.accumulation[self.index(x, y)]
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RiverParams {
+    /// Catchment, in cells, from which the water forms a river.
+    pub min_catchment: f32,
+    /// Rivers shorter than this many cells are dropped.
+    pub min_length: usize,
+    /// The width is `width_scale * catchment ^ width_exponent`.
+    pub width_scale: f32,
+    pub width_exponent: f32,
+    pub max_width: f32,
+    /// Depth of the bed for each unit of width.
+    pub depth_ratio: f32,
+    /// Distance over which the banks blend into the terrain.
+    pub bank_falloff: f32,
+    /// Rivers end when they reach this height.
+    pub sea_level: f32,
+}
+
+impl Default for RiverParams {
+    fn default() -> Self {
+        Self {
+            min_catchment: 100_f32,
+            min_length: 4,
+            width_scale: 0.1,
+            width_exponent: 0.5,
+            max_width: 8_f32,
+            depth_ratio: 0.25,
+            bank_falloff: 2_f32,
+            sea_level: f32::NEG_INFINITY,
+        }
+    }
+}
+
+/// River from its source to the sea, the border of the map or the river it joins,
+/// the last cell being on the other river.
+#[derive(Clone, Debug)]
+pub struct River {
+    pub cells: Vec<(i32, i32)>,
+    /// Water flowing through each cell, in cells of catchment.
+    pub catchment: Vec<f32>,
+    pub widths: Vec<f32>,
+    /// Goes through the cells at the height of the filled terrain, never going up.
+    pub spline: CatmullRom<Vec3D>,
+}
+
+impl River {
+    /// Width at the parameter `t` of the spline, which reaches cell `i` at `t = i`.
+    pub fn width_at(&self, t: f32) -> f32 {
+        let last = self.widths.len() - 1;
+        let t = t.clamp(0_f32, last as f32);
+        let i = (t.floor() as usize).min(last.saturating_sub(1));
+        lerp(self.widths[i], self.widths[(i + 1).min(last)], t - i as f32)
+    }
+}
+
+/// Traces the rivers of the flow map, the longest ones first. At each
+/// confluence the river bringing the most water goes on, the others end.
+pub fn rivers(flow: &FlowMap, params: &RiverParams) -> Vec<River> {
+    let len = flow.width * flow.height;
+    let is_river = |i: usize| flow.accumulation[i] >= params.min_catchment;
+    // upstream river cell bringing the most water to each cell
+    let mut main_upstream: Vec<Option<usize>> = vec![None; len];
+    for index in (0..len).filter(|&i| is_river(i)) {
+        if let Some(receiver) = flow.receivers[index] {
+            let better = match main_upstream[receiver] {
+                None => true,
+                Some(other) => flow.accumulation[index] > flow.accumulation[other],
+            };
+            if better {
+                main_upstream[receiver] = Some(index);
+            }
+        }
+    }
+
+    let mut rivers = Vec::new();
+    for source in (0..len).filter(|&i| is_river(i) && main_upstream[i].is_none()) {
+        let mut indices = vec![source];
+        let mut current = source;
+        while flow.filled[current] > params.sea_level {
+            match flow.receivers[current] {
+                Some(next) => {
+                    indices.push(next);
+                    if main_upstream[next] != Some(current) {
+                        break;
+                    }
+                    current = next;
+                }
+                None => break,
+            }
+        }
+        if indices.len() < params.min_length.max(2) {
+            continue;
+        }
+        let cells: Vec<(i32, i32)> = indices
+            .iter()
+            .map(|&i| ((i % flow.width) as i32, (i / flow.width) as i32))
+            .collect();
+        let catchment: Vec<f32> = indices.iter().map(|&i| flow.accumulation[i]).collect();
+        let widths = catchment
+            .iter()
+            .map(|c| (params.width_scale * c.powf(params.width_exponent)).min(params.max_width))
+            .collect();
+        let points: Vec<Vec3D> = indices
+            .iter()
+            .zip(&cells)
+            .map(|(&i, &(x, y))| Vec3D::new(x as f32, y as f32, flow.filled[i]))
+            .collect();
+        rivers.push(River {
+            cells,
+            catchment,
+            widths,
+            spline: CatmullRom::through(&points),
+        });
+    }
+    rivers.sort_by_key(|river| std::cmp::Reverse(river.cells.len()));
+    rivers
+}
+
+/// Digs the beds of the rivers in the heightmap, deeper for wider rivers.
+pub fn carve_rivers(heightmap: &mut Heightmap, rivers: &[River], params: &RiverParams) {
+    let mut points = Vec::new();
+    let mut radii = Vec::new();
+    for river in rivers {
+        // the spline can overshoot between the cells, the bed must not go up
+        let mut lowest = f32::INFINITY;
+        for (t, point) in sample_spline(&river.spline, 0.5) {
+            let width = river.width_at(t);
+            lowest = lowest.min(point.z);
+            points.push(Vec3D::new(
+                point.x,
+                point.y,
+                lowest - width * params.depth_ratio,
+            ));
+            radii.push(width * 0.5);
+        }
+    }
+    heightmap.carve(&points, &radii, params.bank_falloff, CarveMode::Lower);
+}
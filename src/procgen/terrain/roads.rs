@@ -0,0 +1,199 @@
+// External imports
+use std::collections::HashSet;
+// Crate imports
+use super::heightmap::{CarveMode, Heightmap};
+use super::sample_spline;
+use crate::graph::grid::NEIGHBOURS;
+use crate::graph::heuristic::euclidean;
+use crate::graph::mst::kruskal;
+use crate::graph::{astar, Graph, SearchGraph};
+use crate::math::curves::CatmullRom;
+use crate::math::Vec3D;
+
+#[derive(Clone, Debug)]
+pub struct RoadParams {
+    /// Extra cost of a step for the square of its slope, gentle slopes stay cheap.
+    pub slope_cost: f32,
+    /// Steeper steps aren't allowed.
+    pub max_slope: f32,
+    /// Cells below this height (water) can't be crossed.
+    pub water_level: f32,
+    /// Cost multiplier of the cells already on a road, below 1 so that roads merge.
+    pub reuse_cost: f32,
+    pub width: f32,
+    /// Distance over which the road bed blends into the terrain.
+    pub falloff: f32,
+    /// Cells between the points of the spline, smoothing the grid steps.
+    pub spline_step: usize,
+    /// Cells on each side averaged into the height of the road.
+    pub height_smoothing: usize,
+}
+
+impl Default for RoadParams {
+    fn default() -> Self {
+        Self {
+            slope_cost: 50_f32,
+            max_slope: 1_f32,
+            water_level: f32::NEG_INFINITY,
+            reuse_cost: 0.5,
+            width: 3_f32,
+            falloff: 2_f32,
+            spline_step: 3,
+            height_smoothing: 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Road {
+    pub cells: Vec<(i32, i32)>,
+    pub cost: f32,
+    /// Goes along the cells at the smoothed height of the terrain.
+    pub spline: CatmullRom<Vec3D>,
+}
+
+// The heightmap seen as a graph for the road search.
+struct RoadGraph<'a> {
+    heightmap: &'a Heightmap,
+    params: &'a RoadParams,
+    used: &'a HashSet<(i32, i32)>,
+}
+
+impl SearchGraph for RoadGraph<'_> {
+    type Node = (i32, i32);
+
+    fn successors(&self, (x, y): (i32, i32), out: &mut Vec<((i32, i32), f32)>) {
+        let height = self.heightmap.get(x, y);
+        for &(dx, dy) in &NEIGHBOURS {
+            let (nx, ny) = (x + dx, y + dy);
+            if !self.heightmap.in_bounds(nx, ny) {
+                continue;
+            }
+            let next_height = self.heightmap.get(nx, ny);
+            if next_height < self.params.water_level {
+                continue;
+            }
+            let length = if dx != 0 && dy != 0 {
+                std::f32::consts::SQRT_2
+            } else {
+                1_f32
+            };
+            let slope = (next_height - height).abs() / length;
+            if slope > self.params.max_slope {
+                continue;
+            }
+            let mut cost = length * (1_f32 + self.params.slope_cost * slope * slope);
+            if self.used.contains(&(nx, ny)) {
+                cost *= self.params.reuse_cost;
+            }
+            out.push(((nx, ny), cost));
+        }
+    }
+}
+
+/// Cheapest road between two cells, `None` if the slopes or the water
+/// don't let any through.
+pub fn find_road(
+    heightmap: &Heightmap,
+    from: (i32, i32),
+    to: (i32, i32),
+    params: &RoadParams,
+) -> Option<Road> {
+    find_road_reusing(heightmap, from, to, params, &HashSet::new())
+}
+
+fn find_road_reusing(
+    heightmap: &Heightmap,
+    from: (i32, i32),
+    to: (i32, i32),
+    params: &RoadParams,
+    used: &HashSet<(i32, i32)>,
+) -> Option<Road> {
+    if !heightmap.in_bounds(from.0, from.1) || !heightmap.in_bounds(to.0, to.1) {
+        return None;
+    }
+    let graph = RoadGraph {
+        heightmap,
+        params,
+        used,
+    };
+    // each unit of distance costs at least this much
+    let min_cost = params.reuse_cost.min(1_f32);
+    let path = astar(&graph, from, to, |cell| euclidean(&cell, &to) * min_cost)?;
+    let spline = road_spline(heightmap, &path.nodes, params);
+    Some(Road {
+        cells: path.nodes,
+        cost: path.cost,
+        spline,
+    })
+}
+
+fn road_spline(
+    heightmap: &Heightmap,
+    cells: &[(i32, i32)],
+    params: &RoadParams,
+) -> CatmullRom<Vec3D> {
+    let radius = params.height_smoothing as isize;
+    let smoothed_height = |i: usize| {
+        let start = (i as isize - radius).max(0) as usize;
+        let end = (i + params.height_smoothing).min(cells.len() - 1);
+        let sum: f32 = cells[start..=end]
+            .iter()
+            .map(|&(x, y)| heightmap.get(x, y))
+            .sum();
+        sum / (end - start + 1) as f32
+    };
+    let step = params.spline_step.max(1);
+    let mut indices: Vec<usize> = (0..cells.len()).step_by(step).collect();
+    if *indices.last().unwrap() != cells.len() - 1 {
+        indices.push(cells.len() - 1);
+    }
+    if indices.len() == 1 {
+        indices.push(0);
+    }
+    let points: Vec<Vec3D> = indices
+        .into_iter()
+        .map(|i| Vec3D::new(cells[i].0 as f32, cells[i].1 as f32, smoothed_height(i)))
+        .collect();
+    CatmullRom::through(&points)
+}
+
+/// Roads linking all the points of interest, along a minimum spanning tree
+/// of their distances. The roads are searched one after the other, the
+/// later ones preferring to follow the earlier ones.
+pub fn road_network(
+    heightmap: &Heightmap,
+    points: &[(i32, i32)],
+    params: &RoadParams,
+) -> Vec<Road> {
+    let mut graph = Graph::new();
+    for &point in points {
+        graph.add_node(point);
+    }
+    for a in 0..points.len() {
+        for b in a + 1..points.len() {
+            graph.add_edge(a, b, euclidean(&points[a], &points[b]));
+        }
+    }
+    let mut used = HashSet::new();
+    let mut roads = Vec::new();
+    for edge in kruskal(&graph) {
+        let edge = graph.edge(edge);
+        let road = find_road_reusing(heightmap, points[edge.from], points[edge.to], params, &used);
+        if let Some(road) = road {
+            used.extend(road.cells.iter().copied());
+            roads.push(road);
+        }
+    }
+    roads
+}
+
+/// Levels the terrain under the roads to their height.
+pub fn carve_roads(heightmap: &mut Heightmap, roads: &[Road], params: &RoadParams) {
+    let mut points = Vec::new();
+    for road in roads {
+        points.extend(sample_spline(&road.spline, 0.5).into_iter().map(|(_, p)| p));
+    }
+    let radii = vec![params.width * 0.5; points.len()];
+    heightmap.carve(&points, &radii, params.falloff, CarveMode::Flatten);
+}
@@ -0,0 +1,42 @@
+// External imports
+use image::RgbaImage;
+// Crate imports
+use crate::procgen::grid::Grid;
+
+/// Cell of the dungeons and mazes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Tile {
+    #[default]
+    Wall,
+    Floor,
+    Corridor,
+}
+
+impl Tile {
+    pub fn is_walkable(&self) -> bool {
+        *self != Tile::Wall
+    }
+
+    /// Index of the tile in a tilemap, following the order of the variants.
+    pub fn index(&self) -> u32 {
+        *self as u32
+    }
+
+    pub fn color(&self) -> [u8; 4] {
+        match self {
+            Tile::Wall => [30, 30, 35, 255],
+            Tile::Floor => [200, 190, 170, 255],
+            Tile::Corridor => [150, 140, 125, 255],
+        }
+    }
+}
+
+/// Tilemap indices of the grid in the grid order.
+pub fn tile_indices(grid: &Grid<Tile>) -> Vec<u32> {
+    grid.cells().iter().map(Tile::index).collect()
+}
+
+/// Image of the grid with one pixel per tile.
+pub fn tiles_to_image(grid: &Grid<Tile>) -> RgbaImage {
+    grid.to_image(0, Tile::color)
+}
@@ -0,0 +1,74 @@
+// Crate imports
+use super::palette::PalettedStorage;
+use super::{Voxel, AIR};
+
+/// Cube of `size³` voxels, stored x first then y then z.
+#[derive(Clone, Debug)]
+pub struct Chunk {
+    size: usize,
+    storage: PalettedStorage,
+}
+
+impl Chunk {
+    /// Chunk full of air, the size being 16 or 32.
+    pub fn new(size: usize) -> Self {
+        assert!(
+            size == 16 || size == 32,
+            "A chunk must be 16 or 32 voxels wide!"
+        );
+        Self {
+            size,
+            storage: PalettedStorage::new(size * size * size, AIR),
+        }
+    }
+
+    /// Chunk with the voxels computed from their local coordinates.
+    pub fn from_fn(size: usize, mut f: impl FnMut(usize, usize, usize) -> Voxel) -> Self {
+        let mut chunk = Self::new(size);
+        for z in 0..size {
+            for y in 0..size {
+                for x in 0..size {
+                    chunk.set(x, y, z, f(x, y, z));
+                }
+            }
+        }
+        chunk.storage.compact();
+        chunk
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn storage(&self) -> &PalettedStorage {
+        &self.storage
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + (y + z * self.size) * self.size
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Voxel {
+        self.storage.get(self.index(x, y, z))
+    }
+
+    /// Sets the voxel and returns the previous one.
+    pub fn set(&mut self, x: usize, y: usize, z: usize, voxel: Voxel) -> Voxel {
+        let index = self.index(x, y, z);
+        self.storage.set(index, voxel)
+    }
+
+    pub fn fill(&mut self, voxel: Voxel) {
+        self.storage.fill(voxel);
+    }
+
+    /// Whether the chunk only holds air.
+    pub fn is_empty(&self) -> bool {
+        self.storage.count(AIR) == self.storage.len()
+    }
+
+    /// Drops the unused palette entries.
+    pub fn compact(&mut self) {
+        self.storage.compact();
+    }
+}
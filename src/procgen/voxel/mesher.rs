@@ -0,0 +1,219 @@
+// Greedy meshing: the visible faces of each slice of the chunk are merged
+// into the largest rectangles of the same voxel and ambient occlusion.
+
+// Crate imports
+use super::world::VoxelWorld;
+use super::{ChunkPos, Voxel, AIR};
+use crate::math::{Vec2D, Vec3D};
+use crate::procgen::mesh::Mesh;
+
+/// Mesh of a chunk with the voxel and ambient occlusion of each vertex.
+#[derive(Clone, Debug, Default)]
+pub struct VoxelMesh {
+    /// Positions in world units, the uvs counting voxels so that textures repeat.
+    pub mesh: Mesh,
+    pub voxels: Vec<Voxel>,
+    /// Light reaching each vertex, from 0 in a corner to 1 in the open.
+    pub ao: Vec<f32>,
+}
+
+// Visible face in the mask of a slice.
+#[derive(Copy, Clone, PartialEq)]
+struct Face {
+    voxel: Voxel,
+    // occlusion level (0 to 3) of the corners -u-v, +u-v, +u+v, -u+v
+    ao: [u8; 4],
+}
+
+/// Greedy mesh of the faces of a chunk between solid voxels and air,
+/// the neighbouring chunks hiding the faces and darkening the corners.
+pub fn mesh_chunk(world: &VoxelWorld, pos: ChunkPos) -> VoxelMesh {
+    let size = world.chunk_size();
+    let origin = world.chunk_origin(pos);
+    let origin = [origin.0, origin.1, origin.2];
+    // voxels of the chunk with a border of one voxel from the neighbours
+    let padded = size + 2;
+    let mut voxels = vec![AIR; padded * padded * padded];
+    if let Some(chunk) = world.chunk(pos) {
+        for z in 0..padded {
+            for y in 0..padded {
+                for x in 0..padded {
+                    let inside = |c: usize| c >= 1 && c <= size;
+                    voxels[x + (y + z * padded) * padded] = if inside(x) && inside(y) && inside(z) {
+                        chunk.get(x - 1, y - 1, z - 1)
+                    } else {
+                        world.get(
+                            origin[0] + x as i32 - 1,
+                            origin[1] + y as i32 - 1,
+                            origin[2] + z as i32 - 1,
+                        )
+                    };
+                }
+            }
+        }
+    }
+    let solid = |c: [i32; 3]| {
+        voxels[(c[0] + 1) as usize + ((c[1] + 1) as usize + (c[2] + 1) as usize * padded) * padded]
+            != AIR
+    };
+
+    let mut result = VoxelMesh::default();
+    let mut mask: Vec<Option<Face>> = vec![None; size * size];
+    for d in 0..3 {
+        let (u, v) = ((d + 1) % 3, (d + 2) % 3);
+        for &side in &[1_i32, -1_i32] {
+            for layer in 0..size as i32 {
+                for j in 0..size {
+                    for i in 0..size {
+                        let mut cell = [0_i32; 3];
+                        cell[d] = layer;
+                        cell[u] = i as i32;
+                        cell[v] = j as i32;
+                        let mut front = cell;
+                        front[d] += side;
+                        mask[i + j * size] = if solid(cell) && !solid(front) {
+                            let voxel = voxels[(cell[0] + 1) as usize
+                                + ((cell[1] + 1) as usize + (cell[2] + 1) as usize * padded)
+                                    * padded];
+                            Some(Face {
+                                voxel,
+                                ao: corner_occlusion(&solid, front, u, v),
+                            })
+                        } else {
+                            None
+                        };
+                    }
+                }
+                let plane = layer + if side > 0 { 1 } else { 0 };
+                merge_mask(&mut mask, size, |i, j, w, h, face| {
+                    push_face(
+                        &mut result,
+                        origin,
+                        d,
+                        u,
+                        v,
+                        side,
+                        plane,
+                        (i, j, w, h),
+                        face,
+                    );
+                });
+            }
+        }
+    }
+    result
+}
+
+// Occlusion of the four corners of the face seen from the air cell `front`.
+fn corner_occlusion(
+    solid: &impl Fn([i32; 3]) -> bool,
+    front: [i32; 3],
+    u: usize,
+    v: usize,
+) -> [u8; 4] {
+    let offset = |du: i32, dv: i32| {
+        let mut c = front;
+        c[u] += du;
+        c[v] += dv;
+        solid(c)
+    };
+    let corner = |du: i32, dv: i32| {
+        let (side1, side2, corner) = (offset(du, 0), offset(0, dv), offset(du, dv));
+        if side1 && side2 {
+            3
+        } else {
+            side1 as u8 + side2 as u8 + corner as u8
+        }
+    };
+    [corner(-1, -1), corner(1, -1), corner(1, 1), corner(-1, 1)]
+}
+
+// Calls `emit(i, j, width, height, face)` for each rectangle of equal faces,
+// clearing the mask.
+fn merge_mask(
+    mask: &mut [Option<Face>],
+    size: usize,
+    mut emit: impl FnMut(usize, usize, usize, usize, Face),
+) {
+    for j in 0..size {
+        let mut i = 0;
+        while i < size {
+            let face = match mask[i + j * size] {
+                Some(face) => face,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+            let mut width = 1;
+            while i + width < size && mask[i + width + j * size] == Some(face) {
+                width += 1;
+            }
+            let mut height = 1;
+            'grow: while j + height < size {
+                for k in 0..width {
+                    if mask[i + k + (j + height) * size] != Some(face) {
+                        break 'grow;
+                    }
+                }
+                height += 1;
+            }
+            for h in 0..height {
+                for k in 0..width {
+                    mask[i + k + (j + h) * size] = None;
+                }
+            }
+            emit(i, j, width, height, face);
+            i += width;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn push_face(
+    result: &mut VoxelMesh,
+    origin: [i32; 3],
+    d: usize,
+    u: usize,
+    v: usize,
+    side: i32,
+    plane: i32,
+    (i, j, width, height): (usize, usize, usize, usize),
+    face: Face,
+) {
+    let corner = |du: usize, dv: usize| {
+        let mut p = [0_f32; 3];
+        p[d] = (origin[d] + plane) as f32;
+        p[u] = (origin[u] + (i + du) as i32) as f32;
+        p[v] = (origin[v] + (j + dv) as i32) as f32;
+        Vec3D::new(p[0], p[1], p[2])
+    };
+    let mut n = [0_f32; 3];
+    n[d] = side as f32;
+    let normal = Vec3D::new(n[0], n[1], n[2]);
+    let corners = [(0, 0), (width, 0), (width, height), (0, height)];
+    let mut indices = [0_u32; 4];
+    for (k, &(du, dv)) in corners.iter().enumerate() {
+        let uv = Vec2D::new(du as f32, dv as f32);
+        indices[k] = result.mesh.push_vertex(corner(du, dv), normal, uv);
+        result.voxels.push(face.voxel);
+        result.ao.push(1_f32 - face.ao[k] as f32 / 3_f32);
+    }
+    // counter-clockwise seen from the side the face looks at
+    if side < 0 {
+        indices.swap(1, 3);
+    }
+    let ao = if side < 0 {
+        [face.ao[0], face.ao[3], face.ao[2], face.ao[1]]
+    } else {
+        face.ao
+    };
+    let [a, b, c, e] = indices;
+    // split along the brighter diagonal so the occlusion interpolates evenly
+    if ao[0] + ao[2] > ao[1] + ao[3] {
+        result.mesh.push_triangle(b, c, e);
+        result.mesh.push_triangle(b, e, a);
+    } else {
+        result.mesh.push_quad(a, b, c, e);
+    }
+}
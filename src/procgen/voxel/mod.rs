@@ -0,0 +1,20 @@
+// Chunked voxel worlds: palette compressed chunks addressed in world
+// coordinates, generated on worker threads and meshed greedily.
+
+pub mod chunk;
+pub mod mesher;
+pub mod palette;
+pub mod world;
+
+pub use chunk::Chunk;
+pub use mesher::{mesh_chunk, VoxelMesh};
+pub use palette::PalettedStorage;
+pub use world::VoxelWorld;
+
+/// Block type of a voxel, `AIR` being empty space.
+pub type Voxel = u16;
+
+pub const AIR: Voxel = 0;
+
+/// Position of a chunk, in chunks.
+pub type ChunkPos = (i32, i32, i32);
@@ -0,0 +1,182 @@
+// Crate imports
+use super::Voxel;
+
+/// Fixed number of voxels stored as indices into a palette of the values
+/// in use, with as few bits per index as the palette needs.
+///
+/// A single value takes no bits at all, the indices are packed in `u64`
+/// words without spanning two of them.
+#[derive(Clone, Debug)]
+pub struct PalettedStorage {
+    len: usize,
+    palette: Vec<Voxel>,
+    // number of voxels using each palette entry, 0 for a free entry
+    counts: Vec<usize>,
+    bits: u32,
+    words: Vec<u64>,
+}
+
+impl PalettedStorage {
+    /// `len` voxels of the value `fill`.
+    pub fn new(len: usize, fill: Voxel) -> Self {
+        Self {
+            len,
+            palette: vec![fill],
+            counts: vec![len],
+            bits: 0,
+            words: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Bits used by each index.
+    pub fn bits(&self) -> u32 {
+        self.bits
+    }
+
+    /// Values currently in use.
+    pub fn palette(&self) -> impl Iterator<Item = Voxel> + '_ {
+        self.palette
+            .iter()
+            .zip(&self.counts)
+            .filter(|(_, &count)| count > 0)
+            .map(|(&value, _)| value)
+    }
+
+    /// Number of voxels of the value.
+    pub fn count(&self, value: Voxel) -> usize {
+        self.palette
+            .iter()
+            .zip(&self.counts)
+            .filter(|(&v, _)| v == value)
+            .map(|(_, &count)| count)
+            .sum()
+    }
+
+    /// Memory used by the packed indices, in bytes.
+    pub fn packed_size(&self) -> usize {
+        self.words.len() * 8
+    }
+
+    pub fn get(&self, index: usize) -> Voxel {
+        self.palette[self.palette_index(index)]
+    }
+
+    /// Sets the value and returns the previous one.
+    pub fn set(&mut self, index: usize, value: Voxel) -> Voxel {
+        let old_entry = self.palette_index(index);
+        let old = self.palette[old_entry];
+        if old == value {
+            return old;
+        }
+        let entry = match self.palette.iter().position(|&v| v == value) {
+            Some(entry) => entry,
+            None => self.add_entry(value),
+        };
+        self.counts[old_entry] -= 1;
+        self.counts[entry] += 1;
+        self.write_index(index, entry);
+        old
+    }
+
+    /// Replaces every voxel by the value.
+    pub fn fill(&mut self, value: Voxel) {
+        *self = Self::new(self.len, value);
+    }
+
+    /// Drops the unused palette entries and shrinks the indices.
+    pub fn compact(&mut self) {
+        let values: Vec<Voxel> = (0..self.len).map(|i| self.get(i)).collect();
+        let mut palette: Vec<Voxel> = self.palette().collect();
+        palette.sort_unstable();
+        if palette.is_empty() {
+            palette.push(self.palette[0]);
+        }
+        let mut compacted = Self {
+            len: self.len,
+            counts: vec![0; palette.len()],
+            bits: bits_for(palette.len()),
+            words: Vec::new(),
+            palette,
+        };
+        compacted.words = vec![0; words_for(self.len, compacted.bits)];
+        for (i, value) in values.into_iter().enumerate() {
+            let entry = compacted.palette.binary_search(&value).unwrap();
+            compacted.counts[entry] += 1;
+            compacted.write_index(i, entry);
+        }
+        *self = compacted;
+    }
+
+    fn palette_index(&self, index: usize) -> usize {
+        assert!(index < self.len, "Voxel index out of the storage!");
+        if self.bits == 0 {
+            return 0;
+        }
+        let per_word = 64 / self.bits as usize;
+        let word = self.words[index / per_word];
+        let shift = (index % per_word) as u32 * self.bits;
+        ((word >> shift) & ((1_u64 << self.bits) - 1)) as usize
+    }
+
+    fn write_index(&mut self, index: usize, entry: usize) {
+        if self.bits == 0 {
+            return;
+        }
+        let per_word = 64 / self.bits as usize;
+        let shift = (index % per_word) as u32 * self.bits;
+        let mask = ((1_u64 << self.bits) - 1) << shift;
+        let word = &mut self.words[index / per_word];
+        *word = (*word & !mask) | ((entry as u64) << shift);
+    }
+
+    // Adds a palette entry for the value, reusing a free one if possible
+    // and widening the indices when the palette outgrows them.
+    fn add_entry(&mut self, value: Voxel) -> usize {
+        if let Some(entry) = self.counts.iter().position(|&count| count == 0) {
+            self.palette[entry] = value;
+            return entry;
+        }
+        self.palette.push(value);
+        self.counts.push(0);
+        let bits = bits_for(self.palette.len());
+        if bits > self.bits {
+            self.repack(bits);
+        }
+        self.palette.len() - 1
+    }
+
+    fn repack(&mut self, bits: u32) {
+        let entries: Vec<usize> = (0..self.len).map(|i| self.palette_index(i)).collect();
+        self.bits = bits;
+        self.words = vec![0; words_for(self.len, bits)];
+        for (i, entry) in entries.into_iter().enumerate() {
+            self.write_index(i, entry);
+        }
+    }
+}
+
+// Bits needed to index a palette of this size.
+fn bits_for(palette_len: usize) -> u32 {
+    if palette_len <= 1 {
+        0
+    } else {
+        usize::BITS - (palette_len - 1).leading_zeros()
+    }
+}
+
+fn words_for(len: usize, bits: u32) -> usize {
+    if bits == 0 {
+        0
+    } else {
+        let per_word = 64 / bits as usize;
+        len.div_ceil(per_word)
+    }
+}
@@ -0,0 +1,227 @@
+// External imports
+use std::collections::{HashMap, HashSet};
+// Crate imports
+use super::chunk::Chunk;
+use super::mesher::{mesh_chunk, VoxelMesh};
+use super::{ChunkPos, Voxel, AIR};
+use crate::utils::parallel::parallel_map;
+
+/// Voxels addressed in world coordinates, stored in chunks created on demand.
+///
+/// Chunks whose voxels changed, or whose neighbours changed on their
+/// shared face, are dirty until their mesh is rebuilt.
+#[derive(Clone, Debug)]
+pub struct VoxelWorld {
+    chunk_size: usize,
+    chunks: HashMap<ChunkPos, Chunk>,
+    dirty: HashSet<ChunkPos>,
+}
+
+impl VoxelWorld {
+    /// Empty world with chunks of 16 or 32 voxels.
+    pub fn new(chunk_size: usize) -> Self {
+        assert!(
+            chunk_size == 16 || chunk_size == 32,
+            "A chunk must be 16 or 32 voxels wide!"
+        );
+        Self {
+            chunk_size,
+            chunks: HashMap::new(),
+            dirty: HashSet::new(),
+        }
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Chunk containing a voxel and the coordinates of the voxel in it.
+    pub fn locate(&self, x: i32, y: i32, z: i32) -> (ChunkPos, (usize, usize, usize)) {
+        let size = self.chunk_size as i32;
+        (
+            (x.div_euclid(size), y.div_euclid(size), z.div_euclid(size)),
+            (
+                x.rem_euclid(size) as usize,
+                y.rem_euclid(size) as usize,
+                z.rem_euclid(size) as usize,
+            ),
+        )
+    }
+
+    /// World coordinates of the first voxel of a chunk.
+    pub fn chunk_origin(&self, (cx, cy, cz): ChunkPos) -> (i32, i32, i32) {
+        let size = self.chunk_size as i32;
+        (cx * size, cy * size, cz * size)
+    }
+
+    /// Voxel at the world coordinates, air in the missing chunks.
+    pub fn get(&self, x: i32, y: i32, z: i32) -> Voxel {
+        let (pos, (lx, ly, lz)) = self.locate(x, y, z);
+        match self.chunks.get(&pos) {
+            Some(chunk) => chunk.get(lx, ly, lz),
+            None => AIR,
+        }
+    }
+
+    /// Sets a voxel, creating its chunk if needed, and returns the previous one.
+    pub fn set(&mut self, x: i32, y: i32, z: i32, voxel: Voxel) -> Voxel {
+        let (pos, local) = self.locate(x, y, z);
+        if voxel == AIR && !self.chunks.contains_key(&pos) {
+            return AIR;
+        }
+        let size = self.chunk_size;
+        let old = self
+            .chunks
+            .entry(pos)
+            .or_insert_with(|| Chunk::new(size))
+            .set(local.0, local.1, local.2, voxel);
+        if old != voxel {
+            self.mark_dirty(pos);
+            // the faces and ambient occlusion of the neighbours see this voxel
+            let local = [local.0, local.1, local.2];
+            for axis in 0..3 {
+                let mut neighbour = [pos.0, pos.1, pos.2];
+                if local[axis] == 0 {
+                    neighbour[axis] -= 1;
+                } else if local[axis] == size - 1 {
+                    neighbour[axis] += 1;
+                } else {
+                    continue;
+                }
+                let neighbour = (neighbour[0], neighbour[1], neighbour[2]);
+                if self.chunks.contains_key(&neighbour) {
+                    self.mark_dirty(neighbour);
+                }
+            }
+        }
+        old
+    }
+
+    pub fn chunk(&self, pos: ChunkPos) -> Option<&Chunk> {
+        self.chunks.get(&pos)
+    }
+
+    /// Mutable access to a chunk, marking it dirty.
+    pub fn chunk_mut(&mut self, pos: ChunkPos) -> Option<&mut Chunk> {
+        let chunk = self.chunks.get_mut(&pos)?;
+        self.dirty.insert(pos);
+        Some(chunk)
+    }
+
+    pub fn chunks(&self) -> impl Iterator<Item = (ChunkPos, &Chunk)> + '_ {
+        self.chunks.iter().map(|(&pos, chunk)| (pos, chunk))
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Replaces a chunk, marking it and its loaded neighbours dirty.
+    pub fn insert_chunk(&mut self, pos: ChunkPos, chunk: Chunk) -> Option<Chunk> {
+        assert_eq!(
+            chunk.size(),
+            self.chunk_size,
+            "The chunk size doesn't match the world!"
+        );
+        let old = self.chunks.insert(pos, chunk);
+        self.mark_dirty(pos);
+        self.mark_neighbours_dirty(pos);
+        old
+    }
+
+    pub fn remove_chunk(&mut self, pos: ChunkPos) -> Option<Chunk> {
+        self.dirty.remove(&pos);
+        let old = self.chunks.remove(&pos);
+        if old.is_some() {
+            self.mark_neighbours_dirty(pos);
+        }
+        old
+    }
+
+    pub fn mark_dirty(&mut self, pos: ChunkPos) {
+        self.dirty.insert(pos);
+    }
+
+    fn mark_neighbours_dirty(&mut self, (x, y, z): ChunkPos) {
+        let neighbours = [
+            (x + 1, y, z),
+            (x - 1, y, z),
+            (x, y + 1, z),
+            (x, y - 1, z),
+            (x, y, z + 1),
+            (x, y, z - 1),
+        ];
+        for neighbour in neighbours.iter() {
+            if self.chunks.contains_key(neighbour) {
+                self.dirty.insert(*neighbour);
+            }
+        }
+    }
+
+    pub fn is_dirty(&self, pos: ChunkPos) -> bool {
+        self.dirty.contains(&pos)
+    }
+
+    /// Dirty chunks in a stable order, clearing their flags.
+    pub fn take_dirty(&mut self) -> Vec<ChunkPos> {
+        let mut dirty: Vec<ChunkPos> = self.dirty.drain().collect();
+        dirty.sort_unstable();
+        dirty
+    }
+
+    /// Meshes of the dirty chunks, clearing their flags.
+    pub fn remesh_dirty(&mut self) -> Vec<(ChunkPos, VoxelMesh)> {
+        self.take_dirty()
+            .into_iter()
+            .filter(|pos| self.chunks.contains_key(pos))
+            .map(|pos| (pos, mesh_chunk(self, pos)))
+            .collect()
+    }
+
+    /// Generates the chunks on `threads` worker threads with the voxel at
+    /// each world coordinate, replacing the existing ones.
+    pub fn generate(
+        &mut self,
+        positions: &[ChunkPos],
+        threads: usize,
+        voxel: impl Fn(i32, i32, i32) -> Voxel + Sync,
+    ) {
+        let size = self.chunk_size as i32;
+        self.generate_chunks(positions, threads, |(cx, cy, cz), chunk| {
+            let size_usize = chunk.size();
+            *chunk = Chunk::from_fn(size_usize, |x, y, z| {
+                voxel(
+                    cx * size + x as i32,
+                    cy * size + y as i32,
+                    cz * size + z as i32,
+                )
+            });
+        });
+    }
+
+    /// Generates the chunks on `threads` worker threads, `generator` filling
+    /// each empty chunk from its position. The empty results aren't kept.
+    pub fn generate_chunks(
+        &mut self,
+        positions: &[ChunkPos],
+        threads: usize,
+        generator: impl Fn(ChunkPos, &mut Chunk) + Sync,
+    ) {
+        let size = self.chunk_size;
+        let chunks = parallel_map(positions, threads, |&pos| {
+            let mut chunk = Chunk::new(size);
+            generator(pos, &mut chunk);
+            chunk.compact();
+            chunk
+        });
+        for (&pos, chunk) in positions.iter().zip(chunks) {
+            if chunk.is_empty() {
+                if self.chunks.contains_key(&pos) {
+                    self.remove_chunk(pos);
+                }
+            } else {
+                self.insert_chunk(pos, chunk);
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+pub mod overlapping;
+pub mod solver;
+pub mod tiled;
+
+pub use overlapping::OverlappingModel;
+pub use solver::{Rules, Solver};
+pub use tiled::TiledModel;
+
+use crate::ressources;
+
+#[derive(Debug)]
+pub enum Error {
+    /// No solution was found within the allowed number of backtracks.
+    Contradiction,
+    ResourceLoad {
+        name: String,
+        inner: ressources::Error,
+    },
+    Image(image::ImageError),
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+/// Offsets to the neighbours of a cell, the opposite of the direction `d` is `d ^ 1`.
+pub const DIRECTIONS: [(i32, i32, i32); 6] = [
+    (1, 0, 0),
+    (-1, 0, 0),
+    (0, 1, 0),
+    (0, -1, 0),
+    (0, 0, 1),
+    (0, 0, -1),
+];
+
+/// Size of a 2D (`depth == 1`) or 3D grid, cells are stored x first then y then z.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Dims {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+}
+
+impl Dims {
+    pub fn new(width: usize, height: usize, depth: usize) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+        }
+    }
+
+    pub fn new_2d(width: usize, height: usize) -> Self {
+        Self::new(width, height, 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.width * self.height * self.depth
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of neighbour directions used, 4 in 2D and 6 in 3D.
+    pub fn directions(&self) -> usize {
+        if self.depth > 1 {
+            6
+        } else {
+            4
+        }
+    }
+
+    pub fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + self.width * (y + self.height * z)
+    }
+
+    pub fn coords(&self, index: usize) -> (usize, usize, usize) {
+        (
+            index % self.width,
+            (index / self.width) % self.height,
+            index / (self.width * self.height),
+        )
+    }
+
+    /// Neighbour of the cell in the direction `d`, wrapping around if `periodic`.
+    pub fn neighbour(&self, index: usize, d: usize, periodic: bool) -> Option<usize> {
+        let (x, y, z) = self.coords(index);
+        let (dx, dy, dz) = DIRECTIONS[d];
+        let wrap = |v: usize, dv: i32, size: usize| {
+            let n = v as i32 + dv;
+            if n >= 0 && n < size as i32 {
+                Some(n as usize)
+            } else if periodic {
+                Some(n.rem_euclid(size as i32) as usize)
+            } else {
+                None
+            }
+        };
+        Some(self.index(
+            wrap(x, dx, self.width)?,
+            wrap(y, dy, self.height)?,
+            wrap(z, dz, self.depth)?,
+        ))
+    }
+}
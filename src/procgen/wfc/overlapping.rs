@@ -0,0 +1,260 @@
+// External imports
+use image::RgbaImage;
+use std::collections::HashMap;
+// Crate imports
+use super::{Dims, Error, Rules, Solver, DIRECTIONS};
+use crate::math::Rng;
+use crate::ressources::RessourceLoader;
+
+/// Overlapping WFC model, every N×N (N×N×N in 3D) window of the output
+/// is a pattern found in the sample.
+pub struct OverlappingModel {
+    n: usize,
+    is_3d: bool,
+    patterns: Vec<Vec<usize>>,
+    rules: Rules,
+    /// Colors of the sample values when built from an image.
+    pub palette: Vec<[u8; 4]>,
+}
+
+impl OverlappingModel {
+    /// Builds the model from a sample of values, `periodic_input` lets the
+    /// patterns wrap around the sample and `symmetry` (1 to 8) adds the
+    /// rotated and reflected variants of each 2D pattern.
+    pub fn new(sample: &[usize], dims: Dims, n: usize, periodic_input: bool, symmetry: usize) -> Self {
+        let is_3d = dims.depth > 1;
+        let nz = if is_3d { n } else { 1 };
+        let (max_x, max_y, max_z) = if periodic_input {
+            (dims.width, dims.height, dims.depth)
+        } else {
+            (
+                (dims.width + 1).saturating_sub(n),
+                (dims.height + 1).saturating_sub(n),
+                (dims.depth + 1).saturating_sub(nz),
+            )
+        };
+
+        let mut patterns: Vec<Vec<usize>> = Vec::new();
+        let mut weights: Vec<f32> = Vec::new();
+        let mut indices: HashMap<Vec<usize>, usize> = HashMap::new();
+        for z in 0..max_z {
+            for y in 0..max_y {
+                for x in 0..max_x {
+                    let mut pattern = Vec::with_capacity(n * n * nz);
+                    for dz in 0..nz {
+                        for dy in 0..n {
+                            for dx in 0..n {
+                                pattern.push(
+                                    sample[dims.index(
+                                        (x + dx) % dims.width,
+                                        (y + dy) % dims.height,
+                                        (z + dz) % dims.depth,
+                                    )],
+                                );
+                            }
+                        }
+                    }
+                    let variants = if is_3d {
+                        vec![pattern]
+                    } else {
+                        symmetries(&pattern, n, symmetry)
+                    };
+                    for variant in variants {
+                        match indices.get(&variant) {
+                            Some(&i) => weights[i] += 1_f32,
+                            None => {
+                                indices.insert(variant.clone(), patterns.len());
+                                patterns.push(variant);
+                                weights.push(1_f32);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let directions = if is_3d { 6 } else { 4 };
+        let propagator = (0..directions)
+            .map(|d| {
+                let (dx, dy, dz) = DIRECTIONS[d];
+                patterns
+                    .iter()
+                    .map(|p1| {
+                        (0..patterns.len())
+                            .filter(|&p2| agrees(p1, &patterns[p2], n, nz, dx, dy, dz))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            n,
+            is_3d,
+            patterns,
+            rules: Rules::new(weights, propagator),
+            palette: Vec::new(),
+        }
+    }
+
+    /// Builds a 2D model from an image, each distinct color is a value.
+    pub fn from_image(image: &RgbaImage, n: usize, periodic_input: bool, symmetry: usize) -> Self {
+        let (sample, palette) = image_to_sample(image);
+        let dims = Dims::new_2d(image.width() as usize, image.height() as usize);
+        let mut model = Self::new(&sample, dims, n, periodic_input, symmetry);
+        model.palette = palette;
+        model
+    }
+
+    pub fn from_res(
+        res: &RessourceLoader,
+        name: &str,
+        n: usize,
+        periodic_input: bool,
+        symmetry: usize,
+    ) -> Result<Self, Error> {
+        let image = load_image(res, name)?;
+        Ok(Self::from_image(&image, n, periodic_input, symmetry))
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn patterns(&self) -> &[Vec<usize>] {
+        &self.patterns
+    }
+
+    /// Size of the wave needed for an output of `dims`, when the output
+    /// isn't periodic the patterns of the last cells cover the border.
+    pub fn wave_dims(&self, dims: Dims, periodic: bool) -> Dims {
+        if periodic {
+            return dims;
+        }
+        let nz = if self.is_3d { self.n } else { 1 };
+        Dims::new(
+            (dims.width + 1).saturating_sub(self.n).max(1),
+            (dims.height + 1).saturating_sub(self.n).max(1),
+            (dims.depth + 1).saturating_sub(nz).max(1),
+        )
+    }
+
+    /// Solver over the wave for an output of `dims`, useful to add constraints.
+    pub fn solver(&self, dims: Dims, periodic: bool) -> Solver<'_> {
+        Solver::new(&self.rules, self.wave_dims(dims, periodic), periodic)
+    }
+
+    /// Turns the patterns chosen by the solver into output values.
+    pub fn decode(&self, dims: Dims, periodic: bool, wave: &[usize]) -> Vec<usize> {
+        let wave_dims = self.wave_dims(dims, periodic);
+        let mut output = Vec::with_capacity(dims.len());
+        for z in 0..dims.depth {
+            for y in 0..dims.height {
+                for x in 0..dims.width {
+                    let wx = x.min(wave_dims.width - 1);
+                    let wy = y.min(wave_dims.height - 1);
+                    let wz = z.min(wave_dims.depth - 1);
+                    let pattern = &self.patterns[wave[wave_dims.index(wx, wy, wz)]];
+                    output.push(pattern[(x - wx) + self.n * ((y - wy) + self.n * (z - wz))]);
+                }
+            }
+        }
+        output
+    }
+
+    /// Generates an output of `dims` values, the same seed always gives the same output.
+    pub fn run(
+        &self,
+        dims: Dims,
+        periodic: bool,
+        rng: &mut Rng,
+        max_backtracks: usize,
+    ) -> Result<Vec<usize>, Error> {
+        let wave = self.solver(dims, periodic).run(rng, max_backtracks)?;
+        Ok(self.decode(dims, periodic, &wave))
+    }
+
+    /// Converts the first layer of an output into an image using the palette.
+    pub fn to_image(&self, dims: Dims, values: &[usize]) -> RgbaImage {
+        values_to_image(&self.palette, dims, values)
+    }
+}
+
+// Whether p2 placed at (dx, dy, dz) from p1 agrees with it on their overlap.
+fn agrees(p1: &[usize], p2: &[usize], n: usize, nz: usize, dx: i32, dy: i32, dz: i32) -> bool {
+    let range = |d: i32, size: usize| {
+        if d < 0 {
+            0..(size as i32 + d)
+        } else {
+            d..size as i32
+        }
+    };
+    for z in range(dz, nz) {
+        for y in range(dy, n) {
+            for x in range(dx, n) {
+                let a = p1[x as usize + n * (y as usize + n * z as usize)];
+                let b = p2[(x - dx) as usize + n * ((y - dy) as usize + n * (z - dz) as usize)];
+                if a != b {
+                    return false;
+                }
+            }
+        }
+    }
+    true
+}
+
+// The rotations and reflections of a 2D pattern, in the usual WFC order.
+fn symmetries(pattern: &[usize], n: usize, count: usize) -> Vec<Vec<usize>> {
+    let rotate = |p: &[usize]| -> Vec<usize> {
+        (0..n * n).map(|i| p[n - 1 - i / n + (i % n) * n]).collect()
+    };
+    let reflect = |p: &[usize]| -> Vec<usize> {
+        (0..n * n).map(|i| p[n - 1 - i % n + (i / n) * n]).collect()
+    };
+    let mut variants = vec![pattern.to_vec()];
+    // reflections at odd indices, rotations of the previous unreflected one at even indices
+    while variants.len() < count.clamp(1, 8) {
+        let len = variants.len();
+        let next = if len % 2 == 1 {
+            reflect(&variants[len - 1])
+        } else {
+            rotate(&variants[len - 2])
+        };
+        variants.push(next);
+    }
+    variants
+}
+
+pub(crate) fn load_image(res: &RessourceLoader, name: &str) -> Result<RgbaImage, Error> {
+    let bytes = res.load_bytes(name, &[]).map_err(|e| Error::ResourceLoad {
+        name: name.to_string(),
+        inner: e,
+    })?;
+    Ok(image::load_from_memory(&bytes)
+        .map_err(Error::Image)?
+        .to_rgba8())
+}
+
+// Values of the image pixels and the palette they index, colors are
+// numbered in the order they first appear.
+pub(crate) fn image_to_sample(image: &RgbaImage) -> (Vec<usize>, Vec<[u8; 4]>) {
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let sample = image
+        .pixels()
+        .map(|pixel| match palette.iter().position(|c| *c == pixel.0) {
+            Some(i) => i,
+            None => {
+                palette.push(pixel.0);
+                palette.len() - 1
+            }
+        })
+        .collect();
+    (sample, palette)
+}
+
+pub(crate) fn values_to_image(palette: &[[u8; 4]], dims: Dims, values: &[usize]) -> RgbaImage {
+    RgbaImage::from_fn(dims.width as u32, dims.height as u32, |x, y| {
+        let value = values[dims.index(x as usize, y as usize, 0)];
+        image::Rgba(palette.get(value).copied().unwrap_or([0, 0, 0, 255]))
+    })
+}
@@ -0,0 +1,288 @@
+// Crate imports
+use super::{Dims, Error};
+use crate::math::Rng;
+
+/// Adjacency rules shared by every WFC model.
+///
+/// `propagator[d][p]` lists the patterns allowed in the neighbour in the
+/// direction `d` of a cell holding the pattern `p`.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    pub weights: Vec<f32>,
+    pub propagator: Vec<Vec<Vec<usize>>>,
+}
+
+impl Rules {
+    /// Negative weights count as 0, a pattern of weight 0 is only used when
+    /// nothing else fits.
+    pub fn new(weights: Vec<f32>, propagator: Vec<Vec<Vec<usize>>>) -> Self {
+        Self {
+            weights: weights.into_iter().map(|w| w.max(0_f32)).collect(),
+            propagator,
+        }
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.weights.len()
+    }
+}
+
+// One ban in the trail, `propagated` is set once its consequences were
+// applied to the neighbours so that undoing it knows what to revert.
+#[derive(Copy, Clone, Debug)]
+struct Ban {
+    cell: usize,
+    pattern: usize,
+    propagated: bool,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Decision {
+    cell: usize,
+    pattern: usize,
+    trail_len: usize,
+}
+
+/// Wave function collapse solver over a 2D or 3D grid with backtracking.
+pub struct Solver<'a> {
+    rules: &'a Rules,
+    dims: Dims,
+    periodic: bool,
+    wave: Vec<bool>,
+    // compatible[(cell * patterns + pattern) * 6 + d]
+    compatible: Vec<i32>,
+    counts: Vec<usize>,
+    sum_weights: Vec<f32>,
+    sum_weight_logs: Vec<f32>,
+    weight_logs: Vec<f32>,
+    trail: Vec<Ban>,
+    // index in the trail of the next ban to propagate
+    propagated: usize,
+    decisions: Vec<Decision>,
+    contradiction: bool,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(rules: &'a Rules, dims: Dims, periodic: bool) -> Self {
+        let patterns = rules.pattern_count();
+        let cells = dims.len();
+        // the patterns of weight 0 add nothing to the entropy, `0 * ln(0)` being NaN
+        let weight_logs: Vec<f32> = rules
+            .weights
+            .iter()
+            .map(|w| if *w > 0_f32 { w * w.ln() } else { 0_f32 })
+            .collect();
+        let sum_weight: f32 = rules.weights.iter().sum();
+        let sum_weight_log: f32 = weight_logs.iter().sum();
+
+        let mut initial = vec![0; patterns * 6];
+        for p in 0..patterns {
+            for d in 0..6 {
+                initial[p * 6 + d] = rules
+                    .propagator
+                    .get(d ^ 1)
+                    .map(|prop| prop[p].len() as i32)
+                    .unwrap_or(0);
+            }
+        }
+        let mut compatible = Vec::with_capacity(cells * patterns * 6);
+        for _ in 0..cells {
+            compatible.extend_from_slice(&initial);
+        }
+
+        Self {
+            rules,
+            dims,
+            periodic,
+            wave: vec![true; cells * patterns],
+            compatible,
+            counts: vec![patterns; cells],
+            sum_weights: vec![sum_weight; cells],
+            sum_weight_logs: vec![sum_weight_log; cells],
+            weight_logs,
+            trail: Vec::new(),
+            propagated: 0,
+            decisions: Vec::new(),
+            contradiction: patterns == 0,
+        }
+    }
+
+    pub fn dims(&self) -> Dims {
+        self.dims
+    }
+
+    /// Whether the pattern is still possible in the cell.
+    pub fn is_possible(&self, cell: usize, pattern: usize) -> bool {
+        self.wave[cell * self.rules.pattern_count() + pattern]
+    }
+
+    /// Restricts a cell to the given patterns, to be called before `run`.
+    pub fn constrain(&mut self, cell: usize, allowed: &[usize]) {
+        for p in 0..self.rules.pattern_count() {
+            if !allowed.contains(&p) && self.is_possible(cell, p) {
+                self.ban(cell, p);
+            }
+        }
+    }
+
+    /// Collapses the whole grid, backtracking at most `max_backtracks` times.
+    ///
+    /// Returns the pattern chosen for each cell.
+    pub fn run(&mut self, rng: &mut Rng, max_backtracks: usize) -> Result<Vec<usize>, Error> {
+        let mut backtracks = 0;
+        self.propagate();
+        loop {
+            if self.contradiction {
+                if backtracks >= max_backtracks || !self.backtrack() {
+                    return Err(Error::Contradiction);
+                }
+                backtracks += 1;
+                continue;
+            }
+            match self.lowest_entropy_cell(rng) {
+                Some(cell) => self.observe(cell, rng),
+                None => return Ok(self.result()),
+            }
+        }
+    }
+
+    fn result(&self) -> Vec<usize> {
+        let patterns = self.rules.pattern_count();
+        (0..self.dims.len())
+            .map(|cell| {
+                (0..patterns)
+                    .find(|&p| self.is_possible(cell, p))
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    fn lowest_entropy_cell(&self, rng: &mut Rng) -> Option<usize> {
+        let mut best = None;
+        let mut best_entropy = f32::MAX;
+        for cell in 0..self.dims.len() {
+            if self.counts[cell] <= 1 {
+                continue;
+            }
+            let sum = self.sum_weights[cell];
+            // only patterns of weight 0 left, all as likely
+            let entropy = if sum > 0_f32 {
+                sum.ln() - self.sum_weight_logs[cell] / sum
+            } else {
+                0_f32
+            };
+            let entropy = entropy + 1e-4 * rng.next_f32();
+            if entropy < best_entropy {
+                best_entropy = entropy;
+                best = Some(cell);
+            }
+        }
+        best
+    }
+
+    fn observe(&mut self, cell: usize, rng: &mut Rng) {
+        let patterns = self.rules.pattern_count();
+        let weights: Vec<f32> = (0..patterns)
+            .map(|p| {
+                if self.is_possible(cell, p) {
+                    self.rules.weights[p]
+                } else {
+                    0_f32
+                }
+            })
+            .collect();
+        let chosen = match rng.weighted_index(&weights) {
+            Some(p) => p,
+            None => (0..patterns).find(|&p| self.is_possible(cell, p)).unwrap_or(0),
+        };
+        self.decisions.push(Decision {
+            cell,
+            pattern: chosen,
+            trail_len: self.trail.len(),
+        });
+        for p in 0..patterns {
+            if p != chosen && self.is_possible(cell, p) {
+                self.ban(cell, p);
+            }
+        }
+        self.propagate();
+    }
+
+    // Undoes the last decision and forbids its pattern instead,
+    // returns false when there is nothing left to undo.
+    fn backtrack(&mut self) -> bool {
+        let decision = match self.decisions.pop() {
+            Some(decision) => decision,
+            None => return false,
+        };
+        while self.trail.len() > decision.trail_len {
+            let ban = self.trail.pop().unwrap();
+            self.unban(ban);
+        }
+        self.propagated = self.trail.len();
+        self.contradiction = false;
+        self.ban(decision.cell, decision.pattern);
+        self.propagate();
+        true
+    }
+
+    fn ban(&mut self, cell: usize, pattern: usize) {
+        let patterns = self.rules.pattern_count();
+        self.wave[cell * patterns + pattern] = false;
+        self.counts[cell] -= 1;
+        self.sum_weights[cell] -= self.rules.weights[pattern];
+        self.sum_weight_logs[cell] -= self.weight_logs[pattern];
+        if self.counts[cell] == 0 {
+            self.contradiction = true;
+        }
+        self.trail.push(Ban {
+            cell,
+            pattern,
+            propagated: false,
+        });
+    }
+
+    fn unban(&mut self, ban: Ban) {
+        let rules = self.rules;
+        let patterns = rules.pattern_count();
+        if ban.propagated {
+            for d in 0..self.dims.directions() {
+                let neighbour = match self.dims.neighbour(ban.cell, d, self.periodic) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                for &p in &rules.propagator[d][ban.pattern] {
+                    self.compatible[(neighbour * patterns + p) * 6 + d] += 1;
+                }
+            }
+        }
+        self.wave[ban.cell * patterns + ban.pattern] = true;
+        self.counts[ban.cell] += 1;
+        self.sum_weights[ban.cell] += rules.weights[ban.pattern];
+        self.sum_weight_logs[ban.cell] += self.weight_logs[ban.pattern];
+    }
+
+    fn propagate(&mut self) {
+        let rules = self.rules;
+        let patterns = rules.pattern_count();
+        while self.propagated < self.trail.len() && !self.contradiction {
+            let index = self.propagated;
+            self.propagated += 1;
+            self.trail[index].propagated = true;
+            let Ban { cell, pattern, .. } = self.trail[index];
+            for d in 0..self.dims.directions() {
+                let neighbour = match self.dims.neighbour(cell, d, self.periodic) {
+                    Some(n) => n,
+                    None => continue,
+                };
+                for &p in &rules.propagator[d][pattern] {
+                    let count = &mut self.compatible[(neighbour * patterns + p) * 6 + d];
+                    *count -= 1;
+                    if *count == 0 && self.wave[neighbour * patterns + p] {
+                        self.ban(neighbour, p);
+                    }
+                }
+            }
+        }
+    }
+}
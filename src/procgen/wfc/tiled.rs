@@ -0,0 +1,186 @@
+// External imports
+use image::RgbaImage;
+// Crate imports
+use super::overlapping::{image_to_sample, load_image, values_to_image};
+use super::{Dims, Error, Rules, Solver};
+use crate::math::Rng;
+use crate::ressources::RessourceLoader;
+
+/// Simple tiled WFC model, each cell holds one tile and adjacent tiles
+/// must be allowed next to each other by the rules.
+///
+/// The rule file has one statement per line, `#` starts a comment:
+///
+/// ```text
+/// tile grass 4
+/// tile water 1
+/// x grass water
+/// y grass grass
+/// ```
+///
+/// `tile NAME WEIGHT` declares a tile and `x A B` (or `y`, `z`) allows
+/// the tile `B` right after the tile `A` along that axis.
+pub struct TiledModel {
+    names: Vec<String>,
+    rules: Rules,
+    /// Colors of the tiles when built from an image.
+    pub palette: Vec<[u8; 4]>,
+}
+
+impl TiledModel {
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut names: Vec<String> = Vec::new();
+        let mut weights: Vec<f32> = Vec::new();
+        let mut adjacencies: Vec<(usize, usize, usize)> = Vec::new();
+
+        for (i, line) in source.lines().enumerate() {
+            let parse_error = |message: String| Error::Parse {
+                line: i + 1,
+                message,
+            };
+            let line = line.split('#').next().unwrap_or("");
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                [] => {}
+                ["tile", name] | ["tile", name, _] => {
+                    if names.iter().any(|n| n == name) {
+                        return Err(parse_error(format!("tile `{}` declared twice", name)));
+                    }
+                    let weight = match words.get(2) {
+                        Some(w) => w
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|w| *w > 0_f32)
+                            .ok_or_else(|| parse_error(format!("invalid weight `{}`", w)))?,
+                        None => 1_f32,
+                    };
+                    names.push(name.to_string());
+                    weights.push(weight);
+                }
+                [axis, a, b] => {
+                    let axis = match *axis {
+                        "x" => 0,
+                        "y" => 1,
+                        "z" => 2,
+                        _ => return Err(parse_error(format!("unknown statement `{}`", axis))),
+                    };
+                    let find = |name: &str| {
+                        names
+                            .iter()
+                            .position(|n| n == name)
+                            .ok_or_else(|| parse_error(format!("unknown tile `{}`", name)))
+                    };
+                    adjacencies.push((axis, find(a)?, find(b)?));
+                }
+                _ => return Err(parse_error(format!("invalid statement `{}`", line.trim()))),
+            }
+        }
+
+        let mut propagator = vec![vec![Vec::new(); names.len()]; 6];
+        for (axis, a, b) in adjacencies {
+            add_adjacency(&mut propagator, axis, a, b);
+        }
+        Ok(Self {
+            names,
+            rules: Rules::new(weights, propagator),
+            palette: Vec::new(),
+        })
+    }
+
+    pub fn from_res(res: &RessourceLoader, name: &str) -> Result<Self, Error> {
+        let bytes = res.load_bytes(name, &[]).map_err(|e| Error::ResourceLoad {
+            name: name.to_string(),
+            inner: e,
+        })?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    /// Learns the tiles, their weights and adjacencies from a sample of tile values.
+    pub fn from_sample(sample: &[usize], dims: Dims, periodic_input: bool) -> Self {
+        let count = sample.iter().max().map_or(0, |m| m + 1);
+        let mut weights = vec![0_f32; count];
+        let mut propagator = vec![vec![Vec::new(); count]; 6];
+        for (cell, &tile) in sample.iter().enumerate() {
+            weights[tile] += 1_f32;
+            for axis in 0..dims.directions() / 2 {
+                if let Some(next) = dims.neighbour(cell, axis * 2, periodic_input) {
+                    add_adjacency(&mut propagator, axis, tile, sample[next]);
+                }
+            }
+        }
+        // values missing from the sample can't be placed anywhere
+        for weight in &mut weights {
+            if *weight == 0_f32 {
+                *weight = f32::MIN_POSITIVE;
+            }
+        }
+        Self {
+            names: (0..count).map(|i| i.to_string()).collect(),
+            rules: Rules::new(weights, propagator),
+            palette: Vec::new(),
+        }
+    }
+
+    /// Learns the rules from an image where each distinct color is a tile.
+    pub fn from_image(image: &RgbaImage, periodic_input: bool) -> Self {
+        let (sample, palette) = image_to_sample(image);
+        let dims = Dims::new_2d(image.width() as usize, image.height() as usize);
+        let mut model = Self::from_sample(&sample, dims, periodic_input);
+        model.names = palette
+            .iter()
+            .map(|c| format!("#{:02x}{:02x}{:02x}{:02x}", c[0], c[1], c[2], c[3]))
+            .collect();
+        model.palette = palette;
+        model
+    }
+
+    pub fn from_image_res(res: &RessourceLoader, name: &str, periodic_input: bool) -> Result<Self, Error> {
+        let image = load_image(res, name)?;
+        Ok(Self::from_image(&image, periodic_input))
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn tile_index(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    /// Solver for an output of `dims`, useful to add constraints.
+    pub fn solver(&self, dims: Dims, periodic: bool) -> Solver<'_> {
+        Solver::new(&self.rules, dims, periodic)
+    }
+
+    /// Generates the tile indices of an output of `dims`, the same seed always gives the same output.
+    pub fn run(
+        &self,
+        dims: Dims,
+        periodic: bool,
+        rng: &mut Rng,
+        max_backtracks: usize,
+    ) -> Result<Vec<usize>, Error> {
+        self.solver(dims, periodic).run(rng, max_backtracks)
+    }
+
+    /// Converts the first layer of an output into an image using the palette.
+    pub fn to_image(&self, dims: Dims, tiles: &[usize]) -> RgbaImage {
+        values_to_image(&self.palette, dims, tiles)
+    }
+}
+
+// Allows `b` after `a` along the axis, and so `a` before `b`.
+fn add_adjacency(propagator: &mut [Vec<Vec<usize>>], axis: usize, a: usize, b: usize) {
+    let forward = &mut propagator[axis * 2][a];
+    if !forward.contains(&b) {
+        forward.push(b);
+    }
+    let backward = &mut propagator[axis * 2 + 1][b];
+    if !backward.contains(&a) {
+        backward.push(a);
+    }
+}
@@ -0,0 +1,373 @@
+// External imports
+use gl::types::*;
+use std::ffi::CStr;
+use std::rc::Rc;
+// Crate imports
+use super::reflection::ProgramReflection;
+
+pub mod opengl;
+pub mod recording;
+pub mod software;
+
+pub use recording::{Command, RecordingBackend};
+pub use software::{SoftwareBackend, SoftwareShader};
+
+/// Shared handle to the backend the render objects go through.
+pub type Backend = Rc<dyn RenderBackend>;
+
+/// Value of a uniform, one variant per `glProgramUniform*` call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum UniformValue {
+    Int(i32),
+    UInt(u32),
+    Float(f32),
+    Vec2([f32; 2]),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    Mat3([f32; 9]),
+    Mat4([f32; 16]),
+    /// Arrays from the location of an element, set with one `glProgramUniform*v`
+    /// call of their length.
+    IntArray(Vec<i32>),
+    UIntArray(Vec<u32>),
+    FloatArray(Vec<f32>),
+    Vec2Array(Vec<[f32; 2]>),
+    Vec3Array(Vec<[f32; 3]>),
+    Vec4Array(Vec<[f32; 4]>),
+    Mat3Array(Vec<[f32; 9]>),
+    Mat4Array(Vec<[f32; 16]>),
+}
+
+impl UniformValue {
+    /// Number of elements, 1 for the single values.
+    pub fn count(&self) -> usize {
+        match self {
+            UniformValue::IntArray(v) => v.len(),
+            UniformValue::UIntArray(v) => v.len(),
+            UniformValue::FloatArray(v) => v.len(),
+            UniformValue::Vec2Array(v) => v.len(),
+            UniformValue::Vec3Array(v) => v.len(),
+            UniformValue::Vec4Array(v) => v.len(),
+            UniformValue::Mat3Array(v) => v.len(),
+            UniformValue::Mat4Array(v) => v.len(),
+            _ => 1,
+        }
+    }
+
+    /// The single values of the elements, at the locations following
+    /// the one the value is set to.
+    pub fn elements(&self) -> Vec<UniformValue> {
+        match self {
+            UniformValue::IntArray(v) => v.iter().copied().map(UniformValue::Int).collect(),
+            UniformValue::UIntArray(v) => v.iter().copied().map(UniformValue::UInt).collect(),
+            UniformValue::FloatArray(v) => v.iter().copied().map(UniformValue::Float).collect(),
+            UniformValue::Vec2Array(v) => v.iter().copied().map(UniformValue::Vec2).collect(),
+            UniformValue::Vec3Array(v) => v.iter().copied().map(UniformValue::Vec3).collect(),
+            UniformValue::Vec4Array(v) => v.iter().copied().map(UniformValue::Vec4).collect(),
+            UniformValue::Mat3Array(v) => v.iter().copied().map(UniformValue::Mat3).collect(),
+            UniformValue::Mat4Array(v) => v.iter().copied().map(UniformValue::Mat4).collect(),
+            value => vec![value.clone()],
+        }
+    }
+}
+
+/// Active variable of a linked program, as given by `glGetActiveUniform`.
+///
+/// Arrays are listed once, their name ending with `[0]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActiveVariable {
+    pub name: String,
+    /// GLSL type, like `gl::FLOAT_VEC3`.
+    pub gtype: GLenum,
+    /// Number of elements, 1 if it is not an array.
+    pub size: GLint,
+}
+
+impl ActiveVariable {
+    pub fn new(name: &str, gtype: GLenum, size: GLint) -> Self {
+        Self {
+            name: name.to_owned(),
+            gtype,
+            size,
+        }
+    }
+}
+
+/// The OpenGL calls used by the render module, with the raw pointers
+/// replaced by slices so the calls can be recorded or emulated.
+///
+/// Enums and object names are the OpenGL ones.
+pub trait RenderBackend {
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei);
+    fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32);
+    fn clear(&self, mask: GLbitfield);
+    fn enable(&self, capability: GLenum);
+    fn disable(&self, capability: GLenum);
+    fn blend_func(&self, source: GLenum, destination: GLenum);
+    fn depth_func(&self, func: GLenum);
+
+    fn create_buffer(&self) -> GLuint;
+    fn delete_buffer(&self, id: GLuint);
+    fn bind_buffer(&self, target: GLenum, id: GLuint);
+    /// Binds the whole buffer to the indexed binding point, and to the target.
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, id: GLuint);
+    /// Binds `size` bytes from `offset` to the indexed binding point, and
+    /// the buffer to the target.
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        id: GLuint,
+        offset: usize,
+        size: usize,
+    );
+    /// Uploads the data to the buffer bound to the target.
+    fn buffer_data(&self, target: GLenum, data: &[u8], usage: GLenum);
+    /// Allocates `size` bytes for the buffer bound to the target, leaving them undefined.
+    fn buffer_allocate(&self, target: GLenum, size: usize, usage: GLenum);
+    /// Overwrites part of the buffer bound to the target, `offset` in bytes.
+    fn buffer_sub_data(&self, target: GLenum, offset: usize, data: &[u8]);
+    /// Immutable storage of `size` bytes for the buffer bound to the target.
+    fn buffer_storage(&self, target: GLenum, size: usize, flags: GLbitfield);
+    /// Maps the range of the buffer bound to the target, null on failure.
+    ///
+    /// The pointer stays valid until the buffer is unmapped or deleted.
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: usize,
+        length: usize,
+        access: GLbitfield,
+    ) -> *mut u8;
+    fn unmap_buffer(&self, target: GLenum);
+    /// Fence signaled once the commands issued so far are done.
+    fn fence_sync(&self) -> GLsync;
+    /// Waits at most `timeout` nanoseconds for the fence, flushing the
+    /// commands first. Returns the `glClientWaitSync` status.
+    fn client_wait_sync(&self, sync: GLsync, timeout: u64) -> GLenum;
+    fn delete_sync(&self, sync: GLsync);
+
+    fn create_vertex_array(&self) -> GLuint;
+    fn delete_vertex_array(&self, id: GLuint);
+    fn bind_vertex_array(&self, id: GLuint);
+    fn enable_vertex_array_attrib(&self, vertex_array: GLuint, index: GLuint);
+    /// Format of the attribute, `relative_offset` in bytes from the start of a vertex.
+    #[allow(clippy::too_many_arguments)]
+    fn vertex_array_attrib_format(
+        &self,
+        vertex_array: GLuint,
+        index: GLuint,
+        size: GLint,
+        etype: GLenum,
+        normalized: GLboolean,
+        relative_offset: GLuint,
+    );
+    /// Binding index the attribute reads its buffer from.
+    fn vertex_array_attrib_binding(&self, vertex_array: GLuint, index: GLuint, binding: GLuint);
+    /// Buffer of the binding index, `offset` in bytes.
+    fn vertex_array_vertex_buffer(
+        &self,
+        vertex_array: GLuint,
+        binding: GLuint,
+        buffer: GLuint,
+        offset: usize,
+        stride: GLsizei,
+    );
+    /// Instances drawn before the attributes of the binding advance, 0 for per vertex data.
+    fn vertex_array_binding_divisor(&self, vertex_array: GLuint, binding: GLuint, divisor: GLuint);
+    fn draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei);
+    /// Draws with the bound element buffer, `offset` in bytes.
+    fn draw_elements(&self, mode: GLenum, count: GLsizei, etype: GLenum, offset: usize);
+    fn draw_arrays_instanced(
+        &self,
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        instances: GLsizei,
+        base_instance: GLuint,
+    );
+    #[allow(clippy::too_many_arguments)]
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+        instances: GLsizei,
+        base_vertex: GLint,
+        base_instance: GLuint,
+    );
+    /// Draws the commands of the bound draw indirect buffer, `offset` in bytes.
+    fn multi_draw_arrays_indirect(
+        &self,
+        mode: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    );
+    fn multi_draw_elements_indirect(
+        &self,
+        mode: GLenum,
+        etype: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    );
+    /// Number of vertices of the patches.
+    fn patch_vertices(&self, count: GLint);
+
+    /// Runs the compute program in use over the work groups.
+    fn dispatch_compute(&self, x: GLuint, y: GLuint, z: GLuint);
+    /// Work group counts read at `offset` bytes in the dispatch indirect buffer.
+    fn dispatch_compute_indirect(&self, offset: usize);
+    /// `layout(local_size_x, ...)` of the linked compute program.
+    fn compute_work_group_size(&self, program: GLuint) -> [GLint; 3];
+    #[allow(clippy::too_many_arguments)]
+    fn bind_image_texture(
+        &self,
+        unit: GLuint,
+        texture: GLuint,
+        level: GLint,
+        layered: bool,
+        layer: GLint,
+        access: GLenum,
+        format: GLenum,
+    );
+    /// Orders the shader writes before the later accesses of the kinds.
+    fn memory_barrier(&self, barriers: GLbitfield);
+
+    fn create_shader(&self, stype: GLenum) -> GLuint;
+    fn delete_shader(&self, id: GLuint);
+    /// Compiles the source, the error is the info log.
+    fn compile_shader(&self, id: GLuint, source: &CStr) -> Result<(), String>;
+    fn create_program(&self) -> GLuint;
+    fn delete_program(&self, id: GLuint);
+    fn attach_shader(&self, program: GLuint, shader: GLuint);
+    /// Links the program, the error is the info log.
+    fn link_program(&self, program: GLuint) -> Result<(), String>;
+    fn use_program(&self, id: GLuint);
+    /// Location of the uniform, -1 if the program has no such uniform.
+    fn uniform_location(&self, program: GLuint, name: &str) -> GLint;
+    fn set_uniform(&self, program: GLuint, location: GLint, value: &UniformValue);
+    /// Uniforms used by the linked program, including the block members.
+    fn active_uniforms(&self, program: GLuint) -> Vec<ActiveVariable>;
+    /// Inputs, uniforms and blocks used by the linked program.
+    fn program_reflection(&self, program: GLuint) -> ProgramReflection;
+
+    fn create_texture(&self, target: GLenum) -> GLuint;
+    fn delete_texture(&self, id: GLuint);
+    fn texture_storage_2d(
+        &self,
+        id: GLuint,
+        levels: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    );
+    fn texture_parameter(&self, id: GLuint, pname: GLenum, param: GLint);
+    #[allow(clippy::too_many_arguments)]
+    fn texture_sub_image_2d(
+        &self,
+        id: GLuint,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+        data: &[u8],
+    );
+    fn bind_texture_unit(&self, unit: GLuint, id: GLuint);
+
+    fn create_renderbuffer(&self) -> GLuint;
+    fn delete_renderbuffer(&self, id: GLuint);
+    /// Storage of the renderbuffer, multisampled if `samples` is above 0.
+    fn renderbuffer_storage(
+        &self,
+        id: GLuint,
+        samples: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    );
+
+    fn create_framebuffer(&self) -> GLuint;
+    fn delete_framebuffer(&self, id: GLuint);
+    /// Binds to `FRAMEBUFFER`, `DRAW_FRAMEBUFFER` or `READ_FRAMEBUFFER`, 0
+    /// being the default framebuffer.
+    fn bind_framebuffer(&self, target: GLenum, id: GLuint);
+    /// Framebuffer bound to `DRAW_FRAMEBUFFER` or `READ_FRAMEBUFFER`.
+    fn framebuffer_binding(&self, target: GLenum) -> GLuint;
+    fn framebuffer_texture(&self, id: GLuint, attachment: GLenum, texture: GLuint, level: GLint);
+    fn framebuffer_renderbuffer(&self, id: GLuint, attachment: GLenum, renderbuffer: GLuint);
+    /// Color attachments the fragment outputs are written to, in order.
+    fn framebuffer_draw_buffers(&self, id: GLuint, buffers: &[GLenum]);
+    /// Color attachment `read_pixels` and blits read from.
+    fn framebuffer_read_buffer(&self, id: GLuint, buffer: GLenum);
+    fn check_framebuffer_status(&self, id: GLuint, target: GLenum) -> GLenum;
+    /// Copies the rectangle `[x0, y0, x1, y1]` of `read` to the one of `draw`,
+    /// resolving the multisampled images.
+    fn blit_framebuffer(
+        &self,
+        read: GLuint,
+        draw: GLuint,
+        src: [GLint; 4],
+        dst: [GLint; 4],
+        mask: GLbitfield,
+        filter: GLenum,
+    );
+    /// Pixels of the read framebuffer, tightly packed, bottom row first.
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+    ) -> Vec<u8>;
+}
+
+/// Bytes per pixel of the unsigned byte or float pixel data.
+pub fn pixel_size(format: GLenum, etype: GLenum) -> usize {
+    let channels = match format {
+        gl::RED | gl::DEPTH_COMPONENT | gl::STENCIL_INDEX => 1,
+        gl::RG => 2,
+        gl::RGB | gl::BGR => 3,
+        _ => 4,
+    };
+    let size = match etype {
+        gl::UNSIGNED_BYTE | gl::BYTE => 1,
+        gl::UNSIGNED_SHORT | gl::SHORT | gl::HALF_FLOAT => 2,
+        _ => 4,
+    };
+    channels * size
+}
+
+/// Plain values which can be uploaded as their bytes.
+///
+/// # Safety
+///
+/// The type must have no padding, no pointers and no invalid bit patterns,
+/// like the integers, the floats, and the `#[repr(C)]` structs and arrays of
+/// them without gaps.
+pub unsafe trait Pod: Copy + 'static {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for i8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for i16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl Pod for u64 {}
+unsafe impl Pod for i64 {}
+unsafe impl Pod for f32 {}
+unsafe impl Pod for f64 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Bytes of a slice of plain values, for the uploads.
+pub fn as_bytes<T: Pod>(data: &[T]) -> &[u8] {
+    // `Pod` types have every byte initialized
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
+}
@@ -0,0 +1,760 @@
+// The real backend, forwarding every call to the OpenGL bindings.
+
+// External imports
+use gl::types::*;
+use std::ffi::{CStr, CString};
+// Crate imports
+use super::{pixel_size, ActiveVariable, RenderBackend, UniformValue};
+use crate::render::reflection::{
+    AttributeInfo, BlockInfo, BlockMember, ProgramReflection, UniformInfo,
+};
+
+fn info_log(len: GLint, read: impl FnOnce(GLint, *mut GLchar)) -> String {
+    let mut buffer: Vec<u8> = vec![0; len.max(1) as usize];
+    read(len, buffer.as_mut_ptr() as *mut GLchar);
+    // drop the nul terminator
+    let end = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+    String::from_utf8_lossy(&buffer[..end]).into_owned()
+}
+
+impl RenderBackend for gl::Gl {
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        unsafe { self.Viewport(x, y, width, height) }
+    }
+
+    fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
+        unsafe { self.ClearColor(red, green, blue, alpha) }
+    }
+
+    fn clear(&self, mask: GLbitfield) {
+        unsafe { self.Clear(mask) }
+    }
+
+    fn enable(&self, capability: GLenum) {
+        unsafe { self.Enable(capability) }
+    }
+
+    fn disable(&self, capability: GLenum) {
+        unsafe { self.Disable(capability) }
+    }
+
+    fn blend_func(&self, source: GLenum, destination: GLenum) {
+        unsafe { self.BlendFunc(source, destination) }
+    }
+
+    fn depth_func(&self, func: GLenum) {
+        unsafe { self.DepthFunc(func) }
+    }
+
+    fn create_buffer(&self) -> GLuint {
+        let mut id: GLuint = 0;
+        unsafe { self.CreateBuffers(1, &mut id) }
+        id
+    }
+
+    fn delete_buffer(&self, id: GLuint) {
+        unsafe { self.DeleteBuffers(1, &id) }
+    }
+
+    fn bind_buffer(&self, target: GLenum, id: GLuint) {
+        unsafe { self.BindBuffer(target, id) }
+    }
+
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, id: GLuint) {
+        unsafe { self.BindBufferBase(target, index, id) }
+    }
+
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        id: GLuint,
+        offset: usize,
+        size: usize,
+    ) {
+        unsafe { self.BindBufferRange(target, index, id, offset as GLintptr, size as GLsizeiptr) }
+    }
+
+    fn buffer_data(&self, target: GLenum, data: &[u8], usage: GLenum) {
+        unsafe {
+            self.BufferData(
+                target,
+                data.len() as GLsizeiptr,
+                data.as_ptr() as *const GLvoid,
+                usage,
+            )
+        }
+    }
+
+    fn buffer_allocate(&self, target: GLenum, size: usize, usage: GLenum) {
+        unsafe { self.BufferData(target, size as GLsizeiptr, std::ptr::null(), usage) }
+    }
+
+    fn buffer_sub_data(&self, target: GLenum, offset: usize, data: &[u8]) {
+        unsafe {
+            self.BufferSubData(
+                target,
+                offset as GLintptr,
+                data.len() as GLsizeiptr,
+                data.as_ptr() as *const GLvoid,
+            )
+        }
+    }
+
+    fn buffer_storage(&self, target: GLenum, size: usize, flags: GLbitfield) {
+        unsafe { self.BufferStorage(target, size as GLsizeiptr, std::ptr::null(), flags) }
+    }
+
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: usize,
+        length: usize,
+        access: GLbitfield,
+    ) -> *mut u8 {
+        unsafe {
+            self.MapBufferRange(target, offset as GLintptr, length as GLsizeiptr, access) as *mut u8
+        }
+    }
+
+    fn unmap_buffer(&self, target: GLenum) {
+        unsafe { self.UnmapBuffer(target) };
+    }
+
+    fn fence_sync(&self) -> GLsync {
+        unsafe { self.FenceSync(gl::SYNC_GPU_COMMANDS_COMPLETE, 0) }
+    }
+
+    // the sync comes from `fence_sync`
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn client_wait_sync(&self, sync: GLsync, timeout: u64) -> GLenum {
+        unsafe { self.ClientWaitSync(sync, gl::SYNC_FLUSH_COMMANDS_BIT, timeout) }
+    }
+
+    // the sync comes from `fence_sync`
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn delete_sync(&self, sync: GLsync) {
+        unsafe { self.DeleteSync(sync) }
+    }
+
+    fn create_vertex_array(&self) -> GLuint {
+        let mut id: GLuint = 0;
+        unsafe { self.CreateVertexArrays(1, &mut id) }
+        id
+    }
+
+    fn delete_vertex_array(&self, id: GLuint) {
+        unsafe { self.DeleteVertexArrays(1, &id) }
+    }
+
+    fn bind_vertex_array(&self, id: GLuint) {
+        unsafe { self.BindVertexArray(id) }
+    }
+
+    fn enable_vertex_array_attrib(&self, vertex_array: GLuint, index: GLuint) {
+        unsafe { self.EnableVertexArrayAttrib(vertex_array, index) }
+    }
+
+    fn vertex_array_attrib_format(
+        &self,
+        vertex_array: GLuint,
+        index: GLuint,
+        size: GLint,
+        etype: GLenum,
+        normalized: GLboolean,
+        relative_offset: GLuint,
+    ) {
+        unsafe {
+            self.VertexArrayAttribFormat(
+                vertex_array,
+                index,
+                size,
+                etype,
+                normalized,
+                relative_offset,
+            )
+        }
+    }
+
+    fn vertex_array_attrib_binding(&self, vertex_array: GLuint, index: GLuint, binding: GLuint) {
+        unsafe { self.VertexArrayAttribBinding(vertex_array, index, binding) }
+    }
+
+    fn vertex_array_vertex_buffer(
+        &self,
+        vertex_array: GLuint,
+        binding: GLuint,
+        buffer: GLuint,
+        offset: usize,
+        stride: GLsizei,
+    ) {
+        unsafe {
+            self.VertexArrayVertexBuffer(vertex_array, binding, buffer, offset as GLintptr, stride)
+        }
+    }
+
+    fn vertex_array_binding_divisor(&self, vertex_array: GLuint, binding: GLuint, divisor: GLuint) {
+        unsafe { self.VertexArrayBindingDivisor(vertex_array, binding, divisor) }
+    }
+
+    fn draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        unsafe { self.DrawArrays(mode, first, count) }
+    }
+
+    fn draw_elements(&self, mode: GLenum, count: GLsizei, etype: GLenum, offset: usize) {
+        unsafe { self.DrawElements(mode, count, etype, offset as *const GLvoid) }
+    }
+
+    fn draw_arrays_instanced(
+        &self,
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        instances: GLsizei,
+        base_instance: GLuint,
+    ) {
+        unsafe {
+            self.DrawArraysInstancedBaseInstance(mode, first, count, instances, base_instance)
+        }
+    }
+
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+        instances: GLsizei,
+        base_vertex: GLint,
+        base_instance: GLuint,
+    ) {
+        unsafe {
+            self.DrawElementsInstancedBaseVertexBaseInstance(
+                mode,
+                count,
+                etype,
+                offset as *const GLvoid,
+                instances,
+                base_vertex,
+                base_instance,
+            )
+        }
+    }
+
+    fn multi_draw_arrays_indirect(
+        &self,
+        mode: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    ) {
+        unsafe { self.MultiDrawArraysIndirect(mode, offset as *const GLvoid, draw_count, stride) }
+    }
+
+    fn multi_draw_elements_indirect(
+        &self,
+        mode: GLenum,
+        etype: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    ) {
+        unsafe {
+            self.MultiDrawElementsIndirect(mode, etype, offset as *const GLvoid, draw_count, stride)
+        }
+    }
+
+    fn patch_vertices(&self, count: GLint) {
+        unsafe { self.PatchParameteri(gl::PATCH_VERTICES, count) }
+    }
+
+    fn dispatch_compute(&self, x: GLuint, y: GLuint, z: GLuint) {
+        unsafe { self.DispatchCompute(x, y, z) }
+    }
+
+    fn dispatch_compute_indirect(&self, offset: usize) {
+        unsafe { self.DispatchComputeIndirect(offset as GLintptr) }
+    }
+
+    fn compute_work_group_size(&self, program: GLuint) -> [GLint; 3] {
+        let mut size: [GLint; 3] = [0; 3];
+        unsafe { self.GetProgramiv(program, gl::COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr()) }
+        size
+    }
+
+    fn bind_image_texture(
+        &self,
+        unit: GLuint,
+        texture: GLuint,
+        level: GLint,
+        layered: bool,
+        layer: GLint,
+        access: GLenum,
+        format: GLenum,
+    ) {
+        let layered = if layered { gl::TRUE } else { gl::FALSE };
+        unsafe { self.BindImageTexture(unit, texture, level, layered, layer, access, format) }
+    }
+
+    fn memory_barrier(&self, barriers: GLbitfield) {
+        unsafe { self.MemoryBarrier(barriers) }
+    }
+
+    fn create_shader(&self, stype: GLenum) -> GLuint {
+        unsafe { self.CreateShader(stype) }
+    }
+
+    fn delete_shader(&self, id: GLuint) {
+        unsafe { self.DeleteShader(id) }
+    }
+
+    fn compile_shader(&self, id: GLuint, source: &CStr) -> Result<(), String> {
+        let mut success: GLint = 1;
+        unsafe {
+            self.ShaderSource(id, 1, &source.as_ptr(), std::ptr::null());
+            self.CompileShader(id);
+            self.GetShaderiv(id, gl::COMPILE_STATUS, &mut success);
+        }
+        if success != 0 {
+            return Ok(());
+        }
+        let mut len: GLint = 0;
+        unsafe { self.GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut len) }
+        Err(info_log(len, |len, buffer| unsafe {
+            self.GetShaderInfoLog(id, len, std::ptr::null_mut(), buffer)
+        }))
+    }
+
+    fn create_program(&self) -> GLuint {
+        unsafe { self.CreateProgram() }
+    }
+
+    fn delete_program(&self, id: GLuint) {
+        unsafe { self.DeleteProgram(id) }
+    }
+
+    fn attach_shader(&self, program: GLuint, shader: GLuint) {
+        unsafe { self.AttachShader(program, shader) }
+    }
+
+    fn link_program(&self, program: GLuint) -> Result<(), String> {
+        let mut success: GLint = 1;
+        unsafe {
+            self.LinkProgram(program);
+            self.GetProgramiv(program, gl::LINK_STATUS, &mut success);
+        }
+        if success != 0 {
+            return Ok(());
+        }
+        let mut len: GLint = 0;
+        unsafe { self.GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len) }
+        Err(info_log(len, |len, buffer| unsafe {
+            self.GetProgramInfoLog(program, len, std::ptr::null_mut(), buffer)
+        }))
+    }
+
+    fn use_program(&self, id: GLuint) {
+        unsafe { self.UseProgram(id) }
+    }
+
+    fn uniform_location(&self, program: GLuint, name: &str) -> GLint {
+        match CString::new(name) {
+            Ok(name) => unsafe { self.GetUniformLocation(program, name.as_ptr()) },
+            Err(_) => -1,
+        }
+    }
+
+    fn set_uniform(&self, program: GLuint, location: GLint, value: &UniformValue) {
+        unsafe {
+            match value {
+                UniformValue::Int(v) => self.ProgramUniform1i(program, location, *v),
+                UniformValue::UInt(v) => self.ProgramUniform1ui(program, location, *v),
+                UniformValue::Float(v) => self.ProgramUniform1f(program, location, *v),
+                UniformValue::Vec2(v) => self.ProgramUniform2fv(program, location, 1, v.as_ptr()),
+                UniformValue::Vec3(v) => self.ProgramUniform3fv(program, location, 1, v.as_ptr()),
+                UniformValue::Vec4(v) => self.ProgramUniform4fv(program, location, 1, v.as_ptr()),
+                UniformValue::Mat3(v) => {
+                    self.ProgramUniformMatrix3fv(program, location, 1, gl::FALSE, v.as_ptr())
+                }
+                UniformValue::Mat4(v) => {
+                    self.ProgramUniformMatrix4fv(program, location, 1, gl::FALSE, v.as_ptr())
+                }
+                UniformValue::IntArray(v) => {
+                    self.ProgramUniform1iv(program, location, v.len() as GLsizei, v.as_ptr())
+                }
+                UniformValue::UIntArray(v) => {
+                    self.ProgramUniform1uiv(program, location, v.len() as GLsizei, v.as_ptr())
+                }
+                UniformValue::FloatArray(v) => {
+                    self.ProgramUniform1fv(program, location, v.len() as GLsizei, v.as_ptr())
+                }
+                UniformValue::Vec2Array(v) => self.ProgramUniform2fv(
+                    program,
+                    location,
+                    v.len() as GLsizei,
+                    v.as_ptr() as *const f32,
+                ),
+                UniformValue::Vec3Array(v) => self.ProgramUniform3fv(
+                    program,
+                    location,
+                    v.len() as GLsizei,
+                    v.as_ptr() as *const f32,
+                ),
+                UniformValue::Vec4Array(v) => self.ProgramUniform4fv(
+                    program,
+                    location,
+                    v.len() as GLsizei,
+                    v.as_ptr() as *const f32,
+                ),
+                UniformValue::Mat3Array(v) => self.ProgramUniformMatrix3fv(
+                    program,
+                    location,
+                    v.len() as GLsizei,
+                    gl::FALSE,
+                    v.as_ptr() as *const f32,
+                ),
+                UniformValue::Mat4Array(v) => self.ProgramUniformMatrix4fv(
+                    program,
+                    location,
+                    v.len() as GLsizei,
+                    gl::FALSE,
+                    v.as_ptr() as *const f32,
+                ),
+            }
+        }
+    }
+
+    fn active_uniforms(&self, program: GLuint) -> Vec<ActiveVariable> {
+        let mut count: GLint = 0;
+        let mut max_length: GLint = 0;
+        unsafe {
+            self.GetProgramiv(program, gl::ACTIVE_UNIFORMS, &mut count);
+            self.GetProgramiv(program, gl::ACTIVE_UNIFORM_MAX_LENGTH, &mut max_length);
+        }
+        (0..count as GLuint)
+            .map(|index| {
+                let mut size: GLint = 0;
+                let mut gtype: GLenum = 0;
+                let name = info_log(max_length, |len, buffer| unsafe {
+                    self.GetActiveUniform(
+                        program,
+                        index,
+                        len,
+                        std::ptr::null_mut(),
+                        &mut size,
+                        &mut gtype,
+                        buffer,
+                    )
+                });
+                ActiveVariable { name, gtype, size }
+            })
+            .collect()
+    }
+
+    fn program_reflection(&self, program: GLuint) -> ProgramReflection {
+        let interface_value = |interface: GLenum, pname: GLenum| {
+            let mut value: GLint = 0;
+            unsafe { self.GetProgramInterfaceiv(program, interface, pname, &mut value) }
+            value
+        };
+        let name = |interface: GLenum, index: GLuint| {
+            let max_length = interface_value(interface, gl::MAX_NAME_LENGTH);
+            info_log(max_length, |len, buffer| unsafe {
+                self.GetProgramResourceName(
+                    program,
+                    interface,
+                    index,
+                    len,
+                    std::ptr::null_mut(),
+                    buffer,
+                )
+            })
+        };
+        let properties = |interface: GLenum, index: GLuint, properties: &[GLenum]| {
+            let mut values: Vec<GLint> = vec![0; properties.len()];
+            unsafe {
+                self.GetProgramResourceiv(
+                    program,
+                    interface,
+                    index,
+                    properties.len() as GLsizei,
+                    properties.as_ptr(),
+                    values.len() as GLsizei,
+                    std::ptr::null_mut(),
+                    values.as_mut_ptr(),
+                )
+            }
+            values
+        };
+        let resources =
+            |interface: GLenum| 0..interface_value(interface, gl::ACTIVE_RESOURCES) as GLuint;
+        let blocks = |interface: GLenum| -> Vec<BlockInfo> {
+            resources(interface)
+                .map(|index| {
+                    let values = properties(
+                        interface,
+                        index,
+                        &[gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE],
+                    );
+                    BlockInfo {
+                        name: name(interface, index),
+                        binding: values[0] as GLuint,
+                        data_size: values[1] as usize,
+                        members: Vec::new(),
+                    }
+                })
+                .collect()
+        };
+
+        let mut reflection = ProgramReflection {
+            attributes: resources(gl::PROGRAM_INPUT)
+                .map(|index| {
+                    let values = properties(
+                        gl::PROGRAM_INPUT,
+                        index,
+                        &[gl::TYPE, gl::ARRAY_SIZE, gl::LOCATION],
+                    );
+                    AttributeInfo {
+                        name: name(gl::PROGRAM_INPUT, index),
+                        gtype: values[0] as GLenum,
+                        size: values[1],
+                        location: values[2],
+                    }
+                })
+                .collect(),
+            uniforms: Vec::new(),
+            uniform_blocks: blocks(gl::UNIFORM_BLOCK),
+            storage_blocks: blocks(gl::SHADER_STORAGE_BLOCK),
+        };
+        // the members list their block, the others are in the default block
+        let member_properties = [
+            gl::TYPE,
+            gl::ARRAY_SIZE,
+            gl::BLOCK_INDEX,
+            gl::OFFSET,
+            gl::ARRAY_STRIDE,
+            gl::MATRIX_STRIDE,
+            gl::LOCATION,
+        ];
+        for index in resources(gl::UNIFORM) {
+            let values = properties(gl::UNIFORM, index, &member_properties);
+            let name = name(gl::UNIFORM, index);
+            match reflection.uniform_blocks.get_mut(values[2] as usize) {
+                Some(block) if values[2] >= 0 => block.members.push(BlockMember {
+                    name,
+                    gtype: values[0] as GLenum,
+                    size: values[1],
+                    offset: values[3],
+                    array_stride: values[4],
+                    matrix_stride: values[5],
+                }),
+                _ => reflection.uniforms.push(UniformInfo {
+                    name,
+                    gtype: values[0] as GLenum,
+                    size: values[1],
+                    location: values[6],
+                }),
+            }
+        }
+        for index in resources(gl::BUFFER_VARIABLE) {
+            let values = properties(gl::BUFFER_VARIABLE, index, &member_properties[..6]);
+            if let Some(block) = reflection.storage_blocks.get_mut(values[2] as usize) {
+                block.members.push(BlockMember {
+                    name: name(gl::BUFFER_VARIABLE, index),
+                    gtype: values[0] as GLenum,
+                    size: values[1],
+                    offset: values[3],
+                    array_stride: values[4],
+                    matrix_stride: values[5],
+                });
+            }
+        }
+        reflection
+    }
+
+    fn create_texture(&self, target: GLenum) -> GLuint {
+        let mut id: GLuint = 0;
+        unsafe { self.CreateTextures(target, 1, &mut id) }
+        id
+    }
+
+    fn delete_texture(&self, id: GLuint) {
+        unsafe { self.DeleteTextures(1, &id) }
+    }
+
+    fn texture_storage_2d(
+        &self,
+        id: GLuint,
+        levels: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        unsafe { self.TextureStorage2D(id, levels, internal_format, width, height) }
+    }
+
+    fn texture_parameter(&self, id: GLuint, pname: GLenum, param: GLint) {
+        unsafe { self.TextureParameteri(id, pname, param) }
+    }
+
+    fn texture_sub_image_2d(
+        &self,
+        id: GLuint,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+        data: &[u8],
+    ) {
+        let size = width.max(0) as usize * height.max(0) as usize * pixel_size(format, etype);
+        assert!(data.len() >= size, "The pixel data is smaller than the texture region!");
+        let mut alignment: GLint = 0;
+        let mut unpack_buffer: GLint = 0;
+        unsafe {
+            self.GetIntegerv(gl::UNPACK_ALIGNMENT, &mut alignment);
+            self.GetIntegerv(gl::PIXEL_UNPACK_BUFFER_BINDING, &mut unpack_buffer);
+            // rows are tightly packed and come from memory, not from a pixel unpack buffer
+            self.PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            self.BindBuffer(gl::PIXEL_UNPACK_BUFFER, 0);
+            self.TextureSubImage2D(
+                id,
+                level,
+                x,
+                y,
+                width,
+                height,
+                format,
+                etype,
+                data.as_ptr() as *const GLvoid,
+            );
+            self.PixelStorei(gl::UNPACK_ALIGNMENT, alignment);
+            self.BindBuffer(gl::PIXEL_UNPACK_BUFFER, unpack_buffer as GLuint);
+        }
+    }
+
+    fn bind_texture_unit(&self, unit: GLuint, id: GLuint) {
+        unsafe { self.BindTextureUnit(unit, id) }
+    }
+
+    fn create_renderbuffer(&self) -> GLuint {
+        let mut id: GLuint = 0;
+        unsafe { self.CreateRenderbuffers(1, &mut id) };
+        id
+    }
+
+    fn delete_renderbuffer(&self, id: GLuint) {
+        unsafe { self.DeleteRenderbuffers(1, &id) }
+    }
+
+    fn renderbuffer_storage(
+        &self,
+        id: GLuint,
+        samples: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        unsafe {
+            self.NamedRenderbufferStorageMultisample(id, samples, internal_format, width, height)
+        }
+    }
+
+    fn create_framebuffer(&self) -> GLuint {
+        let mut id: GLuint = 0;
+        unsafe { self.CreateFramebuffers(1, &mut id) };
+        id
+    }
+
+    fn delete_framebuffer(&self, id: GLuint) {
+        unsafe { self.DeleteFramebuffers(1, &id) }
+    }
+
+    fn bind_framebuffer(&self, target: GLenum, id: GLuint) {
+        unsafe { self.BindFramebuffer(target, id) }
+    }
+
+    fn framebuffer_binding(&self, target: GLenum) -> GLuint {
+        let pname = match target {
+            gl::READ_FRAMEBUFFER => gl::READ_FRAMEBUFFER_BINDING,
+            _ => gl::DRAW_FRAMEBUFFER_BINDING,
+        };
+        let mut id: GLint = 0;
+        unsafe { self.GetIntegerv(pname, &mut id) }
+        id as GLuint
+    }
+
+    fn framebuffer_texture(&self, id: GLuint, attachment: GLenum, texture: GLuint, level: GLint) {
+        unsafe { self.NamedFramebufferTexture(id, attachment, texture, level) }
+    }
+
+    fn framebuffer_renderbuffer(&self, id: GLuint, attachment: GLenum, renderbuffer: GLuint) {
+        unsafe { self.NamedFramebufferRenderbuffer(id, attachment, gl::RENDERBUFFER, renderbuffer) }
+    }
+
+    fn framebuffer_draw_buffers(&self, id: GLuint, buffers: &[GLenum]) {
+        unsafe { self.NamedFramebufferDrawBuffers(id, buffers.len() as GLsizei, buffers.as_ptr()) }
+    }
+
+    fn framebuffer_read_buffer(&self, id: GLuint, buffer: GLenum) {
+        unsafe { self.NamedFramebufferReadBuffer(id, buffer) }
+    }
+
+    fn check_framebuffer_status(&self, id: GLuint, target: GLenum) -> GLenum {
+        unsafe { self.CheckNamedFramebufferStatus(id, target) }
+    }
+
+    fn blit_framebuffer(
+        &self,
+        read: GLuint,
+        draw: GLuint,
+        src: [GLint; 4],
+        dst: [GLint; 4],
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        unsafe {
+            self.BlitNamedFramebuffer(
+                read, draw, src[0], src[1], src[2], src[3], dst[0], dst[1], dst[2], dst[3], mask,
+                filter,
+            )
+        }
+    }
+
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+    ) -> Vec<u8> {
+        let size = (width.max(0) * height.max(0)) as usize * pixel_size(format, etype);
+        let mut data: Vec<u8> = vec![0; size];
+        let mut alignment: GLint = 0;
+        let mut pack_buffer: GLint = 0;
+        unsafe {
+            self.GetIntegerv(gl::PACK_ALIGNMENT, &mut alignment);
+            self.GetIntegerv(gl::PIXEL_PACK_BUFFER_BINDING, &mut pack_buffer);
+            // rows are tightly packed and go to memory, not to a pixel pack buffer
+            self.PixelStorei(gl::PACK_ALIGNMENT, 1);
+            self.BindBuffer(gl::PIXEL_PACK_BUFFER, 0);
+            self.ReadnPixels(
+                x,
+                y,
+                width,
+                height,
+                format,
+                etype,
+                size as GLsizei,
+                data.as_mut_ptr() as *mut GLvoid,
+            );
+            self.PixelStorei(gl::PACK_ALIGNMENT, alignment);
+            self.BindBuffer(gl::PIXEL_PACK_BUFFER, pack_buffer as GLuint);
+        }
+        data
+    }
+}
@@ -0,0 +1,1058 @@
+// Backend running without a GPU, it keeps the calls in order so the
+// tests can check the exact command stream.
+
+// External imports
+use gl::types::*;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::ffi::CStr;
+// Crate imports
+use super::{pixel_size, ActiveVariable, RenderBackend, UniformValue};
+use crate::render::reflection::ProgramReflection;
+
+/// One call made on the backend.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Viewport {
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+    },
+    ClearColor {
+        red: f32,
+        green: f32,
+        blue: f32,
+        alpha: f32,
+    },
+    Clear {
+        mask: GLbitfield,
+    },
+    Enable {
+        capability: GLenum,
+    },
+    Disable {
+        capability: GLenum,
+    },
+    BlendFunc {
+        source: GLenum,
+        destination: GLenum,
+    },
+    DepthFunc {
+        func: GLenum,
+    },
+    CreateBuffer {
+        id: GLuint,
+    },
+    DeleteBuffer {
+        id: GLuint,
+    },
+    BindBuffer {
+        target: GLenum,
+        id: GLuint,
+    },
+    BindBufferBase {
+        target: GLenum,
+        index: GLuint,
+        id: GLuint,
+    },
+    BindBufferRange {
+        target: GLenum,
+        index: GLuint,
+        id: GLuint,
+        offset: usize,
+        size: usize,
+    },
+    BufferData {
+        target: GLenum,
+        data: Vec<u8>,
+        usage: GLenum,
+    },
+    BufferAllocate {
+        target: GLenum,
+        size: usize,
+        usage: GLenum,
+    },
+    BufferSubData {
+        target: GLenum,
+        offset: usize,
+        data: Vec<u8>,
+    },
+    BufferStorage {
+        target: GLenum,
+        size: usize,
+        flags: GLbitfield,
+    },
+    MapBufferRange {
+        target: GLenum,
+        offset: usize,
+        length: usize,
+        access: GLbitfield,
+    },
+    UnmapBuffer {
+        target: GLenum,
+    },
+    /// Syncs are identified by their fake address.
+    FenceSync {
+        sync: usize,
+    },
+    ClientWaitSync {
+        sync: usize,
+        timeout: u64,
+    },
+    DeleteSync {
+        sync: usize,
+    },
+    CreateVertexArray {
+        id: GLuint,
+    },
+    DeleteVertexArray {
+        id: GLuint,
+    },
+    BindVertexArray {
+        id: GLuint,
+    },
+    EnableVertexArrayAttrib {
+        vertex_array: GLuint,
+        index: GLuint,
+    },
+    VertexArrayAttribFormat {
+        vertex_array: GLuint,
+        index: GLuint,
+        size: GLint,
+        etype: GLenum,
+        normalized: GLboolean,
+        relative_offset: GLuint,
+    },
+    VertexArrayAttribBinding {
+        vertex_array: GLuint,
+        index: GLuint,
+        binding: GLuint,
+    },
+    VertexArrayVertexBuffer {
+        vertex_array: GLuint,
+        binding: GLuint,
+        buffer: GLuint,
+        offset: usize,
+        stride: GLsizei,
+    },
+    VertexArrayBindingDivisor {
+        vertex_array: GLuint,
+        binding: GLuint,
+        divisor: GLuint,
+    },
+    DrawArrays {
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+    },
+    DrawElements {
+        mode: GLenum,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+    },
+    DrawArraysInstanced {
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        instances: GLsizei,
+        base_instance: GLuint,
+    },
+    DrawElementsInstanced {
+        mode: GLenum,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+        instances: GLsizei,
+        base_vertex: GLint,
+        base_instance: GLuint,
+    },
+    MultiDrawArraysIndirect {
+        mode: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    },
+    MultiDrawElementsIndirect {
+        mode: GLenum,
+        etype: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    },
+    PatchVertices {
+        count: GLint,
+    },
+    DispatchCompute {
+        x: GLuint,
+        y: GLuint,
+        z: GLuint,
+    },
+    DispatchComputeIndirect {
+        offset: usize,
+    },
+    ComputeWorkGroupSize {
+        program: GLuint,
+    },
+    BindImageTexture {
+        unit: GLuint,
+        texture: GLuint,
+        level: GLint,
+        layered: bool,
+        layer: GLint,
+        access: GLenum,
+        format: GLenum,
+    },
+    MemoryBarrier {
+        barriers: GLbitfield,
+    },
+    CreateShader {
+        id: GLuint,
+        stype: GLenum,
+    },
+    DeleteShader {
+        id: GLuint,
+    },
+    CompileShader {
+        id: GLuint,
+        source: String,
+    },
+    CreateProgram {
+        id: GLuint,
+    },
+    DeleteProgram {
+        id: GLuint,
+    },
+    AttachShader {
+        program: GLuint,
+        shader: GLuint,
+    },
+    LinkProgram {
+        program: GLuint,
+    },
+    UseProgram {
+        id: GLuint,
+    },
+    UniformLocation {
+        program: GLuint,
+        name: String,
+        location: GLint,
+    },
+    ActiveUniforms {
+        program: GLuint,
+    },
+    ProgramReflection {
+        program: GLuint,
+    },
+    SetUniform {
+        program: GLuint,
+        location: GLint,
+        value: UniformValue,
+    },
+    CreateTexture {
+        id: GLuint,
+        target: GLenum,
+    },
+    DeleteTexture {
+        id: GLuint,
+    },
+    TextureStorage2D {
+        id: GLuint,
+        levels: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    },
+    TextureParameter {
+        id: GLuint,
+        pname: GLenum,
+        param: GLint,
+    },
+    TextureSubImage2D {
+        id: GLuint,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+        data: Vec<u8>,
+    },
+    BindTextureUnit {
+        unit: GLuint,
+        id: GLuint,
+    },
+    CreateRenderbuffer {
+        id: GLuint,
+    },
+    DeleteRenderbuffer {
+        id: GLuint,
+    },
+    RenderbufferStorage {
+        id: GLuint,
+        samples: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    },
+    CreateFramebuffer {
+        id: GLuint,
+    },
+    DeleteFramebuffer {
+        id: GLuint,
+    },
+    BindFramebuffer {
+        target: GLenum,
+        id: GLuint,
+    },
+    /// The answer is the last framebuffer bound to the target.
+    FramebufferBinding {
+        target: GLenum,
+        id: GLuint,
+    },
+    FramebufferTexture {
+        id: GLuint,
+        attachment: GLenum,
+        texture: GLuint,
+        level: GLint,
+    },
+    FramebufferRenderbuffer {
+        id: GLuint,
+        attachment: GLenum,
+        renderbuffer: GLuint,
+    },
+    FramebufferDrawBuffers {
+        id: GLuint,
+        buffers: Vec<GLenum>,
+    },
+    FramebufferReadBuffer {
+        id: GLuint,
+        buffer: GLenum,
+    },
+    CheckFramebufferStatus {
+        id: GLuint,
+        target: GLenum,
+    },
+    BlitFramebuffer {
+        read: GLuint,
+        draw: GLuint,
+        src: [GLint; 4],
+        dst: [GLint; 4],
+        mask: GLbitfield,
+        filter: GLenum,
+    },
+    ReadPixels {
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+    },
+}
+
+/// Records every call and hands out fake object names.
+///
+/// Names start at 1 and are shared by all object kinds, compiling and
+/// linking always succeed, fences are signaled unless `set_wait_status`
+/// says otherwise. Mapped ranges
+/// point to memory owned by the backend, the writes are not recorded.
+/// Programs have no active uniforms unless given with `set_active_uniforms`,
+/// and an empty reflection unless given with `set_program_reflection`.
+/// Framebuffers are always complete and read back as zeros.
+pub struct RecordingBackend {
+    commands: RefCell<Vec<Command>>,
+    next_id: Cell<GLuint>,
+    locations: RefCell<HashMap<(GLuint, String), GLint>>,
+    mappings: RefCell<Vec<Vec<u8>>>,
+    active_uniforms: RefCell<HashMap<GLuint, Vec<ActiveVariable>>>,
+    reflections: RefCell<HashMap<GLuint, ProgramReflection>>,
+    // draw and read framebuffers
+    framebuffers: Cell<(GLuint, GLuint)>,
+    wait_status: Cell<GLenum>,
+}
+
+impl RecordingBackend {
+    pub fn new() -> Self {
+        Self {
+            commands: RefCell::new(Vec::new()),
+            next_id: Cell::new(1),
+            locations: RefCell::new(HashMap::new()),
+            mappings: RefCell::new(Vec::new()),
+            active_uniforms: RefCell::new(HashMap::new()),
+            reflections: RefCell::new(HashMap::new()),
+            framebuffers: Cell::new((0, 0)),
+            wait_status: Cell::new(gl::ALREADY_SIGNALED),
+        }
+    }
+
+    /// Reflection returned by `program_reflection` for the program.
+    pub fn set_program_reflection(&self, program: GLuint, reflection: ProgramReflection) {
+        self.reflections.borrow_mut().insert(program, reflection);
+    }
+
+    /// Uniforms reported by `active_uniforms` for the program.
+    pub fn set_active_uniforms(&self, program: GLuint, uniforms: Vec<ActiveVariable>) {
+        self.active_uniforms.borrow_mut().insert(program, uniforms);
+    }
+
+    /// Status returned by `client_wait_sync`, `ALREADY_SIGNALED` by default.
+    pub fn set_wait_status(&self, status: GLenum) {
+        self.wait_status.set(status);
+    }
+
+    /// Commands recorded so far.
+    pub fn commands(&self) -> Vec<Command> {
+        self.commands.borrow().clone()
+    }
+
+    /// Commands recorded since the last call, clearing the log.
+    pub fn take_commands(&self) -> Vec<Command> {
+        std::mem::take(&mut *self.commands.borrow_mut())
+    }
+
+    pub fn clear(&self) {
+        self.commands.borrow_mut().clear();
+    }
+
+    fn record(&self, command: Command) {
+        self.commands.borrow_mut().push(command);
+    }
+
+    fn new_id(&self) -> GLuint {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+}
+
+impl Default for RecordingBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RenderBackend for RecordingBackend {
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.record(Command::Viewport {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
+        self.record(Command::ClearColor {
+            red,
+            green,
+            blue,
+            alpha,
+        });
+    }
+
+    fn clear(&self, mask: GLbitfield) {
+        self.record(Command::Clear { mask });
+    }
+
+    fn enable(&self, capability: GLenum) {
+        self.record(Command::Enable { capability });
+    }
+
+    fn disable(&self, capability: GLenum) {
+        self.record(Command::Disable { capability });
+    }
+
+    fn blend_func(&self, source: GLenum, destination: GLenum) {
+        self.record(Command::BlendFunc {
+            source,
+            destination,
+        });
+    }
+
+    fn depth_func(&self, func: GLenum) {
+        self.record(Command::DepthFunc { func });
+    }
+
+    fn create_buffer(&self) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateBuffer { id });
+        id
+    }
+
+    fn delete_buffer(&self, id: GLuint) {
+        self.record(Command::DeleteBuffer { id });
+    }
+
+    fn bind_buffer(&self, target: GLenum, id: GLuint) {
+        self.record(Command::BindBuffer { target, id });
+    }
+
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, id: GLuint) {
+        self.record(Command::BindBufferBase { target, index, id });
+    }
+
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        id: GLuint,
+        offset: usize,
+        size: usize,
+    ) {
+        self.record(Command::BindBufferRange {
+            target,
+            index,
+            id,
+            offset,
+            size,
+        });
+    }
+
+    fn buffer_data(&self, target: GLenum, data: &[u8], usage: GLenum) {
+        self.record(Command::BufferData {
+            target,
+            data: data.to_vec(),
+            usage,
+        });
+    }
+
+    fn buffer_allocate(&self, target: GLenum, size: usize, usage: GLenum) {
+        self.record(Command::BufferAllocate {
+            target,
+            size,
+            usage,
+        });
+    }
+
+    fn buffer_sub_data(&self, target: GLenum, offset: usize, data: &[u8]) {
+        self.record(Command::BufferSubData {
+            target,
+            offset,
+            data: data.to_vec(),
+        });
+    }
+
+    fn buffer_storage(&self, target: GLenum, size: usize, flags: GLbitfield) {
+        self.record(Command::BufferStorage {
+            target,
+            size,
+            flags,
+        });
+    }
+
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: usize,
+        length: usize,
+        access: GLbitfield,
+    ) -> *mut u8 {
+        self.record(Command::MapBufferRange {
+            target,
+            offset,
+            length,
+            access,
+        });
+        // kept until the backend is dropped, the heap data never moves
+        let mut mappings = self.mappings.borrow_mut();
+        mappings.push(vec![0; length]);
+        mappings.last_mut().unwrap().as_mut_ptr()
+    }
+
+    fn unmap_buffer(&self, target: GLenum) {
+        self.record(Command::UnmapBuffer { target });
+    }
+
+    fn fence_sync(&self) -> GLsync {
+        let sync = self.new_id() as usize;
+        self.record(Command::FenceSync { sync });
+        sync as GLsync
+    }
+
+    fn client_wait_sync(&self, sync: GLsync, timeout: u64) -> GLenum {
+        self.record(Command::ClientWaitSync {
+            sync: sync as usize,
+            timeout,
+        });
+        self.wait_status.get()
+    }
+
+    fn delete_sync(&self, sync: GLsync) {
+        self.record(Command::DeleteSync {
+            sync: sync as usize,
+        });
+    }
+
+    fn create_vertex_array(&self) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateVertexArray { id });
+        id
+    }
+
+    fn delete_vertex_array(&self, id: GLuint) {
+        self.record(Command::DeleteVertexArray { id });
+    }
+
+    fn bind_vertex_array(&self, id: GLuint) {
+        self.record(Command::BindVertexArray { id });
+    }
+
+    fn enable_vertex_array_attrib(&self, vertex_array: GLuint, index: GLuint) {
+        self.record(Command::EnableVertexArrayAttrib {
+            vertex_array,
+            index,
+        });
+    }
+
+    fn vertex_array_attrib_format(
+        &self,
+        vertex_array: GLuint,
+        index: GLuint,
+        size: GLint,
+        etype: GLenum,
+        normalized: GLboolean,
+        relative_offset: GLuint,
+    ) {
+        self.record(Command::VertexArrayAttribFormat {
+            vertex_array,
+            index,
+            size,
+            etype,
+            normalized,
+            relative_offset,
+        });
+    }
+
+    fn vertex_array_attrib_binding(&self, vertex_array: GLuint, index: GLuint, binding: GLuint) {
+        self.record(Command::VertexArrayAttribBinding {
+            vertex_array,
+            index,
+            binding,
+        });
+    }
+
+    fn vertex_array_vertex_buffer(
+        &self,
+        vertex_array: GLuint,
+        binding: GLuint,
+        buffer: GLuint,
+        offset: usize,
+        stride: GLsizei,
+    ) {
+        self.record(Command::VertexArrayVertexBuffer {
+            vertex_array,
+            binding,
+            buffer,
+            offset,
+            stride,
+        });
+    }
+
+    fn vertex_array_binding_divisor(&self, vertex_array: GLuint, binding: GLuint, divisor: GLuint) {
+        self.record(Command::VertexArrayBindingDivisor {
+            vertex_array,
+            binding,
+            divisor,
+        });
+    }
+
+    fn draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        self.record(Command::DrawArrays { mode, first, count });
+    }
+
+    fn draw_elements(&self, mode: GLenum, count: GLsizei, etype: GLenum, offset: usize) {
+        self.record(Command::DrawElements {
+            mode,
+            count,
+            etype,
+            offset,
+        });
+    }
+
+    fn draw_arrays_instanced(
+        &self,
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        instances: GLsizei,
+        base_instance: GLuint,
+    ) {
+        self.record(Command::DrawArraysInstanced {
+            mode,
+            first,
+            count,
+            instances,
+            base_instance,
+        });
+    }
+
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+        instances: GLsizei,
+        base_vertex: GLint,
+        base_instance: GLuint,
+    ) {
+        self.record(Command::DrawElementsInstanced {
+            mode,
+            count,
+            etype,
+            offset,
+            instances,
+            base_vertex,
+            base_instance,
+        });
+    }
+
+    fn multi_draw_arrays_indirect(
+        &self,
+        mode: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    ) {
+        self.record(Command::MultiDrawArraysIndirect {
+            mode,
+            offset,
+            draw_count,
+            stride,
+        });
+    }
+
+    fn multi_draw_elements_indirect(
+        &self,
+        mode: GLenum,
+        etype: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    ) {
+        self.record(Command::MultiDrawElementsIndirect {
+            mode,
+            etype,
+            offset,
+            draw_count,
+            stride,
+        });
+    }
+
+    fn patch_vertices(&self, count: GLint) {
+        self.record(Command::PatchVertices { count });
+    }
+
+    fn dispatch_compute(&self, x: GLuint, y: GLuint, z: GLuint) {
+        self.record(Command::DispatchCompute { x, y, z });
+    }
+
+    fn dispatch_compute_indirect(&self, offset: usize) {
+        self.record(Command::DispatchComputeIndirect { offset });
+    }
+
+    // the reflection has no work group size, always one invocation per group
+    fn compute_work_group_size(&self, program: GLuint) -> [GLint; 3] {
+        self.record(Command::ComputeWorkGroupSize { program });
+        [1, 1, 1]
+    }
+
+    fn bind_image_texture(
+        &self,
+        unit: GLuint,
+        texture: GLuint,
+        level: GLint,
+        layered: bool,
+        layer: GLint,
+        access: GLenum,
+        format: GLenum,
+    ) {
+        self.record(Command::BindImageTexture {
+            unit,
+            texture,
+            level,
+            layered,
+            layer,
+            access,
+            format,
+        });
+    }
+
+    fn memory_barrier(&self, barriers: GLbitfield) {
+        self.record(Command::MemoryBarrier { barriers });
+    }
+
+    fn create_shader(&self, stype: GLenum) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateShader { id, stype });
+        id
+    }
+
+    fn delete_shader(&self, id: GLuint) {
+        self.record(Command::DeleteShader { id });
+    }
+
+    fn compile_shader(&self, id: GLuint, source: &CStr) -> Result<(), String> {
+        self.record(Command::CompileShader {
+            id,
+            source: source.to_string_lossy().into_owned(),
+        });
+        Ok(())
+    }
+
+    fn create_program(&self) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateProgram { id });
+        id
+    }
+
+    fn delete_program(&self, id: GLuint) {
+        self.record(Command::DeleteProgram { id });
+    }
+
+    fn attach_shader(&self, program: GLuint, shader: GLuint) {
+        self.record(Command::AttachShader { program, shader });
+    }
+
+    fn link_program(&self, program: GLuint) -> Result<(), String> {
+        self.record(Command::LinkProgram { program });
+        Ok(())
+    }
+
+    fn use_program(&self, id: GLuint) {
+        self.record(Command::UseProgram { id });
+    }
+
+    // every name exists, locations are handed out per program in order
+    fn uniform_location(&self, program: GLuint, name: &str) -> GLint {
+        let location = {
+            let mut locations = self.locations.borrow_mut();
+            let count = locations.keys().filter(|(p, _)| *p == program).count() as GLint;
+            *locations.entry((program, name.to_owned())).or_insert(count)
+        };
+        self.record(Command::UniformLocation {
+            program,
+            name: name.to_owned(),
+            location,
+        });
+        location
+    }
+
+    fn set_uniform(&self, program: GLuint, location: GLint, value: &UniformValue) {
+        self.record(Command::SetUniform {
+            program,
+            location,
+            value: value.clone(),
+        });
+    }
+
+    fn active_uniforms(&self, program: GLuint) -> Vec<ActiveVariable> {
+        self.record(Command::ActiveUniforms { program });
+        self.active_uniforms
+            .borrow()
+            .get(&program)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn program_reflection(&self, program: GLuint) -> ProgramReflection {
+        self.record(Command::ProgramReflection { program });
+        self.reflections
+            .borrow()
+            .get(&program)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn create_texture(&self, target: GLenum) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateTexture { id, target });
+        id
+    }
+
+    fn delete_texture(&self, id: GLuint) {
+        self.record(Command::DeleteTexture { id });
+    }
+
+    fn texture_storage_2d(
+        &self,
+        id: GLuint,
+        levels: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.record(Command::TextureStorage2D {
+            id,
+            levels,
+            internal_format,
+            width,
+            height,
+        });
+    }
+
+    fn texture_parameter(&self, id: GLuint, pname: GLenum, param: GLint) {
+        self.record(Command::TextureParameter { id, pname, param });
+    }
+
+    fn texture_sub_image_2d(
+        &self,
+        id: GLuint,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+        data: &[u8],
+    ) {
+        self.record(Command::TextureSubImage2D {
+            id,
+            level,
+            x,
+            y,
+            width,
+            height,
+            format,
+            etype,
+            data: data.to_vec(),
+        });
+    }
+
+    fn bind_texture_unit(&self, unit: GLuint, id: GLuint) {
+        self.record(Command::BindTextureUnit { unit, id });
+    }
+
+    fn create_renderbuffer(&self) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateRenderbuffer { id });
+        id
+    }
+
+    fn delete_renderbuffer(&self, id: GLuint) {
+        self.record(Command::DeleteRenderbuffer { id });
+    }
+
+    fn renderbuffer_storage(
+        &self,
+        id: GLuint,
+        samples: GLsizei,
+        internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        self.record(Command::RenderbufferStorage {
+            id,
+            samples,
+            internal_format,
+            width,
+            height,
+        });
+    }
+
+    fn create_framebuffer(&self) -> GLuint {
+        let id = self.new_id();
+        self.record(Command::CreateFramebuffer { id });
+        id
+    }
+
+    fn delete_framebuffer(&self, id: GLuint) {
+        self.record(Command::DeleteFramebuffer { id });
+    }
+
+    fn bind_framebuffer(&self, target: GLenum, id: GLuint) {
+        self.record(Command::BindFramebuffer { target, id });
+        let (mut draw, mut read) = self.framebuffers.get();
+        if target != gl::READ_FRAMEBUFFER {
+            draw = id;
+        }
+        if target != gl::DRAW_FRAMEBUFFER {
+            read = id;
+        }
+        self.framebuffers.set((draw, read));
+    }
+
+    fn framebuffer_binding(&self, target: GLenum) -> GLuint {
+        let (draw, read) = self.framebuffers.get();
+        let id = if target == gl::READ_FRAMEBUFFER { read } else { draw };
+        self.record(Command::FramebufferBinding { target, id });
+        id
+    }
+
+    fn framebuffer_texture(&self, id: GLuint, attachment: GLenum, texture: GLuint, level: GLint) {
+        self.record(Command::FramebufferTexture {
+            id,
+            attachment,
+            texture,
+            level,
+        });
+    }
+
+    fn framebuffer_renderbuffer(&self, id: GLuint, attachment: GLenum, renderbuffer: GLuint) {
+        self.record(Command::FramebufferRenderbuffer {
+            id,
+            attachment,
+            renderbuffer,
+        });
+    }
+
+    fn framebuffer_draw_buffers(&self, id: GLuint, buffers: &[GLenum]) {
+        self.record(Command::FramebufferDrawBuffers {
+            id,
+            buffers: buffers.to_vec(),
+        });
+    }
+
+    fn framebuffer_read_buffer(&self, id: GLuint, buffer: GLenum) {
+        self.record(Command::FramebufferReadBuffer { id, buffer });
+    }
+
+    fn check_framebuffer_status(&self, id: GLuint, target: GLenum) -> GLenum {
+        self.record(Command::CheckFramebufferStatus { id, target });
+        gl::FRAMEBUFFER_COMPLETE
+    }
+
+    fn blit_framebuffer(
+        &self,
+        read: GLuint,
+        draw: GLuint,
+        src: [GLint; 4],
+        dst: [GLint; 4],
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        self.record(Command::BlitFramebuffer {
+            read,
+            draw,
+            src,
+            dst,
+            mask,
+            filter,
+        });
+    }
+
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+    ) -> Vec<u8> {
+        self.record(Command::ReadPixels {
+            x,
+            y,
+            width,
+            height,
+            format,
+            etype,
+        });
+        let size = (width.max(0) * height.max(0)) as usize * pixel_size(format, etype);
+        vec![0; size]
+    }
+}
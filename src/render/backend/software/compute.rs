@@ -0,0 +1,145 @@
+// External imports
+use gl::types::*;
+use std::collections::HashMap;
+// Crate imports
+use super::texture::TextureState;
+use super::{BufferRange, ProgramState};
+use crate::math::Vec4D;
+use crate::render::backend::UniformValue;
+
+/// Texture level bound to an image unit.
+#[derive(Clone, Copy)]
+pub(super) struct ImageBinding {
+    pub(super) texture: GLuint,
+    pub(super) access: GLenum,
+}
+
+/// What a compute invocation can read and write: the uniforms of the
+/// program, the storage buffers and the images.
+pub struct ComputeContext<'a> {
+    pub(super) program: &'a ProgramState,
+    pub(super) buffers: &'a mut HashMap<u32, Vec<u8>>,
+    pub(super) indexed_bindings: &'a HashMap<(u32, u32), BufferRange>,
+    pub(super) textures: &'a mut HashMap<u32, TextureState>,
+    pub(super) images: &'a HashMap<u32, ImageBinding>,
+    pub(super) work_group_id: [u32; 3],
+    pub(super) local_invocation_id: [u32; 3],
+    pub(super) local_size: [u32; 3],
+}
+
+impl<'a> ComputeContext<'a> {
+    /// Like `gl_GlobalInvocationID`.
+    pub fn global_invocation_id(&self) -> [u32; 3] {
+        let mut id = [0; 3];
+        for (i, value) in id.iter_mut().enumerate() {
+            *value = self.work_group_id[i] * self.local_size[i] + self.local_invocation_id[i];
+        }
+        id
+    }
+
+    /// Like `gl_WorkGroupID`.
+    pub fn work_group_id(&self) -> [u32; 3] {
+        self.work_group_id
+    }
+
+    /// Like `gl_LocalInvocationID`.
+    pub fn local_invocation_id(&self) -> [u32; 3] {
+        self.local_invocation_id
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<&UniformValue> {
+        self.program
+            .locations
+            .get(name)
+            .and_then(|location| self.program.uniforms.get(location))
+    }
+
+    /// Float uniform, 0 if unset or of another type.
+    pub fn float(&self, name: &str) -> f32 {
+        match self.uniform(name) {
+            Some(UniformValue::Float(v)) => *v,
+            _ => 0_f32,
+        }
+    }
+
+    /// Int uniform, 0 if unset or of another type.
+    pub fn int(&self, name: &str) -> i32 {
+        match self.uniform(name) {
+            Some(UniformValue::Int(v)) => *v,
+            _ => 0,
+        }
+    }
+
+    fn range(&self, index: u32) -> Option<(GLuint, usize, Option<usize>)> {
+        let range = self
+            .indexed_bindings
+            .get(&(gl::SHADER_STORAGE_BUFFER, index))?;
+        Some((range.buffer, range.offset, range.size))
+    }
+
+    /// Bytes of the shader storage buffer bound to the binding point.
+    pub fn storage_block(&self, index: u32) -> Option<&[u8]> {
+        let (buffer, offset, size) = self.range(index)?;
+        let data = self.buffers.get(&buffer)?;
+        let end = size.map_or(data.len(), |size| (offset + size).min(data.len()));
+        data.get(offset..end)
+    }
+
+    /// Writable bytes of the shader storage buffer bound to the binding point.
+    pub fn storage_block_mut(&mut self, index: u32) -> Option<&mut [u8]> {
+        let (buffer, offset, size) = self.range(index)?;
+        let data = self.buffers.get_mut(&buffer)?;
+        let end = size.map_or(data.len(), |size| (offset + size).min(data.len()));
+        data.get_mut(offset..end)
+    }
+
+    /// Size of the image bound to the unit, `(0, 0)` if there is none.
+    pub fn image_size(&self, unit: u32) -> (usize, usize) {
+        self.images
+            .get(&unit)
+            .and_then(|image| self.textures.get(&image.texture))
+            .map_or((0, 0), |texture| texture.size())
+    }
+
+    /// Like `imageLoad`, transparent black if the unit isn't readable.
+    pub fn image_load(&self, unit: u32, x: usize, y: usize) -> Vec4D {
+        self.images
+            .get(&unit)
+            .filter(|image| image.access != gl::WRITE_ONLY)
+            .and_then(|image| self.textures.get(&image.texture))
+            .map_or_else(Vec4D::null, |texture| texture.load(x, y))
+    }
+
+    /// Like `imageStore`, ignored if the unit isn't writable.
+    pub fn image_store(&mut self, unit: u32, x: usize, y: usize, color: Vec4D) {
+        let image = match self.images.get(&unit) {
+            Some(image) if image.access != gl::READ_ONLY => *image,
+            _ => return,
+        };
+        if let Some(texture) = self.textures.get_mut(&image.texture) {
+            texture.store(x, y, color);
+        }
+    }
+}
+
+type KernelFn = dyn Fn(&mut ComputeContext);
+
+/// Compute shader of the software backend, a Rust closure run once per
+/// invocation, one invocation after the other.
+pub struct SoftwareCompute {
+    pub(super) local_size: [u32; 3],
+    pub(super) kernel: Box<KernelFn>,
+}
+
+impl SoftwareCompute {
+    /// `local_size` is the `layout(local_size_x, ...)` of the shader.
+    pub fn new<K>(local_size: [u32; 3], kernel: K) -> Self
+    where
+        K: Fn(&mut ComputeContext) + 'static,
+    {
+        Self {
+            local_size,
+            kernel: Box::new(kernel),
+        }
+    }
+}
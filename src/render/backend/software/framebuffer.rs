@@ -0,0 +1,171 @@
+// External imports
+use gl::types::*;
+use std::collections::HashMap;
+// Crate imports
+use super::raster::Target;
+use super::texture::TextureState;
+
+/// Image attached to a framebuffer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum Attachment {
+    Texture(GLuint),
+    Renderbuffer(GLuint),
+}
+
+/// Storage of a renderbuffer, the samples are only checked for completeness.
+#[derive(Clone, Copy, Default)]
+pub(super) struct RenderbufferState {
+    pub(super) samples: GLsizei,
+    pub(super) width: usize,
+    pub(super) height: usize,
+}
+
+/// Attachments of a framebuffer, its pixels being in a `Target` of the
+/// backend, copied to the color texture after every draw.
+pub(super) struct FramebufferState {
+    pub(super) attachments: HashMap<GLenum, Attachment>,
+    pub(super) draw_buffers: Vec<GLenum>,
+    pub(super) read_buffer: GLenum,
+}
+
+impl FramebufferState {
+    pub(super) fn new() -> Self {
+        Self {
+            attachments: HashMap::new(),
+            draw_buffers: vec![gl::COLOR_ATTACHMENT0],
+            read_buffer: gl::COLOR_ATTACHMENT0,
+        }
+    }
+
+    fn attachment_size(
+        attachment: &Attachment,
+        textures: &HashMap<GLuint, TextureState>,
+        renderbuffers: &HashMap<GLuint, RenderbufferState>,
+    ) -> Option<(usize, usize, GLsizei)> {
+        match attachment {
+            Attachment::Texture(id) => textures.get(id).map(|t| {
+                let (width, height) = t.size();
+                (width, height, 0)
+            }),
+            Attachment::Renderbuffer(id) => renderbuffers
+                .get(id)
+                .map(|r| (r.width, r.height, r.samples)),
+        }
+    }
+
+    /// Size of the drawable area, the smallest of the attachments.
+    pub(super) fn size(
+        &self,
+        textures: &HashMap<GLuint, TextureState>,
+        renderbuffers: &HashMap<GLuint, RenderbufferState>,
+    ) -> (usize, usize) {
+        self.attachments
+            .values()
+            .filter_map(|a| Self::attachment_size(a, textures, renderbuffers))
+            .map(|(width, height, _)| (width, height))
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1)))
+            .unwrap_or((0, 0))
+    }
+
+    /// Same checks as `glCheckFramebufferStatus`.
+    pub(super) fn status(
+        &self,
+        textures: &HashMap<GLuint, TextureState>,
+        renderbuffers: &HashMap<GLuint, RenderbufferState>,
+    ) -> GLenum {
+        if self.attachments.is_empty() {
+            return gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT;
+        }
+        let mut samples = None;
+        for attachment in self.attachments.values() {
+            match Self::attachment_size(attachment, textures, renderbuffers) {
+                Some((width, height, _)) if width == 0 || height == 0 => {
+                    return gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT
+                }
+                None => return gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT,
+                Some((_, _, s)) => {
+                    if *samples.get_or_insert(s) != s {
+                        return gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE;
+                    }
+                }
+            }
+        }
+        let missing =
+            |buffer: &GLenum| *buffer != gl::NONE && !self.attachments.contains_key(buffer);
+        if self.draw_buffers.iter().any(missing) {
+            return gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER;
+        }
+        if missing(&self.read_buffer) {
+            return gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER;
+        }
+        gl::FRAMEBUFFER_COMPLETE
+    }
+
+    /// Texture attached to the first draw buffer, the one the shaders write.
+    pub(super) fn color_texture(&self) -> Option<GLuint> {
+        let buffer = self.draw_buffers.first()?;
+        match self.attachments.get(buffer)? {
+            Attachment::Texture(id) => Some(*id),
+            Attachment::Renderbuffer(_) => None,
+        }
+    }
+}
+
+/// Copies the color of the target into the texture.
+pub(super) fn store_color(target: &Target, texture: &mut TextureState) {
+    let (width, height) = texture.size();
+    for y in 0..height.min(target.height) {
+        for x in 0..width.min(target.width) {
+            texture.store(x, y, target.color[y * target.width + x]);
+        }
+    }
+}
+
+/// Copies the texture into the color of the target.
+pub(super) fn load_color(texture: &TextureState, target: &mut Target) {
+    let (width, height) = texture.size();
+    for y in 0..height.min(target.height) {
+        for x in 0..width.min(target.width) {
+            target.color[y * target.width + x] = texture.load(x, y);
+        }
+    }
+}
+
+/// Copies the rectangles `[x0, y0, x1, y1]` with the nearest filter.
+pub(super) fn blit(
+    source: &Target,
+    destination: &mut Target,
+    src: [GLint; 4],
+    dst: [GLint; 4],
+    mask: GLbitfield,
+) {
+    let (dw, dh) = (dst[2] - dst[0], dst[3] - dst[1]);
+    if dw == 0 || dh == 0 {
+        return;
+    }
+    for y in dst[1].min(dst[3])..dst[1].max(dst[3]) {
+        for x in dst[0].min(dst[2])..dst[0].max(dst[2]) {
+            if x < 0 || y < 0 || x as usize >= destination.width || y as usize >= destination.height
+            {
+                continue;
+            }
+            // sample at the center of the destination pixel
+            let u = (x - dst[0]) as f32 + 0.5;
+            let v = (y - dst[1]) as f32 + 0.5;
+            let sx = src[0] as f32 + u * (src[2] - src[0]) as f32 / dw as f32;
+            let sy = src[1] as f32 + v * (src[3] - src[1]) as f32 / dh as f32;
+            let (sx, sy) = (sx.floor() as i32, sy.floor() as i32);
+            if sx < 0 || sy < 0 || sx as usize >= source.width || sy as usize >= source.height {
+                continue;
+            }
+            let from = sy as usize * source.width + sx as usize;
+            let to = y as usize * destination.width + x as usize;
+            if mask & gl::COLOR_BUFFER_BIT != 0 {
+                destination.color[to] = source.color[from];
+            }
+            if mask & gl::DEPTH_BUFFER_BIT != 0 {
+                destination.depth[to] = source.depth[from];
+            }
+        }
+    }
+}
@@ -0,0 +1,1263 @@
+// Backend drawing on the CPU into its own framebuffer. Far too slow for
+// a game but it runs without a GPU, so tests can check actual pixels.
+
+// External imports
+use gl::types::*;
+use image::RgbaImage;
+use std::cell::{RefCell, RefMut};
+use std::collections::HashMap;
+use std::ffi::CStr;
+use std::path::Path;
+use std::rc::Rc;
+// Crate imports
+use super::{pixel_size, ActiveVariable, RenderBackend, UniformValue};
+use crate::math::Vec4D;
+use crate::render::reflection::{AttributeInfo, ProgramReflection, UniformInfo};
+
+pub mod compute;
+mod framebuffer;
+mod raster;
+pub mod shader;
+mod texture;
+
+pub use compute::{ComputeContext, SoftwareCompute};
+pub use shader::{transform, Fragment, ShaderContext, SoftwareShader, VertexOutput};
+
+use compute::ImageBinding;
+use framebuffer::{Attachment, FramebufferState, RenderbufferState};
+use raster::{Raster, Target};
+use texture::TextureState;
+
+#[derive(Clone, Copy)]
+struct Attribute {
+    enabled: bool,
+    size: GLint,
+    etype: GLenum,
+    normalized: GLboolean,
+    relative_offset: usize,
+    binding: GLuint,
+}
+
+impl Attribute {
+    fn new(index: GLuint) -> Self {
+        Self {
+            enabled: false,
+            size: 4,
+            etype: gl::FLOAT,
+            normalized: gl::FALSE,
+            relative_offset: 0,
+            binding: index,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+struct VertexBinding {
+    buffer: GLuint,
+    offset: usize,
+    stride: usize,
+    divisor: GLuint,
+}
+
+/// Buffer range bound to an indexed binding point, `None` for the whole buffer.
+#[derive(Clone, Copy)]
+pub(super) struct BufferRange {
+    buffer: GLuint,
+    offset: usize,
+    size: Option<usize>,
+}
+
+#[derive(Default)]
+struct VertexArrayState {
+    attributes: Vec<Attribute>,
+    bindings: Vec<VertexBinding>,
+    element_buffer: GLuint,
+}
+
+impl VertexArrayState {
+    fn attribute_mut(&mut self, index: GLuint) -> &mut Attribute {
+        while self.attributes.len() <= index as usize {
+            let next = self.attributes.len() as GLuint;
+            self.attributes.push(Attribute::new(next));
+        }
+        &mut self.attributes[index as usize]
+    }
+
+    fn binding_mut(&mut self, binding: GLuint) -> &mut VertexBinding {
+        if self.bindings.len() <= binding as usize {
+            self.bindings
+                .resize(binding as usize + 1, VertexBinding::default());
+        }
+        &mut self.bindings[binding as usize]
+    }
+}
+
+#[derive(Default)]
+struct ProgramState {
+    locations: HashMap<String, GLint>,
+    uniforms: HashMap<GLint, UniformValue>,
+    shader: Option<Rc<SoftwareShader>>,
+    compute: Option<Rc<SoftwareCompute>>,
+}
+
+struct State {
+    next_id: GLuint,
+    buffers: HashMap<GLuint, Vec<u8>>,
+    // buffers bound to the targets other than the element array
+    bindings: HashMap<GLenum, GLuint>,
+    indexed_bindings: HashMap<(GLenum, GLuint), BufferRange>,
+    // the vertex array 0 stands for the default one
+    vertex_arrays: HashMap<GLuint, VertexArrayState>,
+    vertex_array: GLuint,
+    programs: HashMap<GLuint, ProgramState>,
+    program: GLuint,
+    textures: HashMap<GLuint, TextureState>,
+    units: HashMap<GLuint, GLuint>,
+    images: HashMap<GLuint, ImageBinding>,
+    // the framebuffer 0 stands for the default one
+    framebuffers: HashMap<GLuint, FramebufferState>,
+    renderbuffers: HashMap<GLuint, RenderbufferState>,
+    draw_framebuffer: GLuint,
+    read_framebuffer: GLuint,
+    clear_color: Vec4D,
+    raster: Raster,
+}
+
+impl State {
+    fn new_id(&mut self) -> GLuint {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn vertex_array_mut(&mut self) -> &mut VertexArrayState {
+        self.vertex_arrays.entry(self.vertex_array).or_default()
+    }
+
+    fn bound_data(&self, target: GLenum) -> Option<&Vec<u8>> {
+        let id = match target {
+            gl::ELEMENT_ARRAY_BUFFER => self
+                .vertex_arrays
+                .get(&self.vertex_array)
+                .map(|vertex_array| vertex_array.element_buffer)?,
+            _ => *self.bindings.get(&target)?,
+        };
+        self.buffers.get(&id)
+    }
+
+    fn bound_data_mut(&mut self, target: GLenum) -> Option<&mut Vec<u8>> {
+        let id = match target {
+            gl::ELEMENT_ARRAY_BUFFER => self
+                .vertex_arrays
+                .get(&self.vertex_array)
+                .map(|vertex_array| vertex_array.element_buffer)?,
+            _ => *self.bindings.get(&target)?,
+        };
+        self.buffers.get_mut(&id)
+    }
+
+    // Indices read from the element buffer, with the base vertex added.
+    fn indices(
+        &self,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+        base_vertex: GLint,
+    ) -> Vec<u32> {
+        let data = match self.bound_data(gl::ELEMENT_ARRAY_BUFFER) {
+            Some(data) => data,
+            None => return Vec::new(),
+        };
+        let size = type_size(etype);
+        (0..count.max(0) as usize)
+            .map(|i| read_index(data, offset + i * size, etype) as i64)
+            .map(|index| (index + base_vertex as i64).max(0) as u32)
+            .collect()
+    }
+
+    // Words of the commands in the bound draw indirect buffer.
+    fn indirect_commands(
+        &self,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+        words: usize,
+    ) -> Vec<Vec<u32>> {
+        let data = match self.bound_data(gl::DRAW_INDIRECT_BUFFER) {
+            Some(data) => data,
+            None => return Vec::new(),
+        };
+        let stride = match stride {
+            0 => words * 4,
+            stride => stride as usize,
+        };
+        (0..draw_count.max(0) as usize)
+            .map(|i| {
+                (0..words)
+                    .map(|w| read_index(data, offset + i * stride + w * 4, gl::UNSIGNED_INT))
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn type_size(etype: GLenum) -> usize {
+    match etype {
+        gl::BYTE | gl::UNSIGNED_BYTE => 1,
+        gl::SHORT | gl::UNSIGNED_SHORT | gl::HALF_FLOAT => 2,
+        _ => 4,
+    }
+}
+
+// Unsigned index or command word, 0 when out of the buffer.
+fn read_index(data: &[u8], offset: usize, etype: GLenum) -> u32 {
+    match data.get(offset..offset + type_size(etype)) {
+        Some([b]) => *b as u32,
+        Some([b0, b1]) => u16::from_ne_bytes([*b0, *b1]) as u32,
+        Some([b0, b1, b2, b3]) => u32::from_ne_bytes([*b0, *b1, *b2, *b3]),
+        _ => 0,
+    }
+}
+
+// One component of an attribute, 0 when out of the buffer.
+fn read_component(data: &[u8], offset: usize, etype: GLenum, normalized: bool) -> f32 {
+    let bytes = match data.get(offset..offset + type_size(etype)) {
+        Some(bytes) => bytes,
+        None => return 0_f32,
+    };
+    let (value, max) = match etype {
+        gl::FLOAT => return f32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        gl::BYTE => (bytes[0] as i8 as f32, i8::MAX as f32),
+        gl::UNSIGNED_BYTE => (bytes[0] as f32, u8::MAX as f32),
+        gl::SHORT => (
+            i16::from_ne_bytes([bytes[0], bytes[1]]) as f32,
+            i16::MAX as f32,
+        ),
+        gl::UNSIGNED_SHORT => (
+            u16::from_ne_bytes([bytes[0], bytes[1]]) as f32,
+            u16::MAX as f32,
+        ),
+        gl::INT => (
+            i32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+            i32::MAX as f32,
+        ),
+        gl::UNSIGNED_INT => (
+            u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+            u32::MAX as f32,
+        ),
+        _ => return 0_f32,
+    };
+    if normalized {
+        (value / max).max(-1_f32)
+    } else {
+        value
+    }
+}
+
+/// Backend rasterizing the draw calls in software.
+///
+/// Programs are drawn with the `SoftwareShader` given to `set_shader`,
+/// the GLSL sources are ignored. Points, lines and triangles are
+/// rasterized with a depth buffer, blending and back face culling,
+/// patches are skipped. Textures support the RGBA8 uploads of `Texture`.
+/// Dispatches run the `SoftwareCompute` given to `set_compute_shader`.
+/// Framebuffers draw to the texture of their first draw buffer only,
+/// multisampled renderbuffers are stored with a single sample.
+pub struct SoftwareBackend {
+    state: RefCell<State>,
+    target: RefCell<Target>,
+    // pixels of the framebuffers other than the default one
+    framebuffer_targets: RefCell<HashMap<GLuint, Target>>,
+}
+
+impl SoftwareBackend {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            state: RefCell::new(State {
+                next_id: 1,
+                buffers: HashMap::new(),
+                bindings: HashMap::new(),
+                indexed_bindings: HashMap::new(),
+                vertex_arrays: HashMap::new(),
+                vertex_array: 0,
+                programs: HashMap::new(),
+                program: 0,
+                textures: HashMap::new(),
+                units: HashMap::new(),
+                images: HashMap::new(),
+                framebuffers: HashMap::new(),
+                renderbuffers: HashMap::new(),
+                draw_framebuffer: 0,
+                read_framebuffer: 0,
+                clear_color: Vec4D::new(0_f32, 0_f32, 0_f32, 0_f32),
+                raster: Raster {
+                    viewport: (0, 0, width as i32, height as i32),
+                    depth_test: false,
+                    depth_func: gl::LESS,
+                    blend: false,
+                    blend_func: (gl::ONE, gl::ZERO),
+                    cull_face: false,
+                },
+            }),
+            target: RefCell::new(Target::new(width, height)),
+            framebuffer_targets: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.target.borrow().width
+    }
+
+    pub fn height(&self) -> usize {
+        self.target.borrow().height
+    }
+
+    /// Shader used when drawing with the program.
+    pub fn set_shader(&self, program: GLuint, shader: SoftwareShader) {
+        let mut state = self.state.borrow_mut();
+        state.programs.entry(program).or_default().shader = Some(Rc::new(shader));
+    }
+
+    /// Compute shader run when dispatching with the program.
+    pub fn set_compute_shader(&self, program: GLuint, compute: SoftwareCompute) {
+        let mut state = self.state.borrow_mut();
+        state.programs.entry(program).or_default().compute = Some(Rc::new(compute));
+    }
+
+    /// Runs the compute shader of the program in use once per invocation.
+    fn dispatch(&self, groups: [u32; 3]) {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let program = match state.programs.get(&state.program) {
+            Some(program) => program,
+            None => return,
+        };
+        let compute = match &program.compute {
+            Some(compute) => compute.clone(),
+            None => return,
+        };
+        let mut context = ComputeContext {
+            program,
+            buffers: &mut state.buffers,
+            indexed_bindings: &state.indexed_bindings,
+            textures: &mut state.textures,
+            images: &state.images,
+            work_group_id: [0; 3],
+            local_invocation_id: [0; 3],
+            local_size: compute.local_size,
+        };
+        let [sx, sy, sz] = compute.local_size;
+        for gz in 0..groups[2] {
+            for gy in 0..groups[1] {
+                for gx in 0..groups[0] {
+                    context.work_group_id = [gx, gy, gz];
+                    for lz in 0..sz {
+                        for ly in 0..sy {
+                            for lx in 0..sx {
+                                context.local_invocation_id = [lx, ly, lz];
+                                (compute.kernel)(&mut context);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Color of the pixel, `y` going up from the bottom row.
+    pub fn pixel(&self, x: usize, y: usize) -> Vec4D {
+        let target = self.target.borrow();
+        target.color[y * target.width + x]
+    }
+
+    /// Depth of the pixel, `y` going up from the bottom row.
+    pub fn depth(&self, x: usize, y: usize) -> f32 {
+        let target = self.target.borrow();
+        target.depth[y * target.width + x]
+    }
+
+    /// Color buffer as an image, top row first.
+    pub fn to_image(&self) -> RgbaImage {
+        let target = self.target.borrow();
+        let (width, height) = (target.width, target.height);
+        RgbaImage::from_fn(width as u32, height as u32, |x, y| {
+            let c = target.color[(height - 1 - y as usize) * width + x as usize];
+            let byte = |v: f32| (v * 255_f32).round() as u8;
+            image::Rgba([byte(c.x), byte(c.y), byte(c.z), byte(c.w)])
+        })
+    }
+
+    fn framebuffer_target(&self, id: GLuint) -> RefMut<'_, Target> {
+        if id == 0 {
+            return self.target.borrow_mut();
+        }
+        RefMut::map(self.framebuffer_targets.borrow_mut(), |targets| {
+            targets.entry(id).or_insert_with(|| Target::new(0, 0))
+        })
+    }
+
+    /// Resizes the pixels of the framebuffer to its attachments, starting
+    /// from the content of its color texture.
+    fn attachments_changed(&self, id: GLuint) {
+        let state = self.state.borrow();
+        let framebuffer = match state.framebuffers.get(&id) {
+            Some(framebuffer) => framebuffer,
+            None => return,
+        };
+        let (width, height) = framebuffer.size(&state.textures, &state.renderbuffers);
+        let mut target = Target::new(width, height);
+        let texture = framebuffer
+            .color_texture()
+            .and_then(|texture| state.textures.get(&texture));
+        if let Some(texture) = texture {
+            framebuffer::load_color(texture, &mut target);
+        }
+        self.framebuffer_targets.borrow_mut().insert(id, target);
+    }
+
+    /// Copies the pixels of the framebuffer to its color texture.
+    fn store_framebuffer(&self, id: GLuint) {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let texture = state
+            .framebuffers
+            .get(&id)
+            .and_then(|framebuffer| framebuffer.color_texture())
+            .and_then(|texture| state.textures.get_mut(&texture));
+        let targets = self.framebuffer_targets.borrow();
+        if let (Some(texture), Some(target)) = (texture, targets.get(&id)) {
+            framebuffer::store_color(target, texture);
+        }
+    }
+
+    /// Pixels the framebuffer is read from, its read buffer if it is a texture.
+    fn read_target(&self, id: GLuint) -> Target {
+        let mut target = self.framebuffer_target(id).clone();
+        let state = self.state.borrow();
+        let texture = state
+            .framebuffers
+            .get(&id)
+            .and_then(|framebuffer| framebuffer.attachments.get(&framebuffer.read_buffer))
+            .and_then(|attachment| match attachment {
+                Attachment::Texture(texture) => state.textures.get(texture),
+                Attachment::Renderbuffer(_) => None,
+            });
+        if let Some(texture) = texture {
+            framebuffer::load_color(texture, &mut target);
+        }
+        target
+    }
+
+    pub fn save_png(&self, path: impl AsRef<Path>) -> image::ImageResult<()> {
+        self.to_image()
+            .save_with_format(path, image::ImageFormat::Png)
+    }
+
+    // Runs the vertex shader on the vertices of the draw, in order, for
+    // every instance.
+    fn draw(&self, mode: GLenum, indices: &[u32], instances: u32, base_instance: u32) {
+        let state = self.state.borrow();
+        let program = match state.programs.get(&state.program) {
+            Some(program) => program,
+            None => return,
+        };
+        let shader = match &program.shader {
+            Some(shader) => shader,
+            None => return,
+        };
+        let mut context = ShaderContext {
+            program,
+            textures: &state.textures,
+            units: &state.units,
+            buffers: &state.buffers,
+            indexed_bindings: &state.indexed_bindings,
+            instance: 0,
+        };
+        let empty = VertexArrayState::default();
+        let vertex_array = state
+            .vertex_arrays
+            .get(&state.vertex_array)
+            .unwrap_or(&empty);
+        let no_data = Vec::new();
+        let mut attributes = Vec::with_capacity(vertex_array.attributes.len());
+        let mut target = self.framebuffer_target(state.draw_framebuffer);
+        for instance in 0..instances {
+            context.instance = instance;
+            let vertices: Vec<VertexOutput> = indices
+                .iter()
+                .map(|&index| {
+                    attributes.clear();
+                    for attribute in &vertex_array.attributes {
+                        let mut value = [0_f32, 0_f32, 0_f32, 1_f32];
+                        let binding = vertex_array.bindings.get(attribute.binding as usize);
+                        if let (true, Some(binding)) = (attribute.enabled, binding) {
+                            let data = state.buffers.get(&binding.buffer).unwrap_or(&no_data);
+                            let size = type_size(attribute.etype);
+                            let element = match binding.divisor {
+                                0 => index,
+                                divisor => base_instance + instance / divisor,
+                            };
+                            let start = binding.offset
+                                + element as usize * binding.stride
+                                + attribute.relative_offset;
+                            for (c, v) in value.iter_mut().take(attribute.size as usize).enumerate()
+                            {
+                                *v = read_component(
+                                    data,
+                                    start + c * size,
+                                    attribute.etype,
+                                    attribute.normalized == gl::TRUE,
+                                );
+                            }
+                        }
+                        attributes.push(Vec4D::new(value[0], value[1], value[2], value[3]));
+                    }
+                    (shader.vertex)(&context, &attributes)
+                })
+                .collect();
+            raster::draw(
+                &mut target,
+                &state.raster,
+                shader,
+                &context,
+                mode,
+                &vertices,
+            );
+        }
+        let framebuffer = state.draw_framebuffer;
+        drop(target);
+        drop(state);
+        self.store_framebuffer(framebuffer);
+    }
+}
+
+/// Largest difference between two channels of the images, `None` if
+/// the sizes don't match. Meant for comparing renders to golden images.
+pub fn image_difference(a: &RgbaImage, b: &RgbaImage) -> Option<u8> {
+    if a.dimensions() != b.dimensions() {
+        return None;
+    }
+    Some(
+        a.as_raw()
+            .iter()
+            .zip(b.as_raw().iter())
+            .map(|(a, b)| a.abs_diff(*b))
+            .max()
+            .unwrap_or(0),
+    )
+}
+
+impl RenderBackend for SoftwareBackend {
+    fn viewport(&self, x: GLint, y: GLint, width: GLsizei, height: GLsizei) {
+        self.state.borrow_mut().raster.viewport = (x, y, width, height);
+    }
+
+    fn clear_color(&self, red: f32, green: f32, blue: f32, alpha: f32) {
+        self.state.borrow_mut().clear_color = Vec4D::new(red, green, blue, alpha);
+    }
+
+    fn clear(&self, mask: GLbitfield) {
+        let (color, framebuffer) = {
+            let state = self.state.borrow();
+            (state.clear_color, state.draw_framebuffer)
+        };
+        {
+            let mut target = self.framebuffer_target(framebuffer);
+            if mask & gl::COLOR_BUFFER_BIT != 0 {
+                target.color.iter_mut().for_each(|c| *c = color);
+            }
+            if mask & gl::DEPTH_BUFFER_BIT != 0 {
+                target.depth.iter_mut().for_each(|d| *d = 1_f32);
+            }
+        }
+        self.store_framebuffer(framebuffer);
+    }
+
+    fn enable(&self, capability: GLenum) {
+        let raster = &mut self.state.borrow_mut().raster;
+        match capability {
+            gl::DEPTH_TEST => raster.depth_test = true,
+            gl::BLEND => raster.blend = true,
+            gl::CULL_FACE => raster.cull_face = true,
+            _ => {}
+        }
+    }
+
+    fn disable(&self, capability: GLenum) {
+        let raster = &mut self.state.borrow_mut().raster;
+        match capability {
+            gl::DEPTH_TEST => raster.depth_test = false,
+            gl::BLEND => raster.blend = false,
+            gl::CULL_FACE => raster.cull_face = false,
+            _ => {}
+        }
+    }
+
+    fn blend_func(&self, source: GLenum, destination: GLenum) {
+        self.state.borrow_mut().raster.blend_func = (source, destination);
+    }
+
+    fn depth_func(&self, func: GLenum) {
+        self.state.borrow_mut().raster.depth_func = func;
+    }
+
+    fn create_buffer(&self) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let id = state.new_id();
+        state.buffers.insert(id, Vec::new());
+        id
+    }
+
+    fn delete_buffer(&self, id: GLuint) {
+        self.state.borrow_mut().buffers.remove(&id);
+    }
+
+    fn bind_buffer(&self, target: GLenum, id: GLuint) {
+        let mut state = self.state.borrow_mut();
+        match target {
+            gl::ELEMENT_ARRAY_BUFFER => state.vertex_array_mut().element_buffer = id,
+            _ => {
+                state.bindings.insert(target, id);
+            }
+        }
+    }
+
+    fn bind_buffer_base(&self, target: GLenum, index: GLuint, id: GLuint) {
+        self.bind_buffer(target, id);
+        let range = BufferRange {
+            buffer: id,
+            offset: 0,
+            size: None,
+        };
+        let mut state = self.state.borrow_mut();
+        state.indexed_bindings.insert((target, index), range);
+    }
+
+    fn bind_buffer_range(
+        &self,
+        target: GLenum,
+        index: GLuint,
+        id: GLuint,
+        offset: usize,
+        size: usize,
+    ) {
+        self.bind_buffer(target, id);
+        let range = BufferRange {
+            buffer: id,
+            offset,
+            size: Some(size),
+        };
+        let mut state = self.state.borrow_mut();
+        state.indexed_bindings.insert((target, index), range);
+    }
+
+    fn buffer_data(&self, target: GLenum, data: &[u8], _usage: GLenum) {
+        let mut state = self.state.borrow_mut();
+        let id = match target {
+            gl::ELEMENT_ARRAY_BUFFER => state.vertex_array_mut().element_buffer,
+            _ => state.bindings.get(&target).copied().unwrap_or(0),
+        };
+        if id != 0 {
+            state.buffers.insert(id, data.to_vec());
+        }
+    }
+
+    fn buffer_allocate(&self, target: GLenum, size: usize, usage: GLenum) {
+        self.buffer_data(target, &vec![0; size], usage);
+    }
+
+    fn buffer_sub_data(&self, target: GLenum, offset: usize, data: &[u8]) {
+        let mut state = self.state.borrow_mut();
+        if let Some(buffer) = state.bound_data_mut(target) {
+            let end = (offset + data.len()).min(buffer.len());
+            if offset < end {
+                buffer[offset..end].copy_from_slice(&data[..end - offset]);
+            }
+        }
+    }
+
+    fn buffer_storage(&self, target: GLenum, size: usize, _flags: GLbitfield) {
+        self.buffer_data(target, &vec![0; size], gl::DYNAMIC_DRAW);
+    }
+
+    // the pointer goes straight into the buffer data, which is never
+    // reallocated unless the buffer data is set again
+    fn map_buffer_range(
+        &self,
+        target: GLenum,
+        offset: usize,
+        length: usize,
+        _access: GLbitfield,
+    ) -> *mut u8 {
+        let mut state = self.state.borrow_mut();
+        match state.bound_data_mut(target) {
+            Some(buffer) if offset + length <= buffer.len() => buffer[offset..].as_mut_ptr(),
+            _ => std::ptr::null_mut(),
+        }
+    }
+
+    fn unmap_buffer(&self, _target: GLenum) {}
+
+    // draws are done when the call returns so the fences are always signaled
+    fn fence_sync(&self) -> GLsync {
+        let mut state = self.state.borrow_mut();
+        state.new_id() as usize as GLsync
+    }
+
+    fn client_wait_sync(&self, _sync: GLsync, _timeout: u64) -> GLenum {
+        gl::ALREADY_SIGNALED
+    }
+
+    fn delete_sync(&self, _sync: GLsync) {}
+
+    fn create_vertex_array(&self) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let id = state.new_id();
+        state.vertex_arrays.insert(id, VertexArrayState::default());
+        id
+    }
+
+    fn delete_vertex_array(&self, id: GLuint) {
+        let mut state = self.state.borrow_mut();
+        state.vertex_arrays.remove(&id);
+        if state.vertex_array == id {
+            state.vertex_array = 0;
+        }
+    }
+
+    fn bind_vertex_array(&self, id: GLuint) {
+        self.state.borrow_mut().vertex_array = id;
+    }
+
+    fn enable_vertex_array_attrib(&self, vertex_array: GLuint, index: GLuint) {
+        let mut state = self.state.borrow_mut();
+        let vertex_array = state.vertex_arrays.entry(vertex_array).or_default();
+        vertex_array.attribute_mut(index).enabled = true;
+    }
+
+    fn vertex_array_attrib_format(
+        &self,
+        vertex_array: GLuint,
+        index: GLuint,
+        size: GLint,
+        etype: GLenum,
+        normalized: GLboolean,
+        relative_offset: GLuint,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let vertex_array = state.vertex_arrays.entry(vertex_array).or_default();
+        let attribute = vertex_array.attribute_mut(index);
+        attribute.size = size;
+        attribute.etype = etype;
+        attribute.normalized = normalized;
+        attribute.relative_offset = relative_offset as usize;
+    }
+
+    fn vertex_array_attrib_binding(&self, vertex_array: GLuint, index: GLuint, binding: GLuint) {
+        let mut state = self.state.borrow_mut();
+        let vertex_array = state.vertex_arrays.entry(vertex_array).or_default();
+        vertex_array.attribute_mut(index).binding = binding;
+    }
+
+    fn vertex_array_vertex_buffer(
+        &self,
+        vertex_array: GLuint,
+        binding: GLuint,
+        buffer: GLuint,
+        offset: usize,
+        stride: GLsizei,
+    ) {
+        let mut state = self.state.borrow_mut();
+        let vertex_array = state.vertex_arrays.entry(vertex_array).or_default();
+        let binding = vertex_array.binding_mut(binding);
+        binding.buffer = buffer;
+        binding.offset = offset;
+        binding.stride = stride.max(0) as usize;
+    }
+
+    fn vertex_array_binding_divisor(&self, vertex_array: GLuint, binding: GLuint, divisor: GLuint) {
+        let mut state = self.state.borrow_mut();
+        let vertex_array = state.vertex_arrays.entry(vertex_array).or_default();
+        vertex_array.binding_mut(binding).divisor = divisor;
+    }
+
+    fn draw_arrays(&self, mode: GLenum, first: GLint, count: GLsizei) {
+        self.draw_arrays_instanced(mode, first, count, 1, 0);
+    }
+
+    fn draw_elements(&self, mode: GLenum, count: GLsizei, etype: GLenum, offset: usize) {
+        self.draw_elements_instanced(mode, count, etype, offset, 1, 0, 0);
+    }
+
+    fn draw_arrays_instanced(
+        &self,
+        mode: GLenum,
+        first: GLint,
+        count: GLsizei,
+        instances: GLsizei,
+        base_instance: GLuint,
+    ) {
+        let first = first.max(0) as u32;
+        let indices: Vec<u32> = (first..first + count.max(0) as u32).collect();
+        self.draw(mode, &indices, instances.max(0) as u32, base_instance);
+    }
+
+    fn draw_elements_instanced(
+        &self,
+        mode: GLenum,
+        count: GLsizei,
+        etype: GLenum,
+        offset: usize,
+        instances: GLsizei,
+        base_vertex: GLint,
+        base_instance: GLuint,
+    ) {
+        let indices = self
+            .state
+            .borrow()
+            .indices(count, etype, offset, base_vertex);
+        self.draw(mode, &indices, instances.max(0) as u32, base_instance);
+    }
+
+    fn multi_draw_arrays_indirect(
+        &self,
+        mode: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    ) {
+        let commands = self
+            .state
+            .borrow()
+            .indirect_commands(offset, draw_count, stride, 4);
+        for c in commands {
+            self.draw_arrays_instanced(mode, c[2] as GLint, c[0] as GLsizei, c[1] as GLsizei, c[3]);
+        }
+    }
+
+    fn multi_draw_elements_indirect(
+        &self,
+        mode: GLenum,
+        etype: GLenum,
+        offset: usize,
+        draw_count: GLsizei,
+        stride: GLsizei,
+    ) {
+        let commands = self
+            .state
+            .borrow()
+            .indirect_commands(offset, draw_count, stride, 5);
+        for c in commands {
+            self.draw_elements_instanced(
+                mode,
+                c[0] as GLsizei,
+                etype,
+                c[2] as usize * type_size(etype),
+                c[1] as GLsizei,
+                c[3] as GLint,
+                c[4],
+            );
+        }
+    }
+
+    fn patch_vertices(&self, _count: GLint) {}
+
+    fn dispatch_compute(&self, x: GLuint, y: GLuint, z: GLuint) {
+        self.dispatch([x, y, z]);
+    }
+
+    fn dispatch_compute_indirect(&self, offset: usize) {
+        let groups = {
+            let state = self.state.borrow();
+            let data = match state.bound_data(gl::DISPATCH_INDIRECT_BUFFER) {
+                Some(data) => data,
+                None => return,
+            };
+            let mut groups = [0; 3];
+            for (i, group) in groups.iter_mut().enumerate() {
+                *group = read_index(data, offset + 4 * i, gl::UNSIGNED_INT);
+            }
+            groups
+        };
+        self.dispatch(groups);
+    }
+
+    fn compute_work_group_size(&self, program: GLuint) -> [GLint; 3] {
+        let state = self.state.borrow();
+        state
+            .programs
+            .get(&program)
+            .and_then(|program| program.compute.as_ref())
+            .map_or([0; 3], |compute| {
+                let [x, y, z] = compute.local_size;
+                [x as GLint, y as GLint, z as GLint]
+            })
+    }
+
+    // only the level 0 of 2D textures, stored as RGBA8 whatever the format
+    fn bind_image_texture(
+        &self,
+        unit: GLuint,
+        texture: GLuint,
+        _level: GLint,
+        _layered: bool,
+        _layer: GLint,
+        access: GLenum,
+        _format: GLenum,
+    ) {
+        let image = ImageBinding { texture, access };
+        self.state.borrow_mut().images.insert(unit, image);
+    }
+
+    // invocations run one after the other, their writes are always visible
+    fn memory_barrier(&self, _barriers: GLbitfield) {}
+
+    fn create_shader(&self, _stype: GLenum) -> GLuint {
+        self.state.borrow_mut().new_id()
+    }
+
+    fn delete_shader(&self, _id: GLuint) {}
+
+    fn compile_shader(&self, _id: GLuint, _source: &CStr) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn create_program(&self) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let id = state.new_id();
+        state.programs.insert(id, ProgramState::default());
+        id
+    }
+
+    fn delete_program(&self, id: GLuint) {
+        self.state.borrow_mut().programs.remove(&id);
+    }
+
+    fn attach_shader(&self, _program: GLuint, _shader: GLuint) {}
+
+    fn link_program(&self, _program: GLuint) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn use_program(&self, id: GLuint) {
+        self.state.borrow_mut().program = id;
+    }
+
+    // every name exists, locations are handed out per program in order
+    fn uniform_location(&self, program: GLuint, name: &str) -> GLint {
+        let mut state = self.state.borrow_mut();
+        let program = state.programs.entry(program).or_default();
+        if let Some(&location) = program.locations.get(name) {
+            return location;
+        }
+        let next = program.locations.values().max().map_or(0, |last| last + 1);
+        // the elements of a declared array get consecutive locations
+        let base = name.split('[').next().unwrap_or(name);
+        let first = format!("{}[0]", base);
+        let size = program
+            .shader
+            .as_ref()
+            .and_then(|shader| shader.uniforms.iter().find(|uniform| uniform.name == first))
+            .map(|uniform| uniform.size);
+        match size {
+            Some(size) => {
+                for i in 0..size {
+                    program.locations.insert(format!("{}[{}]", base, i), next + i);
+                }
+                program.locations.insert(base.to_owned(), next);
+                program.locations.get(name).copied().unwrap_or(-1)
+            }
+            None => {
+                program.locations.insert(name.to_owned(), next);
+                next
+            }
+        }
+    }
+
+    fn set_uniform(&self, program: GLuint, location: GLint, value: &UniformValue) {
+        if location < 0 {
+            return;
+        }
+        let mut state = self.state.borrow_mut();
+        if let Some(program) = state.programs.get_mut(&program) {
+            for (i, element) in value.elements().into_iter().enumerate() {
+                program.uniforms.insert(location + i as GLint, element);
+            }
+        }
+    }
+
+    fn active_uniforms(&self, program: GLuint) -> Vec<ActiveVariable> {
+        let state = self.state.borrow();
+        state
+            .programs
+            .get(&program)
+            .and_then(|program| program.shader.as_ref())
+            .map(|shader| shader.uniforms.clone())
+            .unwrap_or_default()
+    }
+
+    // built from the declarations of the shader, without blocks
+    fn program_reflection(&self, program: GLuint) -> ProgramReflection {
+        let shader = {
+            let state = self.state.borrow();
+            match state.programs.get(&program).and_then(|p| p.shader.clone()) {
+                Some(shader) => shader,
+                None => return ProgramReflection::default(),
+            }
+        };
+        let attributes = shader
+            .inputs
+            .iter()
+            .map(|(location, input)| AttributeInfo {
+                name: input.name.clone(),
+                location: *location,
+                gtype: input.gtype,
+                size: input.size,
+            })
+            .collect();
+        let uniforms = shader
+            .uniforms
+            .iter()
+            .map(|uniform| UniformInfo {
+                name: uniform.name.clone(),
+                location: self.uniform_location(program, &uniform.name),
+                gtype: uniform.gtype,
+                size: uniform.size,
+            })
+            .collect();
+        ProgramReflection {
+            attributes,
+            uniforms,
+            ..Default::default()
+        }
+    }
+
+    fn create_texture(&self, _target: GLenum) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let id = state.new_id();
+        state.textures.insert(id, TextureState::new());
+        id
+    }
+
+    fn delete_texture(&self, id: GLuint) {
+        self.state.borrow_mut().textures.remove(&id);
+    }
+
+    fn texture_storage_2d(
+        &self,
+        id: GLuint,
+        _levels: GLsizei,
+        _internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        if let Some(texture) = self.state.borrow_mut().textures.get_mut(&id) {
+            texture.storage(width.max(0) as usize, height.max(0) as usize);
+        }
+    }
+
+    fn texture_parameter(&self, id: GLuint, pname: GLenum, param: GLint) {
+        if let Some(texture) = self.state.borrow_mut().textures.get_mut(&id) {
+            texture.parameter(pname, param);
+        }
+    }
+
+    fn texture_sub_image_2d(
+        &self,
+        id: GLuint,
+        level: GLint,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+        data: &[u8],
+    ) {
+        if level != 0 || x < 0 || y < 0 {
+            return;
+        }
+        if let Some(texture) = self.state.borrow_mut().textures.get_mut(&id) {
+            texture.sub_image(
+                x as usize,
+                y as usize,
+                width.max(0) as usize,
+                height.max(0) as usize,
+                format,
+                etype,
+                data,
+            );
+        }
+    }
+
+    fn bind_texture_unit(&self, unit: GLuint, id: GLuint) {
+        self.state.borrow_mut().units.insert(unit, id);
+    }
+
+    fn create_renderbuffer(&self) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let id = state.new_id();
+        state.renderbuffers.insert(id, RenderbufferState::default());
+        id
+    }
+
+    fn delete_renderbuffer(&self, id: GLuint) {
+        self.state.borrow_mut().renderbuffers.remove(&id);
+    }
+
+    fn renderbuffer_storage(
+        &self,
+        id: GLuint,
+        samples: GLsizei,
+        _internal_format: GLenum,
+        width: GLsizei,
+        height: GLsizei,
+    ) {
+        if let Some(renderbuffer) = self.state.borrow_mut().renderbuffers.get_mut(&id) {
+            *renderbuffer = RenderbufferState {
+                samples: samples.max(0),
+                width: width.max(0) as usize,
+                height: height.max(0) as usize,
+            };
+        }
+    }
+
+    fn create_framebuffer(&self) -> GLuint {
+        let mut state = self.state.borrow_mut();
+        let id = state.new_id();
+        state.framebuffers.insert(id, FramebufferState::new());
+        id
+    }
+
+    fn delete_framebuffer(&self, id: GLuint) {
+        let mut state = self.state.borrow_mut();
+        state.framebuffers.remove(&id);
+        if state.draw_framebuffer == id {
+            state.draw_framebuffer = 0;
+        }
+        if state.read_framebuffer == id {
+            state.read_framebuffer = 0;
+        }
+        self.framebuffer_targets.borrow_mut().remove(&id);
+    }
+
+    fn bind_framebuffer(&self, target: GLenum, id: GLuint) {
+        let mut state = self.state.borrow_mut();
+        if target != gl::READ_FRAMEBUFFER {
+            state.draw_framebuffer = id;
+        }
+        if target != gl::DRAW_FRAMEBUFFER {
+            state.read_framebuffer = id;
+        }
+    }
+
+    fn framebuffer_binding(&self, target: GLenum) -> GLuint {
+        let state = self.state.borrow();
+        if target == gl::READ_FRAMEBUFFER {
+            state.read_framebuffer
+        } else {
+            state.draw_framebuffer
+        }
+    }
+
+    fn framebuffer_texture(&self, id: GLuint, attachment: GLenum, texture: GLuint, level: GLint) {
+        if level != 0 {
+            return;
+        }
+        if let Some(framebuffer) = self.state.borrow_mut().framebuffers.get_mut(&id) {
+            match texture {
+                0 => framebuffer.attachments.remove(&attachment),
+                _ => framebuffer
+                    .attachments
+                    .insert(attachment, Attachment::Texture(texture)),
+            };
+        }
+        self.attachments_changed(id);
+    }
+
+    fn framebuffer_renderbuffer(&self, id: GLuint, attachment: GLenum, renderbuffer: GLuint) {
+        if let Some(framebuffer) = self.state.borrow_mut().framebuffers.get_mut(&id) {
+            match renderbuffer {
+                0 => framebuffer.attachments.remove(&attachment),
+                _ => framebuffer
+                    .attachments
+                    .insert(attachment, Attachment::Renderbuffer(renderbuffer)),
+            };
+        }
+        self.attachments_changed(id);
+    }
+
+    fn framebuffer_draw_buffers(&self, id: GLuint, buffers: &[GLenum]) {
+        if let Some(framebuffer) = self.state.borrow_mut().framebuffers.get_mut(&id) {
+            framebuffer.draw_buffers = buffers.to_vec();
+        }
+        self.attachments_changed(id);
+    }
+
+    fn framebuffer_read_buffer(&self, id: GLuint, buffer: GLenum) {
+        if let Some(framebuffer) = self.state.borrow_mut().framebuffers.get_mut(&id) {
+            framebuffer.read_buffer = buffer;
+        }
+    }
+
+    fn check_framebuffer_status(&self, id: GLuint, _target: GLenum) -> GLenum {
+        if id == 0 {
+            return gl::FRAMEBUFFER_COMPLETE;
+        }
+        let state = self.state.borrow();
+        match state.framebuffers.get(&id) {
+            Some(framebuffer) => framebuffer.status(&state.textures, &state.renderbuffers),
+            None => gl::FRAMEBUFFER_UNDEFINED,
+        }
+    }
+
+    fn blit_framebuffer(
+        &self,
+        read: GLuint,
+        draw: GLuint,
+        src: [GLint; 4],
+        dst: [GLint; 4],
+        mask: GLbitfield,
+        _filter: GLenum,
+    ) {
+        let source = self.read_target(read);
+        framebuffer::blit(&source, &mut self.framebuffer_target(draw), src, dst, mask);
+        self.store_framebuffer(draw);
+    }
+
+    fn read_pixels(
+        &self,
+        x: GLint,
+        y: GLint,
+        width: GLsizei,
+        height: GLsizei,
+        format: GLenum,
+        etype: GLenum,
+    ) -> Vec<u8> {
+        let (width, height) = (width.max(0) as usize, height.max(0) as usize);
+        let mut data = vec![0; width * height * pixel_size(format, etype)];
+        let channels = match format {
+            gl::RGB => 3,
+            gl::RGBA => 4,
+            _ => return data,
+        };
+        if etype != gl::UNSIGNED_BYTE {
+            return data;
+        }
+        let source = self.read_target(self.state.borrow().read_framebuffer);
+        let channel = |c: f32| (c.clamp(0_f32, 1_f32) * 255_f32).round() as u8;
+        for j in 0..height {
+            for i in 0..width {
+                let (sx, sy) = (x + i as GLint, y + j as GLint);
+                if sx < 0 || sy < 0 || sx as usize >= source.width || sy as usize >= source.height {
+                    continue;
+                }
+                let c = source.color[sy as usize * source.width + sx as usize];
+                let pixel = [channel(c.x), channel(c.y), channel(c.z), channel(c.w)];
+                let start = (j * width + i) * channels;
+                data[start..start + channels].copy_from_slice(&pixel[..channels]);
+            }
+        }
+        data
+    }
+}
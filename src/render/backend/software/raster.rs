@@ -0,0 +1,404 @@
+// Primitive assembly, clipping and rasterization of the software backend.
+
+// External imports
+use gl::types::*;
+// Crate imports
+use super::shader::{Fragment, ShaderContext, SoftwareShader, VertexOutput};
+use crate::math::Vec4D;
+
+/// Color and depth buffers, the first row at the bottom.
+#[derive(Clone)]
+pub(super) struct Target {
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) color: Vec<Vec4D>,
+    pub(super) depth: Vec<f32>,
+}
+
+impl Target {
+    pub(super) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            color: vec![Vec4D::new(0_f32, 0_f32, 0_f32, 1_f32); width * height],
+            depth: vec![1_f32; width * height],
+        }
+    }
+}
+
+/// Fixed function state used while drawing.
+pub(super) struct Raster {
+    pub(super) viewport: (i32, i32, i32, i32),
+    pub(super) depth_test: bool,
+    pub(super) depth_func: GLenum,
+    pub(super) blend: bool,
+    pub(super) blend_func: (GLenum, GLenum),
+    pub(super) cull_face: bool,
+}
+
+/// Assembles the shaded vertices into primitives and draws them.
+pub(super) fn draw(
+    target: &mut Target,
+    raster: &Raster,
+    shader: &SoftwareShader,
+    context: &ShaderContext,
+    mode: GLenum,
+    vertices: &[VertexOutput],
+) {
+    let count = vertices.len();
+    let mut triangles = Vec::new();
+    let mut lines = Vec::new();
+    match mode {
+        gl::POINTS => {
+            for vertex in vertices {
+                point(target, raster, shader, context, vertex);
+            }
+        }
+        gl::LINES => {
+            for i in (0..count / 2).map(|l| l * 2) {
+                lines.push([i, i + 1]);
+            }
+        }
+        gl::LINE_STRIP | gl::LINE_LOOP => {
+            for i in 0..count.saturating_sub(1) {
+                lines.push([i, i + 1]);
+            }
+            if mode == gl::LINE_LOOP && count > 2 {
+                lines.push([count - 1, 0]);
+            }
+        }
+        gl::TRIANGLES => {
+            for i in (0..count / 3).map(|t| t * 3) {
+                triangles.push([i, i + 1, i + 2]);
+            }
+        }
+        gl::TRIANGLE_STRIP => {
+            for i in 0..count.saturating_sub(2) {
+                // every other triangle is flipped to keep the winding
+                if i % 2 == 0 {
+                    triangles.push([i, i + 1, i + 2]);
+                } else {
+                    triangles.push([i + 1, i, i + 2]);
+                }
+            }
+        }
+        gl::TRIANGLE_FAN => {
+            for i in 1..count.saturating_sub(1) {
+                triangles.push([0, i, i + 1]);
+            }
+        }
+        _ => {}
+    }
+    for [a, b] in lines {
+        line(target, raster, shader, context, &vertices[a], &vertices[b]);
+    }
+    for [a, b, c] in triangles {
+        let polygon = clip_near(&[&vertices[a], &vertices[b], &vertices[c]]);
+        for i in 1..polygon.len().saturating_sub(1) {
+            triangle(
+                target,
+                raster,
+                shader,
+                context,
+                [&polygon[0], &polygon[i], &polygon[i + 1]],
+            );
+        }
+    }
+}
+
+fn lerp_vertex(a: &VertexOutput, b: &VertexOutput, t: f32) -> VertexOutput {
+    VertexOutput {
+        position: a.position * (1_f32 - t) + b.position * t,
+        varyings: a
+            .varyings
+            .iter()
+            .zip(b.varyings.iter())
+            .map(|(a, b)| a * (1_f32 - t) + b * t)
+            .collect(),
+    }
+}
+
+// Sutherland-Hodgman against the near plane z = -w, the other planes are
+// handled by the viewport bounds and the depth range check.
+fn clip_near(triangle: &[&VertexOutput; 3]) -> Vec<VertexOutput> {
+    let distance = |v: &VertexOutput| v.position.z + v.position.w;
+    let mut polygon = Vec::with_capacity(4);
+    for i in 0..3 {
+        let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+        let (da, db) = (distance(a), distance(b));
+        if da >= 0_f32 {
+            polygon.push(a.clone());
+        }
+        if (da >= 0_f32) != (db >= 0_f32) {
+            polygon.push(lerp_vertex(a, b, da / (da - db)));
+        }
+    }
+    polygon
+}
+
+// Window coordinates, depth and 1/w used for the perspective correction.
+fn window(raster: &Raster, p: Vec4D) -> Option<(f32, f32, f32, f32)> {
+    if p.w <= f32::EPSILON {
+        return None;
+    }
+    let (vx, vy, vw, vh) = raster.viewport;
+    let inv_w = 1_f32 / p.w;
+    Some((
+        vx as f32 + (p.x * inv_w + 1_f32) * 0.5 * vw as f32,
+        vy as f32 + (p.y * inv_w + 1_f32) * 0.5 * vh as f32,
+        (p.z * inv_w + 1_f32) * 0.5,
+        inv_w,
+    ))
+}
+
+fn point(
+    target: &mut Target,
+    raster: &Raster,
+    shader: &SoftwareShader,
+    context: &ShaderContext,
+    vertex: &VertexOutput,
+) {
+    if vertex.position.z + vertex.position.w < 0_f32 {
+        return;
+    }
+    if let Some((x, y, depth, _)) = window(raster, vertex.position) {
+        let fragment = Fragment {
+            x: x.floor() + 0.5,
+            y: y.floor() + 0.5,
+            depth,
+            front_facing: true,
+            varyings: vertex.varyings.clone(),
+        };
+        shade(target, raster, shader, context, fragment);
+    }
+}
+
+// One pixel wide line stepping along its major axis, the last pixel is
+// left out so strips don't draw the joints twice.
+fn line(
+    target: &mut Target,
+    raster: &Raster,
+    shader: &SoftwareShader,
+    context: &ShaderContext,
+    a: &VertexOutput,
+    b: &VertexOutput,
+) {
+    let (da, db) = (a.position.z + a.position.w, b.position.z + b.position.w);
+    if da < 0_f32 && db < 0_f32 {
+        return;
+    }
+    let (a, b) = if da < 0_f32 {
+        (lerp_vertex(a, b, da / (da - db)), b.clone())
+    } else if db < 0_f32 {
+        (a.clone(), lerp_vertex(a, b, da / (da - db)))
+    } else {
+        (a.clone(), b.clone())
+    };
+    let (sa, sb) = match (window(raster, a.position), window(raster, b.position)) {
+        (Some(sa), Some(sb)) => (sa, sb),
+        _ => return,
+    };
+    let steps = (sb.0 - sa.0)
+        .abs()
+        .max((sb.1 - sa.1).abs())
+        .ceil()
+        .max(1_f32) as usize;
+    let varying_count = a.varyings.len().min(b.varyings.len());
+    for k in 0..steps {
+        let t = k as f32 / steps as f32;
+        let x = sa.0 + (sb.0 - sa.0) * t;
+        let y = sa.1 + (sb.1 - sa.1) * t;
+        let (wa, wb) = ((1_f32 - t) * sa.3, t * sb.3);
+        let varyings = (0..varying_count)
+            .map(|j| (wa * a.varyings[j] + wb * b.varyings[j]) / (wa + wb))
+            .collect();
+        let fragment = Fragment {
+            x: x.floor() + 0.5,
+            y: y.floor() + 0.5,
+            depth: sa.2 + (sb.2 - sa.2) * t,
+            front_facing: true,
+            varyings,
+        };
+        shade(target, raster, shader, context, fragment);
+    }
+}
+
+// Depth test, fragment shader and blending of one pixel.
+fn shade(
+    target: &mut Target,
+    raster: &Raster,
+    shader: &SoftwareShader,
+    context: &ShaderContext,
+    fragment: Fragment,
+) {
+    let (vx, vy, vw, vh) = raster.viewport;
+    let (x, y) = (fragment.x.floor() as i32, fragment.y.floor() as i32);
+    if x < vx.max(0)
+        || y < vy.max(0)
+        || x >= (vx + vw).min(target.width as i32)
+        || y >= (vy + vh).min(target.height as i32)
+    {
+        return;
+    }
+    if !(0_f32..=1_f32).contains(&fragment.depth) {
+        return;
+    }
+    let index = y as usize * target.width + x as usize;
+    if raster.depth_test && !depth_pass(raster.depth_func, fragment.depth, target.depth[index]) {
+        return;
+    }
+    let color = match (shader.fragment)(context, &fragment) {
+        Some(color) => color,
+        None => return,
+    };
+    let destination = target.color[index];
+    target.color[index] = if raster.blend {
+        let (sf, df) = raster.blend_func;
+        clamp(
+            mul(color, blend_factor(sf, color, destination))
+                + mul(destination, blend_factor(df, color, destination)),
+        )
+    } else {
+        clamp(color)
+    };
+    if raster.depth_test {
+        target.depth[index] = fragment.depth;
+    }
+}
+
+fn edge(a: (f32, f32), b: (f32, f32), p: (f32, f32)) -> f32 {
+    (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0)
+}
+
+// Edges owning the pixels exactly on them, for counter clockwise triangles with y up.
+fn is_top_left(a: (f32, f32), b: (f32, f32)) -> bool {
+    (a.1 == b.1 && b.0 < a.0) || b.1 < a.1
+}
+
+fn depth_pass(func: GLenum, depth: f32, stored: f32) -> bool {
+    match func {
+        gl::NEVER => false,
+        gl::LESS => depth < stored,
+        gl::EQUAL => depth == stored,
+        gl::LEQUAL => depth <= stored,
+        gl::GREATER => depth > stored,
+        gl::NOTEQUAL => depth != stored,
+        gl::GEQUAL => depth >= stored,
+        _ => true,
+    }
+}
+
+fn mul(a: Vec4D, b: Vec4D) -> Vec4D {
+    Vec4D::new(a.x * b.x, a.y * b.y, a.z * b.z, a.w * b.w)
+}
+
+fn blend_factor(factor: GLenum, source: Vec4D, destination: Vec4D) -> Vec4D {
+    let one = Vec4D::new(1_f32, 1_f32, 1_f32, 1_f32);
+    let splat = |v: f32| Vec4D::new(v, v, v, v);
+    match factor {
+        gl::ZERO => Vec4D::null(),
+        gl::SRC_COLOR => source,
+        gl::ONE_MINUS_SRC_COLOR => one - source,
+        gl::DST_COLOR => destination,
+        gl::ONE_MINUS_DST_COLOR => one - destination,
+        gl::SRC_ALPHA => splat(source.w),
+        gl::ONE_MINUS_SRC_ALPHA => splat(1_f32 - source.w),
+        gl::DST_ALPHA => splat(destination.w),
+        gl::ONE_MINUS_DST_ALPHA => splat(1_f32 - destination.w),
+        _ => one,
+    }
+}
+
+fn clamp(v: Vec4D) -> Vec4D {
+    Vec4D::new(
+        v.x.clamp(0_f32, 1_f32),
+        v.y.clamp(0_f32, 1_f32),
+        v.z.clamp(0_f32, 1_f32),
+        v.w.clamp(0_f32, 1_f32),
+    )
+}
+
+fn triangle(
+    target: &mut Target,
+    raster: &Raster,
+    shader: &SoftwareShader,
+    context: &ShaderContext,
+    vertices: [&VertexOutput; 3],
+) {
+    let (vx, vy, vw, vh) = raster.viewport;
+    let mut screen = [(0_f32, 0_f32, 0_f32, 0_f32); 3];
+    for (s, v) in screen.iter_mut().zip(vertices.iter()) {
+        *s = match window(raster, v.position) {
+            Some(s) => s,
+            None => return,
+        };
+    }
+    let xy = |i: usize| (screen[i].0, screen[i].1);
+    let area = edge(xy(0), xy(1), xy(2));
+    if area == 0_f32 || !area.is_finite() {
+        return;
+    }
+    let front_facing = area > 0_f32;
+    if raster.cull_face && !front_facing {
+        return;
+    }
+    // counter clockwise order for the edge tests
+    let order = if front_facing { [0, 1, 2] } else { [0, 2, 1] };
+    let p = [xy(order[0]), xy(order[1]), xy(order[2])];
+    let area = area.abs();
+    let edges = [(p[1], p[2]), (p[2], p[0]), (p[0], p[1])];
+    let top_left = [
+        is_top_left(p[1], p[2]),
+        is_top_left(p[2], p[0]),
+        is_top_left(p[0], p[1]),
+    ];
+
+    let min_x = p.iter().map(|p| p.0).fold(f32::INFINITY, f32::min);
+    let max_x = p.iter().map(|p| p.0).fold(f32::NEG_INFINITY, f32::max);
+    let min_y = p.iter().map(|p| p.1).fold(f32::INFINITY, f32::min);
+    let max_y = p.iter().map(|p| p.1).fold(f32::NEG_INFINITY, f32::max);
+    let x0 = (min_x.floor() as i32).max(vx).max(0);
+    let x1 = (max_x.ceil() as i32).min(vx + vw).min(target.width as i32);
+    let y0 = (min_y.floor() as i32).max(vy).max(0);
+    let y1 = (max_y.ceil() as i32).min(vy + vh).min(target.height as i32);
+
+    let varying_count = vertices.iter().map(|v| v.varyings.len()).min().unwrap_or(0);
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let center = (x as f32 + 0.5, y as f32 + 0.5);
+            let mut weights = [0_f32; 3];
+            let mut inside = true;
+            for k in 0..3 {
+                let w = edge(edges[k].0, edges[k].1, center);
+                if w < 0_f32 || (w == 0_f32 && !top_left[k]) {
+                    inside = false;
+                    break;
+                }
+                // weight of the vertex opposite to the edge
+                weights[order[k]] = w / area;
+            }
+            if !inside {
+                continue;
+            }
+            let depth: f32 = (0..3).map(|i| weights[i] * screen[i].2).sum();
+            let perspective: Vec<f32> = (0..3).map(|i| weights[i] * screen[i].3).collect();
+            let total: f32 = perspective.iter().sum();
+            let varyings = (0..varying_count)
+                .map(|j| {
+                    (0..3)
+                        .map(|i| perspective[i] * vertices[i].varyings[j])
+                        .sum::<f32>()
+                        / total
+                })
+                .collect();
+            let fragment = Fragment {
+                x: center.0,
+                y: center.1,
+                depth,
+                front_facing,
+                varyings,
+            };
+            shade(target, raster, shader, context, fragment);
+        }
+    }
+}
@@ -0,0 +1,194 @@
+// External imports
+use std::collections::HashMap;
+// Crate imports
+use super::texture::TextureState;
+use super::{BufferRange, ProgramState};
+use crate::math::{Vec2D, Vec3D, Vec4D};
+use crate::render::backend::{ActiveVariable, UniformValue};
+
+/// Result of the vertex shader for one vertex.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexOutput {
+    /// Clip space position, like `gl_Position`.
+    pub position: Vec4D,
+    /// Values interpolated over the primitive for the fragment shader.
+    pub varyings: Vec<f32>,
+}
+
+impl VertexOutput {
+    pub fn new(position: Vec4D, varyings: Vec<f32>) -> Self {
+        Self { position, varyings }
+    }
+}
+
+/// Input of the fragment shader.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fragment {
+    /// Window coordinates of the pixel center, origin at the bottom left.
+    pub x: f32,
+    pub y: f32,
+    /// Depth in `[0, 1]`.
+    pub depth: f32,
+    pub front_facing: bool,
+    /// Perspective correct interpolation of the vertex varyings.
+    pub varyings: Vec<f32>,
+}
+
+/// What the shaders can read: the uniforms of the program, the textures
+/// bound to the units and the buffers bound to the indexed binding points.
+pub struct ShaderContext<'a> {
+    pub(super) program: &'a ProgramState,
+    pub(super) textures: &'a HashMap<u32, TextureState>,
+    pub(super) units: &'a HashMap<u32, u32>,
+    pub(super) buffers: &'a HashMap<u32, Vec<u8>>,
+    pub(super) indexed_bindings: &'a HashMap<(u32, u32), BufferRange>,
+    pub(super) instance: u32,
+}
+
+impl<'a> ShaderContext<'a> {
+    /// Index of the instance being drawn, like `gl_InstanceID`.
+    pub fn instance(&self) -> u32 {
+        self.instance
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<&UniformValue> {
+        self.program
+            .locations
+            .get(name)
+            .and_then(|location| self.program.uniforms.get(location))
+    }
+
+    /// Float uniform, 0 if unset or of another type.
+    pub fn float(&self, name: &str) -> f32 {
+        match self.uniform(name) {
+            Some(UniformValue::Float(v)) => *v,
+            _ => 0_f32,
+        }
+    }
+
+    /// Int uniform, 0 if unset or of another type.
+    pub fn int(&self, name: &str) -> i32 {
+        match self.uniform(name) {
+            Some(UniformValue::Int(v)) => *v,
+            _ => 0,
+        }
+    }
+
+    pub fn vec3(&self, name: &str) -> Vec3D {
+        match self.uniform(name) {
+            Some(UniformValue::Vec3(v)) => Vec3D::new(v[0], v[1], v[2]),
+            _ => Vec3D::null(),
+        }
+    }
+
+    pub fn vec4(&self, name: &str) -> Vec4D {
+        match self.uniform(name) {
+            Some(UniformValue::Vec4(v)) => Vec4D::new(v[0], v[1], v[2], v[3]),
+            _ => Vec4D::null(),
+        }
+    }
+
+    /// Column major matrix uniform, identity if unset.
+    pub fn mat4(&self, name: &str) -> [f32; 16] {
+        match self.uniform(name) {
+            Some(UniformValue::Mat4(m)) => *m,
+            _ => [
+                1_f32, 0_f32, 0_f32, 0_f32, 0_f32, 1_f32, 0_f32, 0_f32, 0_f32, 0_f32, 1_f32, 0_f32,
+                0_f32, 0_f32, 0_f32, 1_f32,
+            ],
+        }
+    }
+
+    /// Bytes bound to the indexed binding point of the target.
+    pub fn block(&self, target: u32, index: u32) -> Option<&[u8]> {
+        let range = self.indexed_bindings.get(&(target, index))?;
+        let data = self.buffers.get(&range.buffer)?;
+        let end = match range.size {
+            Some(size) => (range.offset + size).min(data.len()),
+            None => data.len(),
+        };
+        data.get(range.offset..end)
+    }
+
+    /// Bytes of the uniform buffer bound to the binding point.
+    pub fn uniform_block(&self, index: u32) -> Option<&[u8]> {
+        self.block(gl::UNIFORM_BUFFER, index)
+    }
+
+    /// Bytes of the shader storage buffer bound to the binding point.
+    pub fn storage_block(&self, index: u32) -> Option<&[u8]> {
+        self.block(gl::SHADER_STORAGE_BUFFER, index)
+    }
+
+    /// Samples the texture bound to the unit, opaque black if there is none.
+    pub fn sample(&self, unit: u32, uv: Vec2D) -> Vec4D {
+        self.units
+            .get(&unit)
+            .and_then(|id| self.textures.get(id))
+            .map(|texture| texture.sample(uv))
+            .unwrap_or_else(|| Vec4D::new(0_f32, 0_f32, 0_f32, 1_f32))
+    }
+
+    /// Samples through a sampler uniform holding the texture unit.
+    pub fn texture(&self, sampler: &str, uv: Vec2D) -> Vec4D {
+        self.sample(self.int(sampler) as u32, uv)
+    }
+}
+
+/// Multiplies a column major matrix with the vector.
+pub fn transform(matrix: &[f32; 16], v: Vec4D) -> Vec4D {
+    let row = |i: usize| {
+        matrix[i] * v.x + matrix[4 + i] * v.y + matrix[8 + i] * v.z + matrix[12 + i] * v.w
+    };
+    Vec4D::new(row(0), row(1), row(2), row(3))
+}
+
+type VertexFn = dyn Fn(&ShaderContext, &[Vec4D]) -> VertexOutput;
+type FragmentFn = dyn Fn(&ShaderContext, &Fragment) -> Option<Vec4D>;
+
+/// Shader of the software backend, the stages are Rust closures.
+///
+/// The vertex stage gets the attributes by location, missing components
+/// filled with `(0, 0, 0, 1)`. The fragment stage returns the color or
+/// `None` to discard the fragment. The inputs and uniforms the program
+/// reports as active are the ones declared with `with_input` and
+/// `with_uniform`.
+pub struct SoftwareShader {
+    pub(super) vertex: Box<VertexFn>,
+    pub(super) fragment: Box<FragmentFn>,
+    pub(super) inputs: Vec<(i32, ActiveVariable)>,
+    pub(super) uniforms: Vec<ActiveVariable>,
+}
+
+impl SoftwareShader {
+    pub fn new<V, F>(vertex: V, fragment: F) -> Self
+    where
+        V: Fn(&ShaderContext, &[Vec4D]) -> VertexOutput + 'static,
+        F: Fn(&ShaderContext, &Fragment) -> Option<Vec4D> + 'static,
+    {
+        Self {
+            vertex: Box::new(vertex),
+            fragment: Box::new(fragment),
+            inputs: Vec::new(),
+            uniforms: Vec::new(),
+        }
+    }
+
+    /// Declares a vertex input of the GLSL type at the attribute location.
+    pub fn with_input(mut self, name: &str, location: i32, gtype: u32) -> Self {
+        self.inputs
+            .push((location, ActiveVariable::new(name, gtype, 1)));
+        self
+    }
+
+    /// Declares a uniform of the GLSL type, `size` elements if it is an array.
+    pub fn with_uniform(mut self, name: &str, gtype: u32, size: i32) -> Self {
+        let name = if size > 1 {
+            format!("{}[0]", name)
+        } else {
+            name.to_owned()
+        };
+        self.uniforms.push(ActiveVariable::new(&name, gtype, size));
+        self
+    }
+}
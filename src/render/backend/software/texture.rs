@@ -0,0 +1,153 @@
+// External imports
+use gl::types::*;
+// Crate imports
+use crate::math::{Vec2D, Vec4D};
+
+/// Texture data of the software backend, stored as RGBA8 with the first
+/// row at the bottom like OpenGL.
+pub(super) struct TextureState {
+    width: usize,
+    height: usize,
+    texels: Vec<[u8; 4]>,
+    mag_filter: GLenum,
+    wrap_s: GLenum,
+    wrap_t: GLenum,
+}
+
+impl TextureState {
+    pub(super) fn new() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            texels: Vec::new(),
+            mag_filter: gl::LINEAR,
+            wrap_s: gl::REPEAT,
+            wrap_t: gl::REPEAT,
+        }
+    }
+
+    pub(super) fn storage(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.texels = vec![[0, 0, 0, 255]; width * height];
+    }
+
+    pub(super) fn parameter(&mut self, pname: GLenum, param: GLint) {
+        match pname {
+            gl::TEXTURE_MAG_FILTER => self.mag_filter = param as GLenum,
+            gl::TEXTURE_WRAP_S => self.wrap_s = param as GLenum,
+            gl::TEXTURE_WRAP_T => self.wrap_t = param as GLenum,
+            _ => {}
+        }
+    }
+
+    /// Copies unsigned byte RED, RG, RGB or RGBA data into the rectangle.
+    #[allow(clippy::too_many_arguments)]
+    pub(super) fn sub_image(
+        &mut self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        format: GLenum,
+        etype: GLenum,
+        data: &[u8],
+    ) {
+        let channels = match format {
+            gl::RED => 1,
+            gl::RG => 2,
+            gl::RGB => 3,
+            gl::RGBA => 4,
+            _ => return,
+        };
+        if etype != gl::UNSIGNED_BYTE {
+            return;
+        }
+        for j in 0..height.min(self.height.saturating_sub(y)) {
+            for i in 0..width.min(self.width.saturating_sub(x)) {
+                let start = (j * width + i) * channels;
+                let source = match data.get(start..start + channels) {
+                    Some(source) => source,
+                    None => return,
+                };
+                let mut texel = [0, 0, 0, 255];
+                texel[..channels].copy_from_slice(source);
+                self.texels[(y + j) * self.width + x + i] = texel;
+            }
+        }
+    }
+
+    fn texel(&self, i: i32, j: i32) -> Vec4D {
+        let i = wrap(i, self.width, self.wrap_s);
+        let j = wrap(j, self.height, self.wrap_t);
+        let t = self.texels[j * self.width + i];
+        Vec4D::new(
+            t[0] as f32 / 255_f32,
+            t[1] as f32 / 255_f32,
+            t[2] as f32 / 255_f32,
+            t[3] as f32 / 255_f32,
+        )
+    }
+
+    pub(super) fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Texel like `imageLoad`, transparent black outside of the texture.
+    pub(super) fn load(&self, x: usize, y: usize) -> Vec4D {
+        if x >= self.width || y >= self.height {
+            return Vec4D::null();
+        }
+        self.texel(x as i32, y as i32)
+    }
+
+    /// Writes the texel like `imageStore`, ignored outside of the texture.
+    pub(super) fn store(&mut self, x: usize, y: usize, color: Vec4D) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let channel = |c: f32| (c.clamp(0_f32, 1_f32) * 255_f32).round() as u8;
+        self.texels[y * self.width + x] = [
+            channel(color.x),
+            channel(color.y),
+            channel(color.z),
+            channel(color.w),
+        ];
+    }
+
+    /// Samples the texture without mipmaps, always with the magnification filter.
+    pub(super) fn sample(&self, uv: Vec2D) -> Vec4D {
+        if self.texels.is_empty() {
+            return Vec4D::new(0_f32, 0_f32, 0_f32, 1_f32);
+        }
+        let u = uv.x * self.width as f32;
+        let v = uv.y * self.height as f32;
+        if self.mag_filter == gl::NEAREST {
+            return self.texel(u.floor() as i32, v.floor() as i32);
+        }
+        let (u, v) = (u - 0.5, v - 0.5);
+        let (i, j) = (u.floor(), v.floor());
+        let (fu, fv) = (u - i, v - j);
+        let (i, j) = (i as i32, j as i32);
+        let bottom = self.texel(i, j) * (1_f32 - fu) + self.texel(i + 1, j) * fu;
+        let top = self.texel(i, j + 1) * (1_f32 - fu) + self.texel(i + 1, j + 1) * fu;
+        bottom * (1_f32 - fv) + top * fv
+    }
+}
+
+fn wrap(i: i32, size: usize, mode: GLenum) -> usize {
+    let size = size as i32;
+    let i = match mode {
+        gl::CLAMP_TO_EDGE | gl::CLAMP_TO_BORDER => i.clamp(0, size - 1),
+        gl::MIRRORED_REPEAT => {
+            let period = i.rem_euclid(2 * size);
+            if period < size {
+                period
+            } else {
+                2 * size - 1 - period
+            }
+        }
+        _ => i.rem_euclid(size),
+    };
+    i as usize
+}
@@ -0,0 +1,280 @@
+// Writes Rust values with the std140 or std430 rules of GLSL interface blocks
+// Crate imports
+use super::buffer::{Buffer, BufferType, Error};
+use crate::math::{Mat3, Mat4, Vec2D, Vec3D, Vec4D};
+
+/// Memory layout of a uniform or storage block
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockLayout {
+    /// Uniform blocks, arrays and structs are aligned to 16 bytes
+    Std140,
+    /// Storage blocks, arrays and structs are aligned to their members
+    Std430,
+}
+
+impl BlockLayout {
+    /// Alignment of an array element or a struct member of the layout
+    pub fn aggregate_align(self, align: usize) -> usize {
+        match self {
+            BlockLayout::Std140 => round_up(align, 16),
+            BlockLayout::Std430 => align,
+        }
+    }
+
+    /// Distance between two elements of an array of `T`
+    pub fn array_stride<T: BlockValue>(self) -> usize {
+        round_up(T::SIZE, self.aggregate_align(T::ALIGN))
+    }
+}
+
+/// GLSL type which can be written to a block
+pub trait BlockValue {
+    /// Base alignment in bytes
+    const ALIGN: usize;
+    /// Size in bytes, without the padding up to the next member
+    const SIZE: usize;
+
+    /// Writes the value at the start of `out`, `SIZE` bytes long
+    fn write(&self, layout: BlockLayout, out: &mut [u8]);
+}
+
+fn round_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+fn write_floats(values: &[f32], out: &mut [u8]) {
+    for (chunk, value) in out.chunks_exact_mut(4).zip(values) {
+        chunk.copy_from_slice(&value.to_ne_bytes());
+    }
+}
+
+// matrices are arrays of column vectors padded to a vec4 in both layouts
+fn write_columns(columns: &[&[f32]], out: &mut [u8]) {
+    for (i, column) in columns.iter().enumerate() {
+        write_floats(column, &mut out[16 * i..]);
+    }
+}
+
+impl BlockValue for f32 {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl BlockValue for i32 {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+impl BlockValue for u32 {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        out[..4].copy_from_slice(&self.to_ne_bytes());
+    }
+}
+
+/// Written as a GLSL `bool`, 4 bytes
+impl BlockValue for bool {
+    const ALIGN: usize = 4;
+    const SIZE: usize = 4;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        out[..4].copy_from_slice(&(*self as u32).to_ne_bytes());
+    }
+}
+
+impl BlockValue for Vec2D {
+    const ALIGN: usize = 8;
+    const SIZE: usize = 8;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        write_floats(&[self.x, self.y], out);
+    }
+}
+
+/// Aligned like a `vec4`, a scalar can follow in the last 4 bytes
+impl BlockValue for Vec3D {
+    const ALIGN: usize = 16;
+    const SIZE: usize = 12;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        write_floats(&[self.x, self.y, self.z], out);
+    }
+}
+
+impl BlockValue for Vec4D {
+    const ALIGN: usize = 16;
+    const SIZE: usize = 16;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        write_floats(&[self.x, self.y, self.z, self.w], out);
+    }
+}
+
+/// Three `vec3` columns, each padded to 16 bytes
+impl BlockValue for Mat3 {
+    const ALIGN: usize = 16;
+    const SIZE: usize = 48;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        let column = |j: usize| [self.get(0, j), self.get(1, j), self.get(2, j)];
+        write_columns(&[&column(0), &column(1), &column(2)], out);
+    }
+}
+
+impl BlockValue for Mat4 {
+    const ALIGN: usize = 16;
+    const SIZE: usize = 64;
+
+    fn write(&self, _layout: BlockLayout, out: &mut [u8]) {
+        let columns = self.to_cols_array();
+        write_columns(
+            &[
+                &columns[0..4],
+                &columns[4..8],
+                &columns[8..12],
+                &columns[12..16],
+            ],
+            out,
+        );
+    }
+}
+
+/// Rust struct mirroring a GLSL block or struct, member by member
+pub trait Block {
+    fn write_block(&self, writer: &mut BlockWriter);
+}
+
+/// Appends the members of a block, inserting the padding of the layout
+///
+/// ```ignore
+/// let mut writer = BlockWriter::new(BlockLayout::Std140);
+/// writer.push(&camera.view_projection);
+/// writer.push(&camera.position);
+/// writer.push(&camera.near);
+/// uniform_buffer.set_data(&writer.finish());
+/// ```
+pub struct BlockWriter {
+    layout: BlockLayout,
+    data: Vec<u8>,
+    align: usize,
+}
+
+impl BlockWriter {
+    pub fn new(layout: BlockLayout) -> Self {
+        Self {
+            layout,
+            data: Vec::new(),
+            align: 4,
+        }
+    }
+
+    pub fn layout(&self) -> BlockLayout {
+        self.layout
+    }
+
+    /// Offset of the next member before its alignment
+    pub fn offset(&self) -> usize {
+        self.data.len()
+    }
+
+    // pads to the alignment and makes room for `size` bytes
+    fn reserve(&mut self, align: usize, size: usize) -> usize {
+        let offset = round_up(self.data.len(), align);
+        self.data.resize(offset + size, 0);
+        self.align = self.align.max(align);
+        offset
+    }
+
+    /// Appends the member, returns its offset in the block
+    pub fn push<T: BlockValue>(&mut self, value: &T) -> usize {
+        let offset = self.reserve(T::ALIGN, T::SIZE);
+        value.write(self.layout, &mut self.data[offset..]);
+        offset
+    }
+
+    /// Appends the array member, returns its offset in the block
+    pub fn push_array<T: BlockValue>(&mut self, values: &[T]) -> usize {
+        let align = self.layout.aggregate_align(T::ALIGN);
+        let stride = self.layout.array_stride::<T>();
+        let offset = self.reserve(align, stride * values.len());
+        for (i, value) in values.iter().enumerate() {
+            value.write(self.layout, &mut self.data[offset + i * stride..]);
+        }
+        offset
+    }
+
+    /// Appends the struct member, returns its offset in the block
+    pub fn push_block<T: Block>(&mut self, value: &T) -> usize {
+        let mut member = BlockWriter::new(self.layout);
+        value.write_block(&mut member);
+        let align = self.layout.aggregate_align(member.align);
+        let data = member.finish();
+        let offset = self.reserve(align, data.len());
+        self.data[offset..].copy_from_slice(&data);
+        offset
+    }
+
+    /// Appends the array of structs member, returns its offset in the block
+    pub fn push_block_array<T: Block>(&mut self, values: &[T]) -> usize {
+        let mut offset = self.data.len();
+        for (i, value) in values.iter().enumerate() {
+            let member = self.push_block(value);
+            if i == 0 {
+                offset = member;
+            }
+        }
+        offset
+    }
+
+    /// Bytes of the block, its size rounded up to its alignment
+    pub fn finish(mut self) -> Vec<u8> {
+        let align = self.layout.aggregate_align(self.align);
+        let size = round_up(self.data.len(), align);
+        self.data.resize(size, 0);
+        self.data
+    }
+}
+
+/// Bytes of the value laid out as a block
+pub fn block_bytes<T: Block>(value: &T, layout: BlockLayout) -> Vec<u8> {
+    let mut writer = BlockWriter::new(layout);
+    value.write_block(&mut writer);
+    writer.finish()
+}
+
+/// Bytes of an unsized array of structs, as the last member of a storage block
+pub fn block_array_bytes<T: Block>(values: &[T], layout: BlockLayout) -> Vec<u8> {
+    let mut writer = BlockWriter::new(layout);
+    writer.push_block_array(values);
+    writer.finish()
+}
+
+impl<B> Buffer<B>
+where
+    B: BufferType,
+{
+    /// Set the buffer data to the block laid out with `layout`
+    pub fn set_block<T: Block>(&self, value: &T, layout: BlockLayout) {
+        self.set_data(&block_bytes(value, layout));
+    }
+
+    /// Overwrites the block at `offset` bytes, `offset` being aligned for the target
+    pub fn update_block<T: Block>(
+        &self,
+        offset: usize,
+        value: &T,
+        layout: BlockLayout,
+    ) -> Result<(), Error> {
+        self.update_sub_data(offset, &block_bytes(value, layout))
+    }
+}
@@ -1,10 +1,17 @@
 use gl::{self, types::*};
-use std::mem::size_of;
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+// Crate imports
+use super::backend::{as_bytes, Backend, Pod};
 
 pub trait BufferType {
     const TYPE: GLuint;
 }
 
+/// Buffer types with indexed binding points shared by all the programs
+pub trait IndexedBufferType: BufferType {}
+
 pub struct VertexBufferType;
 impl BufferType for VertexBufferType {
     const TYPE: GLuint = gl::ARRAY_BUFFER;
@@ -17,15 +24,130 @@ impl BufferType for IndexBufferType {
     const TYPE: GLuint = gl::ELEMENT_ARRAY_BUFFER;
 }
 
-pub type IndexBuffer = Buffer<IndexBufferType>;
+pub struct DrawIndirectBufferType;
+impl BufferType for DrawIndirectBufferType {
+    const TYPE: GLuint = gl::DRAW_INDIRECT_BUFFER;
+}
+
+pub type DrawIndirectBuffer = Buffer<DrawIndirectBufferType>;
+
+pub struct DispatchIndirectBufferType;
+impl BufferType for DispatchIndirectBufferType {
+    const TYPE: GLuint = gl::DISPATCH_INDIRECT_BUFFER;
+}
+
+pub type DispatchIndirectBuffer = Buffer<DispatchIndirectBufferType>;
+
+pub struct UniformBufferType;
+impl BufferType for UniformBufferType {
+    const TYPE: GLuint = gl::UNIFORM_BUFFER;
+}
+impl IndexedBufferType for UniformBufferType {}
+
+pub type UniformBuffer = Buffer<UniformBufferType>;
+
+pub struct ShaderStorageBufferType;
+impl BufferType for ShaderStorageBufferType {
+    const TYPE: GLuint = gl::SHADER_STORAGE_BUFFER;
+}
+impl IndexedBufferType for ShaderStorageBufferType {}
+
+pub type ShaderStorageBuffer = Buffer<ShaderStorageBufferType>;
+
+pub struct AtomicCounterBufferType;
+impl BufferType for AtomicCounterBufferType {
+    const TYPE: GLuint = gl::ATOMIC_COUNTER_BUFFER;
+}
+impl IndexedBufferType for AtomicCounterBufferType {}
+
+pub type AtomicCounterBuffer = Buffer<AtomicCounterBufferType>;
+
+pub struct TransformFeedbackBufferType;
+impl BufferType for TransformFeedbackBufferType {
+    const TYPE: GLuint = gl::TRANSFORM_FEEDBACK_BUFFER;
+}
+impl IndexedBufferType for TransformFeedbackBufferType {}
+
+pub type TransformFeedbackBuffer = Buffer<TransformFeedbackBufferType>;
+
+pub struct PixelPackBufferType;
+impl BufferType for PixelPackBufferType {
+    const TYPE: GLuint = gl::PIXEL_PACK_BUFFER;
+}
+
+pub type PixelPackBuffer = Buffer<PixelPackBufferType>;
+
+pub struct PixelUnpackBufferType;
+impl BufferType for PixelUnpackBufferType {
+    const TYPE: GLuint = gl::PIXEL_UNPACK_BUFFER;
+}
+
+pub type PixelUnpackBuffer = Buffer<PixelUnpackBufferType>;
+
+pub struct CopyReadBufferType;
+impl BufferType for CopyReadBufferType {
+    const TYPE: GLuint = gl::COPY_READ_BUFFER;
+}
+
+pub type CopyReadBuffer = Buffer<CopyReadBufferType>;
+
+pub struct CopyWriteBufferType;
+impl BufferType for CopyWriteBufferType {
+    const TYPE: GLuint = gl::COPY_WRITE_BUFFER;
+}
+
+pub type CopyWriteBuffer = Buffer<CopyWriteBufferType>;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The write would go past the end of the allocated storage
+    OutOfBounds {
+        offset: usize,
+        size: usize,
+        capacity: usize,
+    },
+    /// The persistent mapping failed
+    MapFailed,
+    /// The GPU still used the section after the timeout
+    SyncTimeout,
+    /// A ring buffer without sections or bytes, or too large to address
+    InvalidSize {
+        section_size: usize,
+        sections: usize,
+    },
+}
+
+/// How often the data is expected to change
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Usage {
+    /// Set once, drawn many times
+    Static,
+    /// Updated now and then, drawn many times
+    Dynamic,
+    /// Set every frame, drawn a few times
+    Stream,
+}
+
+impl Usage {
+    pub fn to_gl(self) -> GLenum {
+        match self {
+            Usage::Static => gl::STATIC_DRAW,
+            Usage::Dynamic => gl::DYNAMIC_DRAW,
+            Usage::Stream => gl::STREAM_DRAW,
+        }
+    }
+}
 
 /// Generic Buffer struct for all OpenGL buffer types
 pub struct Buffer<B>
 where
     B: BufferType,
 {
-    gl: gl::Gl,
+    backend: Backend,
     id: GLuint,
+    usage: Usage,
+    size: Rc<Cell<usize>>,
+    capacity: Cell<usize>,
     _marker: std::marker::PhantomData<B>,
 }
 
@@ -34,36 +156,341 @@ where
     B: BufferType,
 {
     /// Creates a new buffer
-    pub fn new(gl: &gl::Gl) -> Self {
-        let mut id: GLuint = 0;
-        unsafe { gl.GenBuffers(1, &mut id) }
+    pub fn new(backend: &Backend) -> Self {
+        Self::with_usage(backend, Usage::Static)
+    }
+
+    /// Creates a new buffer with the usage hint
+    pub fn with_usage(backend: &Backend, usage: Usage) -> Self {
+        let id = backend.create_buffer();
         Self {
-            gl: gl.clone(),
+            backend: backend.clone(),
             id,
+            usage,
+            size: Rc::new(Cell::new(0)),
+            capacity: Cell::new(0),
             _marker: std::marker::PhantomData,
         }
     }
 
     /// Set the buffer data on the GPU
-    pub fn set_data<T>(&self, data: &[T]) {
+    ///
+    /// Dynamic buffers keep their storage when the data fits, stream buffers
+    /// always get a new one so the GPU never waits on the previous draws
+    pub fn set_data<T: Pod>(&self, data: &[T]) {
+        let size = std::mem::size_of_val(data);
+        self.bind();
+        if self.usage == Usage::Dynamic && size <= self.capacity.get() {
+            self.backend.buffer_sub_data(B::TYPE, 0, as_bytes(data));
+        } else {
+            self.backend
+                .buffer_data(B::TYPE, as_bytes(data), self.usage.to_gl());
+            self.capacity.set(size);
+        }
+        self.size.set(size);
+    }
+
+    /// Allocates at least `capacity` bytes, the current data is lost when it grows
+    pub fn reserve(&self, capacity: usize) {
+        if capacity > self.capacity.get() {
+            self.bind();
+            self.backend
+                .buffer_allocate(B::TYPE, capacity, self.usage.to_gl());
+            self.capacity.set(capacity);
+            self.size.set(0);
+        }
+    }
+
+    /// Overwrites the data from `offset` bytes, without reallocating
+    pub fn update_sub_data<T: Pod>(&self, offset: usize, data: &[T]) -> Result<(), Error> {
+        let size = std::mem::size_of_val(data);
+        if offset + size > self.capacity.get() {
+            return Err(Error::OutOfBounds {
+                offset,
+                size,
+                capacity: self.capacity.get(),
+            });
+        }
         self.bind();
+        self.backend
+            .buffer_sub_data(B::TYPE, offset, as_bytes(data));
+        self.size.set(self.size.get().max(offset + size));
+        Ok(())
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id
+    }
+    /// Size of the data in bytes
+    pub fn size(&self) -> usize {
+        self.size.get()
+    }
+    /// Size of the data in bytes, following the later uploads
+    pub(crate) fn shared_size(&self) -> Rc<Cell<usize>> {
+        self.size.clone()
+    }
+    /// Size of the allocated storage in bytes
+    pub fn capacity(&self) -> usize {
+        self.capacity.get()
+    }
+    pub fn usage(&self) -> Usage {
+        self.usage
+    }
+    pub fn bind(&self) {
+        self.backend.bind_buffer(B::TYPE, self.id);
+    }
+    pub fn unbind(&self) {
+        self.backend.bind_buffer(B::TYPE, 0 as GLuint);
+    }
+}
+
+impl<B> Drop for Buffer<B>
+where
+    B: BufferType,
+{
+    fn drop(&mut self) {
+        self.backend.delete_buffer(self.id);
+    }
+}
+
+impl<B> Buffer<B>
+where
+    B: IndexedBufferType,
+{
+    /// Binds the whole buffer to the binding point `index`
+    pub fn bind_base(&self, index: GLuint) {
+        self.backend.bind_buffer_base(B::TYPE, index, self.id);
+    }
+    /// Binds `size` bytes from `offset` to the binding point `index`, the
+    /// offset being a multiple of the offset alignment of the target
+    pub fn bind_range(&self, index: GLuint, offset: usize, size: usize) {
+        self.backend
+            .bind_buffer_range(B::TYPE, index, self.id, offset, size);
+    }
+}
+
+/// Persistently mapped buffer split in sections written in turn
+///
+/// Each section is fenced when the next one is started, writing to it again
+/// waits until the GPU is done with the draws that read it
+pub struct RingBuffer<B>
+where
+    B: BufferType,
+{
+    backend: Backend,
+    id: GLuint,
+    ptr: *mut u8,
+    section_size: usize,
+    fences: Vec<Option<GLsync>>,
+    section: usize,
+    cursor: usize,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<B> RingBuffer<B>
+where
+    B: BufferType,
+{
+    const FLAGS: GLbitfield = gl::MAP_WRITE_BIT | gl::MAP_PERSISTENT_BIT | gl::MAP_COHERENT_BIT;
+    /// Longest wait for a section before giving up
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Creates `sections` sections of `section_size` bytes, three being enough
+    /// for the GPU to lag two frames behind
+    pub fn new(backend: &Backend, section_size: usize, sections: usize) -> Result<Self, Error> {
+        let capacity = match section_size.checked_mul(sections) {
+            Some(capacity) if capacity > 0 && capacity <= isize::MAX as usize => capacity,
+            _ => {
+                return Err(Error::InvalidSize {
+                    section_size,
+                    sections,
+                })
+            }
+        };
+        let id = backend.create_buffer();
+        backend.bind_buffer(B::TYPE, id);
+        backend.buffer_storage(B::TYPE, capacity, Self::FLAGS);
+        let ptr = backend.map_buffer_range(B::TYPE, 0, capacity, Self::FLAGS);
+        if ptr.is_null() {
+            backend.delete_buffer(id);
+            return Err(Error::MapFailed);
+        }
+        Ok(Self {
+            backend: backend.clone(),
+            id,
+            ptr,
+            section_size,
+            fences: vec![None; sections],
+            section: 0,
+            cursor: 0,
+            _marker: std::marker::PhantomData,
+        })
+    }
+
+    /// Copies the data in the current section, returns its offset in bytes
+    /// from the start of the buffer
+    pub fn write<T: Pod>(&mut self, data: &[T]) -> Result<usize, Error> {
+        self.write_aligned(data, 4)
+    }
+
+    /// Same as `write` with the offset a multiple of `align`, such as the
+    /// uniform buffer offset alignment for `bind_range`
+    pub fn write_aligned<T: Pod>(&mut self, data: &[T], align: usize) -> Result<usize, Error> {
+        let align = std::mem::align_of::<T>().max(align);
+        let start = self.cursor.div_ceil(align) * align;
+        let size = std::mem::size_of_val(data);
+        if start + size > self.section_size {
+            return Err(Error::OutOfBounds {
+                offset: start,
+                size,
+                capacity: self.section_size,
+            });
+        }
+        let offset = self.section_offset() + start;
+        let bytes = as_bytes(data);
+        // the storage outlives the mapping and the range is in bounds
         unsafe {
-            self.gl.BufferData(
-                B::TYPE,
-                (data.len() * size_of::<T>()) as GLsizeiptr,
-                data.as_ptr() as *const GLvoid,
-                gl::STATIC_DRAW,
-            )
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), self.ptr.add(offset), size);
+        }
+        self.cursor = start + size;
+        Ok(offset)
+    }
+
+    /// Fences the current section once its draws are issued and moves to the
+    /// next one, waiting for the GPU to release it. On a timeout the current
+    /// section is kept, calling again fences its later draws and waits again
+    pub fn next_section(&mut self) -> Result<(), Error> {
+        let fence = self.backend.fence_sync();
+        if let Some(sync) = self.fences[self.section].replace(fence) {
+            self.backend.delete_sync(sync);
+        }
+        let next = (self.section + 1) % self.fences.len();
+        if let Some(sync) = self.fences[next] {
+            let status = self
+                .backend
+                .client_wait_sync(sync, Self::TIMEOUT.as_nanos() as u64);
+            if status == gl::TIMEOUT_EXPIRED || status == gl::WAIT_FAILED {
+                return Err(Error::SyncTimeout);
+            }
+            self.backend.delete_sync(sync);
+            self.fences[next] = None;
         }
+        self.section = next;
+        self.cursor = 0;
+        Ok(())
     }
 
     pub fn id(&self) -> GLuint {
         self.id
     }
+    /// Size of the whole storage in bytes
+    pub fn capacity(&self) -> usize {
+        self.section_size * self.fences.len()
+    }
+    pub fn section_size(&self) -> usize {
+        self.section_size
+    }
+    /// Index of the section being written
+    pub fn section(&self) -> usize {
+        self.section
+    }
+    /// Offset of the section being written, in bytes
+    pub fn section_offset(&self) -> usize {
+        self.section * self.section_size
+    }
+    pub fn bind(&self) {
+        self.backend.bind_buffer(B::TYPE, self.id);
+    }
+}
+
+impl<B> RingBuffer<B>
+where
+    B: IndexedBufferType,
+{
+    /// Binds `size` bytes from `offset` to the binding point `index`
+    pub fn bind_range(&self, index: GLuint, offset: usize, size: usize) {
+        self.backend
+            .bind_buffer_range(B::TYPE, index, self.id, offset, size);
+    }
+}
+
+impl<B> Drop for RingBuffer<B>
+where
+    B: BufferType,
+{
+    fn drop(&mut self) {
+        for sync in self.fences.iter().flatten() {
+            self.backend.delete_sync(*sync);
+        }
+        self.bind();
+        self.backend.unmap_buffer(B::TYPE);
+        self.backend.delete_buffer(self.id);
+    }
+}
+
+/// Integer types usable as indices
+pub trait IndexType: Pod {
+    const TYPE: GLenum;
+}
+
+impl IndexType for u8 {
+    const TYPE: GLenum = gl::UNSIGNED_BYTE;
+}
+
+impl IndexType for u16 {
+    const TYPE: GLenum = gl::UNSIGNED_SHORT;
+}
+
+impl IndexType for u32 {
+    const TYPE: GLenum = gl::UNSIGNED_INT;
+}
+
+/// Element buffer remembering the count and type of its indices
+pub struct IndexBuffer {
+    buffer: Buffer<IndexBufferType>,
+    count: Cell<usize>,
+    etype: Cell<GLenum>,
+}
+
+impl IndexBuffer {
+    pub fn new(backend: &Backend) -> Self {
+        Self {
+            buffer: Buffer::new(backend),
+            count: Cell::new(0),
+            etype: Cell::new(gl::UNSIGNED_INT),
+        }
+    }
+
+    /// Set the indices on the GPU
+    pub fn set_data<T: IndexType>(&self, data: &[T]) {
+        self.buffer.set_data(data);
+        self.count.set(data.len());
+        self.etype.set(T::TYPE);
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.get()
+    }
+    /// OpenGL type of the indices
+    pub fn index_type(&self) -> GLenum {
+        self.etype.get()
+    }
+    /// Size of one index in bytes
+    pub fn index_size(&self) -> usize {
+        match self.etype.get() {
+            gl::UNSIGNED_BYTE => 1,
+            gl::UNSIGNED_SHORT => 2,
+            _ => 4,
+        }
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.buffer.id()
+    }
     pub fn bind(&self) {
-        unsafe { self.gl.BindBuffer(B::TYPE, self.id) };
+        self.buffer.bind();
     }
     pub fn unbind(&self) {
-        unsafe { self.gl.BindBuffer(B::TYPE, 0 as GLuint) };
+        self.buffer.unbind();
     }
 }
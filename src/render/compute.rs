@@ -0,0 +1,177 @@
+// Compute programs with the buffers and images they read and write
+// External imports
+use gl::types::*;
+use std::ffi::CString;
+use std::ops::BitOr;
+// Crate imports
+use super::backend::{Backend, Pod};
+use super::buffer::{DispatchIndirectBuffer, ShaderStorageBuffer};
+use super::shader::{Error, Shader, ShaderProgram};
+use super::texture::Texture;
+use super::uniform::UniformData;
+use crate::ressources::RessourceLoader;
+
+/// How the shader uses an image unit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ImageAccess {
+    ReadOnly,
+    WriteOnly,
+    ReadWrite,
+}
+
+impl ImageAccess {
+    pub fn to_gl(self) -> GLenum {
+        match self {
+            ImageAccess::ReadOnly => gl::READ_ONLY,
+            ImageAccess::WriteOnly => gl::WRITE_ONLY,
+            ImageAccess::ReadWrite => gl::READ_WRITE,
+        }
+    }
+}
+
+/// Kinds of accesses which see the shader writes after `memory_barrier`,
+/// combined with `|`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Barriers(GLbitfield);
+
+impl Barriers {
+    /// Vertex attributes read from buffers
+    pub const VERTEX_ATTRIB_ARRAY: Barriers = Barriers(gl::VERTEX_ATTRIB_ARRAY_BARRIER_BIT);
+    pub const ELEMENT_ARRAY: Barriers = Barriers(gl::ELEMENT_ARRAY_BARRIER_BIT);
+    pub const UNIFORM: Barriers = Barriers(gl::UNIFORM_BARRIER_BIT);
+    /// Textures sampled by the shaders
+    pub const TEXTURE_FETCH: Barriers = Barriers(gl::TEXTURE_FETCH_BARRIER_BIT);
+    pub const SHADER_IMAGE_ACCESS: Barriers = Barriers(gl::SHADER_IMAGE_ACCESS_BARRIER_BIT);
+    /// Indirect draw and dispatch commands
+    pub const COMMAND: Barriers = Barriers(gl::COMMAND_BARRIER_BIT);
+    pub const PIXEL_BUFFER: Barriers = Barriers(gl::PIXEL_BUFFER_BARRIER_BIT);
+    pub const TEXTURE_UPDATE: Barriers = Barriers(gl::TEXTURE_UPDATE_BARRIER_BIT);
+    pub const BUFFER_UPDATE: Barriers = Barriers(gl::BUFFER_UPDATE_BARRIER_BIT);
+    pub const FRAMEBUFFER: Barriers = Barriers(gl::FRAMEBUFFER_BARRIER_BIT);
+    pub const SHADER_STORAGE: Barriers = Barriers(gl::SHADER_STORAGE_BARRIER_BIT);
+    pub const ALL: Barriers = Barriers(gl::ALL_BARRIER_BITS);
+
+    pub fn bits(self) -> GLbitfield {
+        self.0
+    }
+}
+
+impl BitOr for Barriers {
+    type Output = Barriers;
+
+    fn bitor(self, other: Barriers) -> Barriers {
+        Barriers(self.0 | other.0)
+    }
+}
+
+/// Work group counts of an indirect dispatch, as read by the GPU
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DispatchIndirect {
+    pub x: u32,
+    pub y: u32,
+    pub z: u32,
+}
+
+unsafe impl Pod for DispatchIndirect {}
+
+/// Program made of a single compute shader
+pub struct ComputeProgram {
+    program: ShaderProgram,
+    backend: Backend,
+}
+
+impl ComputeProgram {
+    /// Loads and links `name.comp`
+    pub fn from_res(backend: &Backend, res: &RessourceLoader, name: &str) -> Result<Self, Error> {
+        let resource_name = format!("{}.comp", name);
+        let shader = Shader::from_res(backend, res, &resource_name, gl::COMPUTE_SHADER)?;
+        let program = ShaderProgram::from_shaders(backend, vec![shader]).map_err(|message| {
+            Error::LinkError {
+                name: name.to_owned(),
+                message,
+            }
+        })?;
+        Ok(Self::from_program(backend, program))
+    }
+
+    pub fn from_source(backend: &Backend, source: CString) -> Result<Self, String> {
+        let shader = Shader::from_source(backend, source, gl::COMPUTE_SHADER)?;
+        let program = ShaderProgram::from_shaders(backend, vec![shader])?;
+        Ok(Self::from_program(backend, program))
+    }
+
+    /// Wraps a program linked from a compute shader
+    pub fn from_program(backend: &Backend, program: ShaderProgram) -> Self {
+        Self {
+            program,
+            backend: backend.clone(),
+        }
+    }
+
+    pub fn program(&self) -> &ShaderProgram {
+        &self.program
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.program.id()
+    }
+
+    pub fn set_uniform<T>(&self, name: &str, value: &T) -> Result<(), Error>
+    where
+        T: UniformData + ?Sized,
+    {
+        self.program.set_uniform(name, value)
+    }
+
+    /// The `layout(local_size_x, local_size_y, local_size_z)` of the shader
+    pub fn work_group_size(&self) -> [u32; 3] {
+        let [x, y, z] = self.backend.compute_work_group_size(self.id());
+        [x.max(0) as u32, y.max(0) as u32, z.max(0) as u32]
+    }
+
+    /// Binds the buffer to the `layout(binding = index) buffer` block
+    pub fn bind_storage_buffer(&self, index: GLuint, buffer: &ShaderStorageBuffer) {
+        buffer.bind_base(index);
+    }
+
+    /// Binds the level 0 of the texture to the `layout(binding = unit)` image,
+    /// with the format of its storage
+    pub fn bind_image(&self, unit: GLuint, texture: &Texture, access: ImageAccess) {
+        self.backend.bind_image_texture(
+            unit,
+            texture.id(),
+            0,
+            false,
+            0,
+            access.to_gl(),
+            texture.format(),
+        );
+    }
+
+    /// Runs `x * y * z` work groups
+    pub fn dispatch(&self, x: u32, y: u32, z: u32) {
+        self.program.bind();
+        self.backend.dispatch_compute(x, y, z);
+    }
+
+    /// Runs enough work groups to have one invocation per element of the grid
+    pub fn dispatch_size(&self, width: u32, height: u32, depth: u32) {
+        let [x, y, z] = self.work_group_size();
+        let groups = |size: u32, local: u32| size.div_ceil(local.max(1));
+        self.dispatch(groups(width, x), groups(height, y), groups(depth, z));
+    }
+
+    /// Runs the work groups of the `DispatchIndirect` command at `index` in the buffer
+    pub fn dispatch_indirect(&self, commands: &DispatchIndirectBuffer, index: usize) {
+        self.program.bind();
+        commands.bind();
+        self.backend
+            .dispatch_compute_indirect(index * std::mem::size_of::<DispatchIndirect>());
+    }
+
+    /// Makes the writes of the previous dispatches visible to the accesses
+    pub fn memory_barrier(&self, barriers: Barriers) {
+        self.backend.memory_barrier(barriers.bits());
+    }
+}
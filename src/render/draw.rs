@@ -0,0 +1,97 @@
+// External imports
+use gl::types::*;
+// Crate imports
+use super::backend::Pod;
+
+/// How the vertices of a draw call are assembled into primitives.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Topology {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    Triangles,
+    TriangleStrip,
+    TriangleFan,
+    /// Patches of the given number of vertices, for tessellation shaders.
+    Patches(GLint),
+}
+
+impl Topology {
+    pub fn to_gl(&self) -> GLenum {
+        match self {
+            Topology::Points => gl::POINTS,
+            Topology::Lines => gl::LINES,
+            Topology::LineStrip => gl::LINE_STRIP,
+            Topology::LineLoop => gl::LINE_LOOP,
+            Topology::Triangles => gl::TRIANGLES,
+            Topology::TriangleStrip => gl::TRIANGLE_STRIP,
+            Topology::TriangleFan => gl::TRIANGLE_FAN,
+            Topology::Patches(_) => gl::PATCHES,
+        }
+    }
+}
+
+/// What part of the vertices or indices a draw call uses and how many
+/// instances it draws.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawParams {
+    pub topology: Topology,
+    /// First vertex, or first index for indexed draws.
+    pub first: usize,
+    /// Number of vertices or indices, everything after `first` if `None`.
+    pub count: Option<usize>,
+    /// Added to every index, ignored by non indexed draws.
+    pub base_vertex: i32,
+    pub instances: usize,
+    pub base_instance: u32,
+}
+
+impl DrawParams {
+    pub fn new(topology: Topology) -> Self {
+        Self {
+            topology,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for DrawParams {
+    fn default() -> Self {
+        Self {
+            topology: Topology::Triangles,
+            first: 0,
+            count: None,
+            base_vertex: 0,
+            instances: 1,
+            base_instance: 0,
+        }
+    }
+}
+
+/// Command read by `VArray::multi_draw_arrays_indirect`, laid out like
+/// OpenGL's `DrawArraysIndirectCommand`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DrawArraysIndirect {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first: u32,
+    pub base_instance: u32,
+}
+
+unsafe impl Pod for DrawArraysIndirect {}
+
+/// Command read by `VArray::multi_draw_elements_indirect`, laid out like
+/// OpenGL's `DrawElementsIndirectCommand`.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DrawElementsIndirect {
+    pub count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub base_vertex: i32,
+    pub base_instance: u32,
+}
+
+unsafe impl Pod for DrawElementsIndirect {}
@@ -0,0 +1,349 @@
+// Offscreen render targets and pixel readback
+// External imports
+use gl::types::*;
+use image::RgbaImage;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+// Crate imports
+use super::backend::Backend;
+use super::texture::Texture;
+
+/// Why a framebuffer can't be drawn to, from `glCheckFramebufferStatus`
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The default framebuffer doesn't exist
+    Undefined,
+    /// An attachment has no storage or a size of 0
+    IncompleteAttachment,
+    MissingAttachment,
+    /// A draw buffer names a color attachment which isn't attached
+    IncompleteDrawBuffer,
+    /// The read buffer names a color attachment which isn't attached
+    IncompleteReadBuffer,
+    /// The combination of formats isn't supported by the driver
+    Unsupported,
+    /// The attachments don't all have the same number of samples
+    IncompleteMultisample,
+    IncompleteLayerTargets,
+    Unknown(GLenum),
+}
+
+impl Error {
+    /// `None` for `gl::FRAMEBUFFER_COMPLETE`
+    pub fn from_status(status: GLenum) -> Option<Self> {
+        match status {
+            gl::FRAMEBUFFER_COMPLETE => None,
+            gl::FRAMEBUFFER_UNDEFINED => Some(Error::Undefined),
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Some(Error::IncompleteAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Some(Error::MissingAttachment),
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => Some(Error::IncompleteDrawBuffer),
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => Some(Error::IncompleteReadBuffer),
+            gl::FRAMEBUFFER_UNSUPPORTED => Some(Error::Unsupported),
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Some(Error::IncompleteMultisample),
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Some(Error::IncompleteLayerTargets),
+            status => Some(Error::Unknown(status)),
+        }
+    }
+}
+
+/// Attachment point of a framebuffer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Attachment {
+    /// `layout(location = i) out` of the fragment shader
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Attachment {
+    pub fn to_gl(self) -> GLenum {
+        match self {
+            Attachment::Color(i) => gl::COLOR_ATTACHMENT0 + i,
+            Attachment::Depth => gl::DEPTH_ATTACHMENT,
+            Attachment::Stencil => gl::STENCIL_ATTACHMENT,
+            Attachment::DepthStencil => gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+/// Storage which can only be drawn to, blitted and read, for the
+/// depth buffers and the multisampled targets
+pub struct Renderbuffer {
+    id: GLuint,
+    backend: Backend,
+    format: GLenum,
+    width: i32,
+    height: i32,
+    samples: i32,
+}
+
+impl Renderbuffer {
+    /// Storage with the sized internal format, like `gl::DEPTH24_STENCIL8`,
+    /// multisampled if `samples` is above 0
+    pub fn new(backend: &Backend, format: GLenum, width: i32, height: i32, samples: i32) -> Self {
+        let id = backend.create_renderbuffer();
+        backend.renderbuffer_storage(id, samples, format, width, height);
+        Self {
+            id,
+            backend: backend.clone(),
+            format,
+            width,
+            height,
+            samples,
+        }
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn format(&self) -> GLenum {
+        self.format
+    }
+
+    pub fn samples(&self) -> i32 {
+        self.samples
+    }
+}
+
+impl Drop for Renderbuffer {
+    fn drop(&mut self) {
+        self.backend.delete_renderbuffer(self.id);
+    }
+}
+
+/// Render target made of textures and renderbuffers, which are borrowed
+/// for as long as the framebuffer lives
+pub struct Framebuffer<'a> {
+    id: GLuint,
+    backend: Backend,
+    // sizes of the attachments, the drawable area being the smallest
+    attachments: RefCell<BTreeMap<Attachment, (i32, i32)>>,
+    draw_buffers: RefCell<Vec<u32>>,
+    explicit_draw_buffers: Cell<bool>,
+    // invariant, the attachments are added through `&self`
+    _attachments: PhantomData<Cell<&'a ()>>,
+}
+
+impl<'a> Framebuffer<'a> {
+    pub fn new(backend: &Backend) -> Self {
+        Self {
+            id: backend.create_framebuffer(),
+            backend: backend.clone(),
+            attachments: RefCell::new(BTreeMap::new()),
+            draw_buffers: RefCell::new(vec![0]),
+            explicit_draw_buffers: Cell::new(false),
+            _attachments: PhantomData,
+        }
+    }
+
+    /// Framebuffer with a color texture and a depth renderbuffer, the usual
+    /// offscreen target, checked for completeness
+    pub fn with_color_depth(
+        backend: &Backend,
+        color: &'a Texture,
+        depth: &'a Renderbuffer,
+    ) -> Result<Self, Error> {
+        let framebuffer = Self::new(backend);
+        framebuffer.attach_texture(Attachment::Color(0), color);
+        framebuffer.attach_renderbuffer(Attachment::Depth, depth);
+        framebuffer.check()?;
+        Ok(framebuffer)
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id
+    }
+
+    /// Draws and reads to the framebuffer until `unbind`, the viewport has to
+    /// be set to its size
+    pub fn bind(&self) {
+        self.backend.bind_framebuffer(gl::FRAMEBUFFER, self.id);
+    }
+
+    /// Goes back to the default framebuffer
+    pub fn unbind(&self) {
+        self.backend.bind_framebuffer(gl::FRAMEBUFFER, 0);
+    }
+
+    /// Attaches the level 0 of the texture
+    pub fn attach_texture(&self, attachment: Attachment, texture: &'a Texture) {
+        self.backend
+            .framebuffer_texture(self.id, attachment.to_gl(), texture.id(), 0);
+        self.attached(attachment, (texture.width(), texture.height()));
+    }
+
+    pub fn attach_renderbuffer(&self, attachment: Attachment, renderbuffer: &'a Renderbuffer) {
+        self.backend
+            .framebuffer_renderbuffer(self.id, attachment.to_gl(), renderbuffer.id());
+        self.attached(attachment, (renderbuffer.width(), renderbuffer.height()));
+    }
+
+    pub fn detach(&self, attachment: Attachment) {
+        self.backend
+            .framebuffer_texture(self.id, attachment.to_gl(), 0, 0);
+        self.attachments.borrow_mut().remove(&attachment);
+        if let Attachment::Color(_) = attachment {
+            self.update_draw_buffers();
+        }
+    }
+
+    fn attached(&self, attachment: Attachment, size: (i32, i32)) {
+        self.attachments.borrow_mut().insert(attachment, size);
+        if let Attachment::Color(_) = attachment {
+            self.update_draw_buffers();
+        }
+    }
+
+    // the color attachments are all drawn to, in order, unless the draw
+    // buffers were set
+    fn update_draw_buffers(&self) {
+        if self.explicit_draw_buffers.get() {
+            return;
+        }
+        let colors: Vec<u32> = self
+            .attachments
+            .borrow()
+            .keys()
+            .filter_map(|attachment| match attachment {
+                Attachment::Color(i) => Some(*i),
+                _ => None,
+            })
+            .collect();
+        // detaching the last color attachment leaves no draw buffer
+        if *self.draw_buffers.borrow() == colors {
+            return;
+        }
+        self.send_draw_buffers(&colors);
+    }
+
+    fn send_draw_buffers(&self, colors: &[u32]) {
+        let buffers: Vec<GLenum> = colors
+            .iter()
+            .map(|i| Attachment::Color(*i).to_gl())
+            .collect();
+        self.backend.framebuffer_draw_buffers(self.id, &buffers);
+        *self.draw_buffers.borrow_mut() = colors.to_vec();
+    }
+
+    /// Color attachments written by the fragment outputs 0, 1, ...
+    pub fn set_draw_buffers(&self, colors: &[u32]) {
+        self.explicit_draw_buffers.set(true);
+        self.send_draw_buffers(colors);
+    }
+
+    pub fn draw_buffers(&self) -> Vec<u32> {
+        self.draw_buffers.borrow().clone()
+    }
+
+    /// Size of the drawable area, the smallest of the attachments
+    pub fn size(&self) -> (i32, i32) {
+        self.attachments
+            .borrow()
+            .values()
+            .copied()
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1)))
+            .unwrap_or((0, 0))
+    }
+
+    /// Whether the framebuffer can be drawn to
+    pub fn check(&self) -> Result<(), Error> {
+        let status = self
+            .backend
+            .check_framebuffer_status(self.id, gl::FRAMEBUFFER);
+        match Error::from_status(status) {
+            None => Ok(()),
+            Some(error) => Err(error),
+        }
+    }
+
+    /// Copies the rectangle `[x0, y0, x1, y1]` of the framebuffer to the one of
+    /// `target`, the default framebuffer if `None`, resolving the samples
+    /// of the multisampled attachments
+    pub fn blit_to(
+        &self,
+        target: Option<&Framebuffer<'_>>,
+        src: [i32; 4],
+        dst: [i32; 4],
+        mask: GLbitfield,
+        filter: GLenum,
+    ) {
+        let target = target.map_or(0, |target| target.id);
+        self.backend
+            .blit_framebuffer(self.id, target, src, dst, mask, filter);
+    }
+
+    /// Resolves the multisampled color attachment 0 into the one of `target`,
+    /// of the same size
+    pub fn resolve_to(&self, target: &Framebuffer<'_>) {
+        let (width, height) = self.size();
+        let rect = [0, 0, width, height];
+        self.set_read_buffer(0);
+        self.blit_to(Some(target), rect, rect, gl::COLOR_BUFFER_BIT, gl::NEAREST);
+    }
+
+    fn set_read_buffer(&self, color: u32) {
+        self.backend
+            .framebuffer_read_buffer(self.id, Attachment::Color(color).to_gl());
+    }
+
+    /// Pixels of the rectangle of the color attachment, top row first, as
+    /// needed for screenshots and comparisons with reference images
+    pub fn read_pixels(&self, color: u32, x: i32, y: i32, width: u32, height: u32) -> RgbaImage {
+        self.set_read_buffer(color);
+        let previous = self.backend.framebuffer_binding(gl::READ_FRAMEBUFFER);
+        self.backend.bind_framebuffer(gl::READ_FRAMEBUFFER, self.id);
+        let image = read_pixels(&self.backend, x, y, width, height);
+        self.backend.bind_framebuffer(gl::READ_FRAMEBUFFER, previous);
+        image
+    }
+
+    /// The whole color attachment
+    pub fn to_image(&self, color: u32) -> RgbaImage {
+        let (width, height) = self.size();
+        self.read_pixels(color, 0, 0, width.max(0) as u32, height.max(0) as u32)
+    }
+}
+
+impl Drop for Framebuffer<'_> {
+    fn drop(&mut self) {
+        self.backend.delete_framebuffer(self.id);
+    }
+}
+
+/// Pixels of the rectangle of the bound read framebuffer, top row first.
+/// With the default framebuffer bound, this takes a screenshot
+///
+/// Panics if the backend doesn't give 4 bytes per pixel
+pub fn read_pixels(backend: &Backend, x: i32, y: i32, width: u32, height: u32) -> RgbaImage {
+    let data = backend.read_pixels(
+        x,
+        y,
+        width as GLsizei,
+        height as GLsizei,
+        gl::RGBA,
+        gl::UNSIGNED_BYTE,
+    );
+    let row = width as usize * 4;
+    assert_eq!(
+        data.len(),
+        row * height as usize,
+        "The backend read the wrong number of bytes!"
+    );
+    // OpenGL gives the bottom row first
+    let mut flipped = Vec::with_capacity(data.len());
+    for line in data.chunks_exact(row.max(1)).rev() {
+        flipped.extend_from_slice(line);
+    }
+    RgbaImage::from_raw(width, height, flipped).unwrap()
+}
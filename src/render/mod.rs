@@ -1,11 +1,25 @@
+pub mod backend;
+pub mod block;
+pub mod compute;
+pub mod draw;
+pub mod framebuffer;
 pub mod shader;
 pub mod varray;
 pub mod texture;
 pub mod buffer;
+pub mod reflection;
+pub mod uniform;
 //pub mod camera;
 
+pub use backend::{Backend, Pod, RenderBackend};
+pub use block::*;
+pub use compute::*;
 pub use buffer::*;
+pub use draw::*;
+pub use framebuffer::*;
 pub use shader::*;
 pub use varray::*;
 //pub use camera::*;
 pub use texture::*;
+pub use reflection::*;
+pub use uniform::*;
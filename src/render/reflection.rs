@@ -0,0 +1,179 @@
+// What a linked program uses: vertex inputs, uniforms and interface blocks
+// External imports
+use gl::types::*;
+use std::collections::HashSet;
+// Crate imports
+use super::uniform::glsl_type_name;
+use super::varray::VbLayout;
+
+#[derive(Debug)]
+pub enum Error {
+    /// No element of the layouts feeds the vertex input
+    MissingAttribute { name: String, location: GLint },
+    /// Integer and double inputs need an attribute format of their base
+    /// type, `VArray` only sets float ones
+    BaseTypeMismatch {
+        name: String,
+        location: GLint,
+        gtype: &'static str,
+    },
+}
+
+/// Vertex shader input
+#[derive(Clone, Debug, PartialEq)]
+pub struct AttributeInfo {
+    pub name: String,
+    /// -1 for the built-in inputs like `gl_VertexID`
+    pub location: GLint,
+    pub gtype: GLenum,
+    pub size: GLint,
+}
+
+/// Uniform of the default block, set with `ShaderProgram::set_uniform`
+#[derive(Clone, Debug, PartialEq)]
+pub struct UniformInfo {
+    /// Arrays end with `[0]`
+    pub name: String,
+    pub location: GLint,
+    pub gtype: GLenum,
+    pub size: GLint,
+}
+
+/// Member of a uniform or storage block, offsets in bytes
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockMember {
+    pub name: String,
+    pub gtype: GLenum,
+    pub size: GLint,
+    pub offset: GLint,
+    /// 0 if it is not an array
+    pub array_stride: GLint,
+    /// 0 if it is not a matrix
+    pub matrix_stride: GLint,
+}
+
+/// Uniform or storage block
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockInfo {
+    pub name: String,
+    /// Binding point given with `binding =` or `glUniformBlockBinding`
+    pub binding: GLuint,
+    /// Minimal size of the buffer range in bytes
+    pub data_size: usize,
+    pub members: Vec<BlockMember>,
+}
+
+impl BlockInfo {
+    pub fn member(&self, name: &str) -> Option<&BlockMember> {
+        self.members.iter().find(|member| member.name == name)
+    }
+}
+
+/// Active resources of a linked program, as given by the program interface queries
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProgramReflection {
+    pub attributes: Vec<AttributeInfo>,
+    pub uniforms: Vec<UniformInfo>,
+    pub uniform_blocks: Vec<BlockInfo>,
+    pub storage_blocks: Vec<BlockInfo>,
+}
+
+/// Number of attribute locations of a GLSL type, one per matrix column
+fn attribute_locations(gtype: GLenum) -> GLint {
+    match gtype {
+        gl::FLOAT_MAT2 | gl::FLOAT_MAT2x3 | gl::FLOAT_MAT2x4 => 2,
+        gl::FLOAT_MAT3 | gl::FLOAT_MAT3x2 | gl::FLOAT_MAT3x4 => 3,
+        gl::FLOAT_MAT4 | gl::FLOAT_MAT4x2 | gl::FLOAT_MAT4x3 => 4,
+        _ => 1,
+    }
+}
+
+/// Whether the input is read with `glVertexAttribIFormat` or `glVertexAttribLFormat`
+fn needs_non_float_format(gtype: GLenum) -> bool {
+    matches!(
+        gtype,
+        gl::INT
+            | gl::INT_VEC2
+            | gl::INT_VEC3
+            | gl::INT_VEC4
+            | gl::UNSIGNED_INT
+            | gl::UNSIGNED_INT_VEC2
+            | gl::UNSIGNED_INT_VEC3
+            | gl::UNSIGNED_INT_VEC4
+            | gl::DOUBLE
+            | gl::DOUBLE_VEC2
+            | gl::DOUBLE_VEC3
+            | gl::DOUBLE_VEC4
+    )
+}
+
+impl ProgramReflection {
+    pub fn attribute(&self, name: &str) -> Option<&AttributeInfo> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
+    /// Uniform of the default block, arrays found with or without `[0]`
+    pub fn uniform(&self, name: &str) -> Option<&UniformInfo> {
+        self.uniforms
+            .iter()
+            .find(|u| u.name == name || u.name.trim_end_matches("[0]") == name)
+    }
+
+    pub fn uniform_block(&self, name: &str) -> Option<&BlockInfo> {
+        self.uniform_blocks.iter().find(|b| b.name == name)
+    }
+
+    pub fn storage_block(&self, name: &str) -> Option<&BlockInfo> {
+        self.storage_blocks.iter().find(|b| b.name == name)
+    }
+
+    /// Checks the layouts, added in order with `VArray::add_buffer`, against
+    /// the vertex inputs, see `validate_buffers`
+    pub fn validate_layouts(&self, layouts: &[&VbLayout]) -> Result<(), Error> {
+        let mut first_attribute: GLuint = 0;
+        let buffers: Vec<(GLuint, &VbLayout)> = layouts
+            .iter()
+            .map(|layout| {
+                let buffer = (first_attribute, *layout);
+                first_attribute += layout.attribute_count() as GLuint;
+                buffer
+            })
+            .collect();
+        self.validate_buffers(&buffers)
+    }
+
+    /// Checks the layouts, each with the `first_attribute` it was given to
+    /// `VArray::set_buffer`, against the vertex inputs
+    ///
+    /// Every location of an input needs an element. The number of components
+    /// may differ, the missing ones being filled with `(0, 0, 0, 1)` and the
+    /// extra ones dropped. Elements no input uses are fine, the compiler
+    /// removes the unused inputs.
+    pub fn validate_buffers(&self, buffers: &[(GLuint, &VbLayout)]) -> Result<(), Error> {
+        let locations: HashSet<GLint> = buffers
+            .iter()
+            .flat_map(|(first, layout)| {
+                (0..layout.attribute_count()).map(move |i| (first + i as GLuint) as GLint)
+            })
+            .collect();
+        for attribute in self.attributes.iter().filter(|a| a.location >= 0) {
+            for i in 0..attribute_locations(attribute.gtype) * attribute.size.max(1) {
+                let location = attribute.location + i;
+                if !locations.contains(&location) {
+                    return Err(Error::MissingAttribute {
+                        name: attribute.name.clone(),
+                        location,
+                    });
+                }
+            }
+            if needs_non_float_format(attribute.gtype) {
+                return Err(Error::BaseTypeMismatch {
+                    name: attribute.name.clone(),
+                    location: attribute.location,
+                    gtype: glsl_type_name(attribute.gtype),
+                });
+            }
+        }
+        Ok(())
+    }
+}
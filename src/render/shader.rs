@@ -1,7 +1,10 @@
 // External imports
 use gl::types::*;
-use std::{collections::HashMap, ffi::CString};
+use std::{cell::RefCell, collections::HashMap, ffi::CString, rc::Rc};
 // Crate imports
+use super::backend::{ActiveVariable, Backend};
+use super::reflection::ProgramReflection;
+use super::uniform::{glsl_type_name, UniformData};
 use crate::ressources::{self, RessourceLoader};
 
 #[derive(Debug)]
@@ -21,6 +24,27 @@ pub enum Error {
         name: String,
         message: String,
     },
+    /// The program has no active uniform of that name, it may have been optimized out
+    UnknownUniform {
+        name: String,
+        available: Vec<String>,
+    },
+    /// The value can't be assigned to the GLSL type of the uniform
+    UniformTypeMismatch {
+        name: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// More values than the uniform array has elements
+    UniformOutOfBounds {
+        name: String,
+        size: usize,
+        end: usize,
+    },
+    /// The uniform has no location, being in a uniform block
+    UniformWithoutLocation {
+        name: String,
+    },
 }
 
 const POSSIBLE_EXT: [(&str, gl::types::GLenum); 6] = [
@@ -32,24 +56,18 @@ const POSSIBLE_EXT: [(&str, gl::types::GLenum); 6] = [
     (".comp", gl::COMPUTE_SHADER),
 ];
 
-fn create_empty_cstring_with_len(len: usize) -> CString {
-    // allocate buffer of correct size
-    let mut buffer: Vec<u8> = Vec::with_capacity(len + 1);
-    // fill it with len spaces
-    buffer.extend([b' '].iter().cycle().take(len));
-    // convert buffer to CString
-    unsafe { CString::from_vec_unchecked(buffer) }
-}
-
 pub struct ShaderProgram {
     _id: GLuint,
     shaders : Vec<Shader>,
-    //uniforms : HashMap<String, UType>,
-    gl: gl::Gl,
+    // active uniforms by name, arrays without the `[0]`, queried on first use
+    uniforms : RefCell<Option<HashMap<String, ActiveVariable>>>,
+    locations : RefCell<HashMap<String, GLint>>,
+    reflection : RefCell<Option<Rc<ProgramReflection>>>,
+    backend: Backend,
 }
 
 impl ShaderProgram {
-    pub fn from_res(gl: &gl::Gl, res: &RessourceLoader, name: &str) -> Result<Self, Error> {
+    pub fn from_res(backend: &Backend, res: &RessourceLoader, name: &str) -> Result<Self, Error> {
         let ressources_names : Vec<(String,GLenum)> = POSSIBLE_EXT
             .iter()
             // get all coresponding names
@@ -61,51 +79,23 @@ impl ShaderProgram {
         // create the actual shaders from ressources
         let shaders = ressources_names
             .iter()
-            .map(|(ressource_name,stype)| Shader::from_res(gl, res, ressource_name, *stype))
+            .map(|(ressource_name,stype)| Shader::from_res(backend, res, ressource_name, *stype))
             .collect::<Result<Vec<Shader>, Error>>()?;
         // link the shaders into a Progra
-        ShaderProgram::from_shaders(gl, shaders).map_err(|message| Error::LinkError {
+        ShaderProgram::from_shaders(backend, shaders).map_err(|message| Error::LinkError {
             name: name.to_owned(),
             message,
         })
     }
 
-    pub fn from_shaders(gl: &gl::Gl, shaders: Vec<Shader>) -> Result<Self, String> {
-        let id = unsafe { gl.CreateProgram() };
+    pub fn from_shaders(backend: &Backend, shaders: Vec<Shader>) -> Result<Self, String> {
+        let id = backend.create_program();
 
-        for shader in shaders.clone() {
-            unsafe {
-                gl.AttachShader(id, shader.id());
-            }
-        }
-        unsafe {
-            gl.LinkProgram(id);
+        for shader in shaders.iter() {
+            backend.attach_shader(id, shader.id());
         }
 
-        let mut success: GLint = 1;
-        unsafe {
-            gl.GetProgramiv(id, gl::LINK_STATUS, &mut success);
-        }
-
-        if success == 0 {
-            let mut len: gl::types::GLint = 0;
-            unsafe {
-                gl.GetProgramiv(id, gl::INFO_LOG_LENGTH, &mut len);
-            }
-
-            let error = create_empty_cstring_with_len(len as usize);
-
-            unsafe {
-                gl.GetProgramInfoLog(
-                    id,
-                    len,
-                    std::ptr::null_mut(),
-                    error.as_ptr() as *mut gl::types::GLchar,
-                );
-            }
-
-            return Err(format!("{}", error.to_string_lossy().into_owned()));
-        }
+        backend.link_program(id)?;
 
         // for shader in shaders {
         //     unsafe {
@@ -115,9 +105,11 @@ impl ShaderProgram {
 
         Ok(Self {
             _id: id,
-            //uniforms : HashMap::new(),
+            uniforms : RefCell::new(None),
+            locations : RefCell::new(HashMap::new()),
+            reflection : RefCell::new(None),
             shaders,
-            gl: gl.clone(),
+            backend: backend.clone(),
         })
     }
 
@@ -125,23 +117,110 @@ impl ShaderProgram {
         self._id
     }
 
-    pub fn bind(&self) {
-        unsafe {
-            self.gl.UseProgram(self._id);
+    /// Inputs, uniforms and blocks of the program, queried on first use
+    pub fn reflection(&self) -> Rc<ProgramReflection> {
+        self.reflection
+            .borrow_mut()
+            .get_or_insert_with(|| Rc::new(self.backend.program_reflection(self._id)))
+            .clone()
+    }
+
+    /// Active uniforms of the program, by name
+    pub fn uniforms(&self) -> HashMap<String, ActiveVariable> {
+        self.with_uniforms(|uniforms| uniforms.clone())
+    }
+
+    fn with_uniforms<T>(&self, f: impl FnOnce(&HashMap<String, ActiveVariable>) -> T) -> T {
+        let mut uniforms = self.uniforms.borrow_mut();
+        let uniforms = uniforms.get_or_insert_with(|| {
+            self.backend
+                .active_uniforms(self._id)
+                .into_iter()
+                .map(|uniform| (uniform.name.trim_end_matches("[0]").to_owned(), uniform))
+                .collect()
+        });
+        f(uniforms)
+    }
+
+    /// Cached location of the uniform, -1 if it has none
+    pub fn uniform_location(&self, name: &str) -> GLint {
+        if let Some(location) = self.locations.borrow().get(name) {
+            return *location;
         }
+        let location = self.backend.uniform_location(self._id, name);
+        self.locations.borrow_mut().insert(name.to_owned(), location);
+        location
     }
-    pub fn unbind(&self) {
-        unsafe {
-            self.gl.UseProgram(0 as GLuint);
+
+    /// Sets the uniform to a value or, for arrays, to a slice of values
+    ///
+    /// `name[i]` sets the array from the element `i`. The program doesn't
+    /// need to be bound, the value is checked against the type of the uniform.
+    pub fn set_uniform<T>(&self, name: &str, value: &T) -> Result<(), Error>
+    where
+        T: UniformData + ?Sized,
+    {
+        // `lights[2]` is the element 2 of the array `lights`
+        let (base, first) = name
+            .strip_suffix(']')
+            .and_then(|name| name.rsplit_once('['))
+            .and_then(|(base, index)| index.parse::<usize>().ok().map(|index| (base, index)))
+            .unwrap_or((name, 0));
+        let uniform = self.with_uniforms(|uniforms| match uniforms.get(base) {
+            Some(uniform) => Ok(uniform.clone()),
+            None => {
+                let mut available: Vec<String> = uniforms.keys().cloned().collect();
+                available.sort();
+                Err(Error::UnknownUniform {
+                    name: name.to_owned(),
+                    available,
+                })
+            }
+        })?;
+        if !T::accepts(uniform.gtype) {
+            return Err(Error::UniformTypeMismatch {
+                name: name.to_owned(),
+                expected: glsl_type_name(uniform.gtype),
+                found: std::any::type_name::<T>(),
+            });
+        }
+        let value = value.value();
+        let size = uniform.size as usize;
+        if first + value.count() > size {
+            return Err(Error::UniformOutOfBounds {
+                name: name.to_owned(),
+                size,
+                end: first + value.count(),
+            });
         }
+        if value.count() == 0 {
+            return Ok(());
+        }
+        // the elements of an array follow the location of the first one set
+        let element = if uniform.name.ends_with("[0]") {
+            format!("{}[{}]", base, first)
+        } else {
+            base.to_owned()
+        };
+        let location = self.uniform_location(&element);
+        if location < 0 {
+            return Err(Error::UniformWithoutLocation { name: element });
+        }
+        self.backend.set_uniform(self._id, location, &value);
+        Ok(())
+    }
+
+    pub fn bind(&self) {
+        self.backend.use_program(self._id);
+    }
+    pub fn unbind(&self) {
+        self.backend.use_program(0 as GLuint);
     }
 }
 
 impl Drop for ShaderProgram {
     fn drop(&mut self) {
-        unsafe {
-            self.gl.DeleteProgram(self._id);
-        }
+        self.backend.delete_program(self._id);
     }
 }
 
@@ -150,64 +229,38 @@ impl Drop for ShaderProgram {
 pub struct Shader {
     _id: GLuint,
     source: CString,
-    gl: gl::Gl,
+    backend: Backend,
 }
 
 impl Shader {
 
-    pub fn from_res(gl: &gl::Gl, res: &RessourceLoader, name: &str, stype : GLenum) -> Result<Shader, Error> {
+    pub fn from_res(backend: &Backend, res: &RessourceLoader, name: &str, stype : GLenum) -> Result<Shader, Error> {
         let source = res.load_cstring(name).map_err(|e| Error::ResourceLoad {
             name: name.to_owned(),
             inner: e,
         })?;
 
-        Shader::from_source(gl, source, stype).map_err(|e| Error::CompileError {
+        Shader::from_source(backend, source, stype).map_err(|e| Error::CompileError {
             name: name.to_owned(),
             message: e,
         })
     }
 
-    pub fn from_source(gl: &gl::Gl, source: CString, shader_type: GLenum) -> Result<Self, String> {
-        let id = unsafe { gl.CreateShader(shader_type) };
-
-        unsafe {
-            gl.ShaderSource(id, 1, &source[..].as_ptr(), std::ptr::null());
-            gl.CompileShader(id);
-        }
+    pub fn from_source(backend: &Backend, source: CString, shader_type: GLenum) -> Result<Self, String> {
+        let id = backend.create_shader(shader_type);
 
         // check if the compilation was successfull and print the error message if it isn't.
-        let mut success: GLint = 1;
-        unsafe {
-            gl.GetShaderiv(id, gl::COMPILE_STATUS, &mut success);
-        }
-
-        if success == 0 {
-            let mut len: gl::types::GLint = 0;
-            unsafe {
-                gl.GetShaderiv(id, gl::INFO_LOG_LENGTH, &mut len);
-            }
-
-            let error = create_empty_cstring_with_len(len as usize);
-
-            unsafe {
-                gl.GetShaderInfoLog(
-                    id,
-                    len,
-                    std::ptr::null_mut(),
-                    error.as_ptr() as *mut gl::types::GLchar,
-                );
-            }
-
+        if let Err(error) = backend.compile_shader(id, &source) {
             return Err(format!(
                 "There was an error in the shader compilation: {}\n",
-                error.to_string_lossy().into_owned()
+                error
             ));
         }
 
         Ok(Self {
             _id: id,
             source,
-            gl: gl.clone(),
+            backend: backend.clone(),
         })
     }
 
@@ -218,9 +271,7 @@ impl Shader {
 
 impl Drop for Shader {
     fn drop(&mut self) {
-        unsafe {
-            self.gl.DeleteShader(self._id);
-        }
+        self.backend.delete_shader(self._id);
     }
 }
 
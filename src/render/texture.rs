@@ -2,6 +2,7 @@
 use gl::types::*;
 use image::io::Reader as ImageReader;
 // Crate imports
+use super::backend::Backend;
 use crate::utils::color::rgb::RgbColor;
 use crate::ressources::{self, RessourceLoader};
 
@@ -13,10 +14,11 @@ pub enum Error {
 
 pub struct Texture {
     id : GLuint,
-    gl : gl::Gl,
+    backend : Backend,
     data : Vec<u8>,
     width : i32,
     height : i32,
+    format : GLenum,
 }
 
 
@@ -32,7 +34,23 @@ const POSSIBLE_EXT: [&str; 6] = [
 
 impl Texture {
 
-    pub fn from_res(gl : &gl::Gl, res : &RessourceLoader, name : &str) -> Result<Self, Error> {
+    /// Creates an empty 2D texture with the sized internal format, like `gl::RGBA32F`
+    pub fn new(backend : &Backend, width : i32, height : i32, format : GLenum) -> Self {
+        let id = backend.create_texture(gl::TEXTURE_2D);
+        backend.texture_storage_2d(id, 1, format, width, height);
+        backend.texture_parameter(id, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        backend.texture_parameter(id, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+        Self {
+            id,
+            backend : backend.clone(),
+            data : Vec::new(),
+            width,
+            height,
+            format,
+        }
+    }
+
+    pub fn from_res(backend : &Backend, res : &RessourceLoader, name : &str) -> Result<Self, Error> {
         let ressources_names = POSSIBLE_EXT
             .iter()
             .map(|(file_ext)| format!("{}{}", name, file_ext));
@@ -62,26 +80,24 @@ impl Texture {
 
         let width = dims.1 as i32;
         let height = dims.2 as i32;
-        let mut id = 0;
         let data = flat.as_slice();
-        unsafe {
-            gl.CreateTextures(gl::TEXTURE_2D, 1, &mut id);
-            gl.TextureStorage2D(id, 1, storage_format, width, height);
-            gl.TextureParameteri(id, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
-            gl.TextureParameteri(id, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
-            gl.TextureSubImage2D(id, 0, 0, 0, width, height, data_format, gl::UNSIGNED_BYTE, data.as_ptr() as *const GLvoid)
-        }
+        let id = backend.create_texture(gl::TEXTURE_2D);
+        backend.texture_storage_2d(id, 1, storage_format, width, height);
+        backend.texture_parameter(id, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+        backend.texture_parameter(id, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+        backend.texture_sub_image_2d(id, 0, 0, 0, width, height, data_format, gl::UNSIGNED_BYTE, data);
 
         Ok(Self {
             id,
-            gl : gl.clone(),
+            backend : backend.clone(),
             data : data.to_vec(),
             width,
             height,
-
+            format : storage_format,
         })
     }
 
+    /// Changes the CPU copy of a loaded texture, the ones made by `new` have none
     pub fn set_pixel(&mut self, i : usize, j : usize, color : RgbColor) {
         self.data[(i * self.width as usize + j) * 3] = color.red;
         self.data[(i * self.width as usize + j) * 3 + 1] = color.blue;
@@ -93,8 +109,27 @@ impl Texture {
         self.id
     }
 
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Sized internal format of the storage
+    pub fn format(&self) -> GLenum {
+        self.format
+    }
+
     pub fn bind(&self, slot : GLuint) {
-        unsafe {self.gl.BindTextureUnit(slot, self.id)};
+        self.backend.bind_texture_unit(slot, self.id);
     }
 
+}
+
+impl Drop for Texture {
+    fn drop(&mut self) {
+        self.backend.delete_texture(self.id);
+    }
 }
\ No newline at end of file
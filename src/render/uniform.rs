@@ -0,0 +1,323 @@
+// Rust values which can be given to a program uniform, checked against its GLSL type
+// External imports
+use gl::types::*;
+// Crate imports
+use super::backend::UniformValue;
+use crate::math::{Mat3, Mat4, Vec2D, Vec3D, Vec4D};
+
+/// Texture unit given to a sampler uniform
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampler(pub u32);
+
+/// Value of one uniform, or one element of a uniform array
+pub trait Uniform {
+    /// Whether the value can be assigned to a uniform of the GLSL type
+    fn accepts(gtype: GLenum) -> bool;
+    fn value(&self) -> UniformValue;
+    /// Array variant of the values, set with a single call
+    fn array(values: &[Self]) -> UniformValue
+    where
+        Self: Sized;
+}
+
+/// What `ShaderProgram::set_uniform` takes: a value or a slice of values
+pub trait UniformData {
+    fn accepts(gtype: GLenum) -> bool;
+    fn value(&self) -> UniformValue;
+}
+
+impl<T: Uniform> UniformData for T {
+    fn accepts(gtype: GLenum) -> bool {
+        T::accepts(gtype)
+    }
+
+    fn value(&self) -> UniformValue {
+        Uniform::value(self)
+    }
+}
+
+impl<T: Uniform> UniformData for [T] {
+    fn accepts(gtype: GLenum) -> bool {
+        T::accepts(gtype)
+    }
+
+    fn value(&self) -> UniformValue {
+        T::array(self)
+    }
+}
+
+impl Uniform for f32 {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::FLOAT
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Float(*self)
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::FloatArray(values.to_vec())
+    }
+}
+
+impl Uniform for i32 {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::INT || gtype == gl::BOOL || is_sampler(gtype) || is_image(gtype)
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Int(*self)
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::IntArray(values.to_vec())
+    }
+}
+
+impl Uniform for u32 {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::UNSIGNED_INT || gtype == gl::BOOL
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::UInt(*self)
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::UIntArray(values.to_vec())
+    }
+}
+
+impl Uniform for bool {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::BOOL
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Int(*self as i32)
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::IntArray(values.iter().map(|v| *v as i32).collect())
+    }
+}
+
+impl Uniform for Sampler {
+    fn accepts(gtype: GLenum) -> bool {
+        is_sampler(gtype)
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Int(self.0 as i32)
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::IntArray(values.iter().map(|v| v.0 as i32).collect())
+    }
+}
+
+impl Uniform for Vec2D {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::FLOAT_VEC2
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Vec2([self.x, self.y])
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::Vec2Array(values.iter().map(|v| [v.x, v.y]).collect())
+    }
+}
+
+impl Uniform for Vec3D {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::FLOAT_VEC3
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Vec3([self.x, self.y, self.z])
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::Vec3Array(values.iter().map(|v| [v.x, v.y, v.z]).collect())
+    }
+}
+
+impl Uniform for Vec4D {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::FLOAT_VEC4
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Vec4([self.x, self.y, self.z, self.w])
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::Vec4Array(values.iter().map(|v| [v.x, v.y, v.z, v.w]).collect())
+    }
+}
+
+impl Uniform for Mat3 {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::FLOAT_MAT3
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Mat3(mat3_columns(self))
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::Mat3Array(values.iter().map(mat3_columns).collect())
+    }
+}
+
+// GLSL matrices are column major
+fn mat3_columns(matrix: &Mat3) -> [f32; 9] {
+    let mut columns = [0_f32; 9];
+    for (k, value) in columns.iter_mut().enumerate() {
+        *value = matrix.get(k % 3, k / 3);
+    }
+    columns
+}
+
+impl Uniform for Mat4 {
+    fn accepts(gtype: GLenum) -> bool {
+        gtype == gl::FLOAT_MAT4
+    }
+
+    fn value(&self) -> UniformValue {
+        UniformValue::Mat4(self.to_cols_array())
+    }
+
+    fn array(values: &[Self]) -> UniformValue {
+        UniformValue::Mat4Array(values.iter().map(Mat4::to_cols_array).collect())
+    }
+}
+
+/// Whether the GLSL type is a sampler, set with the texture unit
+pub fn is_sampler(gtype: GLenum) -> bool {
+    matches!(
+        gtype,
+        gl::SAMPLER_1D
+            | gl::SAMPLER_2D
+            | gl::SAMPLER_3D
+            | gl::SAMPLER_CUBE
+            | gl::SAMPLER_1D_SHADOW
+            | gl::SAMPLER_2D_SHADOW
+            | gl::SAMPLER_1D_ARRAY
+            | gl::SAMPLER_2D_ARRAY
+            | gl::SAMPLER_1D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_MULTISAMPLE
+            | gl::SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::SAMPLER_CUBE_SHADOW
+            | gl::SAMPLER_CUBE_MAP_ARRAY
+            | gl::SAMPLER_CUBE_MAP_ARRAY_SHADOW
+            | gl::SAMPLER_BUFFER
+            | gl::SAMPLER_2D_RECT
+            | gl::SAMPLER_2D_RECT_SHADOW
+            | gl::INT_SAMPLER_2D
+            | gl::INT_SAMPLER_3D
+            | gl::INT_SAMPLER_CUBE
+            | gl::INT_SAMPLER_2D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_2D
+            | gl::UNSIGNED_INT_SAMPLER_3D
+            | gl::UNSIGNED_INT_SAMPLER_CUBE
+            | gl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+    )
+}
+
+/// Whether the GLSL type is an image, set with the image unit
+pub fn is_image(gtype: GLenum) -> bool {
+    matches!(
+        gtype,
+        gl::IMAGE_1D
+            | gl::IMAGE_2D
+            | gl::IMAGE_3D
+            | gl::IMAGE_2D_RECT
+            | gl::IMAGE_CUBE
+            | gl::IMAGE_BUFFER
+            | gl::IMAGE_1D_ARRAY
+            | gl::IMAGE_2D_ARRAY
+            | gl::IMAGE_CUBE_MAP_ARRAY
+            | gl::IMAGE_2D_MULTISAMPLE
+            | gl::IMAGE_2D_MULTISAMPLE_ARRAY
+            | gl::INT_IMAGE_1D
+            | gl::INT_IMAGE_2D
+            | gl::INT_IMAGE_3D
+            | gl::INT_IMAGE_2D_RECT
+            | gl::INT_IMAGE_CUBE
+            | gl::INT_IMAGE_BUFFER
+            | gl::INT_IMAGE_1D_ARRAY
+            | gl::INT_IMAGE_2D_ARRAY
+            | gl::INT_IMAGE_CUBE_MAP_ARRAY
+            | gl::INT_IMAGE_2D_MULTISAMPLE
+            | gl::INT_IMAGE_2D_MULTISAMPLE_ARRAY
+            | gl::UNSIGNED_INT_IMAGE_1D
+            | gl::UNSIGNED_INT_IMAGE_2D
+            | gl::UNSIGNED_INT_IMAGE_3D
+            | gl::UNSIGNED_INT_IMAGE_2D_RECT
+            | gl::UNSIGNED_INT_IMAGE_CUBE
+            | gl::UNSIGNED_INT_IMAGE_BUFFER
+            | gl::UNSIGNED_INT_IMAGE_1D_ARRAY
+            | gl::UNSIGNED_INT_IMAGE_2D_ARRAY
+            | gl::UNSIGNED_INT_IMAGE_CUBE_MAP_ARRAY
+            | gl::UNSIGNED_INT_IMAGE_2D_MULTISAMPLE
+            | gl::UNSIGNED_INT_IMAGE_2D_MULTISAMPLE_ARRAY
+    )
+}
+
+/// GLSL name of the type, for the error messages
+pub fn glsl_type_name(gtype: GLenum) -> &'static str {
+    match gtype {
+        gl::FLOAT => "float",
+        gl::FLOAT_VEC2 => "vec2",
+        gl::FLOAT_VEC3 => "vec3",
+        gl::FLOAT_VEC4 => "vec4",
+        gl::DOUBLE => "double",
+        gl::DOUBLE_VEC2 => "dvec2",
+        gl::DOUBLE_VEC3 => "dvec3",
+        gl::DOUBLE_VEC4 => "dvec4",
+        gl::INT => "int",
+        gl::INT_VEC2 => "ivec2",
+        gl::INT_VEC3 => "ivec3",
+        gl::INT_VEC4 => "ivec4",
+        gl::UNSIGNED_INT => "uint",
+        gl::UNSIGNED_INT_VEC2 => "uvec2",
+        gl::UNSIGNED_INT_VEC3 => "uvec3",
+        gl::UNSIGNED_INT_VEC4 => "uvec4",
+        gl::BOOL => "bool",
+        gl::BOOL_VEC2 => "bvec2",
+        gl::BOOL_VEC3 => "bvec3",
+        gl::BOOL_VEC4 => "bvec4",
+        gl::FLOAT_MAT2 => "mat2",
+        gl::FLOAT_MAT3 => "mat3",
+        gl::FLOAT_MAT4 => "mat4",
+        gl::FLOAT_MAT2x3 => "mat2x3",
+        gl::FLOAT_MAT2x4 => "mat2x4",
+        gl::FLOAT_MAT3x2 => "mat3x2",
+        gl::FLOAT_MAT3x4 => "mat3x4",
+        gl::FLOAT_MAT4x2 => "mat4x2",
+        gl::FLOAT_MAT4x3 => "mat4x3",
+        gl::SAMPLER_1D => "sampler1D",
+        gl::SAMPLER_2D => "sampler2D",
+        gl::SAMPLER_3D => "sampler3D",
+        gl::SAMPLER_CUBE => "samplerCube",
+        gl::SAMPLER_2D_SHADOW => "sampler2DShadow",
+        gl::SAMPLER_2D_ARRAY => "sampler2DArray",
+        gl::SAMPLER_2D_MULTISAMPLE => "sampler2DMS",
+        gl::SAMPLER_CUBE_MAP_ARRAY => "samplerCubeArray",
+        gl::SAMPLER_BUFFER => "samplerBuffer",
+        gl::INT_SAMPLER_2D => "isampler2D",
+        gl::UNSIGNED_INT_SAMPLER_2D => "usampler2D",
+        gl::IMAGE_2D => "image2D",
+        gl::IMAGE_3D => "image3D",
+        gl::IMAGE_2D_ARRAY => "image2DArray",
+        gl::INT_IMAGE_2D => "iimage2D",
+        gl::UNSIGNED_INT_IMAGE_2D => "uimage2D",
+        _ if is_sampler(gtype) => "sampler",
+        _ if is_image(gtype) => "image",
+        _ => "unknown",
+    }
+}
@@ -1,7 +1,14 @@
 // External imports
 use gl;
 use gl::types::*;
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
+use std::rc::Rc;
 // Crate imports
+use super::backend::Backend;
+use super::draw::{DrawArraysIndirect, DrawElementsIndirect, DrawParams, Topology};
+use crate::math::Mat4;
+use super::DrawIndirectBuffer;
 use super::IndexBuffer;
 use super::VertexBuffer;
 
@@ -20,6 +27,15 @@ impl VbElements {
         }
     }
 
+    pub fn etype(&self) -> GLenum {
+        self.etype
+    }
+
+    /// Number of components
+    pub fn count(&self) -> GLint {
+        self.count
+    }
+
     pub fn get_type_size(etype: GLenum) -> GLuint {
         match etype {
             gl::FLOAT => 4,
@@ -33,6 +49,7 @@ impl VbElements {
 pub struct VbLayout {
     elements: Vec<VbElements>,
     stride: GLsizei,
+    divisor: GLuint,
 }
 
 impl VbLayout {
@@ -40,6 +57,45 @@ impl VbLayout {
         Self {
             elements: Vec::new(),
             stride: 0,
+            divisor: 0,
+        }
+    }
+
+    /// Layout of one `Mat4` per instance, as four column attributes
+    pub fn instance_transforms() -> Self {
+        let mut layout = Self::new();
+        layout.push_mat4();
+        layout.set_divisor(1);
+        layout
+    }
+
+    /// Elements in attribute location order
+    pub fn elements(&self) -> &[VbElements] {
+        &self.elements
+    }
+
+    /// Number of attribute locations used
+    pub fn attribute_count(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn stride(&self) -> GLsizei {
+        self.stride
+    }
+
+    pub fn divisor(&self) -> GLuint {
+        self.divisor
+    }
+
+    /// Advance the attributes every `divisor` instances instead of every vertex, 0 being per vertex
+    pub fn set_divisor(&mut self, divisor: GLuint) {
+        self.divisor = divisor;
+    }
+
+    /// A matrix takes four attribute locations, one per column
+    pub fn push_mat4(&mut self) {
+        for _ in 0..4 {
+            self.push_f32(4);
         }
     }
 
@@ -64,72 +120,253 @@ impl VbLayout {
 
 pub struct VArray {
     _id: GLuint,
-    gl: gl::Gl,
+    backend: Backend,
+    // binding, data size and stride of the per vertex buffer last added
+    vertices: RefCell<Option<(GLuint, Rc<Cell<usize>>, usize)>>,
+    next_attribute: Cell<GLuint>,
+    next_binding: Cell<GLuint>,
 }
 
 impl VArray {
-    pub fn new(gl: &gl::Gl) -> Self {
-        let mut id: GLuint = 0;
-        unsafe { gl.GenVertexArrays(1, &mut id) }
+    pub fn new(backend: &Backend) -> Self {
+        let id = backend.create_vertex_array();
         Self {
             _id: id,
-            gl: gl.clone(),
+            backend: backend.clone(),
+            vertices: RefCell::new(None),
+            next_attribute: Cell::new(0),
+            next_binding: Cell::new(0),
         }
     }
 
+    /// Adds the buffer on the next free binding index, its attributes
+    /// following the ones of the buffers already added
     pub fn add_buffer(&self, vertex_buffer: &VertexBuffer, vertex_buffer_layout: &VbLayout) {
-        self.bind();
-        vertex_buffer.bind();
+        self.set_buffer(
+            self.next_binding.get(),
+            self.next_attribute.get(),
+            vertex_buffer,
+            vertex_buffer_layout,
+        );
+    }
+
+    /// Puts the buffer on the binding index, its attributes at the locations
+    /// starting from `first_attribute`
+    pub fn set_buffer(
+        &self,
+        binding: GLuint,
+        first_attribute: GLuint,
+        vertex_buffer: &VertexBuffer,
+        vertex_buffer_layout: &VbLayout,
+    ) {
         let mut offset: GLuint = 0;
-        let mut elems_count: GLuint = 0;
+        let mut attribute = first_attribute;
         for element in vertex_buffer_layout.elements.as_slice() {
-            unsafe {
-                self.gl.EnableVertexAttribArray(elems_count);
-                self.gl.VertexAttribPointer(
-                    elems_count,
-                    element.count,
-                    element.etype,
-                    element.normalized,
-                    vertex_buffer_layout.stride,
-                    offset as *const GLvoid,
-                );
-                elems_count += 1 as GLuint;
-                offset += element.count as GLuint * VbElements::get_type_size(element.etype);
-            }
+            self.backend.enable_vertex_array_attrib(self._id, attribute);
+            self.backend.vertex_array_attrib_format(
+                self._id,
+                attribute,
+                element.count,
+                element.etype,
+                element.normalized,
+                offset,
+            );
+            self.backend
+                .vertex_array_attrib_binding(self._id, attribute, binding);
+            attribute += 1 as GLuint;
+            offset += element.count as GLuint * VbElements::get_type_size(element.etype);
+        }
+        self.backend.vertex_array_vertex_buffer(
+            self._id,
+            binding,
+            vertex_buffer.id(),
+            0,
+            vertex_buffer_layout.stride,
+        );
+        self.backend
+            .vertex_array_binding_divisor(self._id, binding, vertex_buffer_layout.divisor);
+        self.next_binding.set(self.next_binding.get().max(binding + 1));
+        self.next_attribute.set(self.next_attribute.get().max(attribute));
+        if vertex_buffer_layout.divisor == 0 && vertex_buffer_layout.stride > 0 {
+            let stride = vertex_buffer_layout.stride as usize;
+            *self.vertices.borrow_mut() = Some((binding, vertex_buffer.shared_size(), stride));
+        }
+    }
+
+    /// Points the binding index at `offset` bytes of the buffer, e.g. a
+    /// section of a `RingBuffer`, keeping its attribute formats. The vertex
+    /// count of the binding is then unknown, the draws need a `count`
+    pub fn set_binding_buffer(
+        &self,
+        binding: GLuint,
+        buffer: GLuint,
+        offset: usize,
+        stride: GLsizei,
+    ) {
+        let mut vertices = self.vertices.borrow_mut();
+        if matches!(*vertices, Some((b, _, _)) if b == binding) {
+            *vertices = None;
         }
+        self.backend
+            .vertex_array_vertex_buffer(self._id, binding, buffer, offset, stride);
+    }
+
+    /// Uploads the transforms to the buffer and adds it as a per instance
+    /// `mat4` attribute, returns its first location
+    pub fn add_instance_transforms(&self, buffer: &VertexBuffer, transforms: &[Mat4]) -> GLuint {
+        let data: Vec<[f32; 16]> = transforms.iter().map(|m| m.to_cols_array()).collect();
+        buffer.set_data(&data);
+        let location = self.next_attribute.get();
+        self.add_buffer(buffer, &VbLayout::instance_transforms());
+        location
     }
 
+    /// Number of vertices in the per vertex buffer last added, following
+    /// its later uploads
+    pub fn vertex_count(&self) -> usize {
+        self.vertices
+            .borrow()
+            .as_ref()
+            .map_or(0, |(_, size, stride)| size.get() / stride)
+    }
+
+    /// Draws all the vertices as triangles
     pub fn draw(&self) {
+        self.draw_arrays(&DrawParams::default());
+    }
+
+    /// Draws all the indices as triangles
+    pub fn draw_indexed(&self, index_buffer: &IndexBuffer) {
+        self.draw_elements(index_buffer, &DrawParams::default());
+    }
+
+    /// Draws all the vertices as triangles, `instances` times
+    pub fn draw_instanced(&self, instances: usize) {
+        self.draw_arrays(&DrawParams {
+            instances,
+            ..Default::default()
+        });
+    }
+
+    /// Draws all the indices as triangles, `instances` times
+    pub fn draw_indexed_instanced(&self, index_buffer: &IndexBuffer, instances: usize) {
+        self.draw_elements(
+            index_buffer,
+            &DrawParams {
+                instances,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn set_topology(&self, topology: Topology) {
+        if let Topology::Patches(vertices) = topology {
+            self.backend.patch_vertices(vertices);
+        }
+    }
+
+    pub fn draw_arrays(&self, params: &DrawParams) {
         self.bind();
-        unsafe {
-            self.gl.DrawArrays(gl::TRIANGLES, 0, 3);
+        self.set_topology(params.topology);
+        let count = params
+            .count
+            .unwrap_or_else(|| self.vertex_count().saturating_sub(params.first));
+        let mode = params.topology.to_gl();
+        if params.instances == 1 && params.base_instance == 0 {
+            self.backend
+                .draw_arrays(mode, params.first as GLint, count as GLsizei);
+        } else {
+            self.backend.draw_arrays_instanced(
+                mode,
+                params.first as GLint,
+                count as GLsizei,
+                params.instances as GLsizei,
+                params.base_instance,
+            );
         }
     }
 
-    pub fn draw_indexed(&self, index_buffer: &IndexBuffer) {
+    pub fn draw_elements(&self, index_buffer: &IndexBuffer, params: &DrawParams) {
         self.bind();
         index_buffer.bind();
-        unsafe {
-            self.gl
-                .DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, 0 as *const GLvoid);
+        self.set_topology(params.topology);
+        let count = params
+            .count
+            .unwrap_or_else(|| index_buffer.count().saturating_sub(params.first));
+        let mode = params.topology.to_gl();
+        let offset = params.first * index_buffer.index_size();
+        if params.instances == 1 && params.base_instance == 0 && params.base_vertex == 0 {
+            self.backend
+                .draw_elements(mode, count as GLsizei, index_buffer.index_type(), offset);
+        } else {
+            self.backend.draw_elements_instanced(
+                mode,
+                count as GLsizei,
+                index_buffer.index_type(),
+                offset,
+                params.instances as GLsizei,
+                params.base_vertex,
+                params.base_instance,
+            );
         }
     }
 
+    /// Draws the `DrawArraysIndirect` commands of the range in one call
+    pub fn multi_draw_arrays_indirect(
+        &self,
+        topology: Topology,
+        commands: &DrawIndirectBuffer,
+        range: Range<usize>,
+    ) {
+        self.bind();
+        commands.bind();
+        self.set_topology(topology);
+        let stride = std::mem::size_of::<DrawArraysIndirect>();
+        self.backend.multi_draw_arrays_indirect(
+            topology.to_gl(),
+            range.start * stride,
+            range.len() as GLsizei,
+            stride as GLsizei,
+        );
+    }
+
+    /// Draws the `DrawElementsIndirect` commands of the range in one call
+    pub fn multi_draw_elements_indirect(
+        &self,
+        topology: Topology,
+        index_buffer: &IndexBuffer,
+        commands: &DrawIndirectBuffer,
+        range: Range<usize>,
+    ) {
+        self.bind();
+        index_buffer.bind();
+        commands.bind();
+        self.set_topology(topology);
+        let stride = std::mem::size_of::<DrawElementsIndirect>();
+        self.backend.multi_draw_elements_indirect(
+            topology.to_gl(),
+            index_buffer.index_type(),
+            range.start * stride,
+            range.len() as GLsizei,
+            stride as GLsizei,
+        );
+    }
+
     pub fn id(&self) -> GLuint {
         self._id
     }
 
     pub fn bind(&self) {
-        unsafe { self.gl.BindVertexArray(self._id) };
+        self.backend.bind_vertex_array(self._id);
     }
 
     pub fn unbind(&self) {
-        unsafe { self.gl.BindVertexArray(0) };
+        self.backend.bind_vertex_array(0);
     }
 }
 
 impl Drop for VArray {
     fn drop(&mut self) {
-        unsafe { self.gl.DeleteVertexArrays(1 as GLsizei, &self._id) };
+        self.backend.delete_vertex_array(self._id);
     }
 }
@@ -5,6 +5,7 @@ pub enum Error {
     InvalidValues { message: String },
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct HsbColor {
     pub hue: f32,
     pub saturation: f32,
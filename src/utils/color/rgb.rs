@@ -1,6 +1,7 @@
 use super::HsbColor;
 use std::convert::From;
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct RgbColor {
     pub red: u8,
     pub green: u8,
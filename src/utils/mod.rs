@@ -1,2 +1,3 @@
 pub mod color;
+pub mod parallel;
 pub mod time;
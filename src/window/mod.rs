@@ -1,10 +1,13 @@
 use crate::log;
+use crate::render::Backend;
 use glfw::Context;
 
 use gl;
+use std::rc::Rc;
 
 pub struct App {
     pub gl: gl::Gl,
+    pub backend: Backend,
     glfw: glfw::Glfw,
     pub client_logger: log::Logger,
     pub(crate) core_logger: log::Logger,
@@ -50,6 +53,7 @@ impl App {
             window,
             events,
             glfw,
+            backend: Rc::new(gl.clone()),
             gl: gl.clone(),
         }
     }
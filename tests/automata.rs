@@ -0,0 +1,91 @@
+// Rules are checked on their notation and on known patterns, the cave
+// post processing on the connectivity of what it leaves.
+use pge::math::Rng;
+use pge::procgen::automata::*;
+use pge::procgen::Grid;
+
+#[test]
+fn rule_notation() {
+    let life = Rule::parse("B3/S23").unwrap();
+    assert_eq!(life, Rule::life());
+    assert_eq!(life.to_string(), "B3/S23");
+    assert_eq!(Rule::parse(&life.to_string()).unwrap(), life);
+    assert_eq!(Rule::parse("s23/b3").unwrap(), life);
+    // ranges are written out
+    let ranges = Rule::parse("B5-7,12/S4").unwrap();
+    assert_eq!(ranges.to_string(), "B5,6,7,12/S4");
+    assert_eq!(Rule::parse(&ranges.to_string()).unwrap(), ranges);
+    assert!(Rule::parse("B3x/S2").is_err());
+}
+
+#[test]
+fn generations() {
+    let rule = Rule::parse("B2/S/C3").unwrap();
+    assert_eq!(rule.to_string(), "B2/S/C3");
+    assert_eq!(rule.states, 3);
+    // dying cells go through the intermediate states
+    assert_eq!(rule.next(0, 2), 1);
+    assert_eq!(rule.next(1, 5), 2);
+    assert_eq!(rule.next(2, 2), 0);
+}
+
+#[test]
+fn oscillators() {
+    let mut blinker = Grid::new_2d(5, 5, 0_u8);
+    for x in 1..4 {
+        blinker[(x, 2, 0)] = 1;
+    }
+    let life = CellularAutomaton::new(Rule::life()).with_boundary(Boundary::Constant(0));
+    let next = life.step(&blinker);
+    assert_eq!(next[(2, 1, 0)], 1);
+    assert_eq!(next[(2, 3, 0)], 1);
+    assert_eq!(next[(1, 2, 0)], 0);
+    assert_eq!(life.run(&blinker, 2), blinker);
+    // a glider crosses the wrapping grid back to its start
+    let mut glider = Grid::new_2d(8, 8, 0_u8);
+    for &(x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)].iter() {
+        glider[(x, y, 0)] = 1;
+    }
+    let life = life.with_boundary(Boundary::Wrap);
+    assert_eq!(life.run(&glider, 32), glider);
+}
+
+#[test]
+fn neighborhoods() {
+    assert_eq!(Neighborhood::Moore(1).offsets(0, true).len(), 26);
+    assert_eq!(Neighborhood::VonNeumann(1).offsets(0, true).len(), 6);
+    assert_eq!(Neighborhood::VonNeumann(2).offsets(0, false).len(), 12);
+    let grid = random_grid(10, 10, 1, 0.5, &mut Rng::new(1));
+    let hex =
+        CellularAutomaton::new(Rule::parse("B2/S34").unwrap()).with_neighborhood(Neighborhood::Hex);
+    let same = hex.step_with(&grid, |state, neighbours| {
+        assert_eq!(neighbours.len(), 6);
+        state
+    });
+    assert_eq!(same, grid);
+}
+
+#[test]
+fn connected_caves() {
+    let noise = random_grid(80, 50, 1, 0.45, &mut Rng::new(42));
+    assert_eq!(noise, random_grid(80, 50, 1, 0.45, &mut Rng::new(42)));
+    let automaton = CellularAutomaton::cave();
+    let floor = |cave: &Grid<u8>, neighborhood: &Neighborhood| {
+        Regions::label(cave, neighborhood, |s| *s == 0).count()
+    };
+
+    let mut cave = automaton.run(&noise, 5);
+    let regions = floor(&cave, &Neighborhood::VonNeumann(1));
+    assert!(regions > 1);
+    let tunnels = connect_regions(&mut cave, &Neighborhood::VonNeumann(1), 0);
+    assert_eq!(tunnels, regions - 1);
+    assert_eq!(floor(&cave, &Neighborhood::VonNeumann(1)), 1);
+
+    let mut cave = automaton.run(&noise, 5);
+    keep_largest_region(&mut cave, &Neighborhood::Moore(1), 0, 1);
+    assert_eq!(floor(&cave, &Neighborhood::Moore(1)), 1);
+
+    let mut cave = automaton.run(&noise, 5);
+    remove_small_regions(&mut cave, &Neighborhood::VonNeumann(1), 0, 1_000_000, 1);
+    assert!(cave.cells().iter().all(|&s| s == 1));
+}
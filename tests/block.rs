@@ -0,0 +1,165 @@
+// Offsets and strides of the std140 and std430 layouts, as given by the
+// rules of the GLSL specification for the cases which differ or pad.
+use pge::math::{Mat3, Vec3D};
+use pge::render::*;
+
+use BlockLayout::{Std140, Std430};
+
+fn float_at(data: &[u8], offset: usize) -> f32 {
+    let mut bytes = [0_u8; 4];
+    bytes.copy_from_slice(&data[offset..offset + 4]);
+    f32::from_ne_bytes(bytes)
+}
+
+struct Light {
+    color: Vec3D,
+    intensity: f32,
+}
+
+impl Block for Light {
+    fn write_block(&self, writer: &mut BlockWriter) {
+        writer.push(&self.color);
+        writer.push(&self.intensity);
+    }
+}
+
+struct Scalar(f32);
+
+impl Block for Scalar {
+    fn write_block(&self, writer: &mut BlockWriter) {
+        writer.push(&self.0);
+    }
+}
+
+struct Direction(Vec3D);
+
+impl Block for Direction {
+    fn write_block(&self, writer: &mut BlockWriter) {
+        writer.push(&self.0);
+    }
+}
+
+#[test]
+fn vec3_then_float() {
+    for &layout in [Std140, Std430].iter() {
+        // the float fills the last 4 bytes of the vec3
+        let mut writer = BlockWriter::new(layout);
+        assert_eq!(writer.push(&Vec3D::new(1.0, 2.0, 3.0)), 0);
+        assert_eq!(writer.push(&4_f32), 12);
+        let data = writer.finish();
+        assert_eq!(data.len(), 16);
+        assert_eq!(float_at(&data, 8), 3.0);
+        assert_eq!(float_at(&data, 12), 4.0);
+        // the other way around, the vec3 is aligned to 16
+        let mut writer = BlockWriter::new(layout);
+        assert_eq!(writer.push(&4_f32), 0);
+        assert_eq!(writer.push(&Vec3D::new(1.0, 2.0, 3.0)), 16);
+        assert_eq!(writer.finish().len(), 32);
+    }
+}
+
+#[test]
+fn scalar_arrays() {
+    assert_eq!(Std140.array_stride::<f32>(), 16);
+    assert_eq!(Std430.array_stride::<f32>(), 4);
+    let values = [1_f32, 2_f32, 3_f32];
+
+    let mut writer = BlockWriter::new(Std140);
+    assert_eq!(writer.push(&0_f32), 0);
+    assert_eq!(writer.push_array(&values), 16);
+    assert_eq!(writer.push(&9_f32), 64);
+    let data = writer.finish();
+    assert_eq!(data.len(), 80);
+    assert_eq!(float_at(&data, 48), 3.0);
+
+    let mut writer = BlockWriter::new(Std430);
+    assert_eq!(writer.push(&0_f32), 0);
+    assert_eq!(writer.push_array(&values), 4);
+    assert_eq!(writer.push(&9_f32), 16);
+    let data = writer.finish();
+    assert_eq!(data.len(), 20);
+    assert_eq!(float_at(&data, 12), 3.0);
+}
+
+#[test]
+fn vec3_arrays() {
+    for &layout in [Std140, Std430].iter() {
+        assert_eq!(layout.array_stride::<Vec3D>(), 16);
+        let mut writer = BlockWriter::new(layout);
+        assert_eq!(writer.push(&0_f32), 0);
+        let values = [Vec3D::new(1.0, 2.0, 3.0), Vec3D::new(4.0, 5.0, 6.0)];
+        assert_eq!(writer.push_array(&values), 16);
+        // no scalar in the padding of the last element
+        assert_eq!(writer.push(&9_f32), 48);
+        let data = writer.finish();
+        assert_eq!(data.len(), 64);
+        assert_eq!(float_at(&data, 32), 4.0);
+        assert_eq!(float_at(&data, 40), 6.0);
+    }
+}
+
+#[test]
+fn mat3() {
+    let matrix = Mat3::from_data([1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0]);
+    for &layout in [Std140, Std430].iter() {
+        assert_eq!(layout.array_stride::<Mat3>(), 48);
+        let mut writer = BlockWriter::new(layout);
+        assert_eq!(writer.push(&0_f32), 0);
+        assert_eq!(writer.push(&matrix), 16);
+        assert_eq!(writer.push(&9_f32), 64);
+        let data = writer.finish();
+        assert_eq!(data.len(), 80);
+        // columns padded to a vec4
+        for j in 0..3 {
+            for i in 0..3 {
+                assert_eq!(float_at(&data, 16 + 16 * j + 4 * i), matrix.get(i, j));
+            }
+        }
+    }
+}
+
+#[test]
+fn nested_structs() {
+    // struct { float a; struct { float x; } s; float b; }
+    let mut writer = BlockWriter::new(Std140);
+    assert_eq!(writer.push(&1_f32), 0);
+    assert_eq!(writer.push_block(&Scalar(2.0)), 16);
+    assert_eq!(writer.push(&3_f32), 32);
+    assert_eq!(writer.finish().len(), 48);
+
+    let mut writer = BlockWriter::new(Std430);
+    assert_eq!(writer.push(&1_f32), 0);
+    assert_eq!(writer.push_block(&Scalar(2.0)), 4);
+    assert_eq!(writer.push(&3_f32), 8);
+    assert_eq!(writer.finish().len(), 12);
+
+    for &layout in [Std140, Std430].iter() {
+        // a struct ending with a vec3 is padded, the next member doesn't fill it
+        let mut writer = BlockWriter::new(layout);
+        assert_eq!(writer.push_block(&Direction(Vec3D::new(1.0, 2.0, 3.0))), 0);
+        assert_eq!(writer.push(&4_f32), 16);
+        assert_eq!(writer.finish().len(), 32);
+
+        // struct { float a; Light lights[2]; float b; }
+        let lights = [
+            Light {
+                color: Vec3D::new(1.0, 0.0, 0.0),
+                intensity: 2.0,
+            },
+            Light {
+                color: Vec3D::new(0.0, 1.0, 0.0),
+                intensity: 3.0,
+            },
+        ];
+        let mut writer = BlockWriter::new(layout);
+        assert_eq!(writer.push(&1_f32), 0);
+        assert_eq!(writer.push_block_array(&lights), 16);
+        assert_eq!(writer.push(&4_f32), 48);
+        let data = writer.finish();
+        assert_eq!(data.len(), 64);
+        assert_eq!(float_at(&data, 28), 2.0);
+        assert_eq!(float_at(&data, 36), 1.0);
+        assert_eq!(float_at(&data, 44), 3.0);
+        assert_eq!(block_array_bytes(&lights, layout).len(), 32);
+    }
+}
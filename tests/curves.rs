@@ -0,0 +1,24 @@
+// Sampling along the arc length, checked on a straight segment whose
+// length is known exactly.
+use pge::math::curves::*;
+use pge::math::Vec3D;
+
+fn segment() -> CubicBezier<Vec3D> {
+    let point = |x: f32| Vec3D::new(x, 0.0, 0.0);
+    CubicBezier::new(point(0.0), point(1.0), point(2.0), point(3.0))
+}
+
+#[test]
+fn sample_spacing() {
+    let curve = segment();
+    let table = ArcLengthTable::new(&curve, 100);
+    let points = table.sample_spacing(&curve, 1.0);
+    assert_eq!(points.len(), 4);
+    for (i, point) in points.iter().enumerate() {
+        assert!((point.x - i as f32).abs() < 1e-3, "{} at {}", point, i);
+    }
+    // no spacing to step by
+    for &spacing in [0_f32, -1_f32, f32::NAN].iter() {
+        assert!(table.sample_spacing(&curve, spacing).is_empty());
+    }
+}
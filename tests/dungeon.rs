@@ -0,0 +1,134 @@
+// Mazes must be perfect and levels connected whatever the seed, and a seed
+// must always give the same layout.
+use pge::math::Rng;
+use pge::procgen::automata::{Neighborhood, Regions};
+use pge::procgen::dungeon::*;
+use pge::procgen::maze::{self, Maze};
+use pge::procgen::{Grid, Tile};
+use std::collections::VecDeque;
+
+fn walkable_regions(grid: &Grid<Tile>) -> usize {
+    Regions::label(grid, &Neighborhood::VonNeumann(1), |t| t.is_walkable()).count()
+}
+
+fn walled(grid: &Grid<Tile>) -> bool {
+    let (width, height) = (grid.width(), grid.height());
+    grid.iter().all(|((x, y, _), tile)| {
+        let border = x == 0 || y == 0 || x == width - 1 || y == height - 1;
+        !border || *tile == Tile::Wall
+    })
+}
+
+fn picture(grid: &Grid<Tile>) -> Vec<String> {
+    (0..grid.height())
+        .map(|y| {
+            (0..grid.width())
+                .map(|x| {
+                    if grid[(x, y, 0)].is_walkable() {
+                        '.'
+                    } else {
+                        '#'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A spanning tree of the cells: n - 1 passages and every cell reached.
+fn assert_perfect(maze: &Maze) {
+    let passages: usize = (0..maze.len()).map(|c| maze.passages(c).count()).sum();
+    assert_eq!(passages, 2 * (maze.len() - 1));
+    let mut reached = vec![false; maze.len()];
+    let mut queue = VecDeque::new();
+    reached[0] = true;
+    queue.push_back(0);
+    while let Some(cell) = queue.pop_front() {
+        for next in maze.passages(cell) {
+            if !reached[next] {
+                reached[next] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    assert!(reached.iter().all(|r| *r));
+    assert_eq!(walkable_regions(&maze.to_grid()), 1);
+}
+
+#[test]
+fn perfect_mazes() {
+    for seed in 0..4 {
+        let mut rng = Rng::new(seed);
+        assert_perfect(&maze::recursive_backtracker(17, 11, &mut rng));
+        assert_perfect(&maze::prim(17, 11, &mut rng));
+        assert_perfect(&maze::kruskal(17, 11, &mut rng));
+        assert_perfect(&maze::wilson(17, 11, &mut rng));
+        assert_perfect(&maze::eller(17, 11, 0.5, &mut rng));
+        assert_perfect(&maze::eller(1, 5, 0.5, &mut rng));
+    }
+}
+
+#[test]
+fn maze_seed_pinned() {
+    let maze = maze::recursive_backtracker(4, 3, &mut Rng::new(0));
+    assert_eq!(
+        picture(&maze.to_grid()),
+        vec![
+            "#########",
+            "#.#.....#",
+            "#.#.#.#.#",
+            "#...#.#.#",
+            "#.###.###",
+            "#...#...#",
+            "#########",
+        ]
+    );
+    let maze = maze::wilson(10, 10, &mut Rng::new(5));
+    assert_eq!(maze, maze::wilson(10, 10, &mut Rng::new(5)));
+}
+
+#[test]
+fn connected_dungeons() {
+    for seed in 0..6 {
+        let dungeon = bsp(80, 50, &BspParams::default(), &mut Rng::new(seed));
+        assert!(dungeon.rooms.len() >= 8);
+        assert_eq!(walkable_regions(&dungeon.grid), 1);
+        assert!(walled(&dungeon.grid));
+
+        let dungeon = random_rooms(80, 50, &RoomsParams::default(), &mut Rng::new(seed));
+        assert!(dungeon.rooms.len() >= 5);
+        assert_eq!(walkable_regions(&dungeon.grid), 1);
+        assert!(walled(&dungeon.grid));
+        for (i, a) in dungeon.rooms.iter().enumerate() {
+            for b in dungeon.rooms[i + 1..].iter() {
+                assert!(!a.intersects(b, 1));
+            }
+        }
+
+        let dungeon = drunkards_walk(60, 40, &WalkParams::default(), &mut Rng::new(seed));
+        assert_eq!(walkable_regions(&dungeon.grid), 1);
+        assert!(walled(&dungeon.grid));
+        let floor = dungeon
+            .grid
+            .cells()
+            .iter()
+            .filter(|t| t.is_walkable())
+            .count();
+        assert_eq!(floor, (58.0 * 38.0 * 0.4) as usize);
+    }
+}
+
+#[test]
+fn dungeon_seed_pinned() {
+    let dungeon = bsp(80, 50, &BspParams::default(), &mut Rng::new(0));
+    assert_eq!(dungeon.rooms.len(), 23);
+    assert_eq!(dungeon.rooms[0], Room::new(2, 6, 8, 9));
+    let again = bsp(80, 50, &BspParams::default(), &mut Rng::new(0));
+    assert_eq!(dungeon.grid, again.grid);
+
+    let dungeon = random_rooms(80, 50, &RoomsParams::default(), &mut Rng::new(0));
+    assert_eq!(dungeon.rooms.len(), 25);
+    assert_eq!(dungeon.rooms[0], Room::new(24, 39, 10, 9));
+    let again = random_rooms(80, 50, &RoomsParams::default(), &mut Rng::new(0));
+    assert_eq!(dungeon.grid, again.grid);
+}
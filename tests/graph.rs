@@ -0,0 +1,183 @@
+// Path costs are checked on small grids where they are known by hand, and
+// the searches against each other on random ones.
+use pge::graph::heuristic::{manhattan, octile};
+use pge::graph::mst::{kruskal, prim};
+use pge::graph::*;
+use pge::math::Rng;
+
+/// 5x5 grid with a wall on x = 2 from y = 0 to 3, the way around is by y = 4.
+fn walled_grid() -> GridGraph {
+    let mut grid = GridGraph::new(5, 5).with_diagonal(false);
+    for y in 0..4 {
+        grid.set_wall(2, y);
+    }
+    grid
+}
+
+fn random_grid(rng: &mut Rng, width: usize, height: usize) -> GridGraph {
+    let mut grid = GridGraph::new(width, height);
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            if rng.chance(0.3) {
+                grid.set_wall(x, y);
+            }
+        }
+    }
+    grid.set_cost(0, 0, 1.0);
+    grid.set_cost(width as i32 - 1, height as i32 - 1, 1.0);
+    grid
+}
+
+#[test]
+fn grid_path_costs() {
+    let grid = walled_grid();
+    let goal = (4, 0);
+    // 4 down, 4 across and 4 up
+    let path = astar(&grid, (0, 0), goal, |c| manhattan(&c, &goal)).unwrap();
+    assert_eq!(path.cost, 12.0);
+    assert_eq!(path.nodes.len(), 13);
+    assert_eq!(path.nodes[6], (2, 4));
+    let paths = dijkstra(&grid, (0, 0));
+    assert_eq!(paths.cost(goal), Some(12.0));
+    assert_eq!(paths.cost((1, 0)), Some(1.0));
+    assert_eq!(paths.cost((2, 0)), None);
+    assert_eq!(paths.path_to(goal).unwrap().cost, 12.0);
+
+    // the costs are paid when entering a cell, the mud is walked around
+    let mut grid = GridGraph::new(3, 3).with_diagonal(false);
+    grid.set_cost(1, 0, 5.0);
+    let goal = (2, 0);
+    let path = astar(&grid, (0, 0), goal, |c| manhattan(&c, &goal)).unwrap();
+    assert_eq!(path.cost, 4.0);
+    assert_eq!(path.nodes, vec![(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)]);
+    grid.set_cost(1, 1, 5.0);
+    let path = astar(&grid, (0, 0), goal, |c| manhattan(&c, &goal)).unwrap();
+    assert_eq!(path.cost, 6.0);
+
+    // diagonals cost the square root of 2
+    let grid = GridGraph::new(4, 4);
+    let goal = (3, 3);
+    let path = astar(&grid, (0, 0), goal, |c| octile(&c, &goal)).unwrap();
+    assert!((path.cost - 3_f32 * 2_f32.sqrt()).abs() < 1e-5);
+}
+
+#[test]
+fn weighted_graph_costs() {
+    // 0 -> 1 -> 3 costs 2 + 2, less than 0 -> 3 directly or 0 -> 2 -> 3
+    let mut graph = Graph::new();
+    for i in 0..4 {
+        graph.add_node(i);
+    }
+    graph.add_edge(0, 1, 2.0);
+    graph.add_edge(1, 3, 2.0);
+    graph.add_edge(0, 3, 5.0);
+    graph.add_edge(0, 2, 1.0);
+    graph.add_edge(2, 3, 3.5);
+    let paths = dijkstra(&graph, 0);
+    assert_eq!(paths.cost(3), Some(4.0));
+    assert_eq!(paths.cost(2), Some(1.0));
+    assert_eq!(paths.path_to(3).unwrap().nodes, vec![0, 1, 3]);
+    let path = astar(&graph, 0, 3, |_| 0.0).unwrap();
+    assert_eq!(path.cost, 4.0);
+    assert!(astar(&graph, 3, 0, |_| 0.0).is_none());
+}
+
+#[test]
+fn searches_agree() {
+    let mut rng = Rng::new(7);
+    let mut found = 0;
+    for _ in 0..300 {
+        let grid = random_grid(&mut rng, 20, 15);
+        let goal = (19, 14);
+        let a = astar(&grid, (0, 0), goal, |c| octile(&c, &goal));
+        let jps = jump_point_search(&grid, (0, 0), goal);
+        let cost = dijkstra(&grid, (0, 0)).cost(goal);
+        assert_eq!(a.is_some(), jps.is_some());
+        assert_eq!(a.is_some(), cost.is_some());
+        if let (Some(a), Some(cost)) = (&a, cost) {
+            assert!((a.cost - cost).abs() < 1e-3);
+        }
+        if let (Some(a), Some(jps)) = (a, jps) {
+            found += 1;
+            assert!((a.cost - jps.cost).abs() < 1e-3);
+            // the jumps are straight or diagonal lines of open cells
+            let mut length = 0_f32;
+            for step in jps.nodes.windows(2) {
+                let (dx, dy) = (step[1].0 - step[0].0, step[1].1 - step[0].1);
+                assert!(grid.can_step(step[0].0, step[0].1, dx, dy));
+                length += if dx != 0 && dy != 0 {
+                    2_f32.sqrt()
+                } else {
+                    1_f32
+                };
+            }
+            assert!((length - jps.cost).abs() < 1e-3);
+        }
+        let grid = grid.with_diagonal(false);
+        let a = astar(&grid, (0, 0), goal, |c| manhattan(&c, &goal));
+        let jps = jump_point_search(&grid, (0, 0), goal);
+        assert_eq!(a.map(|p| p.cost), jps.map(|p| p.cost));
+    }
+    assert!(found > 10);
+}
+
+#[test]
+fn traversal_and_components() {
+    let mut graph = Graph::new();
+    for i in 0..6 {
+        graph.add_node(i);
+    }
+    graph.add_edge(0, 1, 1.0);
+    graph.add_edge(0, 2, 1.0);
+    graph.add_edge(1, 3, 1.0);
+    graph.add_edge(4, 5, 1.0);
+    assert_eq!(bfs(&graph, 0), vec![0, 1, 2, 3]);
+    assert_eq!(dfs(&graph, 0), vec![0, 1, 3, 2]);
+    assert_eq!(connected_components(&graph), vec![0, 0, 0, 0, 1, 1]);
+    assert_eq!(topological_sort(&graph), Some(vec![0, 4, 1, 2, 5, 3]));
+    graph.add_edge(3, 0, 1.0);
+    assert_eq!(topological_sort(&graph), None);
+}
+
+#[test]
+fn spanning_trees() {
+    let mut rng = Rng::new(3);
+    for _ in 0..50 {
+        let mut graph = Graph::new();
+        for i in 0..12 {
+            graph.add_node(i);
+        }
+        for _ in 0..30 {
+            let (a, b) = (rng.range_usize(0, 12), rng.range_usize(0, 12));
+            graph.add_undirected_edge(a, b, rng.next_f32());
+        }
+        let weight = |tree: &[usize]| tree.iter().map(|&e| graph.edge(e).weight).sum::<f32>();
+        let (k, p) = (kruskal(&graph), prim(&graph));
+        assert!((weight(&k) - weight(&p)).abs() < 1e-4);
+        // one tree per component
+        let components = connected_components(&graph).into_iter().max().unwrap() + 1;
+        assert_eq!(k.len(), 12 - components);
+        assert_eq!(p.len(), k.len());
+    }
+}
+
+#[test]
+fn flow_field() {
+    let grid = walled_grid();
+    let field = FlowField::new(&grid, &[(4, 0)]);
+    assert_eq!(field.cost(4, 0), 0.0);
+    assert_eq!(field.cost(0, 0), 12.0);
+    assert!(field.direction(4, 0).is_none());
+    assert!(!field.cost(2, 0).is_finite());
+    // following the steps reaches the goal by the shortest path
+    let (mut x, mut y) = (0, 0);
+    let mut steps = 0;
+    while let Some((dx, dy)) = field.step(x, y) {
+        x += dx;
+        y += dy;
+        steps += 1;
+        assert!(steps <= 12);
+    }
+    assert_eq!((x, y), (4, 0));
+    assert_eq!(steps, 12);
+}
@@ -0,0 +1,74 @@
+// Derivations are checked against the words known for classic systems,
+// the stochastic ones against the words pinned for a seed.
+use pge::math::Rng;
+use pge::procgen::lsystem::*;
+
+#[test]
+fn algae() {
+    let system = LSystem::parse("axiom: A\nA -> AB\nB -> A").unwrap();
+    let word = system.derive(5, &mut Rng::new(0));
+    assert_eq!(word_to_string(&word), "ABAABABAABAAB");
+}
+
+#[test]
+fn parametric_branches() {
+    let source = "#define R 1.456\n\
+                  // halves the length at each branch\n\
+                  axiom: A(1)\n\
+                  p1: A(s) : s < 3 -> F(s)[+A(s/R)][-A(s/R)]\n";
+    let system = LSystem::parse(source).unwrap();
+    let word = system.derive(2, &mut Rng::new(0));
+    assert!(word_to_string(&word).starts_with("F(1)[+F(0.68"));
+    let segments = Turtle::new_2d(1.0, 45.0).interpret(&word);
+    assert_eq!(segments.len(), 3);
+    assert!((segments[0].end.y - 1.0).abs() < 1e-4);
+    assert!(segments[0].end.z.abs() < 1e-6);
+    // turning left goes to -x
+    assert!(segments[1].end.x < 0.0);
+    let mesh = tube_mesh(&segments, 6);
+    assert_eq!(mesh.vertex_count(), 3 * 14);
+    assert_eq!(mesh.triangle_count(), 3 * 12);
+}
+
+#[test]
+fn context_sensitive() {
+    // a signal going right, the turns being ignored in the contexts
+    let system = LSystem::parse("#ignore +-\naxiom: baaaaa\nb < a -> b\nb -> a").unwrap();
+    let word = system.derive(2, &mut Rng::new(0));
+    assert_eq!(word_to_string(&word), "aabaaa");
+    // the right context skips the branches
+    let system = LSystem::parse("axiom: a[x]a[y]b\na > b -> c\nb < a -> d\na < b -> e").unwrap();
+    let word = system.step(&system.axiom, &mut Rng::new(0));
+    assert_eq!(word_to_string(&word), "a[x]c[y]e");
+}
+
+#[test]
+fn stochastic_seed_pinned() {
+    let system = LSystem::parse("axiom: F\nF ->(0.5) F[+F]F\nF ->(0.5) F[-F]F").unwrap();
+    assert_eq!(system.rules.len(), 1);
+    let word = system.derive(2, &mut Rng::new(3));
+    assert_eq!(word_to_string(&word), "F[-F]F[+F[-F]F]F[+F]F");
+    assert_eq!(
+        system.derive(4, &mut Rng::new(3)),
+        system.derive(4, &mut Rng::new(3))
+    );
+}
+
+#[test]
+fn parse_errors() {
+    assert!(matches!(
+        LSystem::parse("axiom: A\nA(x) -> B(y)"),
+        Err(Error::Parse { line: 2, .. })
+    ));
+    assert!(matches!(
+        LSystem::parse("axiom: A\nA -> B(2 +)"),
+        Err(Error::Parse { line: 2, .. })
+    ));
+}
+
+#[test]
+fn expressions() {
+    let vars = vec!["x".to_string()];
+    let expr = Expr::parse("-2^2 + max(x, 3) * (1 < 2 && !0)", &vars, &[]).unwrap();
+    assert_eq!(expr.eval(&[5.0]), 1.0);
+}
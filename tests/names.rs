@@ -0,0 +1,126 @@
+// Generated text must follow its constraints and be the same for a seed on
+// every run, these tests pin the outputs for fixed seeds.
+use pge::math::Rng;
+use pge::procgen::grammar::{self, *};
+use pge::procgen::names::*;
+
+const WORDS: &[&str] = &[
+    "Aldor",
+    "Belmar",
+    "Caldera",
+    "Dunmore",
+    "Eldrin",
+    "Farhold",
+    "Galdor",
+    "Helmar",
+    "Islaine",
+    "Jorvik",
+    "Kaldur",
+    "Lorien",
+    "Mordane",
+    "Norwick",
+    "Orlan",
+    "Pelmar",
+    "Quenby",
+    "Rivendor",
+    "Stormhold",
+    "Tarwick",
+];
+
+const STORY: &str = "\
+// a hero and a pet, kept for the whole story
+origin: #[hero:#name#][pet:#animal#]story#
+story: #hero# had #pet.a#. #hero.capitalize# loved #pet.s#.
+name: alice | bob
+animal: owl | fox | pony
+";
+
+fn constraints() -> Constraints {
+    Constraints {
+        min_length: 4,
+        max_length: 9,
+        ..Constraints::default()
+    }
+}
+
+#[test]
+fn names_seed_pinned() {
+    let generator = NameGenerator::from_words(2, WORDS.iter().copied());
+    let mut rng = Rng::new(9);
+    let names: Vec<String> = (0..5)
+        .map(|_| generator.generate(&mut rng, &constraints()).unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec!["Galdorvik", "Farwick", "Islain", "Storvik", "Dunmor"]
+    );
+}
+
+#[test]
+fn names_constraints() {
+    let generator = NameGenerator::from_words(2, WORDS.iter().copied());
+    let mut rng = Rng::new(3);
+    for _ in 0..50 {
+        let name = generator.generate(&mut rng, &constraints()).unwrap();
+        assert!((4..=9).contains(&name.chars().count()), "{}", name);
+        assert!(!WORDS.contains(&name.as_str()), "{}", name);
+    }
+    let name = generator
+        .generate_filtered(&mut rng, &constraints(), |n| n.starts_with("Or"))
+        .unwrap();
+    assert!(name.starts_with("Or"));
+    // nothing to learn from
+    assert!(NameGenerator::new(3)
+        .generate(&mut rng, &constraints())
+        .is_none());
+}
+
+#[test]
+fn grammar_seed_pinned() {
+    let grammar = Grammar::parse(STORY).unwrap();
+    let mut rng = Rng::new(1);
+    let stories: Vec<String> = (0..3)
+        .map(|_| grammar.flatten("#origin#", &mut rng))
+        .collect();
+    assert_eq!(
+        stories,
+        vec![
+            "alice had a fox. Alice loved foxes.",
+            "bob had a fox. Bob loved foxes.",
+            "alice had a pony. Alice loved ponies.",
+        ]
+    );
+}
+
+#[test]
+fn grammar_expansion() {
+    let grammar = Grammar::parse(STORY).unwrap();
+    let mut rng = Rng::new(0);
+    assert_eq!(
+        grammar.flatten("#missing# \\#x", &mut rng),
+        "((missing)) #x"
+    );
+    assert_eq!(
+        grammar.flatten("[c:a,b]#c##c.uppercase#", &mut rng).len(),
+        2
+    );
+    let bob = grammar.flatten_filtered("#name#", &mut rng, 0, 10, 100, |s| s == "bob");
+    assert_eq!(bob.unwrap(), "bob");
+    // endless recursion stops at the depth limit
+    let mut recursive = Grammar::new();
+    recursive.add_rule("x", &["#x#"]);
+    recursive.max_depth = 5;
+    assert_eq!(recursive.flatten("#x#", &mut rng), "#x#");
+    assert!(matches!(
+        Grammar::parse("bad line"),
+        Err(grammar::Error::Parse { line: 1, .. })
+    ));
+}
+
+#[test]
+fn modifiers() {
+    assert_eq!(past_tense("cry loudly"), "cried loudly");
+    assert_eq!(capitalize_all("the old man"), "The Old Man");
+    assert_eq!(plural("fox"), "foxes");
+    assert_eq!(article("owl"), "an owl");
+}
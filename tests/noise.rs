@@ -0,0 +1,85 @@
+// The noise feeds every seeded generator, these tests pin its values for
+// fixed seeds so a change of the output is never silent.
+use pge::math::noise::OpenSimplex;
+use pge::math::{Vec2D, Vec3D};
+
+fn assert_close(value: f32, expected: f32) {
+    assert!(
+        (value - expected).abs() < 1e-5,
+        "{} instead of {}",
+        value,
+        expected
+    );
+}
+
+#[test]
+fn open_simplex_2d_pinned() {
+    let points = [(0.5, 0.25), (3.7, -1.2), (-10.3, 8.9)];
+    let cases = [
+        (0, [-0.39969918, 0.55797446, -0.36623797]),
+        (42, [0.810391, 0.58632344, -0.4448718]),
+        (-7, [-0.50798625, -0.19327694, 0.20712389]),
+    ];
+    for &(seed, expected) in cases.iter() {
+        let noise = OpenSimplex::new(seed);
+        for (&(x, y), &value) in points.iter().zip(expected.iter()) {
+            assert_close(noise.eval2(&Vec2D::new(x, y)), value);
+        }
+    }
+}
+
+#[test]
+fn open_simplex_3d_pinned() {
+    let points = [(0.5, 0.25, 0.75), (3.7, -1.2, 2.4)];
+    let cases = [
+        (0, [0.20459251, -0.38406736]),
+        (42, [0.5927759, 0.02177259]),
+        (-7, [-0.08389264, -0.090660386]),
+    ];
+    for &(seed, expected) in cases.iter() {
+        let noise = OpenSimplex::new(seed);
+        for (&(x, y, z), &value) in points.iter().zip(expected.iter()) {
+            assert_close(noise.eval3(&Vec3D::new(x, y, z)), value);
+        }
+    }
+}
+
+#[test]
+fn open_simplex_range() {
+    for seed in 0..4 {
+        let noise = OpenSimplex::new(seed);
+        for i in 0..100 {
+            for j in 0..100 {
+                let (x, y) = (i as f32 * 0.173, j as f32 * 0.137);
+                let value = noise.eval2(&Vec2D::new(x, y));
+                assert!(
+                    (-1_f32..=1_f32).contains(&value),
+                    "{} at {} {}",
+                    value,
+                    x,
+                    y
+                );
+                let value = noise.eval3(&Vec3D::new(x, y, (i + j) as f32 * 0.05));
+                assert!(
+                    (-1_f32..=1_f32).contains(&value),
+                    "{} at {} {}",
+                    value,
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn open_simplex_seeds_differ() {
+    // the permutation is shuffled from the seed, not from a constant
+    let point = Vec2D::new(0.5, 0.25);
+    let values: Vec<f32> = (0..8).map(|s| OpenSimplex::new(s).eval2(&point)).collect();
+    for (i, a) in values.iter().enumerate() {
+        for b in values.iter().skip(i + 1) {
+            assert_ne!(a, b);
+        }
+    }
+}
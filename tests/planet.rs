@@ -0,0 +1,158 @@
+// The cube faces must be oriented so that the chunks wind outwards, and the
+// chunks must meet without gaps on a face and across the faces.
+use pge::math::DVec3D;
+use pge::procgen::planet::*;
+
+const RESOLUTION: usize = 8;
+const SIDE: usize = RESOLUTION + 1;
+
+fn params() -> PlanetParams {
+    PlanetParams {
+        radius: 1000.0,
+        amplitude: 20.0,
+        skirt_depth: 5.0,
+        resolution: RESOLUTION,
+        ..Default::default()
+    }
+}
+
+fn surface(planet: &Planet, key: ChunkKey) -> Vec<DVec3D> {
+    let chunk = planet.build_chunk(key);
+    (0..SIDE * SIDE).map(|i| chunk.world_position(i)).collect()
+}
+
+#[test]
+fn face_orientation() {
+    let mut normals = Vec::new();
+    for face in CubeFace::ALL.iter() {
+        let (normal, u, v) = face.axes();
+        // unit axes, u × v along the outward normal
+        for axis in [normal, u, v].iter() {
+            assert_eq!(axis.sq_norm(), 1.0);
+        }
+        assert_eq!(u.dot(&v), 0.0);
+        assert_eq!(u.cross(&v), normal, "{:?}", face);
+        assert_eq!(face.cube_point(0.0, 0.0), normal);
+        assert_eq!(face.cube_point(1.0, 0.0), normal + u);
+        assert_eq!(face.cube_point(0.0, 1.0), normal + v);
+        normals.push(normal);
+    }
+    // one face per direction
+    for (i, a) in normals.iter().enumerate() {
+        for b in normals[i + 1..].iter() {
+            assert_ne!(a, b);
+        }
+    }
+    assert_eq!(
+        normals.iter().fold(DVec3D::null(), |s, &n| s + n),
+        DVec3D::null()
+    );
+}
+
+#[test]
+fn sphere_mapping() {
+    for face in CubeFace::ALL.iter() {
+        let (normal, _, _) = face.axes();
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let (u, v) = (i as f64 / 5.0 - 1.0, j as f64 / 5.0 - 1.0);
+                let p = cube_to_sphere(face.cube_point(u, v));
+                assert!((p.norm() - 1.0).abs() < 1e-9, "{:?} {} {}", face, u, v);
+                assert!(p.dot(&normal) > 0.5);
+            }
+        }
+    }
+}
+
+#[test]
+fn seams() {
+    let planet = Planet::new(params());
+    // neighbours on one face
+    let a = ChunkKey {
+        face: CubeFace::PosX,
+        level: 2,
+        x: 1,
+        y: 1,
+    };
+    let b = ChunkKey { x: 2, ..a };
+    let (pa, pb) = (surface(&planet, a), surface(&planet, b));
+    for j in 0..SIDE {
+        let (left, right) = (pa[RESOLUTION + j * SIDE], pb[j * SIDE]);
+        assert!(left.distance(&right) < 1e-3, "{} {}", left, right);
+    }
+    // across the faces, every point on the edge u = 1 of +X is on +Y
+    let pa = surface(&planet, ChunkKey::root(CubeFace::PosX));
+    let pb = surface(&planet, ChunkKey::root(CubeFace::PosY));
+    for j in 0..SIDE {
+        let q = pa[RESOLUTION + j * SIDE];
+        assert!(pb.iter().any(|r| r.distance(&q) < 1e-2), "{}", q);
+    }
+}
+
+#[test]
+fn chunks_wind_outwards() {
+    let planet = Planet::new(params());
+    for face in CubeFace::ALL.iter() {
+        for &(x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)].iter() {
+            let key = ChunkKey {
+                face: *face,
+                level: 1,
+                x,
+                y,
+            };
+            let chunk = planet.build_chunk(key);
+            let m = &chunk.mesh;
+            // the surface triangles come before the skirt
+            for t in m.indices.chunks(3).take(RESOLUTION * RESOLUTION * 2) {
+                let (a, b, c) = (
+                    m.positions[t[0] as usize],
+                    m.positions[t[1] as usize],
+                    m.positions[t[2] as usize],
+                );
+                let out = chunk.world_position(t[0] as usize).to_vec3d();
+                assert!((b - a).cross(&(c - a)).dot(&out) > 0.0, "{:?}", key);
+                assert!(m.normals[t[0] as usize].dot(&out) > 0.0);
+            }
+            let h = chunk.heights.get(3, 3) as f64;
+            let p = chunk.world_position(3 + 3 * SIDE);
+            assert!((p.norm() - 1000.0 - h).abs() < 1e-2);
+        }
+    }
+}
+
+#[test]
+fn lod() {
+    let mut planet = Planet::new(PlanetParams {
+        lod: LodParams {
+            max_level: 6,
+            split_distance: 2.0,
+        },
+        ..params()
+    });
+    let camera = DVec3D::new(1010.0, 0.0, 0.0);
+    let changes = planet.update(camera);
+    // the leaves tile the 6 faces
+    let area: f64 = planet
+        .chunks()
+        .map(|k| 1.0 / 4_f64.powi(k.level as i32))
+        .sum();
+    assert!((area - 6.0).abs() < 1e-9);
+    assert_eq!(changes.added.len(), planet.chunks().count());
+    assert_eq!(planet.chunks().map(|k| k.level).max(), Some(6));
+    let far = planet
+        .chunks()
+        .filter(|k| k.face == CubeFace::NegX)
+        .map(|k| k.level)
+        .max();
+    assert!(far.unwrap() <= 1);
+    assert!(planet.update(camera).is_empty());
+
+    let changes = planet.update(DVec3D::new(0.0, 0.0, -1010.0));
+    assert!(!changes.added.is_empty() && !changes.removed.is_empty());
+    let keys: Vec<_> = changes.added.iter().copied().take(20).collect();
+    let chunks = planet.build_chunks(&keys, 4);
+    assert_eq!(chunks.len(), keys.len());
+    for (chunk, key) in chunks.iter().zip(keys.iter()) {
+        assert_eq!(chunk.key, *key);
+    }
+}
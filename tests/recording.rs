@@ -0,0 +1,330 @@
+// The render objects are checked against the exact command stream of the
+// recording backend, the uploads being compared byte for byte.
+use pge::render::backend::{Command, RecordingBackend};
+use pge::render::*;
+use std::rc::Rc;
+
+fn recording() -> (Rc<RecordingBackend>, Backend) {
+    let recording = Rc::new(RecordingBackend::new());
+    let backend: Backend = recording.clone();
+    (recording, backend)
+}
+
+fn floats(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+}
+
+#[test]
+fn vertex_buffer_uploads() {
+    let (recording, backend) = recording();
+    let vb = VertexBuffer::new(&backend);
+    vb.set_data::<f32>(&[1.0, 2.0]);
+    vb.update_sub_data::<u32>(4, &[7]).unwrap();
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::CreateBuffer { id: 1 },
+            Command::BindBuffer {
+                target: gl::ARRAY_BUFFER,
+                id: 1
+            },
+            Command::BufferData {
+                target: gl::ARRAY_BUFFER,
+                data: floats(&[1.0, 2.0]),
+                usage: gl::STATIC_DRAW
+            },
+            Command::BindBuffer {
+                target: gl::ARRAY_BUFFER,
+                id: 1
+            },
+            Command::BufferSubData {
+                target: gl::ARRAY_BUFFER,
+                offset: 4,
+                data: 7u32.to_ne_bytes().to_vec()
+            },
+        ]
+    );
+    // out of the storage, nothing is sent
+    assert!(vb.update_sub_data::<f32>(4, &[0.0, 0.0]).is_err());
+    assert_eq!(recording.take_commands(), vec![]);
+}
+
+#[test]
+fn vertex_array_layout() {
+    let (recording, backend) = recording();
+    let vb = VertexBuffer::new(&backend);
+    let va = VArray::new(&backend);
+    let mut layout = VbLayout::new();
+    layout.push_f32(2);
+    recording.take_commands();
+    va.add_buffer(&vb, &layout);
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::EnableVertexArrayAttrib {
+                vertex_array: 2,
+                index: 0
+            },
+            Command::VertexArrayAttribFormat {
+                vertex_array: 2,
+                index: 0,
+                size: 2,
+                etype: gl::FLOAT,
+                normalized: gl::FALSE,
+                relative_offset: 0
+            },
+            Command::VertexArrayAttribBinding {
+                vertex_array: 2,
+                index: 0,
+                binding: 0
+            },
+            Command::VertexArrayVertexBuffer {
+                vertex_array: 2,
+                binding: 0,
+                buffer: 1,
+                offset: 0,
+                stride: 8
+            },
+            Command::VertexArrayBindingDivisor {
+                vertex_array: 2,
+                binding: 0,
+                divisor: 0
+            },
+        ]
+    );
+}
+
+#[test]
+fn vertex_count_follows_the_uploads() {
+    let (recording, backend) = recording();
+    let vb = VertexBuffer::with_usage(&backend, Usage::Dynamic);
+    let va = VArray::new(&backend);
+    let mut layout = VbLayout::new();
+    layout.push_f32(2);
+    // added while still empty, filled every frame
+    va.add_buffer(&vb, &layout);
+    let draws = |recording: &RecordingBackend| -> Vec<Command> {
+        recording
+            .take_commands()
+            .into_iter()
+            .filter(|c| matches!(c, Command::DrawArrays { .. }))
+            .collect()
+    };
+    for &vertices in [3_usize, 5, 2].iter() {
+        vb.set_data(&vec![0_f32; 2 * vertices]);
+        va.draw();
+        assert_eq!(
+            draws(&recording),
+            vec![Command::DrawArrays {
+                mode: gl::TRIANGLES,
+                first: 0,
+                count: vertices as i32
+            }]
+        );
+    }
+    // a ring buffer section has no known size
+    va.set_binding_buffer(0, vb.id(), 0, 8);
+    assert_eq!(va.vertex_count(), 0);
+}
+
+#[test]
+fn index_and_indirect_uploads() {
+    let (recording, backend) = recording();
+    let ib = IndexBuffer::new(&backend);
+    ib.set_data::<u16>(&[0, 1, 2]);
+    let commands = DrawIndirectBuffer::new(&backend);
+    commands.set_data(&[DrawArraysIndirect {
+        count: 3,
+        instance_count: 1,
+        first: 0,
+        base_instance: 0,
+    }]);
+    let indirect: Vec<u8> = [3u32, 1, 0, 0]
+        .iter()
+        .flat_map(|v| v.to_ne_bytes())
+        .collect();
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::CreateBuffer { id: 1 },
+            Command::BindBuffer {
+                target: gl::ELEMENT_ARRAY_BUFFER,
+                id: 1
+            },
+            Command::BufferData {
+                target: gl::ELEMENT_ARRAY_BUFFER,
+                data: [0u16, 1, 2].iter().flat_map(|v| v.to_ne_bytes()).collect(),
+                usage: gl::STATIC_DRAW
+            },
+            Command::CreateBuffer { id: 2 },
+            Command::BindBuffer {
+                target: gl::DRAW_INDIRECT_BUFFER,
+                id: 2
+            },
+            Command::BufferData {
+                target: gl::DRAW_INDIRECT_BUFFER,
+                data: indirect,
+                usage: gl::STATIC_DRAW
+            },
+        ]
+    );
+    assert_eq!(ib.count(), 3);
+}
+
+#[test]
+fn buffers_are_deleted() {
+    let (recording, backend) = recording();
+    let vb = VertexBuffer::new(&backend);
+    let ib = IndexBuffer::new(&backend);
+    drop(vb);
+    drop(ib);
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::CreateBuffer { id: 1 },
+            Command::CreateBuffer { id: 2 },
+            Command::DeleteBuffer { id: 1 },
+            Command::DeleteBuffer { id: 2 },
+        ]
+    );
+}
+
+#[test]
+fn ring_buffer_sizes() {
+    let (recording, backend) = recording();
+    for &(section_size, sections) in [(64, 0), (0, 3), (usize::MAX / 2, 3)].iter() {
+        assert!(matches!(
+            RingBuffer::<VertexBufferType>::new(&backend, section_size, sections),
+            Err(buffer::Error::InvalidSize { .. })
+        ));
+    }
+    // nothing is created for the invalid sizes
+    assert_eq!(recording.take_commands(), vec![]);
+    assert!(RingBuffer::<VertexBufferType>::new(&backend, 64, 3).is_ok());
+}
+
+#[test]
+fn ring_buffer_timeout() {
+    let (recording, backend) = recording();
+    let mut ring = RingBuffer::<VertexBufferType>::new(&backend, 64, 2).unwrap();
+    assert_eq!(ring.write(&[1_f32]).unwrap(), 0);
+    ring.next_section().unwrap();
+    assert_eq!(ring.write(&[2_f32]).unwrap(), 64);
+    recording.take_commands();
+    // the GPU still reads the first section, nothing moves
+    recording.set_wait_status(gl::TIMEOUT_EXPIRED);
+    assert!(matches!(
+        ring.next_section(),
+        Err(buffer::Error::SyncTimeout)
+    ));
+    assert_eq!(ring.section(), 1);
+    assert_eq!(ring.write(&[3_f32]).unwrap(), 68);
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::FenceSync { sync: 3 },
+            Command::ClientWaitSync {
+                sync: 2,
+                timeout: 1_000_000_000
+            },
+        ]
+    );
+    // the retry fences the later writes again and waits for the same section
+    recording.set_wait_status(gl::CONDITION_SATISFIED);
+    ring.next_section().unwrap();
+    assert_eq!(ring.section(), 0);
+    assert_eq!(ring.write(&[4_f32]).unwrap(), 0);
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::FenceSync { sync: 4 },
+            Command::DeleteSync { sync: 3 },
+            Command::ClientWaitSync {
+                sync: 2,
+                timeout: 1_000_000_000
+            },
+            Command::DeleteSync { sync: 2 },
+        ]
+    );
+}
+
+#[test]
+fn read_pixels_restores_the_read_framebuffer() {
+    let (recording, backend) = recording();
+    let color = Texture::new(&backend, 2, 2, gl::RGBA8);
+    let framebuffer = Framebuffer::new(&backend);
+    framebuffer.attach_texture(Attachment::Color(0), &color);
+    backend.bind_framebuffer(gl::READ_FRAMEBUFFER, 7);
+    recording.take_commands();
+    framebuffer.read_pixels(0, 0, 0, 2, 2);
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::FramebufferReadBuffer {
+                id: 2,
+                buffer: gl::COLOR_ATTACHMENT0
+            },
+            Command::FramebufferBinding {
+                target: gl::READ_FRAMEBUFFER,
+                id: 7
+            },
+            Command::BindFramebuffer {
+                target: gl::READ_FRAMEBUFFER,
+                id: 2
+            },
+            Command::ReadPixels {
+                x: 0,
+                y: 0,
+                width: 2,
+                height: 2,
+                format: gl::RGBA,
+                etype: gl::UNSIGNED_BYTE
+            },
+            Command::BindFramebuffer {
+                target: gl::READ_FRAMEBUFFER,
+                id: 7
+            },
+        ]
+    );
+    drop(framebuffer);
+    drop(color);
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::DeleteFramebuffer { id: 2 },
+            Command::DeleteTexture { id: 1 },
+        ]
+    );
+}
+
+#[test]
+fn draw_buffers_follow_the_color_attachments() {
+    let (recording, backend) = recording();
+    let first = Texture::new(&backend, 2, 2, gl::RGBA8);
+    let second = Texture::new(&backend, 2, 2, gl::RGBA8);
+    let framebuffer = Framebuffer::new(&backend);
+    let draw_buffers = |recording: &RecordingBackend| -> Vec<Vec<u32>> {
+        recording
+            .take_commands()
+            .into_iter()
+            .filter_map(|c| match c {
+                Command::FramebufferDrawBuffers { buffers, .. } => Some(buffers),
+                _ => None,
+            })
+            .collect()
+    };
+    // the color attachment 0 is the default draw buffer
+    framebuffer.attach_texture(Attachment::Color(0), &first);
+    framebuffer.attach_texture(Attachment::Color(1), &second);
+    assert_eq!(
+        draw_buffers(&recording),
+        vec![vec![gl::COLOR_ATTACHMENT0, gl::COLOR_ATTACHMENT1]]
+    );
+    framebuffer.detach(Attachment::Color(1));
+    framebuffer.detach(Attachment::Color(0));
+    assert_eq!(
+        draw_buffers(&recording),
+        vec![vec![gl::COLOR_ATTACHMENT0], vec![]]
+    );
+    assert_eq!(framebuffer.draw_buffers(), Vec::<u32>::new());
+}
@@ -0,0 +1,91 @@
+// The layouts given to a vertex array are checked against the inputs the
+// program reflection reports, at the locations the layouts are put at.
+use pge::math::Vec4D;
+use pge::render::backend::software::VertexOutput;
+use pge::render::backend::{SoftwareBackend, SoftwareShader};
+use pge::render::*;
+use std::rc::Rc;
+
+fn reflection(inputs: &[(&str, i32, u32)]) -> Rc<ProgramReflection> {
+    let software = Rc::new(SoftwareBackend::new(1, 1));
+    let backend: Backend = software.clone();
+    let program = ShaderProgram::from_shaders(&backend, Vec::new()).unwrap();
+    let shader = inputs.iter().fold(
+        SoftwareShader::new(
+            |_, a| VertexOutput::new(a[0], vec![]),
+            |_, _| Some(Vec4D::null()),
+        ),
+        |shader, &(name, location, gtype)| shader.with_input(name, location, gtype),
+    );
+    software.set_shader(program.id(), shader);
+    program.reflection()
+}
+
+fn layout(counts: &[i32]) -> VbLayout {
+    let mut layout = VbLayout::new();
+    for &count in counts {
+        layout.push_f32(count);
+    }
+    layout
+}
+
+#[test]
+fn component_counts_may_differ() {
+    let reflection = reflection(&[
+        ("a_Position", 0, gl::FLOAT_VEC3),
+        ("a_Uv", 1, gl::FLOAT_VEC2),
+    ]);
+    // the extra components are dropped, the missing ones filled
+    reflection.validate_layouts(&[&layout(&[4, 1])]).unwrap();
+    reflection.validate_layouts(&[&layout(&[2, 4])]).unwrap();
+    // unused elements are fine
+    reflection.validate_layouts(&[&layout(&[3, 2, 4])]).unwrap();
+}
+
+#[test]
+fn inputs_are_checked_at_their_location() {
+    let reflection = reflection(&[
+        ("a_Position", 0, gl::FLOAT_VEC3),
+        ("a_Model", 4, gl::FLOAT_MAT4),
+    ]);
+    let vertex = layout(&[3]);
+    let instance = VbLayout::instance_transforms();
+    // `add_buffer` would put the matrix at 1 to 4
+    assert!(matches!(
+        reflection.validate_layouts(&[&vertex, &instance]),
+        Err(reflection::Error::MissingAttribute { location: 5, .. })
+    ));
+    reflection
+        .validate_buffers(&[(0, &vertex), (4, &instance)])
+        .unwrap();
+    match reflection.validate_buffers(&[(0, &vertex), (5, &instance)]) {
+        Err(reflection::Error::MissingAttribute { name, location: 4 }) => {
+            assert_eq!(name, "a_Model")
+        }
+        result => panic!("{:?}", result),
+    }
+    assert!(matches!(
+        reflection.validate_buffers(&[(4, &instance)]),
+        Err(reflection::Error::MissingAttribute { location: 0, .. })
+    ));
+}
+
+#[test]
+fn integer_inputs_need_an_integer_format() {
+    let reflection = reflection(&[("a_Id", 0, gl::UNSIGNED_INT)]);
+    let mut ids = VbLayout::new();
+    ids.push_u32(1);
+    assert!(matches!(
+        reflection.validate_layouts(&[&ids]),
+        Err(reflection::Error::BaseTypeMismatch {
+            location: 0,
+            gtype: "uint",
+            ..
+        })
+    ));
+    // a missing input is reported first
+    assert!(matches!(
+        reflection.validate_layouts(&[]),
+        Err(reflection::Error::MissingAttribute { location: 0, .. })
+    ));
+}
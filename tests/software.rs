@@ -0,0 +1,288 @@
+// The software backend is the reference the renders are compared to, these
+// tests pin its rasterization rules and a golden image of a full scene.
+use pge::math::Vec4D;
+use pge::render::backend::software::{image_difference, VertexOutput};
+use pge::render::backend::{SoftwareBackend, SoftwareShader};
+use pge::render::*;
+use std::rc::Rc;
+
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden/scene.png");
+
+/// Clip space position, color and `(u, v, checker, _)` of a vertex.
+type Vertex = [[f32; 4]; 3];
+
+fn setup(width: usize, height: usize) -> (Rc<SoftwareBackend>, Backend, ShaderProgram) {
+    let software = Rc::new(SoftwareBackend::new(width, height));
+    let backend: Backend = software.clone();
+    let program = ShaderProgram::from_shaders(&backend, Vec::new()).unwrap();
+    software.set_shader(
+        program.id(),
+        SoftwareShader::new(
+            |_, a| {
+                let varyings = vec![a[1].x, a[1].y, a[1].z, a[1].w, a[2].x, a[2].y, a[2].z];
+                VertexOutput::new(a[0], varyings)
+            },
+            |_, f| {
+                let v = &f.varyings;
+                if v[6] > 0.5 {
+                    // 4x4 checkerboard, showing the perspective correction
+                    let cell = (v[4] * 4_f32).floor() as i32 + (v[5] * 4_f32).floor() as i32;
+                    let c = if cell % 2 == 0 { 0.9 } else { 0.2 };
+                    Some(Vec4D::new(c, c, c, 1_f32))
+                } else {
+                    Some(Vec4D::new(v[0], v[1], v[2], v[3]))
+                }
+            },
+        ),
+    );
+    program.bind();
+    (software, backend, program)
+}
+
+fn draw(backend: &Backend, vertices: &[Vertex]) {
+    let data: Vec<f32> = vertices.iter().flatten().flatten().copied().collect();
+    let vb = VertexBuffer::new(backend);
+    vb.set_data(&data);
+    let mut layout = VbLayout::new();
+    layout.push_f32(4);
+    layout.push_f32(4);
+    layout.push_f32(4);
+    let va = VArray::new(backend);
+    va.add_buffer(&vb, &layout);
+    va.bind();
+    backend.draw_arrays(gl::TRIANGLES, 0, vertices.len() as i32);
+}
+
+fn flat(position: [f32; 4], color: [f32; 4]) -> Vertex {
+    [position, color, [0_f32; 4]]
+}
+
+/// Two triangles covering the rectangle of normalized device coordinates.
+fn rectangle(x0: f32, y0: f32, x1: f32, y1: f32, z: f32, color: [f32; 4]) -> Vec<Vertex> {
+    let v = |x, y| flat([x, y, z, 1_f32], color);
+    vec![
+        v(x0, y0),
+        v(x1, y0),
+        v(x1, y1),
+        v(x0, y0),
+        v(x1, y1),
+        v(x0, y1),
+    ]
+}
+
+/// Perspective projection of a view space point, near 0.5 and far 10.
+fn project(x: f32, y: f32, z: f32) -> [f32; 4] {
+    let (near, far) = (0.5_f32, 10_f32);
+    let c = (far + near) / (near - far);
+    let d = 2_f32 * far * near / (near - far);
+    [x, y, c * z + d, -z]
+}
+
+#[test]
+fn golden_scene() {
+    let (software, backend, _program) = setup(64, 64);
+    backend.clear_color(0.1, 0.2, 0.3, 1.0);
+    backend.clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+    backend.enable(gl::DEPTH_TEST);
+    // checkered floor going away from the camera
+    let floor =
+        |x: f32, z: f32, u: f32, v: f32| [project(x, -0.5, z), [0_f32; 4], [u, v, 1_f32, 0_f32]];
+    let (a, b) = (floor(-1.0, -1.0, 0.0, 0.0), floor(1.0, -1.0, 1.0, 0.0));
+    let (c, d) = (floor(1.0, -5.0, 1.0, 1.0), floor(-1.0, -5.0, 0.0, 1.0));
+    draw(&backend, &[a, b, c, a, c, d]);
+    // red triangle standing on the floor, hiding a part of it
+    let red = [1_f32, 0_f32, 0_f32, 1_f32];
+    draw(
+        &backend,
+        &[
+            flat(project(-0.6, -0.5, -2.5), red),
+            flat(project(0.2, -0.5, -2.5), red),
+            flat(project(-0.2, 0.4, -2.5), red),
+        ],
+    );
+    // half transparent blue triangle in front of everything
+    backend.enable(gl::BLEND);
+    backend.blend_func(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+    let blue = [0_f32, 0_f32, 1_f32, 0.5_f32];
+    draw(
+        &backend,
+        &[
+            flat(project(-0.1, -0.6, -1.6), blue),
+            flat(project(0.8, -0.2, -1.6), blue),
+            flat(project(0.1, 0.5, -1.6), blue),
+        ],
+    );
+    let image = software.to_image();
+    if std::env::var_os("PGE_UPDATE_GOLDEN").is_some() {
+        image.save(GOLDEN).unwrap();
+    }
+    let golden = image::open(GOLDEN).unwrap().to_rgba8();
+    let difference = image_difference(&image, &golden);
+    assert!(
+        matches!(difference, Some(d) if d <= 2),
+        "The render differs from {} by {:?}",
+        GOLDEN,
+        difference
+    );
+}
+
+#[test]
+fn near_plane_clipping() {
+    let (software, backend, _program) = setup(8, 8);
+    let white = [1_f32; 4];
+    // the top vertex is behind the near plane, the triangle is cut at y = -1/3
+    draw(
+        &backend,
+        &[
+            flat([-1.0, -1.0, 0.0, 1.0], white),
+            flat([1.0, -1.0, 0.0, 1.0], white),
+            flat([0.0, 1.0, -3.0, 1.0], white),
+        ],
+    );
+    for y in 0..8 {
+        let drawn = software.pixel(4, y) == Vec4D::new(1.0, 1.0, 1.0, 1.0);
+        // the pixel centers are at y = -7/8, -5/8, -3/8, -1/8, ...
+        assert_eq!(drawn, y < 3, "row {}", y);
+    }
+    // entirely behind the near plane
+    backend.clear(gl::COLOR_BUFFER_BIT);
+    draw(
+        &backend,
+        &[
+            flat([-1.0, -1.0, -2.0, 1.0], white),
+            flat([1.0, -1.0, -2.0, 1.0], white),
+            flat([0.0, 1.0, -2.0, 1.0], white),
+        ],
+    );
+    let image = software.to_image();
+    assert!(image.pixels().all(|p| p.0 == [0, 0, 0, 0]));
+}
+
+#[test]
+fn outside_the_viewport() {
+    let (software, backend, _program) = setup(8, 8);
+    let white = [1_f32; 4];
+    // far larger than the target, only the pixels inside are drawn
+    draw(&backend, &rectangle(-50.0, -50.0, 50.0, 50.0, 0.0, white));
+    let image = software.to_image();
+    assert!(image.pixels().all(|p| p.0 == [255, 255, 255, 255]));
+    backend.clear(gl::COLOR_BUFFER_BIT);
+    draw(&backend, &rectangle(2.0, -1.0, 3.0, 1.0, 0.0, white));
+    let image = software.to_image();
+    assert!(image.pixels().all(|p| p.0 == [0, 0, 0, 0]));
+}
+
+#[test]
+fn top_left_rule() {
+    let (software, backend, _program) = setup(4, 4);
+    backend.enable(gl::BLEND);
+    backend.blend_func(gl::ONE, gl::ONE);
+    let quarter = [0.25_f32, 0_f32, 0_f32, 0_f32];
+    // the edges go through the pixel centers 0.5 and 2.5: the left and
+    // top ones own them, the right and bottom ones don't, with y going up
+    draw(&backend, &rectangle(-0.75, -0.75, 0.25, 0.25, 0.0, quarter));
+    for y in 0..4 {
+        for x in 0..4 {
+            let expected = if x < 2 && (1..3).contains(&y) {
+                0.25
+            } else {
+                0.0
+            };
+            assert_eq!(software.pixel(x, y).x, expected, "pixel {} {}", x, y);
+        }
+    }
+    // the shared diagonal goes through the centers and is drawn once
+    backend.clear(gl::COLOR_BUFFER_BIT);
+    draw(&backend, &rectangle(-1.0, -1.0, 1.0, 1.0, 0.0, quarter));
+    for y in 0..4 {
+        for x in 0..4 {
+            assert_eq!(software.pixel(x, y).x, 0.25, "pixel {} {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn blend_funcs() {
+    let destination = Vec4D::new(0.2, 0.4, 0.6, 0.8);
+    let source = Vec4D::new(0.5, 0.25, 1.0, 0.5);
+    let mul = |a: Vec4D, b: Vec4D| Vec4D::new(a.x * b.x, a.y * b.y, a.z * b.z, a.w * b.w);
+    let splat = |v: f32| Vec4D::new(v, v, v, v);
+    let cases = [
+        (gl::ONE, gl::ZERO, source),
+        (gl::ZERO, gl::ONE, destination),
+        (
+            gl::SRC_ALPHA,
+            gl::ONE_MINUS_SRC_ALPHA,
+            source * 0.5 + destination * 0.5,
+        ),
+        (gl::DST_COLOR, gl::ZERO, mul(source, destination)),
+        (gl::ZERO, gl::SRC_COLOR, mul(source, destination)),
+        (
+            gl::ONE_MINUS_DST_ALPHA,
+            gl::DST_ALPHA,
+            source * 0.2 + destination * 0.8,
+        ),
+        (
+            gl::ONE_MINUS_DST_COLOR,
+            gl::ONE_MINUS_SRC_COLOR,
+            mul(source, splat(1.0) - destination) + mul(destination, splat(1.0) - source),
+        ),
+        // clamped to 1
+        (gl::ONE, gl::ONE, Vec4D::new(0.7, 0.65, 1.0, 1.0)),
+    ];
+    for &(sf, df, expected) in cases.iter() {
+        let (software, backend, _program) = setup(2, 2);
+        backend.clear_color(destination.x, destination.y, destination.z, destination.w);
+        backend.clear(gl::COLOR_BUFFER_BIT);
+        backend.enable(gl::BLEND);
+        backend.blend_func(sf, df);
+        let color = [source.x, source.y, source.z, source.w];
+        draw(&backend, &rectangle(-1.0, -1.0, 1.0, 1.0, 0.0, color));
+        let pixel = software.pixel(1, 1);
+        let error = pixel - expected;
+        assert!(
+            error
+                .x
+                .abs()
+                .max(error.y.abs())
+                .max(error.z.abs())
+                .max(error.w.abs())
+                < 1e-6,
+            "{:#x} {:#x}: {} instead of {}",
+            sf,
+            df,
+            pixel,
+            expected
+        );
+    }
+}
+
+#[test]
+fn depth_funcs() {
+    // the stored depth is 0.5, the drawn one 0.25, 0.5 or 0.75
+    let cases = [
+        (gl::NEVER, [false, false, false]),
+        (gl::LESS, [true, false, false]),
+        (gl::EQUAL, [false, true, false]),
+        (gl::LEQUAL, [true, true, false]),
+        (gl::GREATER, [false, false, true]),
+        (gl::NOTEQUAL, [true, false, true]),
+        (gl::GEQUAL, [false, true, true]),
+        (gl::ALWAYS, [true, true, true]),
+    ];
+    let green = [0_f32, 1_f32, 0_f32, 1_f32];
+    for &(func, expected) in cases.iter() {
+        for (&z, &passes) in [-0.5_f32, 0.0, 0.5].iter().zip(expected.iter()) {
+            let (software, backend, _program) = setup(2, 2);
+            backend.clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
+            backend.enable(gl::DEPTH_TEST);
+            draw(&backend, &rectangle(-1.0, -1.0, 1.0, 1.0, 0.0, [1_f32; 4]));
+            backend.depth_func(func);
+            draw(&backend, &rectangle(-1.0, -1.0, 1.0, 1.0, z, green));
+            let drawn = software.pixel(0, 0) == Vec4D::new(0.0, 1.0, 0.0, 1.0);
+            assert_eq!(drawn, passes, "{:#x} at {}", func, z);
+            let depth = if passes { (z + 1_f32) * 0.5 } else { 0.5 };
+            assert_eq!(software.depth(0, 0), depth);
+        }
+    }
+}
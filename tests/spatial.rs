@@ -0,0 +1,178 @@
+// Every spatial structure must answer the queries exactly like a brute force
+// search over the same items, including after removals.
+use pge::math::geometry::{Aabb2, Aabb3, Ray, Ray2};
+use pge::math::spatial::*;
+use pge::math::{Rng, Vec2D, Vec3D};
+
+const ITEMS: usize = 1000;
+
+fn random_boxes(seed: u64) -> Vec<Aabb3> {
+    let mut rng = Rng::new(seed);
+    (0..ITEMS)
+        .map(|_| {
+            let mut coord = || rng.range_f32(0.0, 100.0);
+            let center = Vec3D::new(coord(), coord(), coord());
+            let half = rng.range_f32(0.1, 2.0);
+            Aabb3::from_center(center, Vec3D::new(half, half, half))
+        })
+        .collect()
+}
+
+/// The three structures with every box inserted, then every third one removed.
+fn structures(boxes: &[Aabb3]) -> (Bvh3<usize>, Octree<usize>, SpatialHash3<usize>) {
+    let world = Aabb3::new(Vec3D::null(), Vec3D::new(100.0, 100.0, 100.0));
+    let mut bvh = Bvh3::new();
+    let mut octree = Octree::new(world, 6);
+    let mut hash = SpatialHash3::new(4.0);
+    let mut handles = Vec::new();
+    for (i, bounds) in boxes.iter().enumerate() {
+        let handle = bvh.insert(*bounds, i);
+        assert_eq!(octree.insert(*bounds, i), handle);
+        assert_eq!(hash.insert(*bounds, i), handle);
+        handles.push(handle);
+    }
+    for handle in handles.iter().step_by(3) {
+        assert!(bvh.remove(*handle).is_some());
+        assert!(octree.remove(*handle).is_some());
+        assert!(hash.remove(*handle).is_some());
+    }
+    (bvh, octree, hash)
+}
+
+fn alive() -> Vec<usize> {
+    (0..ITEMS).filter(|i| i % 3 != 0).collect()
+}
+
+fn sorted(handles: Vec<Handle>) -> Vec<usize> {
+    let mut indices: Vec<usize> = handles.iter().map(|h| h.index()).collect();
+    indices.sort_unstable();
+    indices
+}
+
+#[test]
+fn box_and_radius_queries() {
+    let boxes = random_boxes(1);
+    let (bvh, octree, hash) = structures(&boxes);
+    let mut rng = Rng::new(2);
+    for _ in 0..20 {
+        let radius = rng.range_f32(0.0, 30.0);
+        let mut coord = || rng.range_f32(-10.0, 110.0);
+        let (a, b) = (
+            Vec3D::new(coord(), coord(), coord()),
+            Vec3D::new(coord(), coord(), coord()),
+        );
+        let area = Aabb3::new(
+            Vec3D::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            Vec3D::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        );
+        let expected: Vec<usize> = alive()
+            .into_iter()
+            .filter(|i| boxes[*i].intersects(&area))
+            .collect();
+        assert_eq!(sorted(bvh.query_box(&area)), expected);
+        assert_eq!(sorted(octree.query_box(&area)), expected);
+        assert_eq!(sorted(hash.query_box(&area)), expected);
+
+        let center = Vec3D::new(coord(), coord(), coord());
+        let expected: Vec<usize> = alive()
+            .into_iter()
+            .filter(|i| boxes[*i].sq_distance(center) <= radius * radius)
+            .collect();
+        assert_eq!(sorted(bvh.query_radius(center, radius)), expected);
+        assert_eq!(sorted(octree.query_radius(center, radius)), expected);
+        assert_eq!(sorted(hash.query_radius(center, radius)), expected);
+    }
+    // the built tree holds every box
+    let built = Bvh3::build(boxes.iter().copied().zip(0..ITEMS).collect());
+    let area = Aabb3::new(Vec3D::new(20.0, 20.0, 20.0), Vec3D::new(40.0, 45.0, 50.0));
+    let mut found: Vec<usize> = built
+        .query_box(&area)
+        .iter()
+        .map(|h| *built.get(*h).unwrap())
+        .collect();
+    found.sort_unstable();
+    let expected: Vec<usize> = (0..ITEMS).filter(|i| boxes[*i].intersects(&area)).collect();
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn nearest_items() {
+    let boxes = random_boxes(3);
+    let (bvh, octree, hash) = structures(&boxes);
+    // inside the items and far away from all of them
+    let points = [
+        Vec3D::new(50.0, 50.0, 50.0),
+        Vec3D::new(0.0, 100.0, 3.0),
+        Vec3D::new(5000.0, -3000.0, 800.0),
+    ];
+    for point in points.iter() {
+        let mut distances: Vec<f32> = alive()
+            .into_iter()
+            .map(|i| boxes[i].distance(*point))
+            .collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for &k in [1, 10, 50].iter() {
+            for found in [
+                bvh.k_nearest(*point, k),
+                octree.k_nearest(*point, k),
+                hash.k_nearest(*point, k),
+            ]
+            .iter()
+            {
+                let found: Vec<f32> = found.iter().map(|(_, d)| *d).collect();
+                assert_eq!(found, distances[..k].to_vec(), "{} nearest of {}", k, point);
+            }
+        }
+    }
+}
+
+#[test]
+fn ray_casts() {
+    let boxes = random_boxes(4);
+    let (bvh, octree, hash) = structures(&boxes);
+    let mut rng = Rng::new(5);
+    for _ in 0..50 {
+        let mut coord = || rng.range_f32(-20.0, 120.0);
+        let origin = Vec3D::new(coord(), coord(), coord());
+        let direction = Vec3D::new(coord(), coord(), coord()) - origin;
+        let ray = Ray::new(origin, direction.normalized());
+        let expected = alive()
+            .into_iter()
+            .filter_map(|i| ray.intersect_aabb(&boxes[i]).map(|t| (i, t)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+        for &max_dist in [30_f32, f32::INFINITY].iter() {
+            let expected = expected.filter(|(_, t)| *t <= max_dist).map(|(i, _)| i);
+            for hit in [
+                bvh.ray_cast(&ray, max_dist),
+                octree.ray_cast(&ray, max_dist),
+                hash.ray_cast(&ray, max_dist),
+            ]
+            .iter()
+            {
+                assert_eq!(hit.map(|(h, _)| h.index()), expected, "{:?}", ray);
+            }
+        }
+    }
+}
+
+#[test]
+fn two_dimensions() {
+    let mut quadtree = QuadTree::new(Aabb2::new(Vec2D::null(), Vec2D::new(10.0, 10.0)), 4);
+    let mut hash = SpatialHash2::new(1.0);
+    let bounds = Aabb2::from_center(Vec2D::new(3.0, 3.0), Vec2D::new(0.1, 0.1));
+    let handle = quadtree.insert(bounds, 0);
+    assert_eq!(hash.insert(bounds, 0), handle);
+    let ray = Ray2::new(Vec2D::null(), Vec2D::new(1.0, 1.0).normalized());
+    assert_eq!(quadtree.ray_cast(&ray, 100.0).unwrap().0, handle);
+    assert_eq!(hash.ray_cast(&ray, f32::INFINITY).unwrap().0, handle);
+    let ray = Ray2::new(Vec2D::null(), Vec2D::new(-1.0, 1.0).normalized());
+    assert!(hash.ray_cast(&ray, f32::INFINITY).is_none());
+    let far = Vec2D::new(1e6, -1e6);
+    assert_eq!(hash.k_nearest(far, 3).len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn hash_cell_size_positive() {
+    SpatialHash3::<usize>::new(0.0);
+}
@@ -0,0 +1,122 @@
+// The flow of water must reach the border from every cell and conserve the
+// rain, the rivers and roads are checked on terrains shaped for them.
+use pge::math::curves::Curve;
+use pge::procgen::terrain::*;
+
+const SIZE: i32 = 64;
+
+/// Sloping towards y = 0, with a valley along x = 32 and a pit to fill.
+fn valley() -> Heightmap {
+    Heightmap::from_fn(SIZE as usize, SIZE as usize, |x, y| {
+        let dx = (x as f32 - 32.0).abs();
+        let noise = ((x * 7 + y * 13) % 5) as f32 * 0.05;
+        let pit = if (x, y) == (32, 40) { 10.0 } else { 0.0 };
+        y as f32 * 0.5 + dx * 0.8 + noise - pit
+    })
+}
+
+fn cells() -> impl Iterator<Item = (i32, i32)> {
+    (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+}
+
+#[test]
+fn flow_reaches_the_border() {
+    let flow = FlowMap::new(&valley());
+    for (x, y) in cells() {
+        // strictly going down the filled heights, so without cycles
+        let (mut cx, mut cy) = (x, y);
+        let mut steps = 0;
+        while let Some((nx, ny)) = flow.receiver(cx, cy) {
+            assert!(flow.filled_height(nx, ny) < flow.filled_height(cx, cy));
+            cx = nx;
+            cy = ny;
+            steps += 1;
+            assert!(steps < SIZE * SIZE);
+        }
+        let border = cx == 0 || cy == 0 || cx == SIZE - 1 || cy == SIZE - 1;
+        assert!(border, "{} {} drains to {} {}", x, y, cx, cy);
+    }
+}
+
+#[test]
+fn flow_accumulation() {
+    let flow = FlowMap::new(&valley());
+    // each cell gets its own rain and the one of the cells draining into it
+    let mut upstream = vec![0_f32; (SIZE * SIZE) as usize];
+    for (x, y) in cells() {
+        if let Some((rx, ry)) = flow.receiver(x, y) {
+            upstream[(rx + ry * SIZE) as usize] += flow.accumulation(x, y);
+        }
+    }
+    for (x, y) in cells() {
+        let expected = 1_f32 + upstream[(x + y * SIZE) as usize];
+        assert_eq!(flow.accumulation(x, y), expected, "{} {}", x, y);
+    }
+    // all the rain leaves the map
+    let outflow: f32 = cells()
+        .filter(|&(x, y)| flow.receiver(x, y).is_none())
+        .map(|(x, y)| flow.accumulation(x, y))
+        .sum();
+    assert_eq!(outflow, (SIZE * SIZE) as f32);
+}
+
+#[test]
+fn rivers_follow_the_valley() {
+    let map = valley();
+    let flow = FlowMap::new(&map);
+    let params = RiverParams {
+        min_catchment: 30.0,
+        ..Default::default()
+    };
+    let rivers = rivers(&flow, &params);
+    assert!(!rivers.is_empty());
+    let main = &rivers[0];
+    assert!(main.cells.len() > 20);
+    for widths in main.widths.windows(2) {
+        assert!(widths[1] >= widths[0]);
+    }
+    let points = main.spline.sample(20);
+    assert!(points.first().unwrap().z >= points.last().unwrap().z);
+
+    let mut carved = map.clone();
+    carve_rivers(&mut carved, &rivers, &params);
+    let (x, y) = main.cells[main.cells.len() / 2];
+    assert!(carved.get(x, y) < map.get(x, y));
+    for (after, before) in carved.grid().cells().iter().zip(map.grid().cells()) {
+        assert!(after <= before);
+    }
+}
+
+#[test]
+fn roads_avoid_steep_slopes_and_water() {
+    // a steep wall with a gap for y in 30..34
+    let mut map = Heightmap::new(40, 40);
+    for y in 0..40 {
+        if !(30..34).contains(&y) {
+            map.set(20, y, 10.0);
+        }
+    }
+    let params = RoadParams::default();
+    let road = find_road(&map, (5, 5), (35, 5), &params).unwrap();
+    assert!(road
+        .cells
+        .iter()
+        .any(|&(x, y)| x == 20 && (30..34).contains(&y)));
+    for step in road.cells.windows(2) {
+        let climb = map.get(step[0].0, step[0].1) - map.get(step[1].0, step[1].1);
+        assert!(climb.abs() <= params.max_slope);
+    }
+    let points = [(5, 5), (35, 5), (5, 35), (35, 35)];
+    assert_eq!(road_network(&map, &points, &params).len(), 3);
+
+    // a river across the whole map can't be forded
+    let mut lake = Heightmap::from_fn(10, 10, |_, _| 2.0);
+    for y in 0..10 {
+        lake.set(5, y, 0.0);
+    }
+    let wet = RoadParams {
+        water_level: 1.0,
+        ..Default::default()
+    };
+    assert!(find_road(&lake, (1, 1), (8, 8), &wet).is_none());
+}
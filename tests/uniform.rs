@@ -0,0 +1,130 @@
+// Uniform arrays are set with a single call from the location of their
+// first element, checked on the recorded commands and the software backend.
+use pge::math::{Vec3D, Vec4D};
+use pge::render::backend::software::VertexOutput;
+use pge::render::backend::{
+    ActiveVariable, Command, RecordingBackend, SoftwareBackend, SoftwareShader, UniformValue,
+};
+use pge::render::*;
+use std::rc::Rc;
+
+fn recording_program() -> (Rc<RecordingBackend>, ShaderProgram) {
+    let recording = Rc::new(RecordingBackend::new());
+    let backend: Backend = recording.clone();
+    let program = ShaderProgram::from_shaders(&backend, Vec::new()).unwrap();
+    recording.set_active_uniforms(
+        program.id(),
+        vec![
+            ActiveVariable::new("u_Weights[0]", gl::FLOAT, 4),
+            ActiveVariable::new("u_Offsets[0]", gl::FLOAT_VEC3, 2),
+            ActiveVariable::new("u_Output", gl::IMAGE_2D, 1),
+            ActiveVariable::new("u_Counts", gl::UNSIGNED_INT_IMAGE_2D, 1),
+        ],
+    );
+    recording.take_commands();
+    (recording, program)
+}
+
+#[test]
+fn arrays_are_set_in_one_call() {
+    let (recording, program) = recording_program();
+    let id = program.id();
+    program
+        .set_uniform("u_Weights[1]", &[1_f32, 2_f32, 3_f32][..])
+        .unwrap();
+    let offsets = [Vec3D::new(1.0, 2.0, 3.0), Vec3D::new(4.0, 5.0, 6.0)];
+    program.set_uniform("u_Offsets", &offsets[..]).unwrap();
+    assert_eq!(
+        recording.take_commands(),
+        vec![
+            Command::ActiveUniforms { program: id },
+            Command::UniformLocation {
+                program: id,
+                name: "u_Weights[1]".to_owned(),
+                location: 0
+            },
+            Command::SetUniform {
+                program: id,
+                location: 0,
+                value: UniformValue::FloatArray(vec![1.0, 2.0, 3.0])
+            },
+            Command::UniformLocation {
+                program: id,
+                name: "u_Offsets[0]".to_owned(),
+                location: 1
+            },
+            Command::SetUniform {
+                program: id,
+                location: 1,
+                value: UniformValue::Vec3Array(vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]])
+            },
+        ]
+    );
+    // past the end of the array, nothing is sent
+    assert!(matches!(
+        program.set_uniform("u_Weights[2]", &[0_f32; 3][..]),
+        Err(shader::Error::UniformOutOfBounds {
+            size: 4,
+            end: 5,
+            ..
+        })
+    ));
+    assert_eq!(recording.take_commands(), vec![]);
+}
+
+#[test]
+fn images_take_their_unit() {
+    let (recording, program) = recording_program();
+    program.set_uniform("u_Output", &2_i32).unwrap();
+    program.set_uniform("u_Counts", &3_i32).unwrap();
+    let values: Vec<UniformValue> = recording
+        .take_commands()
+        .into_iter()
+        .filter_map(|command| match command {
+            Command::SetUniform { value, .. } => Some(value),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(values, vec![UniformValue::Int(2), UniformValue::Int(3)]);
+    assert!(matches!(
+        program.set_uniform("u_Output", &2_f32),
+        Err(shader::Error::UniformTypeMismatch {
+            expected: "image2D",
+            ..
+        })
+    ));
+}
+
+#[test]
+fn software_arrays() {
+    let software = Rc::new(SoftwareBackend::new(2, 2));
+    let backend: Backend = software.clone();
+    let program = ShaderProgram::from_shaders(&backend, Vec::new()).unwrap();
+    software.set_shader(
+        program.id(),
+        SoftwareShader::new(
+            |_, a| VertexOutput::new(a[0], vec![]),
+            |c, _| {
+                Some(Vec4D::new(
+                    c.float("u_Weights[0]"),
+                    c.float("u_Weights[1]"),
+                    c.float("u_Weights[2]"),
+                    c.float("u_Weights[3]"),
+                ))
+            },
+        )
+        .with_uniform("u_Weights", gl::FLOAT, 4),
+    );
+    program
+        .set_uniform("u_Weights[1]", &[0.25_f32, 0.5_f32, 1_f32][..])
+        .unwrap();
+    program.bind();
+    let vb = VertexBuffer::new(&backend);
+    vb.set_data::<f32>(&[-1.0, -1.0, 3.0, -1.0, -1.0, 3.0]);
+    let mut layout = VbLayout::new();
+    layout.push_f32(2);
+    let va = VArray::new(&backend);
+    va.add_buffer(&vb, &layout);
+    va.draw();
+    assert_eq!(software.pixel(0, 0), Vec4D::new(0.0, 0.25, 0.5, 1.0));
+}
@@ -0,0 +1,136 @@
+// The packed storage must keep every voxel through the palette growing and
+// shrinking, and the meshes must face outwards with no hidden faces.
+use pge::procgen::voxel::*;
+
+fn assert_outwards(mesh: &VoxelMesh) {
+    let m = &mesh.mesh;
+    for triangle in m.indices.chunks(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| m.positions[triangle[i] as usize]);
+        let normal = (b - a).cross(&(c - a));
+        assert!(normal.dot(&m.normals[triangle[0] as usize]) > 0.0);
+    }
+}
+
+#[test]
+fn palette_growth() {
+    let mut storage = PalettedStorage::new(4096, 0);
+    assert_eq!(storage.bits(), 0);
+    assert_eq!(storage.packed_size(), 0);
+    // every new value may widen the indices, the values set before are kept
+    let value = |i: usize| (i * 37 % 20) as u16;
+    for i in 0..4096 {
+        assert_eq!(storage.set(i, value(i)), 0);
+        if i % 97 == 0 || i < 40 {
+            for j in 0..=i {
+                assert_eq!(storage.get(j), value(j), "{} after setting {}", j, i);
+            }
+        }
+    }
+    assert_eq!(storage.bits(), 5);
+    for i in 0..4096 {
+        assert_eq!(storage.get(i), value(i));
+    }
+    // overwriting keeps the other values
+    for i in (0..4096).step_by(3) {
+        assert_eq!(storage.set(i, 300), value(i));
+    }
+    for i in 0..4096 {
+        let expected = if i % 3 == 0 { 300 } else { value(i) };
+        assert_eq!(storage.get(i), expected);
+    }
+}
+
+#[test]
+fn palette_compaction() {
+    let mut storage = PalettedStorage::new(4096, 0);
+    for i in 0..4096 {
+        storage.set(i, (i % 5) as u16);
+    }
+    assert_eq!(storage.bits(), 3);
+    for i in 0..4096 {
+        if i % 5 >= 3 {
+            storage.set(i, 1);
+        }
+    }
+    // 3 values left fit in 2 bits
+    storage.compact();
+    assert_eq!(storage.bits(), 2);
+    assert_eq!(storage.count(1), 819 * 3);
+    for i in 0..4096 {
+        let expected = if i % 5 >= 3 { 1 } else { (i % 5) as u16 };
+        assert_eq!(storage.get(i), expected);
+    }
+    storage.fill(9);
+    storage.compact();
+    assert_eq!(storage.bits(), 0);
+    assert_eq!(storage.get(17), 9);
+}
+
+#[test]
+fn greedy_meshes() {
+    let mut world = VoxelWorld::new(16);
+    world.set(3, 4, 5, 7);
+    assert_eq!(world.get(3, 4, 5), 7);
+    assert_eq!(world.get(-3, 4, 5), 0);
+    let mesh = mesh_chunk(&world, (0, 0, 0));
+    assert_eq!(mesh.mesh.triangle_count(), 12);
+    assert!(mesh.ao.iter().all(|&a| a == 1.0));
+    assert!(mesh.voxels.iter().all(|&v| v == 7));
+    assert_outwards(&mesh);
+
+    // a floor across two chunks: top, bottom and 3 sides, none between the chunks
+    let mut world = VoxelWorld::new(16);
+    for x in 0..32 {
+        for y in 0..16 {
+            world.set(x, y, 0, 1);
+        }
+    }
+    let mesh = mesh_chunk(&world, (0, 0, 0));
+    assert_eq!(mesh.mesh.triangle_count(), 10);
+    assert_outwards(&mesh);
+    // a block on the floor darkens the corners around it
+    world.set(5, 5, 1, 2);
+    let mesh = mesh_chunk(&world, (0, 0, 0));
+    assert_outwards(&mesh);
+    assert!(mesh.ao.iter().any(|&a| a < 1.0));
+}
+
+#[test]
+fn dirty_chunks() {
+    let mut world = VoxelWorld::new(16);
+    world.set(0, 0, 0, 1);
+    world.set(-1, 0, 0, 1);
+    assert_eq!(world.take_dirty(), vec![(-1, 0, 0), (0, 0, 0)]);
+    assert!(world.take_dirty().is_empty());
+    world.set(15, 3, 3, 1);
+    assert_eq!(world.take_dirty(), vec![(0, 0, 0)]);
+    // on the border, the neighbour chunk is remeshed too
+    world.set(0, 3, 3, 2);
+    assert_eq!(world.take_dirty(), vec![(-1, 0, 0), (0, 0, 0)]);
+}
+
+#[test]
+fn parallel_generation() {
+    let terrain = |x: i32, y: i32, z: i32| if z < ((x + y) % 7) - 3 { 1 } else { 0 };
+    let positions: Vec<_> = (-2..2)
+        .flat_map(|x| (-2..2).flat_map(move |y| (-1..1).map(move |z| (x, y, z))))
+        .collect();
+    let mut parallel = VoxelWorld::new(32);
+    parallel.generate(&positions, 4, terrain);
+    let mut serial = VoxelWorld::new(32);
+    serial.generate(&positions, 1, terrain);
+    for x in -64..64 {
+        for z in -32..32 {
+            assert_eq!(parallel.get(x, 5, z), terrain(x, 5, z));
+            assert_eq!(serial.get(x, 5, z), terrain(x, 5, z));
+        }
+    }
+    // the empty upper chunks are dropped
+    assert!(parallel.chunk_count() > 16 && parallel.chunk_count() < 32);
+    let meshes = parallel.remesh_dirty();
+    assert_eq!(meshes.len(), parallel.chunk_count());
+    for (_, mesh) in meshes.iter() {
+        assert_outwards(mesh);
+    }
+    assert!(parallel.take_dirty().is_empty());
+}